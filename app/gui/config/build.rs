@@ -1,8 +1,51 @@
+use std::path::Path;
+use std::process::Command;
+
 const CONFIG_PATH: &str = "../config.yaml";
+const GIT_DIR_PATH: &str = "../../../.git";
+/// The environment variable the build script sets to the IDE version read from the changelog.
+const IDE_VERSION_ENV: &str = "ENSO_IDE_VERSION";
+
+/// Pass the IDE version to the compiler as the `ENSO_IDE_VERSION` environment variable. Nothing is
+/// passed when the crate is not built by the build script.
+fn set_ide_version() {
+    println!("cargo:rerun-if-env-changed={}", IDE_VERSION_ENV);
+    if let Ok(version) = std::env::var(IDE_VERSION_ENV) {
+        println!("cargo:rustc-env={}={}", IDE_VERSION_ENV, version);
+    }
+}
+
+/// Make cargo rerun the build script when the built commit changes: when `HEAD` is switched to
+/// another branch, or when the branch it points to is moved, also by updating the packed refs.
+fn rerun_if_commit_changed() {
+    let git_dir = Path::new(GIT_DIR_PATH);
+    let head_path = git_dir.join("HEAD");
+    println!("cargo:rerun-if-changed={}", head_path.display());
+    let head = std::fs::read_to_string(&head_path).unwrap_or_default();
+    let head_ref = head.trim().strip_prefix("ref: ").map(|head_ref| git_dir.join(head_ref));
+    let packed_refs = Some(git_dir.join("packed-refs"));
+    // Cargo reruns the build script on every build if a watched file does not exist.
+    for path in head_ref.into_iter().chain(packed_refs).filter(|path| path.exists()) {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+}
+
+/// Pass the hash of the built commit to the compiler as the `ENSO_IDE_COMMIT_HASH` environment
+/// variable. Nothing is passed when the sources are not in a git repository.
+fn set_commit_hash() {
+    rerun_if_commit_changed();
+    let output = Command::new("git").args(["rev-parse", "HEAD"]).output();
+    let hash = output.ok().filter(|output| output.status.success());
+    if let Some(hash) = hash.and_then(|output| String::from_utf8(output.stdout).ok()) {
+        println!("cargo:rustc-env=ENSO_IDE_COMMIT_HASH={}", hash.trim());
+    }
+}
 
 fn main() {
     println!("cargo:rerun-if-changed={}", CONFIG_PATH);
     println!("cargo:rerun-if-changed=build.rs");
 
     config_reader::generate_config_module_from_yaml(CONFIG_PATH);
+    set_ide_version();
+    set_commit_hash();
 }
@@ -32,6 +32,20 @@ pub fn engine_version_requirement() -> semver::VersionReq {
 }
 
 
+
+// ==================
+// === Build Info ===
+// ==================
+
+/// The version of the IDE, read from the changelog by the build script. Unknown if the IDE was not
+/// built by the build script.
+pub const IDE_VERSION: Option<&str> = option_env!("ENSO_IDE_VERSION");
+
+/// The hash of the commit the IDE was built from, if it was built from a git repository.
+pub const COMMIT_HASH: Option<&str> = option_env!("ENSO_IDE_COMMIT_HASH");
+
+
+
 // ============
 // === Args ===
 // ============
@@ -88,6 +88,19 @@ impl BackendService {
     }
 }
 
+impl Display for BackendService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ProjectManager { endpoint } => write!(f, "Project Manager at {endpoint}"),
+            Self::LanguageServer { json_endpoint, binary_endpoint, namespace, project_name } => {
+                writeln!(f, "Language Server of project {namespace}.{project_name}")?;
+                writeln!(f, "JSON endpoint: {json_endpoint}")?;
+                write!(f, "Binary endpoint: {binary_endpoint}")
+            }
+        }
+    }
+}
+
 
 
 // ===============
@@ -9,6 +9,7 @@ use crate::FailedIde;
 
 use engine_protocol::project_manager;
 use engine_protocol::project_manager::ProjectName;
+use enso_frp as frp;
 use ensogl::application::Application;
 use ensogl::system::web;
 use uuid::Uuid;
@@ -24,6 +25,9 @@ use uuid::Uuid;
 //     https://github.com/enso-org/ide/issues/1034
 const PROJECT_MANAGER_TIMEOUT_SEC: u64 = 2 * 60 * 60;
 
+/// The local storage key under which the Root View state is persisted.
+const ROOT_VIEW_STATE_KEY: &str = "enso-ide-root-view-state";
+
 
 
 // ==============
@@ -46,15 +50,19 @@ pub struct ProjectNotFound {
 /// The IDE initializer.
 #[derive(Clone, Debug)]
 pub struct Initializer {
-    config: config::Startup,
-    logger: Logger,
+    config:                config::Startup,
+    logger:                Logger,
+    /// The project displayed when the IDE was closed, reopened if no project is requested
+    /// explicitly and it still exists.
+    restored_project_name: Option<ProjectName>,
 }
 
 impl Initializer {
     /// Create [`Initializer`] with given configuration.
     pub fn new(config: config::Startup) -> Self {
         let logger = Logger::new("ide::Initializer");
-        Self { config, logger }
+        let restored_project_name = None;
+        Self { config, logger, restored_project_name }
     }
 
     /// Initialize all Ide objects and structures (executor, views, controllers, integration etc.)
@@ -74,41 +82,100 @@ impl Initializer {
 
     /// Initialize all Ide objects and structures (executor, views, controllers, integration etc.)
     #[profile(Task)]
-    pub async fn start(self) -> Result<Ide, FailedIde> {
+    pub async fn start(mut self) -> Result<Ide, FailedIde> {
         info!(self.logger, "Starting IDE with the following config: {self.config:?}");
 
         let ensogl_app = ensogl::application::Application::new("root");
         Initializer::register_views(&ensogl_app);
         let view = ensogl_app.new_view::<ide_view::root::View>();
 
+        // When no project is requested explicitly, reopen the project displayed when the IDE was
+        // closed. The Root View does not restore its state by itself, see
+        // `ide_view::root::View::set_state_storage`.
+        let storage = LocalStorage::new(ROOT_VIEW_STATE_KEY);
+        let restored_state = view.set_state_storage(storage);
+        if self.config.project_name.is_none() {
+            let restored_state = restored_state.filter(|state| state.project_displayed);
+            let last_project = restored_state.and_then(|state| state.last_project);
+            self.restored_project_name = last_project.map(ProjectName::from);
+        }
+
         // IDE was opened with `project` argument, we should skip the Welcome Screen.
         // We are doing it early, because Controllers initialization
         // takes some time and Welcome Screen might be visible for a brief moment while
-        // controllers are not ready.
-        if self.config.project_name.is_some() {
-            view.switch_view_to_project();
+        // controllers are not ready. The progress of opening is displayed instead.
+        let project_name =
+            self.config.project_name.as_ref().or(self.restored_project_name.as_ref());
+        if let Some(project_name) = project_name {
+            view.switch_view_to_opening_project();
+            view.set_opening_project_step(format!("Connecting to project {project_name}..."));
+            view.skip_transition();
         }
 
-        let status_bar = view.status_bar().clone_ref();
         ensogl_app.display.add_child(&view);
-        // TODO [mwu] Once IDE gets some well-defined mechanism of reporting
-        //      issues to user, such information should be properly passed
-        //      in case of setup failure.
-        match self.initialize_ide_controller().await {
-            Ok(controller) => {
-                let ide = Ide::new(ensogl_app, view.clone_ref(), controller);
-                info!(self.logger, "Setup done.");
-                Ok(ide)
-            }
-            Err(error) => {
-                let message = format!("Failed to initialize application: {error}");
-                error!(self.logger, "{message}");
-                status_bar.add_event(ide_view::status_bar::event::Label::new(message));
-                Err(FailedIde { view })
+        view.set_backend_info(self.config.backend.to_string());
+        loop {
+            match self.initialize_ide_controller().await {
+                Ok(controller) => {
+                    // The restored project might have been removed, then no project is opened.
+                    if controller.current_project().is_none()
+                        && !view.welcome_screen_visible.value()
+                    {
+                        view.switch_view_to_welcome_screen();
+                    }
+                    let ide = Ide::new(ensogl_app, view.clone_ref(), controller);
+                    info!(self.logger, "Setup done.");
+                    break Ok(ide);
+                }
+                Err(error) => {
+                    let message = format!("Failed to initialize application: {error}");
+                    error!(self.logger, "{message}");
+                    let can_skip_project = self.can_initialize_without_project();
+                    view.set_back_to_welcome_available(can_skip_project);
+                    view.show_error(message);
+                    if Self::wait_for_retry(&view).await {
+                        info!(self.logger, "Retrying the IDE initialization.");
+                    } else if can_skip_project {
+                        // The Welcome Screen is displayed already, but it needs a controller to
+                        // list, open and create projects.
+                        info!(self.logger, "Initializing the IDE without a project.");
+                        self.config.project_name = None;
+                        self.restored_project_name = None;
+                    } else {
+                        break Err(FailedIde { view });
+                    }
+                }
             }
         }
     }
 
+    /// Check whether the IDE controller can be initialized without opening any project, which is
+    /// the case if the Project Manager is used and a project was going to be opened. Otherwise,
+    /// the failure was not caused by the project, and there is no point in retrying without it.
+    fn can_initialize_without_project(&self) -> bool {
+        let uses_project_manager =
+            matches!(self.config.backend, config::BackendService::ProjectManager { .. });
+        let has_project =
+            self.config.project_name.is_some() || self.restored_project_name.is_some();
+        uses_project_manager && has_project
+    }
+
+    /// Wait until the user decides what to do after a failure displayed on the error screen.
+    /// Returns `true` if the user requested retrying, and `false` if they went back to the Welcome
+    /// Screen.
+    async fn wait_for_retry(view: &ide_view::root::View) -> bool {
+        let (sender, mut receiver) = futures::channel::mpsc::unbounded();
+        frp::new_network! { network
+            returned_to_welcome_screen <- view.welcome_screen_visible.on_true().constant(false);
+            retried <- view.retry_requested.constant(true);
+            decision <- any(retried, returned_to_welcome_screen);
+            _sent <- decision.map(move |retry| sender.unbounded_send(*retry).is_ok());
+        }
+        let retry = receiver.next().await.unwrap_or_default();
+        drop(network);
+        retry
+    }
+
     fn register_views(app: &Application) {
         app.views.register::<ide_view::root::View>();
         app.views.register::<ide_view::graph_editor::GraphEditor>();
@@ -142,7 +209,10 @@ impl Initializer {
         match &self.config.backend {
             ProjectManager { endpoint } => {
                 let project_manager = self.setup_project_manager(endpoint).await?;
-                let project_name = self.config.project_name.clone();
+                let project_name = match &self.config.project_name {
+                    Some(name) => Some(name.clone()),
+                    None => self.find_restored_project(&project_manager).await?,
+                };
                 let controller = controller::ide::Desktop::new(project_manager, project_name);
                 Ok(Rc::new(controller.await?))
             }
@@ -167,6 +237,25 @@ impl Initializer {
         }
     }
 
+    /// The name of the restored project, if it still exists. Unlike the project requested
+    /// explicitly, a missing restored project is not created, as the user might have removed it.
+    /// The Welcome Screen is displayed instead.
+    async fn find_restored_project(
+        &self,
+        project_manager: &Rc<dyn project_manager::API>,
+    ) -> FallibleResult<Option<ProjectName>> {
+        let name = match &self.restored_project_name {
+            Some(name) => name.clone(),
+            None => return Ok(None),
+        };
+        let initializer = WithProjectManager::new(project_manager.clone_ref(), name.clone());
+        let exists = initializer.project_exists().await?;
+        if !exists {
+            warning!(self.logger, "The restored project {name} does not exist anymore.");
+        }
+        Ok(exists.then(|| name))
+    }
+
     /// Create and configure a new project manager client and register it within the global
     /// executor.
     #[profile(Task)]
@@ -239,6 +328,13 @@ impl WithProjectManager {
             .ok_or_else(|| ProjectNotFound { name: self.project_name.clone() }.into())
     }
 
+    /// Check whether the project with the name specified when constructing this initializer
+    /// exists.
+    pub async fn project_exists(&self) -> FallibleResult<bool> {
+        let response = self.project_manager.list_projects(&None).await?;
+        Ok(response.projects.iter().any(|project| project.name == self.project_name))
+    }
+
     /// Look for the project with the name specified when constructing this initializer,
     /// or, if it does not exist, create it. The id of found/created project is returned.
     pub async fn get_project_or_create_new(&self) -> FallibleResult<Uuid> {
@@ -254,6 +350,50 @@ impl WithProjectManager {
 
 
 
+// ====================
+// === LocalStorage ===
+// ====================
+
+/// The Root View state storage backed by the browser local storage.
+#[derive(Clone, Debug)]
+pub struct LocalStorage {
+    logger: Logger,
+    key:    String,
+}
+
+impl LocalStorage {
+    /// Constructor. The document is stored under the given key.
+    pub fn new(key: impl Into<String>) -> Self {
+        let logger = Logger::new("initializer::LocalStorage");
+        let key = key.into();
+        Self { logger, key }
+    }
+
+    fn storage(&self) -> Option<web_sys::Storage> {
+        let storage = web_sys::window().and_then(|window| window.local_storage().ok().flatten());
+        if storage.is_none() {
+            warning!(self.logger, "The local storage is not available.");
+        }
+        storage
+    }
+}
+
+impl ide_view::root::StateStorage for LocalStorage {
+    fn load(&self) -> Option<String> {
+        self.storage()?.get_item(&self.key).ok().flatten()
+    }
+
+    fn save(&self, document: &str) {
+        if let Some(storage) = self.storage() {
+            if storage.set_item(&self.key, document).is_err() {
+                warning!(self.logger, "Cannot save the Root View state to the local storage.");
+            }
+        }
+    }
+}
+
+
+
 // =============
 // === Utils ===
 // =============
@@ -303,4 +443,18 @@ mod test {
         let project = initializer.get_project_or_create_new().await;
         assert_eq!(expected_id, project.expect("Couldn't get project."))
     }
+
+    #[wasm_bindgen_test(async)]
+    async fn project_exists() {
+        let logger = Logger::new("test");
+        let mock_client = project_manager::MockClient::default();
+        let project_name = ProjectName::new_unchecked("TestProject");
+        let project_lists = project_manager::response::ProjectList { projects: vec![] };
+        let count = None;
+        expect_call!(mock_client.list_projects(count) => Ok(project_lists));
+
+        let project_manager = Rc::new(mock_client);
+        let initializer = WithProjectManager { logger, project_manager, project_name };
+        assert!(!initializer.project_exists().await.expect("Couldn't list projects."));
+    }
 }
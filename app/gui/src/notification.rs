@@ -5,6 +5,7 @@ use crate::prelude::*;
 
 use flo_stream::MessagePublisher;
 use flo_stream::Subscriber;
+use futures::stream;
 
 
 
@@ -19,13 +20,87 @@ use flo_stream::Subscriber;
 /// therefore there is no need for setting big buffers.
 pub const NOTIFICATION_BUFFER_SIZE: usize = 36;
 
+/// The policy of a [`Publisher`] for the subscribers which do not keep up with the published
+/// messages, applied when the buffer of a subscriber is full.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum Backpressure {
+    /// Block the publishing until all subscribers read a message from their buffers of the given
+    /// capacity.
+    Block { capacity: usize },
+    /// Drop the oldest message from the full buffer of the given capacity, so the subscribers
+    /// which do not keep up miss some messages, but never block the publishing.
+    DropOldest { capacity: usize },
+    /// Replace the message not yet read by a subscriber with the newly published one, so the
+    /// subscriber always reads the latest message. Suitable for the messages describing the whole
+    /// state, like the current value of a property.
+    Coalesce,
+}
+
+impl Default for Backpressure {
+    fn default() -> Self {
+        Self::Block { capacity: NOTIFICATION_BUFFER_SIZE }
+    }
+}
+
+/// The `flo_stream` publisher implementing the [`Backpressure`] policy.
+enum Inner<Message> {
+    Blocking(flo_stream::Publisher<Message>),
+    Expiring(flo_stream::ExpiringPublisher<Message>),
+}
+
+impl<Message: Clone> Inner<Message> {
+    fn new(backpressure: Backpressure) -> Self {
+        match backpressure {
+            Backpressure::Block { capacity } =>
+                Self::Blocking(flo_stream::Publisher::new(capacity)),
+            Backpressure::DropOldest { capacity } =>
+                Self::Expiring(flo_stream::ExpiringPublisher::new(capacity)),
+            Backpressure::Coalesce => Self::Expiring(flo_stream::ExpiringPublisher::new(1)),
+        }
+    }
+
+    fn republish(&self) -> Self {
+        match self {
+            Self::Blocking(publisher) => Self::Blocking(publisher.republish()),
+            Self::Expiring(publisher) => Self::Expiring(publisher.republish()),
+        }
+    }
+}
+
+impl<Message: 'static + Send + Clone> Inner<Message> {
+    fn publisher(&mut self) -> &mut dyn MessagePublisher<Message = Message> {
+        match self {
+            Self::Blocking(publisher) => publisher,
+            Self::Expiring(publisher) => publisher,
+        }
+    }
+}
+
 /// A notification publisher which implements Debug, Default and CloneRef (which is same as
 /// republishing for the same stream) and uses internal mutability.
-pub struct Publisher<Message>(RefCell<flo_stream::Publisher<Message>>);
+///
+/// The default publisher blocks when its subscribers do not keep up, see [`Backpressure`] for the
+/// other policies. The publisher remembers the last published message, so the subscribers created
+/// late can start from it, see [`Publisher::subscribe_with_last`].
+pub struct Publisher<Message> {
+    inner: RefCell<Inner<Message>>,
+    /// The last message sent to the subscribers, shared by the republished publishers.
+    last:  Rc<RefCell<Option<Message>>>,
+}
 
 impl<Message: Clone> Default for Publisher<Message> {
     fn default() -> Self {
-        Self(RefCell::new(flo_stream::Publisher::new(NOTIFICATION_BUFFER_SIZE)))
+        Self::new(default())
+    }
+}
+
+impl<Message: Clone> Publisher<Message> {
+    /// Constructor of a publisher with the given policy for the subscribers which do not keep up.
+    pub fn new(backpressure: Backpressure) -> Self {
+        let inner = RefCell::new(Inner::new(backpressure));
+        let last = default();
+        Self { inner, last }
     }
 }
 
@@ -43,25 +118,41 @@ impl<Message: Clone> CloneRef for Publisher<Message> {
 
 impl<Message: Clone> Clone for Publisher<Message> {
     fn clone(&self) -> Self {
-        Self(RefCell::new(self.0.borrow().republish()))
+        let inner = RefCell::new(self.inner.borrow().republish());
+        let last = self.last.clone_ref();
+        Self { inner, last }
     }
 }
 
-impl<Message> Publisher<Message>
-where
-    Message: 'static + Send,
-    flo_stream::Publisher<Message>: MessagePublisher<Message = Message>,
-{
+impl<Message: 'static + Send + Clone> Publisher<Message> {
     /// Publish a message to the subscribers of this object.
     pub fn publish(&self, message: Message) -> StaticBoxFuture<()> {
-        self.0.borrow_mut().publish(message)
+        let when_ready = self.inner.borrow_mut().publisher().when_ready();
+        let last = self.last.clone_ref();
+        async move {
+            let sender = when_ready.await;
+            *last.borrow_mut() = Some(message.clone());
+            sender.send(message);
+        }
+        .boxed_local()
     }
 
     /// Create a subscription to this publisher
     ///
     /// Any future messages sent here will also be sent to this subscriber.
     pub fn subscribe(&self) -> Subscriber<Message> {
-        self.0.borrow_mut().subscribe()
+        self.inner.borrow_mut().publisher().subscribe()
+    }
+
+    /// Create a subscription to this publisher, which starts with the last message sent to the
+    /// subscribers, if there was any.
+    ///
+    /// Any future messages sent here will also be sent to this subscriber. This way a subscriber
+    /// created after the publishing of the current state, like a late-constructed view, does not
+    /// miss it.
+    pub fn subscribe_with_last(&self) -> impl Stream<Item = Message> + Unpin {
+        let last = self.last.borrow().clone();
+        stream::iter(last).chain(self.subscribe())
     }
 
     /// Use global executor to publish a message.
@@ -70,3 +161,53 @@ where
         executor::global::spawn(notify);
     }
 }
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::test_utils::TestWithLocalPoolExecutor;
+
+    #[test]
+    fn subscribing_with_last_message() {
+        let mut fixture = TestWithLocalPoolExecutor::set_up();
+        let publisher = Publisher::default();
+        let mut early = publisher.subscribe_with_last();
+        fixture.expect_completion(publisher.publish(1));
+        fixture.expect_completion(publisher.publish(2));
+        let mut subscriber = publisher.subscribe();
+        let mut with_last = publisher.clone_ref().subscribe_with_last();
+        assert_eq!(fixture.expect_completion(with_last.next()), Some(2));
+        fixture.expect_pending(subscriber.next());
+        fixture.expect_completion(publisher.publish(3));
+        assert_eq!(fixture.expect_completion(with_last.next()), Some(3));
+        assert_eq!(fixture.expect_completion(subscriber.next()), Some(3));
+        assert_eq!(fixture.expect_completion(early.next()), Some(1));
+    }
+
+    #[test]
+    fn backpressure_policies() {
+        let mut fixture = TestWithLocalPoolExecutor::set_up();
+        let mut received = |backpressure| {
+            let publisher = Publisher::new(backpressure);
+            let subscriber = publisher.subscribe();
+            let mut published = 0;
+            for message in 1..=3 {
+                if publisher.publish(message).now_or_never().is_some() {
+                    published += 1;
+                }
+            }
+            drop(publisher);
+            let received = fixture.expect_completion(subscriber.collect::<Vec<_>>());
+            (published, received)
+        };
+        assert_eq!(received(Backpressure::Block { capacity: 2 }), (2, vec![1, 2]));
+        assert_eq!(received(Backpressure::DropOldest { capacity: 2 }), (3, vec![2, 3]));
+        assert_eq!(received(Backpressure::Coalesce), (3, vec![3]));
+    }
+}
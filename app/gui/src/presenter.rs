@@ -29,6 +29,20 @@ pub use searcher::Searcher;
 
 
 
+// ===============
+// === Attempt ===
+// ===============
+
+/// An operation of opening a project, which can be retried from the error screen of the view.
+#[derive(Clone, Debug)]
+enum Attempt {
+    Open { project_name: String },
+    Create { template: Option<String> },
+    Initialize,
+}
+
+
+
 // =============
 // === Model ===
 // =============
@@ -39,6 +53,8 @@ struct Model {
     current_project: RefCell<Option<Project>>,
     controller:      controller::Ide,
     view:            view::root::View,
+    /// The last started operation of opening a project.
+    last_attempt:    RefCell<Option<Attempt>>,
 }
 
 impl Model {
@@ -47,37 +63,43 @@ impl Model {
     fn setup_and_display_new_project(self: Rc<Self>) {
         // Remove the old integration first. We want to be sure the old and new integrations will
         // not race for the view.
-        *self.current_project.borrow_mut() = None;
+        let had_project = self.current_project.borrow_mut().take().is_some();
+        *self.last_attempt.borrow_mut() = Some(Attempt::Initialize);
         let project_model = match self.controller.current_project() {
             Some(model) => model,
             None => return,
         };
-        self.view.switch_view_to_project();
+        self.view.switch_view_to_opening_project();
+        let step = format!("Initializing project {}...", project_model.name());
+        self.view.set_opening_project_step(step);
+        // A fresh view is used for every new project, so no state of the previous one leaks.
+        let project_view =
+            if had_project { self.view.replace_active_project() } else { self.view.project() };
         // We know the name of new project before it loads. We set it right now to avoid
         // displaying a placeholder on the scene during loading.
-        let project_view = self.view.project();
-        let status_bar = self.view.status_bar().clone_ref();
         let breadcrumbs = &project_view.graph().model.breadcrumbs;
         breadcrumbs.project_name(project_model.name().to_string());
+        if let Some(id) = self.view.active_project.value() {
+            self.view.set_project_name(id, project_model.name().to_string());
+        }
 
+        let project_name = project_model.name().to_string();
         let status_notifications = self.controller.status_notifications().clone_ref();
         let ide_controller = self.controller.clone_ref();
         let project_controller = controller::Project::new(project_model, status_notifications);
-        let project_presenter = presenter::Project::initialize(
-            ide_controller,
-            project_controller,
-            project_view,
-            status_bar,
-        );
+        let project_presenter =
+            presenter::Project::initialize(ide_controller, project_controller, project_view);
         crate::executor::global::spawn(async move {
             match project_presenter.await {
                 Ok(project) => {
                     *self.current_project.borrow_mut() = Some(project);
+                    self.view.set_last_project(project_name);
+                    self.view.switch_view_to_project();
                 }
                 Err(err) => {
                     let err_msg = format!("Failed to initialize project: {}", err);
                     error!(self.logger, "{err_msg}");
-                    self.controller.status_notifications().publish_event(err_msg);
+                    self.view.show_error(err_msg);
                 }
             }
         });
@@ -87,15 +109,24 @@ impl Model {
     /// a second one for opening the project.
     #[profile(Task)]
     pub fn open_project(&self, project_name: String) {
+        let attempt = Attempt::Open { project_name: project_name.clone() };
+        *self.last_attempt.borrow_mut() = Some(attempt);
         let logger = self.logger.clone_ref();
         let controller = self.controller.clone_ref();
+        let view = self.view.clone_ref();
+        view.switch_view_to_opening_project();
+        view.set_opening_project_step(format!("Opening project {project_name}..."));
         crate::executor::global::spawn(async move {
             if let Ok(managing_api) = controller.manage_projects() {
                 if let Err(err) = managing_api.open_project_by_name(project_name).await {
                     error!(logger, "Cannot open project by name: {err}.");
+                    view.show_error(format!("Cannot open project: {err}."));
                 }
             } else {
                 warning!(logger, "Project opening failed: no ProjectManagingAPI available.");
+                view.show_error(
+                    "Cannot open project: the backend does not manage projects.".to_owned(),
+                );
             }
         });
     }
@@ -106,7 +137,11 @@ impl Model {
     fn create_project(&self, template: Option<&str>) {
         let logger = self.logger.clone_ref();
         let controller = self.controller.clone_ref();
+        let view = self.view.clone_ref();
         let template = template.map(ToOwned::to_owned);
+        *self.last_attempt.borrow_mut() = Some(Attempt::Create { template: template.clone() });
+        view.switch_view_to_opening_project();
+        view.set_opening_project_step("Creating a new project...".to_owned());
         crate::executor::global::spawn(async move {
             if let Ok(managing_api) = controller.manage_projects() {
                 if let Err(err) = managing_api.create_new_project(template.clone()).await {
@@ -118,12 +153,46 @@ impl Model {
                     } else {
                         error!(logger, "Could not create new project: {err}.");
                     }
+                    view.show_error(format!("Could not create new project: {err}."));
                 }
             } else {
                 warning!(logger, "Project creation failed: no ProjectManagingAPI available.");
+                let message = "Could not create new project: the backend does not manage projects.";
+                view.show_error(message.to_owned());
             }
         });
     }
+
+    /// Repeat the last operation of opening a project, after it failed.
+    fn retry(self: &Rc<Self>) {
+        let attempt = self.last_attempt.borrow().clone();
+        match attempt {
+            Some(Attempt::Open { project_name }) => self.open_project(project_name),
+            Some(Attempt::Create { template }) => self.create_project(template.as_deref()),
+            Some(Attempt::Initialize) => self.clone_ref().setup_and_display_new_project(),
+            None => warning!(self.logger, "Cannot retry: no project was being opened."),
+        }
+    }
+
+    /// Update the list of projects displayed on the Welcome Screen.
+    fn refresh_projects_list(self: &Rc<Self>) {
+        executor::global::spawn(self.clone_ref().set_projects_list_on_welcome_screen());
+    }
+
+    #[profile(Detail)]
+    async fn set_projects_list_on_welcome_screen(self: Rc<Self>) {
+        if let Ok(project_manager) = self.controller.manage_projects() {
+            match project_manager.list_projects().await {
+                Ok(projects) => {
+                    let names = projects.into_iter().map(|p| p.name.into()).collect::<Vec<_>>();
+                    self.view.welcome_screen().frp.set_projects_list(names);
+                }
+                Err(err) => {
+                    error!(self.logger, "Unable to get list of projects: {err}.");
+                }
+            }
+        }
+    }
 }
 
 
@@ -150,7 +219,8 @@ impl Presenter {
     pub fn new(controller: controller::Ide, view: ide_view::root::View) -> Self {
         let logger = Logger::new("Presenter");
         let current_project = default();
-        let model = Rc::new(Model { logger, controller, view, current_project });
+        let last_attempt = default();
+        let model = Rc::new(Model { logger, controller, view, current_project, last_attempt });
 
         frp::new_network! { network
             let welcome_view_frp = &model.view.welcome_screen().frp;
@@ -158,8 +228,11 @@ impl Presenter {
             eval welcome_view_frp.create_project((templ) model.create_project(templ.as_deref()));
 
             let root_frp = &model.view.frp;
-            root_frp.switch_view_to_project <+ welcome_view_frp.create_project.constant(());
-            root_frp.switch_view_to_project <+ welcome_view_frp.open_project.constant(());
+            eval_ root_frp.retry_requested (model.retry());
+
+            // The list of projects might have changed while the project was opened.
+            returned_to_welcome_screen <- root_frp.welcome_screen_visible.on_true();
+            eval_ returned_to_welcome_screen (model.refresh_projects_list());
         }
 
 
@@ -171,7 +244,7 @@ impl Presenter {
         self.setup_status_bar_notification_handler();
         self.setup_controller_notification_handler();
         self.model.clone_ref().setup_and_display_new_project();
-        executor::global::spawn(self.clone_ref().set_projects_list_on_welcome_screen());
+        self.model.refresh_projects_list();
         self
     }
 
@@ -181,22 +254,22 @@ impl Presenter {
 
         let logger = self.model.logger.clone_ref();
         let process_map = SharedHashMap::<ControllerHandle, ViewHandle>::new();
-        let status_bar = self.model.view.status_bar().clone_ref();
+        let view = self.model.view.clone_ref();
         let status_notifications = self.model.controller.status_notifications().subscribe();
         let weak = Rc::downgrade(&self.model);
         spawn_stream_handler(weak, status_notifications, move |notification, _| {
             match notification {
                 StatusNotification::Event { label } => {
-                    status_bar.add_event(ide_view::status_bar::event::Label::new(label));
+                    view.add_status_event(ide_view::status_bar::event::Label::new(label));
                 }
                 StatusNotification::BackgroundTaskStarted { label, handle } => {
-                    status_bar.add_process(ide_view::status_bar::process::Label::new(label));
-                    let view_handle = status_bar.last_process.value();
+                    view.add_status_process(ide_view::status_bar::process::Label::new(label));
+                    let view_handle = view.last_status_process.value();
                     process_map.insert(handle, view_handle);
                 }
                 StatusNotification::BackgroundTaskFinished { handle } => {
                     if let Some(view_handle) = process_map.remove(&handle) {
-                        status_bar.finish_process(view_handle);
+                        view.finish_status_process(view_handle);
                     } else {
                         warning!(logger, "Controllers finished process not displayed in view");
                     }
@@ -218,21 +291,6 @@ impl Presenter {
             futures::future::ready(())
         });
     }
-
-    #[profile(Detail)]
-    async fn set_projects_list_on_welcome_screen(self) {
-        if let Ok(project_manager) = self.model.controller.manage_projects() {
-            match project_manager.list_projects().await {
-                Ok(projects) => {
-                    let names = projects.into_iter().map(|p| p.name.into()).collect::<Vec<_>>();
-                    self.model.view.welcome_screen().frp.set_projects_list(names);
-                }
-                Err(err) => {
-                    error!(self.model.logger, "Unable to get list of projects: {err}.");
-                }
-            }
-        }
-    }
 }
 
 
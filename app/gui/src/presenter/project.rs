@@ -27,7 +27,6 @@ struct Model {
     graph_controller: controller::ExecutedGraph,
     ide_controller:   controller::Ide,
     view:             view::project::View,
-    status_bar:       view::status_bar::View,
     graph:            presenter::Graph,
     code:             presenter::Code,
     searcher:         RefCell<Option<presenter::Searcher>>,
@@ -40,7 +39,6 @@ impl Model {
         controller: controller::Project,
         init_result: controller::project::InitializationResult,
         view: view::project::View,
-        status_bar: view::status_bar::View,
     ) -> Self {
         let logger = Logger::new("presenter::Project");
         let graph_controller = init_result.main_graph;
@@ -60,7 +58,6 @@ impl Model {
             graph_controller,
             ide_controller,
             view,
-            status_bar,
             graph,
             code,
             searcher,
@@ -172,10 +169,9 @@ impl Project {
         controller: controller::Project,
         init_result: controller::project::InitializationResult,
         view: view::project::View,
-        status_bar: view::status_bar::View,
     ) -> Self {
         let network = frp::Network::new("presenter::Project");
-        let model = Model::new(ide_controller, controller, init_result, view, status_bar);
+        let model = Model::new(ide_controller, controller, init_result, view);
         Self { network, model: Rc::new(model) }.init()
     }
 
@@ -250,7 +246,7 @@ impl Project {
                     crate::BACKEND_DISCONNECTED_MESSAGE,
             };
             let message = view::status_bar::event::Label::from(message);
-            model.status_bar.add_event(message);
+            model.view.add_status_event(message);
             std::future::ready(())
         });
         self
@@ -281,9 +277,8 @@ impl Project {
         ide_controller: controller::Ide,
         controller: controller::Project,
         view: view::project::View,
-        status_bar: view::status_bar::View,
     ) -> FallibleResult<Self> {
         let init_result = controller.initialize().await?;
-        Ok(Self::new(ide_controller, controller, init_result, view, status_bar))
+        Ok(Self::new(ide_controller, controller, init_result, view))
     }
 }
@@ -98,6 +98,9 @@ const MACOS_TRAFFIC_LIGHTS_SIDE_OFFSET: f32 = 13.0;
 const MACOS_TRAFFIC_LIGHTS_VERTICAL_CENTER: f32 =
     -MACOS_TRAFFIC_LIGHTS_SIDE_OFFSET - MACOS_TRAFFIC_LIGHTS_CONTENT_HEIGHT / 2.0;
 const MAX_ZOOM: f32 = 1.0;
+/// The extension of Enso project archives. Dropped project archives are not imported as data
+/// files into the graph, as opening them is not the responsibility of the Graph Editor.
+pub const PROJECT_ARCHIVE_EXTENSION: &str = "enso-project";
 
 fn traffic_lights_gap_width() -> f32 {
     let is_macos = ARGS.platform.map(|p| p.is_macos()) == Some(true);
@@ -3531,7 +3534,9 @@ fn new_graph_editor(app: &Application) -> GraphEditor {
         files_with_positions <- files_received.map3(&cursor_pos_in_scene,&default_gap,
             |files,cursor_pos,default_gap| {
                 let single_offset = default_gap + node::HEIGHT;
-                files.iter().enumerate().map(|(index,file)| {
+                let is_data = |file:&&ensogl_drop_manager::File|
+                    file.extension() != Some(PROJECT_ARCHIVE_EXTENSION);
+                files.iter().filter(is_data).enumerate().map(|(index,file)| {
                     let offset = Vector2(0.0, single_offset * index as f32);
                     (file.clone_ref(),cursor_pos+offset)
                 }).collect_vec()
@@ -0,0 +1,198 @@
+//! The about overlay, displaying the IDE version, the commit it was built from, the supported
+//! engine version, and the licenses. The information is read from the build-time metadata, see
+//! [`BuildInfo`]. The overlay is displayed as a modal widget by the Root View, see
+//! [`crate::root`].
+
+use crate::prelude::*;
+use ensogl::display::shape::*;
+
+use crate::graph_editor::component::node::input::area::TEXT_SIZE;
+
+use enso_frp as frp;
+use ensogl::application::Application;
+use ensogl::display;
+use ensogl::display::style;
+use ensogl_hardcoded_theme as theme;
+use ensogl_text as text;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The width of the overlay.
+const WIDTH: f32 = 480.0;
+/// The height of the overlay.
+const HEIGHT: f32 = 240.0;
+/// Padding inside the overlay.
+const PADDING: f32 = 24.0;
+/// The title of the overlay.
+const TITLE: &str = "Enso IDE";
+/// The licenses of the IDE and the engine.
+const LICENSES: &str = "The IDE is licensed under the AGPL v3 license.\nThe engine is licensed \
+                        under the Apache 2.0 license.";
+/// The hint describing how to close the overlay.
+const HINT: &str = "Press Escape to close.";
+
+
+
+// =================
+// === BuildInfo ===
+// =================
+
+/// The metadata of the IDE build displayed by the overlay.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub struct BuildInfo {
+    pub version:                  Option<String>,
+    pub commit_hash:              Option<String>,
+    /// The engine version required by the IDE, see [`enso_config::engine_version_supported`]. It
+    /// is not the version of the engine running the opened project.
+    pub supported_engine_version: String,
+}
+
+impl BuildInfo {
+    /// The metadata of the running IDE, read at build time.
+    pub fn current() -> Self {
+        let version = enso_config::IDE_VERSION.map(ToOwned::to_owned);
+        let commit_hash = enso_config::COMMIT_HASH.map(ToOwned::to_owned);
+        let supported_engine_version = enso_config::engine_version_supported.to_owned();
+        Self { version, commit_hash, supported_engine_version }
+    }
+
+    /// The description displayed by the overlay.
+    fn description(&self) -> String {
+        let version = self.version.as_deref().unwrap_or("unknown");
+        let commit_hash = self.commit_hash.as_deref().unwrap_or("unknown");
+        let engine_version = &self.supported_engine_version;
+        format!(
+            "Version: {version}\nCommit: {commit_hash}\nSupported engine version: \
+             {engine_version}\n\n{LICENSES}\n\n{HINT}"
+        )
+    }
+}
+
+
+
+// ==================
+// === Background ===
+// ==================
+
+mod background {
+    use super::*;
+
+    ensogl::define_shape_system! {
+        (style:Style) {
+            let theme         = style::Path::from(theme::application::about::background);
+            let width         = Var::<Pixels>::from("input_size.x");
+            let height        = Var::<Pixels>::from("input_size.y");
+            let corner_radius = style.get_number(theme.sub("corner_radius"));
+            let shape         = Rect((&width,&height)).corners_radius(corner_radius.px());
+            shape.fill(style.get_color(&theme)).into()
+        }
+    }
+}
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+ensogl::define_endpoints! {
+    Input {
+        /// Display the build metadata.
+        set_build_info(BuildInfo),
+    }
+    Output {}
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+/// The about overlay model.
+#[derive(Clone, CloneRef, Debug)]
+pub struct Model {
+    display_object: display::object::Instance,
+    _background:    background::View,
+    _title:         text::Area,
+    details:        text::Area,
+}
+
+impl Model {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let logger = Logger::new("About");
+        let scene = &app.display.default_scene;
+        let display_object = display::object::Instance::new(&logger);
+        let background = background::View::new(&logger);
+        background.size.set(Vector2(WIDTH, HEIGHT));
+        scene.layers.modal.add_exclusive(&background);
+        display_object.add_child(&background);
+
+        let style = StyleWatch::new(&scene.style_sheet);
+        let label = |color_path, y: f32| {
+            let label = text::Area::new(app);
+            label.remove_from_scene_layer(&scene.layers.main);
+            label.add_to_scene_layer(&scene.layers.modal_text);
+            label.set_default_color(style.get_color(color_path));
+            label.set_position_xy(Vector2(-WIDTH / 2.0 + PADDING, y + TEXT_SIZE / 2.0));
+            display_object.add_child(&label);
+            label
+        };
+        let top = HEIGHT / 2.0 - PADDING;
+        let title = label(theme::application::about::title, top);
+        let details = label(theme::application::about::text, top - 2.0 * TEXT_SIZE);
+        title.set_content(TITLE);
+        Self { display_object, _background: background, _title: title, details }
+    }
+
+    fn set_build_info(&self, info: &BuildInfo) {
+        self.details.set_content(info.description());
+    }
+}
+
+
+
+// ============
+// === View ===
+// ============
+
+/// The about overlay. See the module docs to learn more.
+#[derive(Clone, CloneRef, Debug)]
+#[allow(missing_docs)]
+pub struct View {
+    model:   Model,
+    pub frp: Frp,
+}
+
+impl Deref for View {
+    type Target = Frp;
+    fn deref(&self) -> &Self::Target {
+        &self.frp
+    }
+}
+
+impl View {
+    /// Constructor. The overlay displays the metadata of the running IDE initially.
+    pub fn new(app: &Application) -> Self {
+        let model = Model::new(app);
+        let frp = Frp::new();
+        let network = &frp.network;
+        frp::extend! { network
+            eval frp.set_build_info ((info) model.set_build_info(info));
+        }
+        model.set_build_info(&BuildInfo::current());
+        Self { model, frp }
+    }
+}
+
+impl display::Object for View {
+    fn display_object(&self) -> &display::object::Instance {
+        &self.model.display_object
+    }
+}
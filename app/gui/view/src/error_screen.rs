@@ -0,0 +1,139 @@
+//! The view displayed when opening a project or initializing the backend connection fails. It
+//! displays the error details and a hint about the available actions: retrying the failed operation
+//! or, if it is available, going back to the Welcome Screen. The actions are handled by the Root
+//! View, see [`crate::root`].
+
+use crate::prelude::*;
+use ensogl::display::shape::StyleWatch;
+
+use crate::graph_editor::component::node::input::area::TEXT_SIZE;
+
+use enso_frp as frp;
+use ensogl::application::Application;
+use ensogl::display;
+use ensogl_hardcoded_theme as theme;
+use ensogl_text as text;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The title displayed above the error details.
+const TITLE: &str = "Something went wrong";
+/// The hint describing the available actions.
+const HINT: &str = "Press Enter to retry or Escape to go back to the Welcome Screen.";
+/// The hint describing the available actions when going back to the Welcome Screen is not
+/// available.
+const RETRY_ONLY_HINT: &str = "Press Enter to retry.";
+/// The vertical distance between the lines of the error screen.
+const LINE_SPACING: f32 = 2.0 * TEXT_SIZE;
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+ensogl::define_endpoints! {
+    Input {
+        /// Display the details of the error.
+        set_error(String),
+        /// Set whether going back to the Welcome Screen is available, changing the hint.
+        set_back_to_welcome_available(bool),
+    }
+    Output {}
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+/// The error screen model.
+#[derive(Clone, CloneRef, Debug)]
+pub struct Model {
+    display_object: display::object::Instance,
+    title:          text::Area,
+    details:        text::Area,
+    hint:           text::Area,
+}
+
+impl Model {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let logger = Logger::new("ErrorScreen");
+        let display_object = display::object::Instance::new(&logger);
+        let style = StyleWatch::new(&app.display.default_scene.style_sheet);
+        let line = |color_path, y: f32| {
+            let label = text::Area::new(app);
+            label.set_default_color(style.get_color(color_path));
+            label.set_position_y(y + TEXT_SIZE / 2.0);
+            display_object.add_child(&label);
+            label
+        };
+        let title = line(theme::application::error_screen::title, LINE_SPACING);
+        let details = line(theme::application::error_screen::text, 0.0);
+        let hint = line(theme::application::error_screen::hint, -LINE_SPACING);
+        title.set_content(TITLE);
+        hint.set_content(HINT);
+        Self { display_object, title, details, hint }
+    }
+
+    fn set_error(&self, message: &str) {
+        self.details.set_content(message);
+    }
+
+    fn set_back_to_welcome_available(&self, available: bool) {
+        self.hint.set_content(if available { HINT } else { RETRY_ONLY_HINT });
+    }
+}
+
+
+
+// ============
+// === View ===
+// ============
+
+/// The error screen. See the module docs to learn more.
+#[derive(Clone, CloneRef, Debug)]
+#[allow(missing_docs)]
+pub struct View {
+    model:   Model,
+    pub frp: Frp,
+}
+
+impl Deref for View {
+    type Target = Frp;
+    fn deref(&self) -> &Self::Target {
+        &self.frp
+    }
+}
+
+impl View {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let model = Model::new(app);
+        let frp = Frp::new();
+        let network = &frp.network;
+        frp::extend! { network
+            eval frp.set_error ((message) model.set_error(message));
+            eval frp.set_back_to_welcome_available ((available)
+                model.set_back_to_welcome_available(*available)
+            );
+            // Keep every line centered.
+            eval model.title.width ((width) model.title.set_position_x(-width / 2.0));
+            eval model.details.width ((width) model.details.set_position_x(-width / 2.0));
+            eval model.hint.width ((width) model.hint.set_position_x(-width / 2.0));
+        }
+        Self { model, frp }
+    }
+}
+
+impl display::Object for View {
+    fn display_object(&self) -> &display::object::Instance {
+        &self.model.display_object
+    }
+}
@@ -28,14 +28,22 @@
 #![recursion_limit = "1024"]
 
 #[allow(clippy::option_map_unit_fn)]
+pub mod about;
 pub mod code_editor;
 pub mod debug_mode_popup;
 pub mod documentation;
+pub mod error_screen;
+pub mod modal;
 pub mod open_dialog;
+pub mod opening_project;
 pub mod project;
+pub mod project_tabs;
 pub mod root;
+pub mod route;
 pub mod searcher;
+pub mod settings;
 pub mod status_bar;
+pub mod toast;
 pub mod window_control_buttons;
 
 pub use ide_view_graph_editor as graph_editor;
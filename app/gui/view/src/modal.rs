@@ -0,0 +1,232 @@
+//! The modal layer, displaying modal widgets (like confirmation dialogs) above all other views.
+//!
+//! While any modal widget is shown, the layer displays a dimming backdrop covering the whole scene,
+//! and the `modal_backdrop` and `modal` scene layers are marked as event-blocking, so the shapes of
+//! the underlying views do not receive mouse events. The Root View also disables the shortcuts of
+//! the underlying views, see [`crate::root`]. Modal widgets are stacked: showing a new one hides the
+//! currently displayed one, which is restored once the new one is hidden.
+//!
+//! The modal widgets should place their shapes on the `modal` and `modal_text` scene layers, so
+//! they are displayed above the backdrop.
+
+use crate::prelude::*;
+use ensogl::display::shape::*;
+
+use enso_frp as frp;
+use ensogl::application::Application;
+use ensogl::display;
+use ensogl::display::style;
+
+
+
+// ================
+// === Backdrop ===
+// ================
+
+mod backdrop {
+    use super::*;
+
+    ensogl::define_shape_system! {
+        (style:Style) {
+            let width  = Var::<Pixels>::from("input_size.x");
+            let height = Var::<Pixels>::from("input_size.y");
+            let theme  = style::Path::from(ensogl_hardcoded_theme::application::modal::backdrop);
+            let color  = style.get_color(&theme);
+            Rect((&width,&height)).fill(color).into()
+        }
+    }
+}
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+ensogl::define_endpoints! {
+    Input {
+        /// Hide the top modal widget, restoring the one shown before it.
+        hide_modal(),
+        /// Hide all modal widgets.
+        hide_all_modals(),
+    }
+    Output {
+        /// The number of shown modal widgets, including the ones hidden under the top one.
+        modals_count(usize),
+        /// Whether any modal widget is shown.
+        modal_shown(bool),
+    }
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+/// The modal layer model. Stores the stack of modal widgets.
+#[derive(Clone, CloneRef, Debug)]
+pub struct Model {
+    display_object: display::object::Instance,
+    scene:          display::Scene,
+    backdrop:       backdrop::View,
+    modals:         Rc<RefCell<Vec<display::object::Instance>>>,
+}
+
+impl Model {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let logger = Logger::new("ModalLayer");
+        let display_object = display::object::Instance::new(&logger);
+        let scene = app.display.default_scene.clone_ref();
+        let backdrop = backdrop::View::new(&logger);
+        scene.layers.modal_backdrop.add_exclusive(&backdrop);
+        let modals = default();
+        Self { display_object, scene, backdrop, modals }
+    }
+
+    /// Show the modal widget on top of the stack, hiding the currently displayed one.
+    pub fn push(&self, modal: &impl display::Object) {
+        let modal = modal.display_object().clone_ref();
+        let mut modals = self.modals.borrow_mut();
+        match modals.last() {
+            Some(top) => self.display_object.remove_child(top),
+            None => {
+                self.display_object.add_child(&self.backdrop);
+                self.set_blocks_events(true);
+            }
+        }
+        self.display_object.add_child(&modal);
+        modals.push(modal);
+    }
+
+    /// Hide the top modal widget, restoring the one shown before it.
+    pub fn pop(&self) {
+        let mut modals = self.modals.borrow_mut();
+        if let Some(top) = modals.pop() {
+            self.display_object.remove_child(&top);
+        }
+        match modals.last() {
+            Some(top) => self.display_object.add_child(top),
+            None => {
+                self.display_object.remove_child(&self.backdrop);
+                self.set_blocks_events(false);
+            }
+        }
+    }
+
+    /// Hide the modal widget, wherever it is in the stack. If it is the top one, the one shown
+    /// before it is restored. Returns `false` if the widget is not shown.
+    pub fn remove(&self, modal: &impl display::Object) -> bool {
+        let modal = modal.display_object();
+        let index = self.modals.borrow().iter().position(|shown| shown == modal);
+        match index {
+            Some(index) if index + 1 == self.count() => self.pop(),
+            Some(index) => drop(self.modals.borrow_mut().remove(index)),
+            None => {}
+        }
+        index.is_some()
+    }
+
+    /// Whether the modal widget is shown, including being hidden under the top one.
+    pub fn contains(&self, modal: &impl display::Object) -> bool {
+        self.modals.borrow().iter().any(|shown| shown == modal.display_object())
+    }
+
+    /// Hide all modal widgets.
+    pub fn clear(&self) {
+        for modal in self.modals.borrow_mut().drain(..) {
+            self.display_object.remove_child(&modal);
+        }
+        self.display_object.remove_child(&self.backdrop);
+        self.set_blocks_events(false);
+    }
+
+    /// Mark the modal scene layers as event-blocking while any modal widget is shown. The layers
+    /// contain the backdrop shape even if it is hidden, so they cannot be marked permanently.
+    fn set_blocks_events(&self, blocks_events: bool) {
+        self.scene.layers.modal_backdrop.set_blocks_events(blocks_events);
+        self.scene.layers.modal.set_blocks_events(blocks_events);
+    }
+
+    /// The number of shown modal widgets.
+    pub fn count(&self) -> usize {
+        self.modals.borrow().len()
+    }
+
+    /// Update the backdrop to cover the whole scene.
+    fn set_scene_size(&self, size: Vector2) {
+        self.backdrop.size.set(size);
+    }
+}
+
+
+
+// ============
+// === View ===
+// ============
+
+/// The modal layer. See the module docs to learn more.
+#[derive(Clone, CloneRef, Debug)]
+#[allow(missing_docs)]
+pub struct View {
+    model:   Model,
+    pub frp: Frp,
+}
+
+impl Deref for View {
+    type Target = Frp;
+    fn deref(&self) -> &Self::Target {
+        &self.frp
+    }
+}
+
+impl View {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let model = Model::new(app);
+        let frp = Frp::new();
+        let network = &frp.network;
+        let scene_shape = app.display.default_scene.shape().clone_ref();
+        frp::extend! { network
+            eval scene_shape ((shape) model.set_scene_size(Vector2(shape.width, shape.height)));
+            eval_ frp.hide_modal (model.pop());
+            eval_ frp.hide_all_modals (model.clear());
+            hidden <- any(&frp.hide_modal, &frp.hide_all_modals);
+            count <- hidden.map(f_!(model.count()));
+            frp.source.modals_count <+ count;
+            frp.source.modal_shown <+ count.map(|count| *count > 0);
+        }
+        let shape = scene_shape.value();
+        model.set_scene_size(Vector2(shape.width, shape.height));
+        Self { model, frp }
+    }
+
+    /// Show the modal widget on top of the currently shown ones. It stays displayed until hidden
+    /// with the `hide_modal` or `hide_all_modals` FRP inputs.
+    pub fn show(&self, modal: &impl display::Object) {
+        self.model.push(modal);
+        self.frp.source.modals_count.emit(self.model.count());
+        self.frp.source.modal_shown.emit(true);
+    }
+
+    /// Hide the modal widget, wherever it is in the stack. Does nothing if it is not shown.
+    pub fn hide(&self, modal: &impl display::Object) {
+        if self.model.remove(modal) {
+            let count = self.model.count();
+            self.frp.source.modals_count.emit(count);
+            self.frp.source.modal_shown.emit(count > 0);
+        }
+    }
+
+    /// Whether the modal widget is shown, including being hidden under the top one.
+    pub fn is_shown(&self, modal: &impl display::Object) -> bool {
+        self.model.contains(modal)
+    }
+}
+
+impl display::Object for View {
+    fn display_object(&self) -> &display::object::Instance {
+        &self.model.display_object
+    }
+}
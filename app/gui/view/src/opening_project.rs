@@ -0,0 +1,161 @@
+//! The view displayed while a project is being opened: after the user picks a project on the
+//! Welcome Screen, but before its Project View is ready. It displays a spinner and a message
+//! describing the current step of opening, like connecting to the backend or compiling the
+//! project.
+
+use crate::prelude::*;
+use ensogl::display::shape::*;
+
+use crate::graph_editor::component::node::input::area::TEXT_SIZE;
+
+use enso_frp as frp;
+use ensogl::application::Application;
+use ensogl::display;
+use ensogl::display::style;
+use ensogl_hardcoded_theme as theme;
+use ensogl_text as text;
+use std::f32::consts::PI;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The diameter of the spinner.
+const SPINNER_SIZE: f32 = 40.0;
+/// The width of the spinner's ring.
+const SPINNER_WIDTH: f32 = 4.0;
+/// The full turns the spinner makes per second.
+const SPINNER_SPEED: f32 = 1.0;
+/// The gap between the spinner and the step message.
+const GAP: f32 = 16.0;
+
+
+
+// ===============
+// === Spinner ===
+// ===============
+
+mod spinner {
+    use super::*;
+
+    ensogl::define_shape_system! {
+        (style:Style, rotation:f32) {
+            let theme        = style::Path::from(theme::application::opening_project::spinner);
+            let radius_outer = SPINNER_SIZE / 2.0;
+            let radius_inner = radius_outer - SPINNER_WIDTH;
+            let ring         = Circle(radius_outer.px()) - Circle(radius_inner.px());
+            let angle: Var<f32> = (PI * 1.5).into();
+            let mask         = Plane().cut_angle(angle).rotate(rotation);
+            let shape        = ring * mask;
+            shape.fill(style.get_color(&theme)).into()
+        }
+    }
+}
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+ensogl::define_endpoints! {
+    Input {
+        /// Display the message describing the current step of opening the project.
+        set_step(String),
+    }
+    Output {}
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+/// The model of the view displayed while opening a project.
+#[derive(Clone, CloneRef, Debug)]
+pub struct Model {
+    display_object: display::object::Instance,
+    spinner:        spinner::View,
+    label:          text::Area,
+}
+
+impl Model {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let logger = Logger::new("OpeningProject");
+        let display_object = display::object::Instance::new(&logger);
+        let spinner = spinner::View::new(&logger);
+        spinner.size.set(Vector2(SPINNER_SIZE, SPINNER_SIZE));
+        spinner.set_position_y((SPINNER_SIZE + GAP) / 2.0);
+        display_object.add_child(&spinner);
+
+        let label = text::Area::new(app);
+        let style = StyleWatch::new(&app.display.default_scene.style_sheet);
+        let text_color = style.get_color(theme::application::opening_project::text);
+        label.set_default_color(text_color);
+        label.set_position_y(-(SPINNER_SIZE + GAP) / 2.0 + TEXT_SIZE / 2.0);
+        display_object.add_child(&label);
+        Self { display_object, spinner, label }
+    }
+
+    fn set_step(&self, message: &str) {
+        self.label.set_content(message);
+    }
+
+    /// Update the label position, so the message stays centered.
+    fn set_label_width(&self, width: f32) {
+        self.label.set_position_x(-width / 2.0);
+    }
+
+    /// Rotate the spinner according to the frame time in milliseconds.
+    fn update_spinner(&self, time: f32) {
+        let rotation = -(time / 1000.0 * SPINNER_SPEED).fract() * 2.0 * PI;
+        self.spinner.rotation.set(rotation);
+    }
+}
+
+
+
+// ============
+// === View ===
+// ============
+
+/// The view displayed while opening a project. See the module docs to learn more.
+#[derive(Clone, CloneRef, Debug)]
+#[allow(missing_docs)]
+pub struct View {
+    model:   Model,
+    pub frp: Frp,
+}
+
+impl Deref for View {
+    type Target = Frp;
+    fn deref(&self) -> &Self::Target {
+        &self.frp
+    }
+}
+
+impl View {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let model = Model::new(app);
+        let frp = Frp::new();
+        let network = &frp.network;
+        let scene = &app.display.default_scene;
+        frp::extend! { network
+            eval frp.set_step ((message) model.set_step(message));
+            eval model.label.width ((width) model.set_label_width(*width));
+            eval scene.frp.frame_time ((time) model.update_spinner(*time));
+        }
+        Self { model, frp }
+    }
+}
+
+impl display::Object for View {
+    fn display_object(&self) -> &display::object::Instance {
+        &self.model.display_object
+    }
+}
@@ -30,6 +30,47 @@ use ide_view_graph_editor::NodeSource;
 
 
 
+// ==============
+// === Themes ===
+// ==============
+
+/// Register the builtin themes in the application.
+pub fn register_themes(app: &Application) {
+    ensogl_hardcoded_theme::builtin::dark::register(app);
+    ensogl_hardcoded_theme::builtin::light::register(app);
+}
+
+/// The theme selected by the startup arguments.
+pub fn default_theme() -> Theme {
+    match ARGS.theme.as_deref() {
+        Some("dark") => Theme::Dark,
+        _ => Theme::Light,
+    }
+}
+
+/// Enable the theme in the application. The builtin themes also set the class of the root HTML
+/// element, so the DOM-based views are styled accordingly. Other themes are enabled by their names,
+/// so they need to be registered in the application's theme manager first.
+pub fn enable_theme(app: &Application, theme: &Theme) {
+    match theme {
+        Theme::Light => {
+            ensogl_hardcoded_theme::builtin::light::enable(app);
+            set_html_style("light-theme");
+        }
+        Theme::Dark => {
+            ensogl_hardcoded_theme::builtin::dark::enable(app);
+            set_html_style("dark-theme");
+        }
+        Theme::Other(name) => app.themes.set_enabled(&[name]),
+    }
+}
+
+fn set_html_style(style: &'static str) {
+    web::document.with_element_by_id_or_warn("root", |root| root.set_class_name(style));
+}
+
+
+
 // ===========
 // === FRP ===
 // ===========
@@ -64,6 +105,10 @@ ensogl::define_endpoints! {
         close_open_dialog(),
         /// Simulates a style toggle press event.
         toggle_style(),
+        /// Set the style of IDE. See [`enable_theme`].
+        set_style(Theme),
+        /// Request opening the Settings View, which is displayed by the Root View.
+        show_settings(),
         /// Saves the currently opened module to file.
         save_module(),
         /// Undo the last user's action.
@@ -78,6 +123,12 @@ ensogl::define_endpoints! {
         enable_debug_mode(),
         // Disable Debug Mode of Graph Editor.
         disable_debug_mode(),
+        /// Enable or disable the compact mode, in which the window control buttons and the prompt
+        /// are hidden, leaving the whole screen to the graph. It is set by the Root View in its
+        /// fullscreen mode.
+        set_compact_mode(bool),
+        /// Report the event in the status bar, which is displayed by the Root View.
+        add_status_event(crate::status_bar::event::Label),
     }
 
     Output {
@@ -93,6 +144,9 @@ ensogl::define_endpoints! {
         fullscreen_visualization_shown (bool),
         drop_files_enabled             (bool),
         debug_mode                     (bool),
+        settings_requested             (),
+        compact_mode                   (bool),
+        status_event                   (crate::status_bar::event::Label),
     }
 }
 
@@ -197,24 +251,7 @@ impl Model {
 
     /// Sets style of IDE to the one defined by parameter `theme`.
     pub fn set_style(&self, theme: Theme) {
-        match theme {
-            Theme::Light => self.set_light_style(),
-            _ => self.set_dark_style(),
-        }
-    }
-
-    fn set_light_style(&self) {
-        ensogl_hardcoded_theme::builtin::light::enable(&self.app);
-        self.set_html_style("light-theme");
-    }
-
-    fn set_dark_style(&self) {
-        ensogl_hardcoded_theme::builtin::dark::enable(&self.app);
-        self.set_html_style("dark-theme");
-    }
-
-    fn set_html_style(&self, style: &'static str) {
-        web::document.with_element_by_id_or_warn("root", |root| root.set_class_name(style));
+        enable_theme(&self.app, &theme);
     }
 
     fn searcher_left_top_position_when_under_node_at(position: Vector2<f32>) -> Vector2<f32> {
@@ -285,6 +322,18 @@ impl Model {
         js::fullscreen();
     }
 
+    fn set_compact_mode(&self, compact: bool) {
+        let chrome = self.window_control_buttons.iter().map(|buttons| buttons.display_object());
+        let chrome = chrome.chain(iter::once(self.prompt_background.display_object()));
+        for element in chrome {
+            if compact {
+                self.display_object.remove_child(element);
+            } else {
+                self.display_object.add_child(element);
+            }
+        }
+    }
+
     fn show_open_dialog(&self) {
         self.display_object.add_child(&*self.open_dialog);
     }
@@ -355,18 +404,9 @@ impl Deref for View {
 impl View {
     /// Constructor.
     pub fn new(app: &Application) -> Self {
-        ensogl_hardcoded_theme::builtin::dark::register(app);
-        ensogl_hardcoded_theme::builtin::light::register(app);
-        let theme = match ARGS.theme.as_deref() {
-            Some("dark") => {
-                ensogl_hardcoded_theme::builtin::dark::enable(app);
-                Theme::Dark
-            }
-            _ => {
-                ensogl_hardcoded_theme::builtin::light::enable(app);
-                Theme::Light
-            }
-        };
+        register_themes(app);
+        let theme = default_theme();
+        enable_theme(app, &theme);
 
         display::style::javascript::expose_to_window(&app.themes);
 
@@ -383,7 +423,7 @@ impl View {
 
         // FIXME[WD]: Think how to refactor it, as it needs to be done before model, as we do not
         //   want shader recompilation. Model uses styles already.
-        model.set_style(theme);
+        model.set_style(theme.clone());
         // TODO[WD]: This should not be needed after the theme switching issue is implemented.
         //   See: https://github.com/enso-org/ide/issues/795
         app.themes.update();
@@ -552,6 +592,12 @@ impl View {
                 _            => Theme::Light,
             });
             frp.source.style     <+ style_press_on_off;
+            frp.source.style     <+ frp.set_style;
+
+
+            // === Settings ===
+
+            frp.source.settings_requested <+ frp.show_settings;
             eval frp.style ((style) model.set_style(style.clone()));
 
 
@@ -632,10 +678,22 @@ impl View {
 
             model.debug_mode_popup.enabled <+ frp.enable_debug_mode;
             model.debug_mode_popup.disabled <+ frp.disable_debug_mode;
+
+            // === Compact Mode ===
+
+            compact_mode <- frp.set_compact_mode.on_change();
+            eval compact_mode ((compact) model.set_compact_mode(*compact));
+            frp.source.compact_mode <+ compact_mode;
+
+
+            // === Status Bar ===
+
+            frp.source.status_event <+ frp.add_status_event;
         }
         init.emit(());
         std::mem::forget(prompt_visibility);
 
+        frp.source.style.emit(theme);
         Self { model, frp }
     }
 
@@ -707,7 +765,15 @@ impl application::View for View {
             (Press, "debug_mode", DEBUG_MODE_SHORTCUT, "disable_debug_mode"),
         ])
             .iter()
-            .map(|(a, b, c, d)| Self::self_shortcut_when(*a, *c, *d, *b))
+            .map(|(a, b, c, d)| {
+                let shortcut = Self::self_shortcut_when(*a, *c, *d, *b);
+                // Disabling the prompt should not prevent other actions bound to the same keys.
+                if *d == "disable_prompt" {
+                    shortcut.as_parallel()
+                } else {
+                    shortcut
+                }
+            })
             .collect()
     }
 }
@@ -0,0 +1,352 @@
+//! The tab bar of the projects opened in the Root View, see [`crate::root`].
+//!
+//! Every opened project has a tab displaying its name, laid out in the order of opening below the
+//! status bar. The tab of the active project is highlighted. Clicking a tab requests activating its
+//! project, and clicking the close button of a tab requests closing it. The requests are handled by
+//! the Root View.
+
+use crate::prelude::*;
+use ensogl::display::shape::*;
+
+use crate::graph_editor::component::node::input::area::TEXT_SIZE;
+use crate::root::ProjectId;
+use crate::window_control_buttons::close;
+
+use enso_frp as frp;
+use ensogl::application::Application;
+use ensogl::display;
+use ensogl::display::style;
+use ensogl_hardcoded_theme as theme;
+use ensogl_text as text;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The width of a tab.
+const TAB_WIDTH: f32 = 160.0;
+/// The height of a tab.
+const HEIGHT: f32 = 28.0;
+/// Padding inside a tab.
+const PADDING: f32 = 10.0;
+/// The size of the close button of a tab.
+const CLOSE_BUTTON_SIZE: f32 = 12.0;
+/// The gap between the tabs.
+const GAP: f32 = 4.0;
+/// The distance between the top edge of the screen and the tabs, leaving space for the status bar.
+const TOP_OFFSET: f32 = 48.0;
+
+
+
+// ==================
+// === Background ===
+// ==================
+
+mod background {
+    use super::*;
+
+    ensogl::define_shape_system! {
+        (style:Style, color_rgba:Vector4<f32>) {
+            let theme         = style::Path::from(theme::application::project_tabs::background);
+            let width         = Var::<Pixels>::from("input_size.x");
+            let height        = Var::<Pixels>::from("input_size.y");
+            let corner_radius = style.get_number(theme.sub("corner_radius"));
+            let shape         = Rect((&width,&height)).corners_radius(corner_radius.px());
+            shape.fill(color_rgba).into()
+        }
+    }
+}
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+ensogl::define_endpoints! {
+    Input {
+        /// Display the tabs of the projects, in the order of opening.
+        set_projects(Vec<ProjectId>),
+        /// Highlight the tab of the active project.
+        set_active_project(Option<ProjectId>),
+        /// Set the name displayed in the tab of the project.
+        set_project_name(ProjectId, String),
+    }
+    Output {
+        /// Emitted when the user clicks the tab of the project.
+        activate_project(ProjectId),
+        /// Emitted when the user clicks the close button of the tab of the project.
+        close_project(ProjectId),
+    }
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+/// A single displayed tab.
+#[derive(Debug)]
+struct Tab {
+    id:             ProjectId,
+    display_object: display::object::Instance,
+    background:     background::View,
+    label:          text::Area,
+    // The close button is kept to be dropped together with the tab.
+    _close_button:  close::View,
+    /// The network passing the clicks on the tab to the tab bar.
+    _network:       frp::Network,
+}
+
+/// The project tabs model.
+#[derive(Clone, CloneRef, Debug)]
+pub struct Model {
+    app:            Application,
+    logger:         Logger,
+    display_object: display::object::Instance,
+    _network:       frp::Network,
+    style:          StyleWatch,
+    /// Emitted when any tab is clicked.
+    tab_clicked:    frp::Source<ProjectId>,
+    /// Emitted when the close button of any tab is clicked.
+    close_clicked:  frp::Source<ProjectId>,
+    tabs:           Rc<RefCell<Vec<Tab>>>,
+    names:          Rc<RefCell<HashMap<ProjectId, String>>>,
+    active_project: Rc<Cell<Option<ProjectId>>>,
+    scene_size:     Rc<Cell<Vector2>>,
+}
+
+impl Model {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let app = app.clone_ref();
+        let logger = Logger::new("ProjectTabs");
+        let display_object = display::object::Instance::new(&logger);
+        let network = frp::Network::new("ProjectTabsModel");
+        frp::extend! { network
+            tab_clicked <- source::<ProjectId>();
+            close_clicked <- source::<ProjectId>();
+        }
+        ensogl::shapes_order_dependencies! {
+            app.display.default_scene => {
+                background -> close::shape;
+            }
+        };
+        let style = StyleWatch::new(&app.display.default_scene.style_sheet);
+        let tabs = default();
+        let names = default();
+        let active_project = default();
+        let scene_size = default();
+        Self {
+            app,
+            logger,
+            display_object,
+            _network: network,
+            style,
+            tab_clicked,
+            close_clicked,
+            tabs,
+            names,
+            active_project,
+            scene_size,
+        }
+    }
+
+    /// Display the tabs of the projects, keeping the already displayed tabs of the projects which
+    /// are still opened.
+    pub fn set_projects(&self, ids: &[ProjectId]) {
+        let mut old_tabs = mem::take(&mut *self.tabs.borrow_mut());
+        let tabs = ids.iter().map(|id| match old_tabs.iter().position(|tab| tab.id == *id) {
+            Some(index) => old_tabs.remove(index),
+            None => self.new_tab(*id),
+        });
+        let tabs = tabs.collect_vec();
+        *self.tabs.borrow_mut() = tabs;
+        self.update_labels();
+        self.update_colors();
+        self.update_layout();
+    }
+
+    /// Highlight the tab of the active project.
+    pub fn set_active_project(&self, id: Option<ProjectId>) {
+        self.active_project.set(id);
+        self.update_colors();
+    }
+
+    /// Set the name displayed in the tab of the project. Projects without a name are labeled by
+    /// their position in the tab bar.
+    pub fn set_project_name(&self, id: ProjectId, name: &str) {
+        self.names.borrow_mut().insert(id, name.to_owned());
+        self.update_labels();
+    }
+
+    /// The identifiers of the projects whose tabs are displayed.
+    pub fn projects(&self) -> Vec<ProjectId> {
+        self.tabs.borrow().iter().map(|tab| tab.id).collect()
+    }
+
+    fn new_tab(&self, id: ProjectId) -> Tab {
+        let scene = &self.app.display.default_scene;
+        let display_object = display::object::Instance::new(&self.logger);
+        let background = background::View::new(&self.logger);
+        background.size.set(Vector2(TAB_WIDTH, HEIGHT));
+        scene.layers.panel.add_exclusive(&background);
+        display_object.add_child(&background);
+
+        let label = text::Area::new(&self.app);
+        label.remove_from_scene_layer(&scene.layers.main);
+        label.add_to_scene_layer(&scene.layers.panel_text);
+        label.set_position_x(-TAB_WIDTH / 2.0 + PADDING);
+        label.set_position_y(TEXT_SIZE / 2.0);
+        display_object.add_child(&label);
+
+        let close_button = close::View::new(&self.app);
+        close_button.set_size(Vector2(CLOSE_BUTTON_SIZE, CLOSE_BUTTON_SIZE));
+        close_button.set_position_x(TAB_WIDTH / 2.0 - PADDING - CLOSE_BUTTON_SIZE / 2.0);
+        scene.layers.panel.add_exclusive(&close_button);
+        display_object.add_child(&close_button);
+        self.display_object.add_child(&display_object);
+
+        let tab_clicked = &self.tab_clicked;
+        let close_clicked = &self.close_clicked;
+        frp::new_network! { network
+            eval_ background.events.mouse_down (tab_clicked.emit(id));
+            eval_ close_button.clicked (close_clicked.emit(id));
+        }
+        let _close_button = close_button;
+        Tab { id, display_object, background, label, _close_button, _network: network }
+    }
+
+    fn update_labels(&self) {
+        let names = self.names.borrow();
+        let text_color = self.style.get_color(theme::application::project_tabs::text);
+        for (index, tab) in self.tabs.borrow().iter().enumerate() {
+            let name = names.get(&tab.id).cloned();
+            let name = name.unwrap_or_else(|| format!("Project {}", index + 1));
+            tab.label.set_content(name);
+            tab.label.set_color_all(text_color);
+        }
+    }
+
+    fn update_colors(&self) {
+        let active_project = self.active_project.get();
+        let active_color = self.style.get_color(theme::application::project_tabs::active);
+        let color = self.style.get_color(theme::application::project_tabs::background);
+        for tab in self.tabs.borrow().iter() {
+            let color = if Some(tab.id) == active_project { active_color } else { color };
+            tab.background.color_rgba.set(color.into());
+        }
+    }
+
+    fn set_scene_size(&self, size: Vector2) {
+        self.scene_size.set(size);
+        self.update_layout();
+    }
+
+    fn update_layout(&self) {
+        let tabs = self.tabs.borrow();
+        let width = tabs.len() as f32 * (TAB_WIDTH + GAP) - GAP;
+        let left = -width / 2.0 + TAB_WIDTH / 2.0;
+        let y = self.scene_size.get().y / 2.0 - TOP_OFFSET - HEIGHT / 2.0;
+        for (index, tab) in tabs.iter().enumerate() {
+            let x = left + index as f32 * (TAB_WIDTH + GAP);
+            tab.display_object.set_position_xy(Vector2(x.round(), y.round()));
+        }
+    }
+}
+
+
+
+// ============
+// === View ===
+// ============
+
+/// The tab bar of the opened projects. See the module docs to learn more.
+#[derive(Clone, CloneRef, Debug)]
+#[allow(missing_docs)]
+pub struct View {
+    model:   Model,
+    pub frp: Frp,
+}
+
+impl Deref for View {
+    type Target = Frp;
+    fn deref(&self) -> &Self::Target {
+        &self.frp
+    }
+}
+
+impl View {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let model = Model::new(app);
+        let frp = Frp::new();
+        let network = &frp.network;
+        let scene = &app.display.default_scene;
+        let scene_shape = scene.shape().clone_ref();
+        frp::extend! { network
+            eval scene_shape ((shape) model.set_scene_size(Vector2(shape.width, shape.height)));
+            eval frp.set_projects ((ids) model.set_projects(ids));
+            eval frp.set_active_project ((id) model.set_active_project(*id));
+            eval frp.set_project_name (((id, name)) model.set_project_name(*id, name));
+            frp.source.activate_project <+ model.tab_clicked;
+            frp.source.close_project <+ model.close_clicked;
+        }
+        let shape = scene_shape.value();
+        model.set_scene_size(Vector2(shape.width, shape.height));
+        Self { model, frp }
+    }
+
+    /// The identifiers of the projects whose tabs are displayed.
+    pub fn projects(&self) -> Vec<ProjectId> {
+        self.model.projects()
+    }
+}
+
+impl display::Object for View {
+    fn display_object(&self) -> &display::object::Instance {
+        &self.model.display_object
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clicking_tabs() {
+        let app = Application::new("root");
+        let tabs = View::new(&app);
+        let first = ProjectId::new_v4();
+        let second = ProjectId::new_v4();
+        let network = &tabs.network;
+        frp::extend! { network
+            activated <- tabs.activate_project.sampler();
+            // Closing the project removes its tab, like the Root View does.
+            remaining <- tabs.close_project.map(f!([tabs] (id) {
+                tabs.projects().into_iter().filter(|t| t != id).collect_vec()
+            }));
+            tabs.set_projects <+ remaining;
+        }
+        tabs.set_projects(vec![first, second]);
+        tabs.set_active_project(Some(first));
+        assert_eq!(tabs.projects(), vec![first, second]);
+
+        let second_tab = tabs.model.tabs.borrow()[1].background.clone_ref();
+        second_tab.events.mouse_down.emit(frp::io::mouse::PrimaryButton);
+        assert_eq!(activated.value(), second);
+
+        let first_close_button = tabs.model.tabs.borrow()[0]._close_button.clone_ref();
+        first_close_button.click();
+        assert_eq!(tabs.projects(), vec![second]);
+    }
+}
@@ -1,16 +1,120 @@
 //! Root View of the IDE.
 //!
-//! The main entry point to the IDE which can display either Welcome Screen or Project View.
-//! Initially displays Welcome Screen. Lazily initializes Project View on `switch_view_to_project`
-//! call.
+//! The main entry point to the IDE, displaying one of the views described by [`State`]: the
+//! Welcome Screen, the progress of opening a project, the Project View of the active project, the
+//! error screen, or the Settings View. The displayed views are switched with an animated slide.
+//! Above them, the Root View displays the chrome shared by all views: the status bar, the modal
+//! widgets (see [`crate::modal`]), and the toasts (see [`crate::toast`]). See [`View`] to learn
+//! more.
 
 use ensogl::prelude::*;
 
+use crate::route::ProjectPanel;
+use crate::route::Route;
+
 use enso_frp as frp;
 use ensogl::application;
 use ensogl::application::Application;
 use ensogl::display;
+use ensogl::Animation;
+use ensogl_component::drop_manager;
+use ensogl_hardcoded_theme::Theme;
+use serde::Deserialize;
+use serde::Serialize;
 use std::rc::Rc;
+use uuid::Uuid;
+
+
+
+// =================
+// === ProjectId ===
+// =================
+
+/// Identifier of a project opened in the Root View.
+pub type ProjectId = Uuid;
+
+
+
+// ======================
+// === PersistedState ===
+// ======================
+
+/// The state of the Root View persisted between the IDE runs by the [`StateStorage`], so
+/// relaunching the IDE returns the user to where they left off.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PersistedState {
+    /// The name of the last opened project, see the `set_last_project` FRP input.
+    pub last_project:      Option<String>,
+    /// Whether the Project View was displayed, so the last project should be reopened.
+    pub project_displayed: bool,
+    /// Whether the code editor of the displayed Project View was shown.
+    pub code_editor_shown: bool,
+    /// The name of the IDE theme.
+    pub theme:             Option<String>,
+}
+
+impl PersistedState {
+    /// Parse the state from a JSON document.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Serialize the state to a JSON document.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Storage of the persisted Root View state document. The document is loaded once the storage is
+/// provided to the Root View, see [`View::set_state_storage`], and saved on every change of the
+/// displayed view, the theme, or the last opened project.
+pub trait StateStorage: Debug {
+    /// Load the document. Returns `None` if there is no document saved yet.
+    fn load(&self) -> Option<String>;
+
+    /// Save the document.
+    fn save(&self, document: &str);
+}
+
+/// Whether the dropped file is an Enso project archive.
+fn is_project_archive(file: &drop_manager::File) -> bool {
+    file.extension() == Some(crate::graph_editor::PROJECT_ARCHIVE_EXTENSION)
+}
+
+/// Disable the default shortcuts of the view while any modal widget is shown, so they do not act on
+/// the view covered by the modal.
+fn block_shortcuts_while_modal_shown<V: application::View>(
+    view: &V,
+    modal_layer: &crate::modal::View,
+) {
+    let network = view.network();
+    let modal_shown = modal_layer.modal_shown.clone_ref();
+    for shortcut in V::default_shortcuts() {
+        let enabled = view.shortcut_enabled(shortcut);
+        frp::extend! { network
+            enabled <+ modal_shown.map(|shown| !shown);
+        }
+        enabled.emit(!modal_shown.value());
+    }
+}
+
+/// The name under which the theme is persisted.
+fn theme_name(theme: &Theme) -> String {
+    match theme {
+        Theme::Light => "light".into(),
+        Theme::Dark => "dark".into(),
+        Theme::Other(name) => name.to_string(),
+    }
+}
+
+/// The theme persisted under the name. See [`theme_name`].
+fn theme_from_name(name: &str) -> Theme {
+    match name {
+        "light" => Theme::Light,
+        "dark" => Theme::Dark,
+        name => Theme::Other(name.into()),
+    }
+}
 
 
 
@@ -18,26 +122,109 @@ use std::rc::Rc;
 // === Model ===
 // =============
 
-/// Two possible states of Root View.
-#[derive(Clone, Debug, PartialEq)]
-enum State {
+/// Possible states of Root View, describing the displayed view. The state is reported by the
+/// `state_changed` FRP output.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum State {
     /// Displaying Welcome Screen.
     WelcomeScreen,
+    /// Displaying the progress of opening a project.
+    OpeningProject,
     /// Displaying Project View with some opened project.
     OpenedProject,
+    /// Displaying the error screen after a failed attempt of opening a project.
+    Error,
+    /// Displaying the Settings View.
+    Settings,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self::WelcomeScreen
+    }
+}
+
+/// The direction of sliding the views during a transition. Views slide forward when opening a
+/// project, and backward when going back to the Welcome Screen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Direction {
+    Forward,
+    Backward,
+}
+
+impl Direction {
+    /// The horizontal offset from which the incoming view slides in, as a fraction of the scene
+    /// width.
+    fn incoming_offset(self) -> f32 {
+        match self {
+            Self::Forward => 1.0,
+            Self::Backward => -1.0,
+        }
+    }
+}
+
+/// An ongoing transition between two displayed views.
+#[derive(Debug)]
+struct Transition {
+    outgoing:  display::object::Instance,
+    incoming:  display::object::Instance,
+    direction: Direction,
+}
+
+/// A Project View of an opened project.
+#[derive(Debug)]
+struct OpenedProject {
+    id:       ProjectId,
+    view:     crate::project::View,
+    /// The network passing the style changes, settings requests, panel changes, and status events
+    /// of the Project View to the Root View.
+    _network: frp::Network,
 }
 
 /// Root View model. Stores both Welcome Screen and Project views and handles their
 /// visibility.
 #[derive(Clone, CloneRef, Debug)]
 pub struct Model {
-    app:            Application,
-    logger:         Logger,
+    app: Application,
+    logger: Logger,
     display_object: display::object::Instance,
-    state:          Rc<CloneCell<State>>,
-    status_bar:     crate::status_bar::View,
-    welcome_view:   crate::welcome_screen::View,
-    project_view:   Rc<CloneCell<Option<crate::project::View>>>,
+    network: frp::Network,
+    state: Rc<CloneCell<State>>,
+    theme: Rc<RefCell<Theme>>,
+    /// Emitted when the style of any Project View changes.
+    project_style: frp::Source<Theme>,
+    /// Emitted when any Project View requests opening the Settings View.
+    project_settings_requested: frp::Source,
+    /// Emitted when any Project View shows or hides its code editor.
+    project_panel_changed: frp::Source,
+    /// Emitted when a Project View is constructed.
+    project_view_created: frp::Source<ProjectId>,
+    /// Emitted when a Project View is torn down.
+    project_view_destroyed: frp::Source<ProjectId>,
+    status_bar: crate::status_bar::View,
+    welcome_view: crate::welcome_screen::View,
+    opening_view: crate::opening_project::View,
+    error_view: crate::error_screen::View,
+    settings_view: crate::settings::View,
+    /// The state to restore when the Settings View is closed.
+    state_before_settings: Rc<CloneCell<State>>,
+    modal_layer: crate::modal::View,
+    about_view: crate::about::View,
+    toasts: crate::toast::View,
+    project_tabs: crate::project_tabs::View,
+    /// Opened projects in the order of opening.
+    projects: Rc<RefCell<Vec<OpenedProject>>>,
+    active_project: Rc<Cell<Option<ProjectId>>>,
+    displayed_view: Rc<RefCell<display::object::Instance>>,
+    transition: Rc<RefCell<Option<Transition>>>,
+    /// The number of started transitions. It is used as the target of the transition animation,
+    /// so every transition animates the value by one, see [`Model::update_transition`].
+    transitions: Rc<Cell<usize>>,
+    /// The storage to which the state of the Root View is saved, see [`View::set_state_storage`].
+    storage: Rc<RefCell<Option<Box<dyn StateStorage>>>>,
+    last_project: Rc<RefCell<Option<String>>>,
+    drop_manager: drop_manager::Manager,
+    fullscreen_mode: Rc<Cell<bool>>,
 }
 
 impl Model {
@@ -46,44 +233,479 @@ impl Model {
         let app = app.clone_ref();
         let logger = Logger::new("RootView");
         let display_object = display::object::Instance::new(&logger);
+        let network = frp::Network::new("RootViewModel");
+        frp::extend! { network
+            project_style <- source::<Theme>();
+            project_settings_requested <- source_();
+            project_panel_changed <- source_();
+            project_view_created <- source::<ProjectId>();
+            project_view_destroyed <- source::<ProjectId>();
+        }
         let state = Rc::new(CloneCell::new(State::WelcomeScreen));
+        crate::project::register_themes(&app);
+        let theme = crate::project::default_theme();
+        crate::project::enable_theme(&app, &theme);
+        let theme = Rc::new(RefCell::new(theme));
         let status_bar = crate::status_bar::View::new(&app);
         display_object.add_child(&status_bar);
         let welcome_view = app.new_view::<crate::welcome_screen::View>();
-        let project_view = Rc::new(CloneCell::new(None));
+        let projects = default();
+        let active_project = default();
         display_object.add_child(&welcome_view);
+        let opening_view = crate::opening_project::View::new(&app);
+        let error_view = crate::error_screen::View::new(&app);
+        let settings_view = crate::settings::View::new(&app);
+        let state_before_settings = Rc::new(CloneCell::new(State::WelcomeScreen));
+        let modal_layer = crate::modal::View::new(&app);
+        display_object.add_child(&modal_layer);
+        block_shortcuts_while_modal_shown(&welcome_view, &modal_layer);
+        let about_view = crate::about::View::new(&app);
+        let toasts = crate::toast::View::new(&app);
+        display_object.add_child(&toasts);
+        let project_tabs = crate::project_tabs::View::new(&app);
+        let displayed_view = Rc::new(RefCell::new(welcome_view.display_object().clone_ref()));
+        let transition = default();
+        let transitions = default();
+        let storage = default();
+        let last_project = default();
+        let drop_manager = drop_manager::Manager::new(&app.display.default_scene.dom.root);
+        let fullscreen_mode = default();
 
-        Self { app, logger, display_object, status_bar, welcome_view, project_view, state }
+        Self {
+            app,
+            logger,
+            display_object,
+            network,
+            theme,
+            project_style,
+            project_settings_requested,
+            project_panel_changed,
+            project_view_created,
+            project_view_destroyed,
+            status_bar,
+            welcome_view,
+            opening_view,
+            error_view,
+            settings_view,
+            state_before_settings,
+            modal_layer,
+            about_view,
+            toasts,
+            project_tabs,
+            projects,
+            active_project,
+            displayed_view,
+            transition,
+            transitions,
+            state,
+            storage,
+            last_project,
+            drop_manager,
+            fullscreen_mode,
+        }
     }
 
     /// Switch displayed view from Project View to Welcome Screen. Project View will not be
     /// deallocated.
     pub fn switch_view_to_welcome_screen(&self) {
         self.state.set(State::WelcomeScreen);
-        if let Some(project_view) = self.project_view.get() {
-            self.display_object.remove_child(&project_view);
+        self.show(&self.welcome_view, Direction::Backward);
+    }
+
+    /// Switch displayed view to the progress of opening a project. The Project View is displayed
+    /// once `switch_view_to_project` is called.
+    pub fn switch_view_to_opening_project(&self) {
+        self.state.set(State::OpeningProject);
+        self.show(&self.opening_view, Direction::Forward);
+    }
+
+    /// Switch displayed view to the error screen, displaying the error message.
+    pub fn show_error(&self, message: &str) {
+        self.state.set(State::Error);
+        self.error_view.set_error(message.to_owned());
+        self.show(&self.error_view, Direction::Forward);
+    }
+
+    /// Switch displayed view to the Settings View. Does nothing if it is already displayed.
+    pub fn switch_view_to_settings(&self) {
+        let state = self.state.get();
+        if state != State::Settings {
+            self.state_before_settings.set(state);
+            self.state.set(State::Settings);
+            self.settings_view.refresh_keybindings();
+            self.show(&self.settings_view, Direction::Forward);
+        }
+    }
+
+    /// Open the Settings View, or close it if it is already displayed.
+    pub fn toggle_settings(&self) {
+        if self.state.get() == State::Settings {
+            self.close_settings()
+        } else {
+            self.switch_view_to_settings()
         }
-        self.display_object.add_child(&self.welcome_view);
     }
 
-    /// Switch displayed view from Welcome Screen to Project View. Will initialize Project View if
-    /// it wasn't initialized before.
+    /// Close the Settings View, switching back to the view displayed before it was opened. Does
+    /// nothing if the Settings View is not displayed.
+    pub fn close_settings(&self) {
+        if self.state.get() == State::Settings {
+            let state = self.state_before_settings.get();
+            let view = match state {
+                State::WelcomeScreen | State::Settings => None,
+                State::OpeningProject => Some(self.opening_view.display_object().clone_ref()),
+                State::OpenedProject =>
+                    self.active_project_view().map(|view| view.display_object().clone_ref()),
+                State::Error => Some(self.error_view.display_object().clone_ref()),
+            };
+            match view {
+                Some(view) => {
+                    self.state.set(state);
+                    self.show(&view, Direction::Backward);
+                }
+                None => self.switch_view_to_welcome_screen(),
+            }
+        }
+    }
+
+    /// Switch displayed view from Welcome Screen to Project View of the active project. Will open
+    /// a new project if there is no active one.
     pub fn switch_view_to_project(&self) {
         self.state.set(State::OpenedProject);
-        self.display_object.remove_child(&self.welcome_view);
-        self.display_object.add_child(&self.get_or_init_project_view());
+        self.show(&self.get_or_init_project_view(), Direction::Forward);
     }
 
-    /// Perform lazy initialization of the underlaying Project View.
+    /// Perform lazy initialization of the underlaying Project View. Returns the view of the active
+    /// project, opening a new project if there is no active one.
     pub fn get_or_init_project_view(&self) -> crate::project::View {
-        self.init_project_view();
-        self.project_view.get().expect("Project view initialization failed.")
+        if self.active_project.get().is_none() {
+            self.add_project(ProjectId::new_v4());
+        }
+        self.active_project_view().expect("Project view initialization failed.")
+    }
+
+    /// Open the project in a new Project View and display it. If the project is already opened,
+    /// it is only activated.
+    pub fn open_project(&self, id: ProjectId) {
+        if self.project_view(id).is_none() {
+            self.add_project(id);
+        }
+        self.activate_project(id);
+    }
+
+    /// Display the Project View of the already opened project.
+    pub fn activate_project(&self, id: ProjectId) {
+        match self.project_view(id) {
+            None => warning!(self.logger, "Cannot activate project {id}, as it is not opened."),
+            Some(view) => {
+                self.active_project.set(Some(id));
+                self.state.set(State::OpenedProject);
+                self.show(&view, Direction::Forward);
+            }
+        }
+    }
+
+    /// Close the project, destroying its Project View. If the project was the active one, the
+    /// previously opened project is activated, or the Welcome Screen is displayed if there are no
+    /// projects left.
+    pub fn close_project(&self, id: ProjectId) {
+        let mut projects = self.projects.borrow_mut();
+        match projects.iter().position(|project| project.id == id) {
+            None => warning!(self.logger, "Cannot close project {id}, as it is not opened."),
+            Some(index) => {
+                self.finish_transition();
+                let closed = projects.remove(index);
+                self.display_object.remove_child(&closed.view);
+                let neighbour = projects.get(index.saturating_sub(1)).map(|project| project.id);
+                drop(projects);
+                self.project_view_destroyed.emit(id);
+                if self.active_project.get() == Some(id) {
+                    self.active_project.set(None);
+                    match neighbour {
+                        Some(neighbour) => self.activate_project(neighbour),
+                        None => self.switch_view_to_welcome_screen(),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Activate the project being `offset` positions after the active one in the order of opening,
+    /// wrapping around the list ends. If no project is active, the offset is counted from the
+    /// position before the first project. Does nothing if there are no opened projects.
+    pub fn activate_project_at_offset(&self, offset: isize) {
+        let projects = self.opened_projects();
+        let active = self.active_project.get();
+        let active_index = projects.iter().position(|id| Some(*id) == active);
+        let count = projects.len() as isize;
+        if count > 0 {
+            let base = active_index.map_or(-1, |index| index as isize);
+            let index = (base + offset).rem_euclid(count) as usize;
+            self.activate_project(projects[index]);
+        }
+    }
+
+    /// Identifiers of all opened projects in the order of opening.
+    pub fn opened_projects(&self) -> Vec<ProjectId> {
+        self.projects.borrow().iter().map(|project| project.id).collect()
+    }
+
+    /// The identifier of the active project.
+    pub fn active_project(&self) -> Option<ProjectId> {
+        self.active_project.get()
+    }
+
+    /// The Project View of the opened project.
+    pub fn project_view(&self, id: ProjectId) -> Option<crate::project::View> {
+        let projects = self.projects.borrow();
+        let project = projects.iter().find(|project| project.id == id);
+        project.map(|project| project.view.clone_ref())
+    }
+
+    fn active_project_view(&self) -> Option<crate::project::View> {
+        self.active_project.get().and_then(|id| self.project_view(id))
+    }
+
+    /// Replace the Project View of the active project with a freshly constructed one, tearing the
+    /// old one down. The new view is identified by a new [`ProjectId`] and takes the place of the
+    /// old one, both in the order of opening and on the screen. If there is no active project, a
+    /// new one is opened. Returns the new view.
+    pub fn replace_active_project(&self) -> crate::project::View {
+        let active = self.active_project.get();
+        let index = active.and_then(|id| self.projects.borrow().iter().position(|p| p.id == id));
+        match index {
+            None => {
+                self.active_project.set(None);
+                self.get_or_init_project_view()
+            }
+            Some(index) => {
+                self.finish_transition();
+                let id = ProjectId::new_v4();
+                let project = self.new_opened_project(id);
+                let view = project.view.clone_ref();
+                let old = mem::replace(&mut self.projects.borrow_mut()[index], project);
+                let displayed = self.displayed_view.borrow().clone_ref();
+                self.display_object.remove_child(&old.view);
+                if &displayed == old.view.display_object() {
+                    self.display_object.add_child(&view);
+                    *self.displayed_view.borrow_mut() = view.display_object().clone_ref();
+                }
+                self.active_project.set(Some(id));
+                self.project_view_destroyed.emit(old.id);
+                self.project_view_created.emit(id);
+                view
+            }
+        }
+    }
+
+    fn add_project(&self, id: ProjectId) {
+        let project = self.new_opened_project(id);
+        self.projects.borrow_mut().push(project);
+        if self.active_project.get().is_none() {
+            self.active_project.set(Some(id));
+        }
+        self.project_view_created.emit(id);
+    }
+
+    fn new_opened_project(&self, id: ProjectId) -> OpenedProject {
+        let view = self.app.new_view::<crate::project::View>();
+        block_shortcuts_while_modal_shown(&view, &self.modal_layer);
+        block_shortcuts_while_modal_shown(view.graph(), &self.modal_layer);
+        block_shortcuts_while_modal_shown(view.searcher(), &self.modal_layer);
+        block_shortcuts_while_modal_shown(view.code_editor(), &self.modal_layer);
+        view.set_style(self.theme());
+        view.set_compact_mode(self.fullscreen_mode.get());
+        let project_style = &self.project_style;
+        let project_settings_requested = &self.project_settings_requested;
+        let project_panel_changed = &self.project_panel_changed;
+        let code_editor = view.code_editor();
+        let status_bar = &self.status_bar;
+        frp::new_network! { network
+            eval view.style ((style) project_style.emit(style));
+            eval_ view.settings_requested (project_settings_requested.emit(()));
+            eval_ code_editor.is_visible (project_panel_changed.emit(()));
+            status_bar.add_event <+ view.status_event;
+        }
+        OpenedProject { id, view, _network: network }
     }
 
-    fn init_project_view(&self) {
-        if self.project_view.get().is_none() {
-            let view = self.app.new_view::<crate::project::View>();
-            self.project_view.set(Some(view));
+    /// Switch the displayed view according to the route.
+    pub fn set_route(&self, route: &Route) {
+        match route {
+            Route::WelcomeScreen => self.switch_view_to_welcome_screen(),
+            Route::Settings => self.switch_view_to_settings(),
+            Route::Project { id, panel } => {
+                self.open_project(*id);
+                if let Some(view) = self.project_view(*id) {
+                    match panel {
+                        Some(ProjectPanel::Graph) => view.code_editor().hide(),
+                        Some(ProjectPanel::Code) => view.code_editor().show(),
+                        None => {}
+                    }
+                }
+            }
+        }
+    }
+
+    /// The route of the displayed view. The transient states, like opening a project or the error
+    /// screen, have no route.
+    pub fn current_route(&self) -> Option<Route> {
+        match self.state.get() {
+            State::WelcomeScreen => Some(Route::WelcomeScreen),
+            State::Settings => Some(Route::Settings),
+            State::OpenedProject => self.active_project_view().map(|view| {
+                let id = self.active_project.get().expect("Project view without active project.");
+                let code_editor_visible = view.code_editor().is_visible.value();
+                let panel =
+                    if code_editor_visible { ProjectPanel::Code } else { ProjectPanel::Graph };
+                Route::Project { id, panel: Some(panel) }
+            }),
+            State::OpeningProject | State::Error => None,
+        }
+    }
+
+    /// The theme of the IDE.
+    pub fn theme(&self) -> Theme {
+        self.theme.borrow().clone()
+    }
+
+    /// The theme opposite to the current one: the dark theme for the light one and vice versa.
+    /// Other themes are toggled to the light one.
+    fn toggled_theme(&self) -> Theme {
+        match self.theme() {
+            Theme::Light => Theme::Dark,
+            _ => Theme::Light,
+        }
+    }
+
+    /// Enable the theme and update the styles of all Project Views. Returns `false` if the theme
+    /// was already set.
+    fn set_theme(&self, theme: &Theme) -> bool {
+        let changed = *self.theme.borrow() != *theme;
+        if changed {
+            *self.theme.borrow_mut() = theme.clone();
+            crate::project::enable_theme(&self.app, theme);
+            let views = self.projects.borrow().iter().map(|p| p.view.clone_ref()).collect_vec();
+            for view in views {
+                view.set_style(theme.clone());
+            }
+        }
+        changed
+    }
+
+    /// Show the about overlay above all views. Does nothing if it is already shown.
+    pub fn show_about(&self) {
+        if !self.about_visible() {
+            self.modal_layer.show(&self.about_view);
+        }
+    }
+
+    /// Hide the about overlay.
+    pub fn hide_about(&self) {
+        self.modal_layer.hide(&self.about_view);
+    }
+
+    /// Whether the about overlay is shown.
+    pub fn about_visible(&self) -> bool {
+        self.modal_layer.is_shown(&self.about_view)
+    }
+
+    /// Enable or disable the fullscreen mode, hiding or restoring the status bar and the toasts,
+    /// and switching the compact mode of all Project Views.
+    fn set_fullscreen_mode(&self, fullscreen: bool) {
+        self.fullscreen_mode.set(fullscreen);
+        let chrome = [self.status_bar.display_object(), self.toasts.display_object()];
+        for element in chrome {
+            if fullscreen {
+                self.display_object.remove_child(element);
+            } else {
+                self.display_object.add_child(element);
+            }
+        }
+        let views = self.projects.borrow().iter().map(|p| p.view.clone_ref()).collect_vec();
+        for view in views {
+            view.set_compact_mode(fullscreen);
+        }
+        self.update_project_tabs_visibility();
+    }
+
+    /// Show the project tabs while the Project View is displayed, unless in the fullscreen mode.
+    fn update_project_tabs_visibility(&self) {
+        let project_displayed = self.state.get() == State::OpenedProject;
+        if project_displayed && !self.fullscreen_mode.get() {
+            self.display_object.add_child(&self.project_tabs);
+        } else {
+            self.display_object.remove_child(&self.project_tabs);
+        }
+    }
+
+    /// The state of the Root View to be persisted.
+    pub fn persisted_state(&self) -> PersistedState {
+        let state = self.state.get();
+        let project_displayed = matches!(state, State::OpenedProject | State::OpeningProject);
+        let code_editor_shown =
+            self.active_project_view().map_or(false, |view| view.code_editor().is_visible.value());
+        PersistedState {
+            last_project: self.last_project.borrow().clone(),
+            project_displayed,
+            code_editor_shown,
+            theme: Some(theme_name(&self.theme())),
+        }
+    }
+
+    fn set_last_project(&self, name: &str) {
+        *self.last_project.borrow_mut() = Some(name.to_owned());
+    }
+
+    /// Save the state of the Root View to the storage, if provided.
+    fn save_state(&self) {
+        if let Some(storage) = &*self.storage.borrow() {
+            match self.persisted_state().to_json() {
+                Ok(document) => storage.save(&document),
+                Err(err) => warning!(self.logger, "Cannot serialize the Root View state: {err}."),
+            }
+        }
+    }
+
+    /// Start the transition from the displayed view to the provided one. The previous transition
+    /// is finished immediately. Does nothing if the view is already displayed.
+    fn show(&self, view: &impl display::Object, direction: Direction) {
+        let incoming = view.display_object().clone_ref();
+        let outgoing = self.displayed_view.replace(incoming.clone_ref());
+        if outgoing != incoming {
+            self.finish_transition();
+            self.display_object.add_child(&incoming);
+            self.transitions.set(self.transitions.get() + 1);
+            *self.transition.borrow_mut() = Some(Transition { outgoing, incoming, direction });
+            self.update_transition(self.transitions.get() as f32 - 1.0);
+        }
+    }
+
+    /// The target value of the transition animation. See [`Model::update_transition`].
+    fn transition_target(&self) -> f32 {
+        self.transitions.get() as f32
+    }
+
+    /// Update the positions of the views taking part in the transition. The `animation_value` is
+    /// the value of the animation targeting [`Model::transition_target`]. As every transition
+    /// increases the target by one, the progress of the current transition is the fractional part
+    /// of the distance travelled towards the target.
+    fn update_transition(&self, animation_value: f32) {
+        if let Some(transition) = &*self.transition.borrow() {
+            let progress = (1.0 - (self.transition_target() - animation_value)).clamp(0.0, 1.0);
+            let width = self.app.display.default_scene.shape().value().width;
+            let offset = transition.direction.incoming_offset() * width;
+            transition.incoming.set_position_x(offset * (1.0 - progress));
+            transition.outgoing.set_position_x(-offset * progress);
+        }
+    }
+
+    /// Finish the ongoing transition, detaching the outgoing view.
+    fn finish_transition(&self) {
+        if let Some(transition) = self.transition.borrow_mut().take() {
+            self.display_object.remove_child(&transition.outgoing);
+            transition.outgoing.set_position_x(0.0);
+            transition.incoming.set_position_x(0.0);
         }
     }
 }
@@ -98,10 +720,125 @@ ensogl::define_endpoints! {
     Input {
         /// Switch displayed view to Project View. Lazily intializes Project View.
         switch_view_to_project(),
-        /// Switch displayed view to Welcome Screen.
+        /// Switch displayed view to Welcome Screen. The Project Views are detached from the display
+        /// hierarchy, but not destroyed.
         switch_view_to_welcome_screen(),
+        /// Switch displayed view to the progress of opening a project.
+        switch_view_to_opening_project(),
+        /// Display the message describing the current step of opening the project.
+        set_opening_project_step(String),
+        /// Switch displayed view to the error screen, displaying the error message.
+        show_error(String),
+        /// Retry the operation which failed. Does nothing if the error screen is not displayed.
+        retry(),
+        /// Go back from the error screen to Welcome Screen. Does nothing if the error screen is
+        /// not displayed, or going back is not available.
+        back_to_welcome(),
+        /// Set whether going back from the error screen to Welcome Screen is available. It should
+        /// be disabled when the Welcome Screen cannot work, for example, when the backend
+        /// connection failed. Available by default.
+        set_back_to_welcome_available(bool),
+        /// Open the project in a new Project View and display it.
+        open_project(ProjectId),
+        /// Close the project and destroy its Project View.
+        close_project(ProjectId),
+        /// Display the Project View of an already opened project.
+        activate_project(ProjectId),
+        /// Finish the ongoing transition between the displayed views immediately.
+        skip_transition(),
+        /// Hide the top modal widget, restoring the one shown before it. See
+        /// [`View::show_modal`].
+        hide_modal(),
+        /// Hide all modal widgets.
+        hide_all_modals(),
+        /// Show a transient notification above the displayed view.
+        show_notification(crate::toast::Notification),
+        /// Set the theme of the whole IDE, including the styles of all Project Views.
+        set_theme(Theme),
+        /// Switch between the light and dark theme.
+        toggle_theme(),
+        /// Switch displayed view to the Settings View.
+        switch_view_to_settings(),
+        /// Close the Settings View, switching back to the previously displayed view.
+        close_settings(),
+        /// Open the Settings View, or close it if it is already displayed.
+        toggle_settings(),
+        /// Switch displayed view to Welcome Screen.
+        go_to_welcome(),
+        /// Display the Project View of the project opened after the active one.
+        next_project_tab(),
+        /// Display the Project View of the project opened before the active one.
+        previous_project_tab(),
+        /// Display the description of the backend connection in the Settings View.
+        set_backend_info(String),
+        /// Switch the displayed view according to the route, used for deep linking. Routing to a
+        /// project only opens its Project View: loading the project is the responsibility of the
+        /// component setting the route.
+        set_route(Route),
+        /// Set the name of the last opened project, to be persisted in the Root View state.
+        set_last_project(String),
+        /// Set the name displayed in the tab of the opened project.
+        set_project_name(ProjectId, String),
+        /// Show the about overlay with the IDE build metadata above the displayed view.
+        show_about(),
+        /// Hide the about overlay.
+        hide_about(),
+        /// Enter or leave the fullscreen mode, in which the status bar and the toasts are hidden,
+        /// and all Project Views are switched to the compact mode.
+        toggle_fullscreen_mode(),
+        /// Display the event in the status bar.
+        add_status_event(crate::status_bar::event::Label),
+        /// Display the process in the status bar until it is finished. The identifier of the
+        /// process is emitted by the `last_status_process` FRP output.
+        add_status_process(crate::status_bar::process::Label),
+        /// Finish the process displayed in the status bar.
+        finish_status_process(crate::status_bar::process::Id),
     }
     Output {
+        /// Whether the Welcome Screen is displayed.
+        welcome_screen_visible(bool),
+        /// Identifiers of all opened projects in the order of opening.
+        opened_projects(Vec<ProjectId>),
+        /// The identifier of the active project.
+        active_project(Option<ProjectId>),
+        /// Emitted when the animated transition between the displayed views finishes, once the
+        /// outgoing view is detached from the display hierarchy.
+        transition_finished(),
+        /// Whether any modal widget is shown.
+        modal_shown(bool),
+        /// Whether the error screen is displayed.
+        error_screen_visible(bool),
+        /// Emitted when the user requests retrying the operation which failed. Should be handled
+        /// by the component which started the operation.
+        retry_requested(),
+        /// The theme of the IDE, including the changes made by the style toggle of a Project View.
+        current_theme(Theme),
+        /// Whether the Settings View is displayed.
+        settings_visible(bool),
+        /// The route of the displayed view. Not emitted for the views which have no route, like
+        /// the error screen.
+        current_route(Route),
+        /// Whether the about overlay is shown.
+        about_visible(bool),
+        /// Emitted when the Project View of the project is constructed, so the controllers can bind
+        /// their models to it.
+        project_view_created(ProjectId),
+        /// Emitted when the Project View of the project is torn down. The view should not be used
+        /// afterwards.
+        project_view_destroyed(ProjectId),
+        /// Emitted for every Enso project archive dropped onto the IDE window.
+        project_archive_dropped(drop_manager::File),
+        /// Emitted for every data file dropped onto the IDE window while the Project View is not
+        /// displayed. While it is displayed, the Graph Editor imports the dropped data files. Note
+        /// that only the file name is available, as browsers do not expose the file paths.
+        data_file_dropped(drop_manager::File),
+        /// Whether the fullscreen mode is enabled.
+        fullscreen_mode(bool),
+        /// The identifier of the process most recently added to the status bar.
+        last_status_process(crate::status_bar::process::Id),
+        /// The new state of the Root View, emitted whenever it switches to a different kind of
+        /// view.
+        state_changed(State),
     }
 }
 
@@ -111,7 +848,15 @@ ensogl::define_endpoints! {
 // === View ===
 // ============
 
-/// Root View of the IDE. Displays either Welcome Screen or Project View.
+/// Root View of the IDE. Initially displays the Welcome Screen.
+///
+/// Many projects can be opened at once, each one having its own Project View, constructed lazily
+/// and identified by the [`ProjectId`]. Only the Project View of the active project is displayed,
+/// together with the tab bar of all opened projects (see [`crate::project_tabs`]), through which
+/// the user can switch between the projects and close them.
+/// The global navigation (going to the Welcome Screen, switching between the opened projects, and
+/// toggling the Settings View) is exposed as the Root View commands with default shortcuts, so it
+/// works regardless of the displayed view.
 #[derive(Clone, CloneRef, Debug)]
 #[allow(missing_docs)]
 pub struct View {
@@ -132,23 +877,204 @@ impl View {
         let model = Model::new(app);
         let frp = Frp::new();
         let network = &frp.network;
+        let transition = Animation::<f32>::new(network);
         frp::extend! { network
             eval_ frp.switch_view_to_project(model.switch_view_to_project());
             eval_ frp.switch_view_to_welcome_screen(model.switch_view_to_welcome_screen());
+            eval_ frp.switch_view_to_opening_project(model.switch_view_to_opening_project());
+            model.opening_view.set_step <+ frp.set_opening_project_step;
+            eval frp.show_error((message) model.show_error(message));
+            let welcome_view = &model.welcome_view;
+            settings_opened <- any_(...);
+            settings_opened <+ frp.switch_view_to_settings;
+            settings_opened <+ welcome_view.open_settings;
+            settings_opened <+ model.project_settings_requested;
+            eval_ settings_opened (model.switch_view_to_settings());
+            eval_ frp.close_settings (model.close_settings());
+            eval_ frp.toggle_settings (model.toggle_settings());
+            eval_ frp.go_to_welcome (model.switch_view_to_welcome_screen());
+            eval_ frp.next_project_tab (model.activate_project_at_offset(1));
+            eval_ frp.previous_project_tab (model.activate_project_at_offset(-1));
+            eval frp.set_route ((route) model.set_route(route));
+            model.settings_view.set_backend_info <+ frp.set_backend_info;
+            retry <- frp.retry.gate(&frp.error_screen_visible);
+            back_to_welcome_available <- frp.set_back_to_welcome_available.sampler();
+            model.error_view.set_back_to_welcome_available <+ frp.set_back_to_welcome_available;
+            back_to_welcome_requested <- frp.back_to_welcome.gate(&frp.error_screen_visible);
+            back_to_welcome <- back_to_welcome_requested.gate(&back_to_welcome_available);
+            eval_ retry (model.switch_view_to_opening_project());
+            eval_ back_to_welcome (model.switch_view_to_welcome_screen());
+            frp.source.retry_requested <+ retry;
+            eval frp.open_project((id) model.open_project(*id));
+            eval frp.close_project((id) model.close_project(*id));
+            eval frp.activate_project((id) model.activate_project(*id));
+            view_changed <- any_(...);
+            view_changed <+ frp.switch_view_to_project;
+            view_changed <+ frp.switch_view_to_welcome_screen;
+            view_changed <+ frp.switch_view_to_opening_project;
+            view_changed <+ frp.show_error;
+            view_changed <+ retry;
+            view_changed <+ back_to_welcome;
+            view_changed <+ settings_opened;
+            view_changed <+ frp.close_settings;
+            view_changed <+ frp.set_route;
+            view_changed <+ frp.toggle_settings;
+            view_changed <+ frp.go_to_welcome;
+            view_changed <+ frp.next_project_tab;
+            view_changed <+ frp.previous_project_tab;
+            view_changed <+ frp.open_project;
+            view_changed <+ frp.close_project;
+            view_changed <+ frp.activate_project;
+            view_changed <+ model.project_view_created;
+            view_changed <+ model.project_view_destroyed;
+            frp.source.project_view_created <+ model.project_view_created;
+            frp.source.project_view_destroyed <+ model.project_view_destroyed;
+            frp.source.opened_projects <+ view_changed.map(f_!(model.opened_projects()));
+            frp.source.active_project <+ view_changed.map(f_!(model.active_project()));
+            welcome_screen_visible <- view_changed.map(
+                f_!(model.state.get() == State::WelcomeScreen)
+            );
+            frp.source.welcome_screen_visible <+ welcome_screen_visible.on_change();
+            error_screen_visible <- view_changed.map(f_!(model.state.get() == State::Error));
+            frp.source.error_screen_visible <+ error_screen_visible.on_change();
+            settings_visible <- view_changed.map(f_!(model.state.get() == State::Settings));
+            frp.source.settings_visible <+ settings_visible.on_change();
+            frp.source.state_changed <+ view_changed.map(f_!(model.state.get())).on_change();
+            route_changed <- any(&view_changed, &model.project_panel_changed);
+            current_route <- route_changed.map(f_!(model.current_route())).unwrap();
+            frp.source.current_route <+ current_route.on_change();
+
+            transition.target <+ view_changed.map(f_!(model.transition_target()));
+            transition.skip <+ frp.skip_transition;
+            eval transition.value ((value) model.update_transition(*value));
+            eval_ transition.on_end (model.finish_transition());
+            frp.source.transition_finished <+ transition.on_end;
+
+            let modal_layer = &model.modal_layer;
+            modal_layer.hide_modal <+ frp.hide_modal;
+            modal_layer.hide_all_modals <+ frp.hide_all_modals;
+            frp.source.modal_shown <+ modal_layer.modal_shown;
+
+            model.toasts.show_notification <+ frp.show_notification;
+
+            let status_bar = &model.status_bar;
+            status_bar.add_event <+ frp.add_status_event;
+            status_bar.add_process <+ frp.add_status_process;
+            status_bar.finish_process <+ frp.finish_status_process;
+            frp.source.last_status_process <+ status_bar.last_process;
+
+            theme <- any(...);
+            theme <+ frp.set_theme;
+            theme <+ model.project_style;
+            theme <+ frp.toggle_theme.map(f_!(model.toggled_theme()));
+            theme_changed <- theme.map(f!((theme) model.set_theme(theme))).on_true();
+            frp.source.current_theme <+ theme_changed.map(f_!(model.theme()));
+            model.settings_view.set_theme <+ frp.current_theme;
+        }
+        frp::extend! { network
+            eval frp.set_last_project ((name) model.set_last_project(name));
+            persisted_state_changed <- any_(...);
+            persisted_state_changed <+ frp.current_route;
+            persisted_state_changed <+ frp.current_theme;
+            persisted_state_changed <+ frp.set_last_project;
+            eval_ persisted_state_changed (model.save_state());
+
+            eval_ frp.show_about (model.show_about());
+            eval_ frp.hide_about (model.hide_about());
+            about_changed <- any_(...);
+            about_changed <+ frp.show_about;
+            about_changed <+ frp.hide_about;
+            about_changed <+ model.modal_layer.modals_count;
+            frp.source.about_visible <+ about_changed.map(f_!(model.about_visible())).on_change();
+        }
+        let files_received = model.drop_manager.files_received().clone_ref();
+        frp::extend! { network
+            file_dropped <= files_received;
+            frp.source.project_archive_dropped <+ file_dropped.filter(is_project_archive);
+            data_file_dropped <- file_dropped.filter(|file| !is_project_archive(file));
+            project_displayed <- view_changed.map(f_!(model.state.get() == State::OpenedProject));
+            eval_ project_displayed (model.update_project_tabs_visibility());
+            frp.source.data_file_dropped <+ data_file_dropped.gate_not(&project_displayed);
+
+            fullscreen_mode <- frp.toggle_fullscreen_mode.toggle();
+            eval fullscreen_mode ((fullscreen) model.set_fullscreen_mode(*fullscreen));
+            frp.source.fullscreen_mode <+ fullscreen_mode;
+        }
+        let project_tabs = &model.project_tabs;
+        frp::extend! { network
+            project_tabs.set_projects <+ frp.opened_projects;
+            project_tabs.set_active_project <+ frp.active_project;
+            project_tabs.set_project_name <+ frp.set_project_name;
+            frp.activate_project <+ project_tabs.activate_project;
+            frp.close_project <+ project_tabs.close_project;
         }
+        frp.source.welcome_screen_visible.emit(true);
+        frp.set_back_to_welcome_available(true);
+        frp.source.current_theme.emit(model.theme());
+        model.settings_view.set_theme(model.theme());
         Self { model, frp }
     }
 
-    /// Status Bar view from Project View.
+    /// Status Bar displayed above all views. Prefer writing to it through the Root View FRP.
     pub fn status_bar(&self) -> &crate::status_bar::View {
         &self.model.status_bar
     }
 
-    /// Lazily initializes Project View.
+    /// Project View of the active project. Lazily initializes Project View.
     pub fn project(&self) -> crate::project::View {
         self.model.get_or_init_project_view()
     }
 
+    /// Replace the Project View of the active project with a fresh one, tearing the old one down.
+    /// Should be used when another project is loaded in place of the active one. See
+    /// [`Model::replace_active_project`].
+    pub fn replace_active_project(&self) -> crate::project::View {
+        self.model.replace_active_project()
+    }
+
+    /// Show the modal widget above all views, dimming and blocking them. It stays displayed until
+    /// hidden with the `hide_modal` or `hide_all_modals` FRP inputs. Modal widgets can be stacked.
+    pub fn show_modal(&self, modal: &impl display::Object) {
+        self.model.modal_layer.show(modal)
+    }
+
+    /// Project View of the opened project.
+    pub fn project_by_id(&self, id: ProjectId) -> Option<crate::project::View> {
+        self.model.project_view(id)
+    }
+
+    /// Set the storage of the Root View state. The state is loaded from the storage and restored
+    /// immediately, and is saved to it on every change. Restoring sets the theme and the last
+    /// opened project, and shows the code editor if it was shown. The state is not restored by the
+    /// constructor, because views are constructed by [`Application::new_view`] without access to
+    /// any platform storage, and the Root Views constructed in tests must not load the state of the
+    /// user's IDE. Reopening the project is the responsibility of the caller, so the restored state
+    /// is returned.
+    pub fn set_state_storage(
+        &self,
+        storage: impl StateStorage + 'static,
+    ) -> Option<PersistedState> {
+        let document = storage.load();
+        *self.model.storage.borrow_mut() = Some(Box::new(storage));
+        let state = document.and_then(|document| match PersistedState::from_json(&document) {
+            Ok(state) => Some(state),
+            Err(err) => {
+                warning!(self.model.logger, "Cannot parse the Root View state: {err}.");
+                None
+            }
+        })?;
+        if let Some(name) = &state.last_project {
+            self.set_last_project(name.clone());
+        }
+        if let Some(theme) = &state.theme {
+            self.set_theme(theme_from_name(theme));
+        }
+        if state.project_displayed && state.code_editor_shown {
+            self.project().code_editor().show();
+        }
+        Some(state)
+    }
+
     /// Welcome View.
     pub fn welcome_screen(&self) -> &crate::welcome_screen::View {
         &self.model.welcome_view
@@ -179,4 +1105,104 @@ impl application::View for View {
     fn app(&self) -> &Application {
         &self.model.app
     }
+
+    fn default_shortcuts() -> Vec<application::shortcut::Shortcut> {
+        use application::shortcut::ActionType::*;
+        (&[
+            (Press, "error_screen_visible & !modal_shown", "enter", "retry"),
+            (Press, "error_screen_visible & !modal_shown", "escape", "back_to_welcome"),
+            (Press, "settings_visible & !modal_shown", "escape", "close_settings"),
+            (Press, "settings_visible & !modal_shown", "t", "toggle_theme"),
+            (Press, "!modal_shown", "cmd ,", "toggle_settings"),
+            (Press, "!modal_shown", "cmd shift h", "go_to_welcome"),
+            (Press, "!modal_shown", "cmd shift a", "show_about"),
+            (Press, "!modal_shown", "cmd shift f", "toggle_fullscreen_mode"),
+            // The about overlay is a modal itself.
+            (Press, "about_visible", "escape", "hide_about"),
+            (Press, "!modal_shown", "ctrl tab", "next_project_tab"),
+            (Press, "!modal_shown", "ctrl shift tab", "previous_project_tab"),
+        ])
+            .iter()
+            .map(|(a, b, c, d)| Self::self_shortcut_when(*a, *c, *d, *b))
+            .collect()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ensogl::application::shortcut::ActionType;
+    use ensogl::application::shortcut::Rule;
+
+    #[test]
+    fn shortcuts_blocked_while_modal_shown() {
+        let app = Application::new("root");
+        let root = app.new_view::<View>();
+        let layers = &app.display.default_scene.layers;
+        // The Project View cannot be constructed outside the browser, so one of its components is
+        // tested alone.
+        let code_editor = app.new_view::<crate::code_editor::View>();
+        block_shortcuts_while_modal_shown(&code_editor, &root.model.modal_layer);
+        let toggle = Rule::new(ActionType::Press, "ctrl `");
+        app.shortcuts.simulate(&toggle);
+        assert!(code_editor.is_visible.value());
+
+        let modal = display::object::Instance::new(Logger::new("Modal"));
+        root.show_modal(&modal);
+        assert!(root.modal_shown.value());
+        assert!(layers.modal_backdrop.blocks_events() && layers.modal.blocks_events());
+        app.shortcuts.simulate(&toggle);
+        assert!(code_editor.is_visible.value());
+
+        root.hide_modal();
+        assert!(!root.modal_shown.value());
+        assert!(!layers.modal_backdrop.blocks_events() && !layers.modal.blocks_events());
+        app.shortcuts.simulate(&toggle);
+        assert!(!code_editor.is_visible.value());
+    }
+
+    #[test]
+    fn root_shortcuts_blocked_while_modal_shown() {
+        let app = Application::new("root");
+        let root = app.new_view::<View>();
+        let toggle_settings = Rule::new(ActionType::Press, "cmd ,");
+        let modal = display::object::Instance::new(Logger::new("Modal"));
+        root.show_modal(&modal);
+        app.shortcuts.simulate(&toggle_settings);
+        assert!(!root.settings_visible.value());
+
+        root.hide_modal();
+        app.shortcuts.simulate(&toggle_settings);
+        assert!(root.settings_visible.value());
+        root.show_modal(&modal);
+        app.shortcuts.simulate(&Rule::new(ActionType::Press, "escape"));
+        app.shortcuts.simulate(&Rule::new(ActionType::Press, "cmd shift h"));
+        assert!(root.settings_visible.value());
+
+        root.hide_modal();
+        root.show_about();
+        assert!(root.about_visible.value());
+        app.shortcuts.simulate(&Rule::new(ActionType::Press, "escape"));
+        assert!(!root.about_visible.value());
+        assert!(root.settings_visible.value());
+    }
+
+    #[test]
+    fn back_to_welcome_availability() {
+        let app = Application::new("root");
+        let root = app.new_view::<View>();
+        root.show_error("Cannot connect to the Project Manager.");
+        root.set_back_to_welcome_available(false);
+        root.back_to_welcome();
+        assert!(root.error_screen_visible.value());
+        root.set_back_to_welcome_available(true);
+        root.back_to_welcome();
+        assert!(root.welcome_screen_visible.value());
+    }
 }
@@ -0,0 +1,157 @@
+//! Routes describing what the Root View displays, in a form suitable for URL fragments and launch
+//! arguments. For example, the `#project/<id>/graph` route describes the graph editor of the
+//! project with the given identifier. Routes are applied by the `set_route` FRP input of the Root
+//! View, which also reports the current route, see [`crate::root`].
+
+use crate::prelude::*;
+
+use crate::root::ProjectId;
+
+use std::str::FromStr;
+
+
+
+// =============
+// === Error ===
+// =============
+
+/// Error raised when a string cannot be parsed as a route.
+#[derive(Clone, Debug)]
+pub struct InvalidRoute {
+    route: String,
+}
+
+impl Display for InvalidRoute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid route \"{}\".", self.route)
+    }
+}
+
+impl std::error::Error for InvalidRoute {}
+
+
+
+// ====================
+// === ProjectPanel ===
+// ====================
+
+/// The main panel of the Project View.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum ProjectPanel {
+    Graph,
+    Code,
+}
+
+impl ProjectPanel {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Graph => "graph",
+            Self::Code => "code",
+        }
+    }
+}
+
+
+
+// =============
+// === Route ===
+// =============
+
+/// A route describing what the Root View displays.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum Route {
+    WelcomeScreen,
+    Settings,
+    /// The Project View of the project. If the panel is not specified, the Project View is
+    /// displayed as it was left.
+    Project {
+        id:    ProjectId,
+        panel: Option<ProjectPanel>,
+    },
+}
+
+impl Default for Route {
+    fn default() -> Self {
+        Self::WelcomeScreen
+    }
+}
+
+impl FromStr for Route {
+    type Err = InvalidRoute;
+
+    /// Parse the route. The leading `#` and trailing `/` are optional, so both URL fragments and
+    /// plain launch arguments are accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidRoute { route: s.to_owned() };
+        let path = s.trim_start_matches('#').trim_end_matches('/');
+        let segments = path.split('/').collect_vec();
+        match segments.as_slice() {
+            [""] | ["welcome"] => Ok(Self::WelcomeScreen),
+            ["settings"] => Ok(Self::Settings),
+            ["project", id, rest @ ..] => {
+                let id = ProjectId::parse_str(id).map_err(|_| invalid())?;
+                let panel = match rest {
+                    [] => None,
+                    ["graph"] => Some(ProjectPanel::Graph),
+                    ["code"] => Some(ProjectPanel::Code),
+                    _ => return Err(invalid()),
+                };
+                Ok(Self::Project { id, panel })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl Display for Route {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WelcomeScreen => write!(f, "#welcome"),
+            Self::Settings => write!(f, "#settings"),
+            Self::Project { id, panel: None } => write!(f, "#project/{id}"),
+            Self::Project { id, panel: Some(panel) } => write!(f, "#project/{id}/{}", panel.name()),
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_and_printing_routes() {
+        let id = ProjectId::new_v4();
+        let routes = [
+            Route::WelcomeScreen,
+            Route::Settings,
+            Route::Project { id, panel: None },
+            Route::Project { id, panel: Some(ProjectPanel::Graph) },
+            Route::Project { id, panel: Some(ProjectPanel::Code) },
+        ];
+        for route in routes {
+            assert_eq!(route.to_string().parse::<Route>().unwrap(), route);
+        }
+        assert_eq!("".parse::<Route>().unwrap(), Route::WelcomeScreen);
+        assert_eq!("settings/".parse::<Route>().unwrap(), Route::Settings);
+        let graph = format!("project/{id}/graph");
+        let expected = Route::Project { id, panel: Some(ProjectPanel::Graph) };
+        assert_eq!(graph.parse::<Route>().unwrap(), expected);
+    }
+
+    #[test]
+    fn parsing_invalid_routes() {
+        let id = ProjectId::new_v4();
+        let invalid = ["#unknown", "#project", "#project/not-an-id", &format!("#project/{id}/x")];
+        for route in invalid {
+            assert!(route.parse::<Route>().is_err(), "{route} should be invalid");
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! The Settings View, displaying the IDE configuration in three panels: the keybindings, the theme,
+//! and the backend connection. It is displayed by the Root View, which also handles the actions
+//! available in the Settings View, see [`crate::root`].
+
+use crate::prelude::*;
+use ensogl::display::shape::StyleWatch;
+
+use crate::graph_editor::component::node::input::area::TEXT_SIZE;
+
+use enso_frp as frp;
+use ensogl::application::Application;
+use ensogl::display;
+use ensogl_hardcoded_theme as theme;
+use ensogl_hardcoded_theme::Theme;
+use ensogl_text as text;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The width of a single panel, including the gap between panels.
+const PANEL_WIDTH: f32 = 360.0;
+/// The vertical position of the panel titles.
+const TITLE_Y: f32 = 200.0;
+/// The vertical distance between the panel title and its content.
+const TITLE_GAP: f32 = 2.0 * TEXT_SIZE;
+/// The hint describing how to switch the theme.
+const THEME_HINT: &str = "Press T to switch between the light and dark theme.";
+/// The hint describing how to leave the Settings View.
+const CLOSE_HINT: &str = "Press Escape to close the settings.";
+
+
+
+// =============
+// === Panel ===
+// =============
+
+/// A single panel of the Settings View: a title and a multi-line content.
+#[derive(Clone, CloneRef, Debug)]
+struct Panel {
+    title:   text::Area,
+    content: text::Area,
+}
+
+impl Panel {
+    fn new(app: &Application, style: &StyleWatch, title: &str, index: usize) -> Self {
+        let x = (index as f32 - 1.5) * PANEL_WIDTH;
+        let title_label = text::Area::new(app);
+        title_label.set_default_color(style.get_color(theme::application::settings::title));
+        title_label.set_content(title);
+        title_label.set_position_xy(Vector2(x, TITLE_Y + TEXT_SIZE / 2.0));
+        let content = text::Area::new(app);
+        content.set_default_color(style.get_color(theme::application::settings::text));
+        content.set_position_xy(Vector2(x, TITLE_Y - TITLE_GAP + TEXT_SIZE / 2.0));
+        Self { title: title_label, content }
+    }
+
+    fn set_content(&self, content: impl Into<String>) {
+        self.content.set_content(content.into());
+    }
+}
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+ensogl::define_endpoints! {
+    Input {
+        /// Update the keybindings panel with the currently active shortcuts.
+        refresh_keybindings(),
+        /// Display the theme of the IDE.
+        set_theme(Theme),
+        /// Display the description of the backend connection.
+        set_backend_info(String),
+    }
+    Output {}
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+/// The Settings View model.
+#[derive(Clone, CloneRef, Debug)]
+pub struct Model {
+    app:            Application,
+    display_object: display::object::Instance,
+    keybindings:    Panel,
+    theme:          Panel,
+    backend:        Panel,
+}
+
+impl Model {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let app = app.clone_ref();
+        let logger = Logger::new("SettingsView");
+        let display_object = display::object::Instance::new(&logger);
+        let style = StyleWatch::new(&app.display.default_scene.style_sheet);
+        let keybindings = Panel::new(&app, &style, "Keybindings", 0);
+        let theme = Panel::new(&app, &style, "Theme", 1);
+        let backend = Panel::new(&app, &style, "Backend", 2);
+        for panel in [&keybindings, &theme, &backend] {
+            display_object.add_child(&panel.title);
+            display_object.add_child(&panel.content);
+        }
+        backend.set_content(format!("Not connected.\n\n{CLOSE_HINT}"));
+        Self { app, display_object, keybindings, theme, backend }
+    }
+
+    fn refresh_keybindings(&self) {
+        let documentation = self.app.shortcuts.documentation();
+        let lines = documentation.iter().flat_map(|(target, shortcuts)| {
+            let header = iter::once(format!("{target}:"));
+            let entries = shortcuts
+                .iter()
+                .map(|shortcut| format!("    {} - {}", shortcut.rule.pattern, shortcut.command));
+            header.chain(entries)
+        });
+        self.keybindings.set_content(lines.collect_vec().join("\n"));
+    }
+
+    fn set_theme(&self, theme: &Theme) {
+        let name = match theme {
+            Theme::Light => "Light",
+            Theme::Dark => "Dark",
+            Theme::Other(name) => name,
+        };
+        self.theme.set_content(format!("Current theme: {name}.\n\n{THEME_HINT}"));
+    }
+
+    fn set_backend_info(&self, info: &str) {
+        self.backend.set_content(format!("{info}\n\n{CLOSE_HINT}"));
+    }
+}
+
+
+
+// ============
+// === View ===
+// ============
+
+/// The Settings View. See the module docs to learn more.
+#[derive(Clone, CloneRef, Debug)]
+#[allow(missing_docs)]
+pub struct View {
+    model:   Model,
+    pub frp: Frp,
+}
+
+impl Deref for View {
+    type Target = Frp;
+    fn deref(&self) -> &Self::Target {
+        &self.frp
+    }
+}
+
+impl View {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let model = Model::new(app);
+        let frp = Frp::new();
+        let network = &frp.network;
+        frp::extend! { network
+            eval_ frp.refresh_keybindings (model.refresh_keybindings());
+            eval frp.set_theme ((theme) model.set_theme(theme));
+            eval frp.set_backend_info ((info) model.set_backend_info(info));
+        }
+        Self { model, frp }
+    }
+}
+
+impl display::Object for View {
+    fn display_object(&self) -> &display::object::Instance {
+        &self.model.display_object
+    }
+}
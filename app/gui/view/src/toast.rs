@@ -0,0 +1,300 @@
+//! Transient notifications (toasts) displayed above all other views, like the Welcome Screen and
+//! the Project View.
+//!
+//! The toasts are stacked at the bottom of the screen, the newest one being the lowest. Each toast
+//! is hidden once its timeout passes.
+
+use crate::prelude::*;
+use ensogl::display::shape::*;
+
+use crate::graph_editor::component::node::input::area::TEXT_SIZE;
+
+use enso_frp as frp;
+use ensogl::application::Application;
+use ensogl::display;
+use ensogl::display::style;
+use ensogl_hardcoded_theme as theme;
+use ensogl_text as text;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The width of a toast.
+const WIDTH: f32 = 360.0;
+/// The height of a toast.
+const HEIGHT: f32 = 28.0;
+/// Padding inside a toast.
+const PADDING: f32 = 12.0;
+/// The radius of the circle indicating the notification level.
+const INDICATOR_RADIUS: f32 = 4.0;
+/// The gap between the stacked toasts.
+const GAP: f32 = 8.0;
+/// Margin between the toasts and the bottom edge of the screen.
+const MARGIN: f32 = 12.0;
+/// The default time after which a notification is hidden.
+pub const DEFAULT_TIMEOUT_MS: f32 = 5000.0;
+
+
+
+// ====================
+// === Notification ===
+// ====================
+
+/// The severity of a notification.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum Level {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Default for Level {
+    fn default() -> Self {
+        Self::Info
+    }
+}
+
+impl Level {
+    fn color_path(self) -> style::StaticPath {
+        match self {
+            Self::Info => theme::application::toast::level::info,
+            Self::Warning => theme::application::toast::level::warning,
+            Self::Error => theme::application::toast::level::error,
+        }
+    }
+}
+
+/// A notification displayed as a toast.
+#[derive(Clone, Debug, PartialEq)]
+#[allow(missing_docs)]
+pub struct Notification {
+    pub level:      Level,
+    pub message:    String,
+    /// The time after which the notification is hidden. Notifications with non-positive timeout
+    /// stay until hidden with the `hide_all_notifications` FRP input.
+    pub timeout_ms: f32,
+}
+
+impl Notification {
+    /// Constructor.
+    pub fn new(level: Level, message: impl Into<String>, timeout_ms: f32) -> Self {
+        let message = message.into();
+        Self { level, message, timeout_ms }
+    }
+}
+
+impl Default for Notification {
+    fn default() -> Self {
+        Self::new(default(), "", DEFAULT_TIMEOUT_MS)
+    }
+}
+
+
+
+// ==================
+// === Background ===
+// ==================
+
+mod background {
+    use super::*;
+
+    ensogl::define_shape_system! {
+        (style:Style, level_color:Vector4<f32>) {
+            let theme         = style::Path::from(theme::application::toast::background);
+            let width         = Var::<Pixels>::from("input_size.x");
+            let height        = Var::<Pixels>::from("input_size.y");
+            let corner_radius = style.get_number(theme.sub("corner_radius"));
+            let shape         = Rect((&width,&height)).corners_radius(corner_radius.px());
+            let background    = shape.fill(style.get_color(&theme));
+            let indicator_x   = -WIDTH / 2.0 + PADDING + INDICATOR_RADIUS;
+            let indicator     = Circle(INDICATOR_RADIUS.px()).translate_x(indicator_x.px());
+            let indicator     = indicator.fill(level_color);
+            (background + indicator).into()
+        }
+    }
+}
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+ensogl::define_endpoints! {
+    Input {
+        /// Show the notification as a new toast.
+        show_notification(Notification),
+        /// Hide all toasts.
+        hide_all_notifications(),
+    }
+    Output {
+        /// The number of displayed toasts.
+        notifications_count(usize),
+    }
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+/// A single displayed toast.
+#[derive(Debug)]
+struct Toast {
+    display_object: display::object::Instance,
+    // The shape and the label are kept to be dropped together with the toast.
+    _background:    background::View,
+    _label:         text::Area,
+    /// The frame time after which the toast is hidden.
+    expiry_time:    Option<f32>,
+}
+
+/// The toasts model.
+#[derive(Clone, CloneRef, Debug)]
+pub struct Model {
+    app:            Application,
+    logger:         Logger,
+    display_object: display::object::Instance,
+    style:          StyleWatch,
+    toasts:         Rc<RefCell<Vec<Toast>>>,
+    time:           Rc<Cell<f32>>,
+    scene_size:     Rc<Cell<Vector2>>,
+}
+
+impl Model {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let app = app.clone_ref();
+        let logger = Logger::new("Toasts");
+        let display_object = display::object::Instance::new(&logger);
+        let style = StyleWatch::new(&app.display.default_scene.style_sheet);
+        let toasts = default();
+        let time = default();
+        let scene_size = default();
+        Self { app, logger, display_object, style, toasts, time, scene_size }
+    }
+
+    /// Display the notification as a new toast.
+    pub fn show(&self, notification: &Notification) {
+        let scene = &self.app.display.default_scene;
+        let display_object = display::object::Instance::new(&self.logger);
+        let background = background::View::new(&self.logger);
+        background.size.set(Vector2(WIDTH, HEIGHT));
+        let level_color = self.style.get_color(notification.level.color_path());
+        background.level_color.set(level_color.into());
+        scene.layers.notification.add_exclusive(&background);
+        display_object.add_child(&background);
+
+        let label = text::Area::new(&self.app);
+        label.remove_from_scene_layer(&scene.layers.main);
+        label.add_to_scene_layer(&scene.layers.notification_text);
+        let text_color = self.style.get_color(theme::application::toast::text);
+        label.set_default_color(text_color);
+        label.set_content(notification.message.clone());
+        label.set_color_all(text_color);
+        label.set_position_x(-WIDTH / 2.0 + 2.0 * (PADDING + INDICATOR_RADIUS));
+        label.set_position_y(TEXT_SIZE / 2.0);
+        display_object.add_child(&label);
+
+        self.display_object.add_child(&display_object);
+        let timeout = notification.timeout_ms;
+        let expiry_time = (timeout > 0.0).then(|| self.time.get() + timeout);
+        let toast = Toast { display_object, _background: background, _label: label, expiry_time };
+        self.toasts.borrow_mut().push(toast);
+        self.update_layout();
+    }
+
+    /// Hide the toasts whose timeout passed. Returns `true` if any toast was hidden.
+    fn remove_expired(&self, time: f32) -> bool {
+        self.time.set(time);
+        let mut toasts = self.toasts.borrow_mut();
+        let count = toasts.len();
+        toasts.retain(|toast| toast.expiry_time.map_or(true, |expiry_time| expiry_time > time));
+        let changed = toasts.len() != count;
+        drop(toasts);
+        if changed {
+            self.update_layout();
+        }
+        changed
+    }
+
+    /// Hide all toasts.
+    pub fn clear(&self) {
+        self.toasts.borrow_mut().clear();
+    }
+
+    /// The number of displayed toasts.
+    pub fn count(&self) -> usize {
+        self.toasts.borrow().len()
+    }
+
+    fn set_scene_size(&self, size: Vector2) {
+        self.scene_size.set(size);
+        self.update_layout();
+    }
+
+    fn update_layout(&self) {
+        let bottom = -self.scene_size.get().y / 2.0 + MARGIN + HEIGHT / 2.0;
+        for (index, toast) in self.toasts.borrow().iter().rev().enumerate() {
+            let y = bottom + index as f32 * (HEIGHT + GAP);
+            toast.display_object.set_position_y(y.round());
+        }
+    }
+}
+
+
+
+// ============
+// === View ===
+// ============
+
+/// The toasts displaying transient notifications. See the module docs to learn more.
+#[derive(Clone, CloneRef, Debug)]
+#[allow(missing_docs)]
+pub struct View {
+    model:   Model,
+    pub frp: Frp,
+}
+
+impl Deref for View {
+    type Target = Frp;
+    fn deref(&self) -> &Self::Target {
+        &self.frp
+    }
+}
+
+impl View {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let model = Model::new(app);
+        let frp = Frp::new();
+        let network = &frp.network;
+        let scene = &app.display.default_scene;
+        let scene_shape = scene.shape().clone_ref();
+        frp::extend! { network
+            eval scene_shape ((shape) model.set_scene_size(Vector2(shape.width, shape.height)));
+            eval frp.show_notification ((notification) model.show(notification));
+            eval_ frp.hide_all_notifications (model.clear());
+            expired <- scene.frp.frame_time.map(f!((time) model.remove_expired(*time))).on_true();
+            changed <- any_(...);
+            changed <+ frp.show_notification;
+            changed <+ frp.hide_all_notifications;
+            changed <+ expired;
+            frp.source.notifications_count <+ changed.map(f_!(model.count()));
+        }
+        let shape = scene_shape.value();
+        model.set_scene_size(Vector2(shape.width, shape.height));
+        Self { model, frp }
+    }
+}
+
+impl display::Object for View {
+    fn display_object(&self) -> &display::object::Instance {
+        &self.model.display_object
+    }
+}
@@ -2,6 +2,10 @@
 //!
 //! It is opened when the IDE launches without any project or entry point selected. It
 //! displays a list of available projects, template cards and "new project" button.
+//!
+//! The Welcome Screen does not manage the projects itself. It emits the requests of the user, like
+//! opening, creating, renaming or deleting a project, on its FRP outputs, and displays the projects
+//! list set by the controller.
 
 // === Standard Linter Configuration ===
 #![deny(non_ascii_idents)]
@@ -49,10 +53,16 @@ mod css_class {
     pub const CARD_SPREADSHEETS: &str = "enso-internal-card-spreadsheets";
     pub const CARD_GEO: &str = "enso-internal-card-geo";
     pub const CARD_VISUALIZE: &str = "enso-internal-card-visualize";
+    pub const PROJECT: &str = "enso-internal-project";
+    pub const PROJECT_LINK: &str = "enso-internal-project-link";
+    pub const PROJECT_NAME: &str = "enso-internal-project-name";
+    pub const PROJECT_ACTION: &str = "enso-internal-project-action";
+    pub const SELECTED: &str = "enso-internal-selected";
 }
 
 mod css_id {
     pub const NEW_PROJECT: &str = "enso-internal-projects-list-new-project";
+    pub const SETTINGS: &str = "enso-internal-settings";
 }
 
 
@@ -100,6 +110,29 @@ impl ClickableElement {
 
 
 
+// ================
+// === Requests ===
+// ================
+
+/// The request to rename a project, made by editing its name on the projects list.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub struct ProjectRename {
+    pub name:     String,
+    pub new_name: String,
+}
+
+/// The choice of the template of a new project.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TemplateChoice {
+    /// The name of the template passed to the Engine, or `None` for an empty project.
+    pub template:     Option<String>,
+    /// The name suggested for the new project, or `None` to let the controller choose one.
+    pub project_name: Option<String>,
+}
+
+
+
 // =============
 // === Model ===
 // =============
@@ -176,12 +209,26 @@ ensogl::define_endpoints! {
     Input {
         /// Set a displayed list of projects.
         set_projects_list(Vec<String>),
+        /// Highlight the project with the given name on the projects list, or no project.
+        select_project(Option<String>),
     }
     Output {
         /// Open project by name.
         open_project(String),
-        /// Create a new project. Optional argument is a template name.
+        /// The project highlighted on the projects list changed, either because the user opened
+        /// it, or by `select_project`.
+        project_selected(Option<String>),
+        /// Create a new project. Optional argument is a template name. Emitted together with
+        /// `template_chosen`.
         create_project(Option<String>),
+        /// Create a new project with the chosen template and parameters.
+        template_chosen(TemplateChoice),
+        /// Rename a project.
+        rename_project(ProjectRename),
+        /// Delete the project with the given name.
+        delete_project(String),
+        /// Open the Settings View.
+        open_settings(),
     }
 }
 
@@ -223,16 +270,31 @@ impl View {
             // === Setup event handlers for all WelcomeScreen components. ===
 
             model.side_menu.set_projects_list <+ frp.set_projects_list;
-
+            model.side_menu.select_project <+ frp.select_project;
+        }
+        frp::extend! { network
             let open_template = model.template_cards.output.source.open_template.clone_ref();
-            frp.output.source.create_project <+ open_template.some();
-
             let new_project = model.side_menu.output.source.new_project.clone_ref();
-            frp.output.source.create_project <+ new_project.constant(None);
+            empty_project <- new_project.constant(TemplateChoice::default());
+            template_chosen <- any(&open_template, &empty_project);
+            frp.output.source.template_chosen <+ template_chosen;
+            frp.output.source.create_project <+ template_chosen.map(|c| c.template.clone());
 
             let open_project = model.side_menu.output.source.open_project.clone_ref();
             frp.output.source.open_project <+ open_project;
         }
+        frp::extend! { network
+            let project_selected = model.side_menu.output.source.project_selected.clone_ref();
+            frp.output.source.project_selected <+ project_selected;
+            let rename_project = model.side_menu.output.source.rename_project.clone_ref();
+            frp.output.source.rename_project <+ rename_project;
+            let delete_project = model.side_menu.output.source.delete_project.clone_ref();
+            frp.output.source.delete_project <+ delete_project;
+        }
+        frp::extend! { network
+            let open_settings = model.side_menu.output.source.open_settings.clone_ref();
+            frp.output.source.open_settings <+ open_settings;
+        }
 
         Self { model, frp }
     }
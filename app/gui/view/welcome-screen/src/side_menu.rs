@@ -1,14 +1,134 @@
 //! Side menu for Welcome Screen.
 //!
-//! Side menu contains a list of available projects and a "new project" button.
+//! Side menu contains a list of available projects, a "new project" button and a "settings" button.
+//! Every project on the list can be opened, renamed or deleted.
 
 use ensogl::prelude::*;
 use ensogl::system::web::traits::*;
 
 use crate::ClickableElement;
+use crate::ProjectRename;
 
 use enso_frp as frp;
 use ensogl::system::web;
+use web::Closure;
+use web::HtmlDivElement;
+use web::JsCast;
+
+
+
+// ====================
+// === ProjectEntry ===
+// ====================
+
+/// The endpoints of the side menu FRP the entries of the projects list emit the requests on.
+#[derive(Clone, CloneRef, Debug)]
+struct EntryEndpoints {
+    open_project:   frp::Any<String>,
+    rename_project: frp::Any<ProjectRename>,
+    delete_project: frp::Any<String>,
+}
+
+/// An entry of the projects list. Clicking the project name opens the project. The project can be
+/// renamed by editing its name in place, after clicking the "rename" button, and deleted with the
+/// "delete" button. The rename request is emitted when the edited name loses focus.
+///
+/// The fields with the leading underscore keep the event handlers of the entry alive.
+#[derive(Clone, Debug)]
+struct ProjectEntry {
+    name:           String,
+    root_dom:       web::Element,
+    _link:          ClickableElement,
+    _rename_button: ClickableElement,
+    _delete_button: ClickableElement,
+    _network:       frp::Network,
+    _blur_handle:   web::EventListenerHandle,
+}
+
+impl ProjectEntry {
+    fn new(name: &str, endpoints: &EntryEndpoints) -> Self {
+        let name = name.to_owned();
+        let root_dom = web::document.create_element_or_panic("li");
+        root_dom.set_class_name(crate::css_class::PROJECT);
+        let link = web::document.create_div_or_panic();
+        link.set_class_name(crate::css_class::PROJECT_LINK);
+        link.set_inner_html(r#"<img src="assets/project.svg"/>"#);
+        let name_dom = web::document.create_div_or_panic();
+        name_dom.set_class_name(crate::css_class::PROJECT_NAME);
+        name_dom.set_inner_text(&name);
+        link.append_or_warn(&name_dom);
+        root_dom.append_or_warn(&link);
+        let link = ClickableElement::new(link.unchecked_into());
+        let rename_button = Self::create_action_button(&root_dom, "Rename");
+        let delete_button = Self::create_action_button(&root_dom, "Delete");
+
+        let rename_request = {
+            let (name_dom, name) = (name_dom.clone(), name.clone());
+            move |new_name: &String| Self::rename_request(&name_dom, &name, new_name)
+        };
+        frp::new_network! { network
+            edited_name <- source::<String>();
+            is_editing <- bool(&edited_name, &rename_button.click);
+            eval_ rename_button.click ([name_dom] Self::start_renaming(&name_dom));
+            endpoints.open_project <+ link.click.gate_not(&is_editing).constant(name.clone());
+            endpoints.delete_project <+ delete_button.click.constant(name.clone());
+            endpoints.rename_project <+ edited_name.filter_map(rename_request);
+        }
+        let on_blur: web::JsEventHandler = Closure::new(f_!([name_dom, edited_name] {
+            name_dom.set_attribute_or_warn("contenteditable", "false");
+            edited_name.emit(name_dom.inner_text());
+        }));
+        let _blur_handle = web::add_event_listener(&name_dom, "blur", on_blur);
+        Self {
+            name,
+            root_dom,
+            _link: link,
+            _rename_button: rename_button,
+            _delete_button: delete_button,
+            _network: network,
+            _blur_handle,
+        }
+    }
+
+    fn create_action_button(root_dom: &web::Element, label: &str) -> ClickableElement {
+        let element = web::document.create_element_or_panic("button");
+        element.set_class_name(crate::css_class::PROJECT_ACTION);
+        element.set_text_content(Some(label));
+        root_dom.append_or_warn(&element);
+        ClickableElement::new(element)
+    }
+
+    fn start_renaming(name_dom: &HtmlDivElement) {
+        name_dom.set_attribute_or_warn("contenteditable", "true");
+        // Focusing an element attached to the document never fails.
+        name_dom.focus().ok();
+    }
+
+    /// The request to rename the project `name` to the `new_name` typed by the user. An empty or
+    /// unchanged name cancels the renaming, restoring the displayed name.
+    fn rename_request(
+        name_dom: &HtmlDivElement,
+        name: &str,
+        new_name: &str,
+    ) -> Option<ProjectRename> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() || new_name == name {
+            name_dom.set_inner_text(name);
+            None
+        } else {
+            Some(ProjectRename { name: name.to_owned(), new_name: new_name.to_owned() })
+        }
+    }
+
+    /// Mark the entry as the selected one, or unmark it.
+    fn set_selected(&self, selected: bool) {
+        let class = match selected {
+            true => format!("{} {}", crate::css_class::PROJECT, crate::css_class::SELECTED),
+            false => crate::css_class::PROJECT.to_owned(),
+        };
+        self.root_dom.set_class_name(&class);
+    }
+}
 
 
 
@@ -21,8 +141,10 @@ pub struct Model {
     logger:             Logger,
     pub root_dom:       web::Element,
     new_project_button: ClickableElement,
+    settings_button:    ClickableElement,
     projects_list_dom:  web::Element,
-    projects:           Rc<RefCell<Vec<ClickableElement>>>,
+    projects:           Rc<RefCell<Vec<ProjectEntry>>>,
+    selected:           Rc<RefCell<Option<String>>>,
 }
 
 impl Model {
@@ -35,33 +157,49 @@ impl Model {
         let projects_list_dom = Self::create_projects_list();
         root_dom.append_or_warn(&projects_list_dom);
         let new_project_button = Self::create_new_project_button(&projects_list_dom);
+        let settings_button = Self::create_settings_button(&root_dom);
         let projects = default();
+        let selected = default();
 
-        Self { logger, root_dom, projects_list_dom, projects, new_project_button }
+        Self {
+            logger,
+            root_dom,
+            projects_list_dom,
+            projects,
+            selected,
+            new_project_button,
+            settings_button,
+        }
     }
 
-    pub fn set_projects_list(&self, projects: &[String], open_project: &frp::Any<String>) {
+    fn set_projects_list(&self, projects: &[String], endpoints: &EntryEndpoints) {
         self.clear_projects_list();
         for name in projects {
-            self.add_projects_list_entry(name, open_project);
+            self.add_projects_list_entry(name, endpoints);
         }
+        let selected = self.selected.borrow().clone();
+        self.select_project(selected);
+    }
+
+    /// Highlight the project with the given name on the list, or no project.
+    fn select_project(&self, name: Option<String>) {
+        for entry in self.projects.borrow().iter() {
+            entry.set_selected(name.as_ref() == Some(&entry.name));
+        }
+        *self.selected.borrow_mut() = name;
     }
 
     fn clear_projects_list(&self) {
         for entry in self.projects.borrow_mut().iter() {
-            entry.element.remove();
+            entry.root_dom.remove();
         }
         self.projects.borrow_mut().clear();
     }
 
-    fn add_projects_list_entry(&self, name: &str, open_project: &frp::Any<String>) {
-        let entry = Self::create_project_list_entry(name);
-        let network = &entry.network;
-        frp::extend! { network
-            open_project <+ entry.click.constant(name.to_owned());
-        }
+    fn add_projects_list_entry(&self, name: &str, endpoints: &EntryEndpoints) {
+        let entry = ProjectEntry::new(name, endpoints);
         let new_project_button = &self.new_project_button;
-        self.projects_list_dom.insert_before_or_warn(&entry, new_project_button);
+        self.projects_list_dom.insert_before_or_warn(&entry.root_dom, new_project_button);
         self.projects.borrow_mut().push(entry);
     }
 
@@ -73,6 +211,16 @@ impl Model {
         ClickableElement::new(element)
     }
 
+    fn create_settings_button(root_dom: &web::Element) -> ClickableElement {
+        let list = web::document.create_element_or_panic("ul");
+        let element = web::document.create_element_or_panic("li");
+        element.set_id(crate::css_id::SETTINGS);
+        element.set_text_content(Some("Settings"));
+        list.append_or_warn(&element);
+        root_dom.append_or_warn(&list);
+        ClickableElement::new(element)
+    }
+
     fn create_header(text: &str) -> web::Element {
         let header = web::document.create_element_or_panic("h2");
         header.set_text_content(Some(text));
@@ -82,12 +230,6 @@ impl Model {
     fn create_projects_list() -> web::Element {
         web::document.create_element_or_panic("ul")
     }
-
-    fn create_project_list_entry(project_name: &str) -> ClickableElement {
-        let element = web::document.create_element_or_panic("li");
-        element.set_inner_html(&format!(r#"<img src="assets/project.svg"/> {}"#, project_name));
-        ClickableElement::new(element)
-    }
 }
 
 
@@ -100,12 +242,22 @@ ensogl::define_endpoints! {
     Input {
         // Set displayed list of projects.
         set_projects_list(Vec<String>),
+        // Highlight the project with the given name on the list, or no project.
+        select_project(Option<String>),
     }
     Output {
         // New project button was clicked.
         new_project(),
         // Project with `name` was selected from the projects list.
         open_project(String),
+        // The highlighted project changed, either by opening a project or by `select_project`.
+        project_selected(Option<String>),
+        // The name of a project on the list was edited.
+        rename_project(ProjectRename),
+        // The delete button of a project on the list was clicked.
+        delete_project(String),
+        // Settings button was clicked.
+        open_settings(),
     }
 }
 
@@ -115,7 +267,8 @@ ensogl::define_endpoints! {
 // === SideMenu ===
 // ================
 
-/// Side menu for Welcome Screen. Contains a list of available projects and a "new project" button.
+/// Side menu for Welcome Screen. Contains a list of available projects, a "new project" button and
+/// a "settings" button.
 #[derive(Debug, Clone, CloneRef)]
 pub struct SideMenu {
     pub model: Model,
@@ -137,10 +290,19 @@ impl SideMenu {
 
         let network = &frp.network;
         frp::extend! { network
-            let open_project = &frp.output.source.open_project;
-            eval frp.set_projects_list([model, open_project] (list) model.set_projects_list(list, &open_project));
+            let open_project = frp.output.source.open_project.clone_ref();
+            let rename_project = frp.output.source.rename_project.clone_ref();
+            let delete_project = frp.output.source.delete_project.clone_ref();
+            let endpoints = EntryEndpoints { open_project, rename_project, delete_project };
+            eval frp.set_projects_list([model] (list) model.set_projects_list(list, &endpoints));
+
+            opened_project <- frp.output.source.open_project.map(|name| Some(name.clone()));
+            selected_project <- any(&frp.select_project, &opened_project);
+            eval selected_project ((name) model.select_project(name.clone()));
+            frp.output.source.project_selected <+ selected_project;
 
             frp.output.source.new_project <+ model.new_project_button.click;
+            frp.output.source.open_settings <+ model.settings_button.click;
         }
 
         Self { frp, model }
@@ -7,6 +7,7 @@ use ensogl::prelude::*;
 use ensogl::system::web::traits::*;
 
 use crate::ClickableElement;
+use crate::TemplateChoice;
 
 use enso_frp as frp;
 use ensogl::system::web;
@@ -29,6 +30,7 @@ struct CardDefinition {
     header:               &'static str,
     content:              &'static str,
     template:             &'static str,
+    project_name:         &'static str,
 }
 
 
@@ -40,6 +42,7 @@ const CARD_SPREADSHEETS: CardDefinition = CardDefinition {
     header:               "Combine spreadsheets",
     content:              "Glue multiple spreadsheets together to analyse all your data at once.",
     template:             "orders",
+    project_name:         "Orders",
 };
 const CARD_GEO: CardDefinition = CardDefinition {
     class:                crate::css_class::CARD_GEO,
@@ -47,6 +50,7 @@ const CARD_GEO: CardDefinition = CardDefinition {
     header:               "Geospatial analysis",
     content:              "Learn where to open a coffee shop to maximize your income.",
     template:             "restaurants",
+    project_name:         "Restaurants",
 };
 const CARD_VISUALIZE: CardDefinition = CardDefinition {
     class:                crate::css_class::CARD_VISUALIZE,
@@ -54,6 +58,7 @@ const CARD_VISUALIZE: CardDefinition = CardDefinition {
     header:               "Analyze GitHub stars",
     content:              "Find out which of Enso's repositories are most popular over time.",
     template:             "stargazers",
+    project_name:         "Stargazers",
 };
 
 
@@ -63,6 +68,7 @@ const CARD_VISUALIZE: CardDefinition = CardDefinition {
 struct Card {
     pub clickable_element: ClickableElement,
     pub template_name:     &'static str,
+    pub project_name:      &'static str,
 }
 
 impl Deref for Card {
@@ -86,7 +92,7 @@ pub struct Model {
 
 impl Model {
     /// Constructor.
-    pub fn new(logger: Logger, open_template: &frp::Any<String>) -> Self {
+    pub fn new(logger: Logger, open_template: &frp::Any<TemplateChoice>) -> Self {
         let root_dom = web::document.create_element_or_panic("main");
         root_dom.set_class_name(crate::css_class::CONTENT);
         let templates = web::document.create_div_or_panic();
@@ -104,12 +110,14 @@ impl Model {
     }
 
     /// Attach click event for every card to `open_template` FRP endpoint.
-    fn setup_event_listeners(&self, open_template: &frp::Any<String>) {
+    fn setup_event_listeners(&self, open_template: &frp::Any<TemplateChoice>) {
         for card in self.cards.iter() {
             let network = &card.network;
-            let template_name = card.template_name.to_owned();
+            let template = Some(card.template_name.to_owned());
+            let project_name = Some(card.project_name.to_owned());
+            let choice = TemplateChoice { template, project_name };
             frp::extend! { network
-                open_template <+ card.click.constant(template_name);
+                open_template <+ card.click.constant(choice);
             }
         }
     }
@@ -163,7 +171,9 @@ impl Model {
         card.append_or_warn(&text_content);
 
         let clickable_element = ClickableElement::new(card.unchecked_into());
-        Card { clickable_element, template_name: definition.template }
+        let template_name = definition.template;
+        let project_name = definition.project_name;
+        Card { clickable_element, template_name, project_name }
     }
 }
 
@@ -176,8 +186,8 @@ impl Model {
 ensogl::define_endpoints! {
     Input {}
     Output {
-        // Create a new project from template `name`.
-        open_template(String),
+        // Create a new project from the chosen template.
+        open_template(TemplateChoice),
     }
 }
 
@@ -185,6 +185,8 @@ commands.build.rust = async function (argv) {
     // causing build to fail when building a crate that doesn't have `enso_profiler` in its
     // dependency tree.
     process.env.ENSO_ENABLE_PROC_MACRO_SPAN = 1
+    // The IDE version displayed by the about overlay, see `app/gui/config/build.rs`.
+    process.env.ENSO_IDE_VERSION = `${release.currentVersion()}`
     await run_cargo('wasm-pack', args)
     await patch_file(paths.wasm.glue, js_workaround_patcher)
     await fs.rename(paths.wasm.mainRaw, paths.wasm.main)
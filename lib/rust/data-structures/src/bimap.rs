@@ -0,0 +1,289 @@
+//! A bidirectional map, storing one-to-one pairs which can be looked up by either of their sides.
+
+use crate::prelude::*;
+
+use std::borrow::Borrow;
+use std::hash::Hash;
+
+
+
+// ===================
+// === Overwritten ===
+// ===================
+
+/// The pairs removed from the [`BiMap`] by inserting a new pair, because they shared one of its
+/// sides.
+#[derive(Clone, Debug, Derivative, Eq, PartialEq)]
+#[derivative(Default(bound = ""))]
+pub struct Overwritten<L, R> {
+    /// The pair which contained the inserted left value.
+    pub by_left:  Option<(L, R)>,
+    /// The pair which contained the inserted right value, unless it is the same as `by_left`.
+    pub by_right: Option<(L, R)>,
+}
+
+impl<L, R> Overwritten<L, R> {
+    /// Check if no pair was removed.
+    pub fn is_empty(&self) -> bool {
+        self.by_left.is_none() && self.by_right.is_none()
+    }
+}
+
+
+
+// =============
+// === BiMap ===
+// =============
+
+/// A one-to-one map between left and right values, with O(1) lookup in both directions. Both sides
+/// are stored twice, so they are expected to be cheap to clone, like identifiers.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+#[derive(Clone, Debug)]
+pub struct BiMap<L, R> {
+    left_to_right: HashMap<L, R>,
+    right_to_left: HashMap<R, L>,
+}
+
+impl<L, R> BiMap<L, R>
+where
+    L: Clone + Eq + Hash,
+    R: Clone + Eq + Hash,
+{
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// The number of pairs.
+    pub fn len(&self) -> usize {
+        self.left_to_right.len()
+    }
+
+    /// Check if the map contains no pairs.
+    pub fn is_empty(&self) -> bool {
+        self.left_to_right.is_empty()
+    }
+
+    /// Insert the pair. The pairs containing either of its sides are removed and returned.
+    pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
+        let by_left = self.remove_by_left(&left);
+        let by_right = self.remove_by_right(&right);
+        self.left_to_right.insert(left.clone(), right.clone());
+        self.right_to_left.insert(right, left);
+        Overwritten { by_left, by_right }
+    }
+
+    /// The right value paired with the left one.
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        self.left_to_right.get(left)
+    }
+
+    /// The left value paired with the right one.
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        self.right_to_left.get(right)
+    }
+
+    /// Check if the left value is paired.
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        self.left_to_right.contains_key(left)
+    }
+
+    /// Check if the right value is paired.
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        self.right_to_left.contains_key(right)
+    }
+
+    /// Remove the pair containing the left value.
+    pub fn remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        let (left, right) = self.left_to_right.remove_entry(left)?;
+        self.right_to_left.remove(&right);
+        Some((left, right))
+    }
+
+    /// Remove the pair containing the right value.
+    pub fn remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        let (right, left) = self.right_to_left.remove_entry(right)?;
+        self.left_to_right.remove(&left);
+        Some((left, right))
+    }
+
+    /// The entry of the left value, for in-place lookup and modification of its pair.
+    pub fn entry_by_left(&mut self, left: L) -> Entry<L, R> {
+        Entry {
+            key:      left,
+            forward:  &mut self.left_to_right,
+            backward: &mut self.right_to_left,
+        }
+    }
+
+    /// The entry of the right value, for in-place lookup and modification of its pair.
+    pub fn entry_by_right(&mut self, right: R) -> Entry<R, L> {
+        Entry {
+            key:      right,
+            forward:  &mut self.right_to_left,
+            backward: &mut self.left_to_right,
+        }
+    }
+
+    /// Remove all pairs.
+    pub fn clear(&mut self) {
+        self.left_to_right.clear();
+        self.right_to_left.clear();
+    }
+
+    /// Iterator over all pairs, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&L, &R)> {
+        self.left_to_right.iter()
+    }
+}
+
+impl<L, R> FromIterator<(L, R)> for BiMap<L, R>
+where
+    L: Clone + Eq + Hash,
+    R: Clone + Eq + Hash,
+{
+    fn from_iter<T: IntoIterator<Item = (L, R)>>(iter: T) -> Self {
+        let mut map = Self::new();
+        for (left, right) in iter {
+            map.insert(left, right);
+        }
+        map
+    }
+}
+
+
+
+// =============
+// === Entry ===
+// =============
+
+/// The entry of one side of the [`BiMap`], called the key, giving access to the value on the other
+/// side. Both [`BiMap::entry_by_left`] and [`BiMap::entry_by_right`] return this type, with the
+/// type parameters swapped accordingly.
+#[derive(Debug)]
+pub struct Entry<'a, K, V> {
+    key:      K,
+    forward:  &'a mut HashMap<K, V>,
+    backward: &'a mut HashMap<V, K>,
+}
+
+impl<'a, K, V> Entry<'a, K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone + Eq + Hash,
+{
+    /// The key of the entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// The value paired with the key.
+    pub fn get(&self) -> Option<&V> {
+        self.forward.get(&self.key)
+    }
+
+    /// Pair the key with the value, returning the value it was paired with before. If the value was
+    /// paired with another key, that pair is removed.
+    pub fn insert(self, value: V) -> Option<V> {
+        let old_value = self.forward.remove(&self.key);
+        if let Some(old_value) = &old_value {
+            self.backward.remove(old_value);
+        }
+        if let Some(old_key) = self.backward.remove(&value) {
+            self.forward.remove(&old_key);
+        }
+        self.forward.insert(self.key.clone(), value.clone());
+        self.backward.insert(value, self.key);
+        old_value
+    }
+
+    /// Remove the pair containing the key, returning the value it was paired with.
+    pub fn remove(self) -> Option<V> {
+        let value = self.forward.remove(&self.key)?;
+        self.backward.remove(&value);
+        Some(value)
+    }
+
+    /// The value paired with the key. If the key is not paired, it is paired with the value
+    /// constructed by `f` first. If that value was paired with another key, that pair is removed.
+    pub fn or_insert_with(self, f: impl FnOnce() -> V) -> &'a V {
+        if !self.forward.contains_key(&self.key) {
+            let value = f();
+            if let Some(old_key) = self.backward.remove(&value) {
+                self.forward.remove(&old_key);
+            }
+            self.backward.insert(value.clone(), self.key.clone());
+            self.forward.insert(self.key.clone(), value);
+        }
+        &self.forward[&self.key]
+    }
+
+    /// The value paired with the key, pairing it with the provided value if it is not paired. See
+    /// [`Self::or_insert_with`].
+    pub fn or_insert(self, value: V) -> &'a V {
+        self.or_insert_with(|| value)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_lookup() {
+        let mut map = BiMap::new();
+        assert!(map.insert(1, "a").is_empty());
+        assert!(map.insert(2, "b").is_empty());
+        assert_eq!(map.get_by_left(&1), Some(&"a"));
+        assert_eq!(map.get_by_right("b"), Some(&2));
+        let overwritten = map.insert(1, "b");
+        assert_eq!(overwritten.by_left, Some((1, "a")));
+        assert_eq!(overwritten.by_right, Some((2, "b")));
+        assert_eq!(map.len(), 1);
+        assert!(!map.contains_right("a"));
+        assert!(!map.contains_left(&2));
+        assert_eq!(map.remove_by_right("b"), Some((1, "b")));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn entries() {
+        let mut map: BiMap<i32, &str> = [(1, "a"), (2, "b")].into_iter().collect();
+        assert_eq!(map.entry_by_left(1).or_insert("c"), &"a");
+        assert_eq!(map.entry_by_left(3).or_insert("c"), &"c");
+        assert_eq!(map.entry_by_right("b").get(), Some(&2));
+        assert_eq!(map.entry_by_right("b").insert(4), Some(2));
+        assert_eq!(map.get_by_left(&4), Some(&"b"));
+        assert!(!map.contains_left(&2));
+        assert_eq!(map.entry_by_left(5).insert("a"), None);
+        assert!(!map.contains_left(&1));
+        assert_eq!(map.entry_by_left(5).remove(), Some("a"));
+        let pairs = map.iter().map(|(l, r)| (*l, *r)).sorted().collect_vec();
+        assert_eq!(pairs, vec![(3, "c"), (4, "b")]);
+    }
+}
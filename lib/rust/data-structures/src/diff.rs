@@ -0,0 +1,169 @@
+//! Computing edit scripts between two sequences of unique ids, for reconciling a list with its
+//! updated version without rebuilding it, like view components mirroring model lists.
+
+use crate::prelude::*;
+
+use std::hash::Hash;
+
+
+
+// ============
+// === Edit ===
+// ============
+
+/// A single operation of an edit script. The indices refer to the sequence as modified by all the
+/// preceding edits of the script.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum Edit<Id> {
+    /// Remove the element at the index.
+    Remove { index: usize },
+    /// Insert the new element at the index.
+    Insert { index: usize, id: Id },
+    /// Remove the element at the `from` index, and then insert it at the `to` index.
+    Move { from: usize, to: usize },
+}
+
+impl<Id> Edit<Id> {
+    /// Apply the edit to the elements. The new elements are constructed with `new_element`.
+    pub fn apply<T>(&self, elements: &mut Vec<T>, new_element: impl FnOnce(&Id) -> T) {
+        match self {
+            Self::Remove { index } => {
+                elements.remove(*index);
+            }
+            Self::Insert { index, id } => elements.insert(*index, new_element(id)),
+            Self::Move { from, to } => {
+                let element = elements.remove(*from);
+                elements.insert(*to, element);
+            }
+        }
+    }
+}
+
+/// Apply all edits of the script to the elements. See [`Edit::apply`].
+pub fn apply<Id, T>(
+    elements: &mut Vec<T>,
+    edits: &[Edit<Id>],
+    mut new_element: impl FnMut(&Id) -> T,
+) {
+    for edit in edits {
+        edit.apply(elements, &mut new_element);
+    }
+}
+
+
+
+// ============
+// === Diff ===
+// ============
+
+/// Compute an edit script transforming the old sequence to the new one. The ids in each sequence
+/// must be unique. The script is minimal: it removes the ids missing in the new sequence, inserts
+/// the ids missing in the old one, and moves as few of the remaining ids as possible, keeping the
+/// longest subsequence of them in place. The removals come first, then the insertions and moves in
+/// the order of the new sequence.
+///
+/// Takes O(n log n + e * n) time, where e is the number of edits.
+pub fn diff<Id: Clone + Eq + Hash>(old: &[Id], new: &[Id]) -> Vec<Edit<Id>> {
+    let new_positions: HashMap<&Id, usize> =
+        new.iter().enumerate().map(|(i, id)| (id, i)).collect();
+    let mut edits = Vec::new();
+    for (index, id) in old.iter().enumerate().rev() {
+        if !new_positions.contains_key(id) {
+            edits.push(Edit::Remove { index });
+        }
+    }
+    let mut current = old.iter().filter(|id| new_positions.contains_key(id)).collect_vec();
+    let retained_positions = current.iter().map(|id| new_positions[id]).collect_vec();
+    let in_place: HashSet<usize> = longest_increasing_subsequence(&retained_positions)
+        .into_iter()
+        .map(|index| retained_positions[index])
+        .collect();
+    let position_of = |current: &[&Id], id: &Id| current.iter().position(|i| *i == id);
+    for (new_index, id) in new.iter().enumerate() {
+        if in_place.contains(&new_index) {
+            continue;
+        }
+        let from = position_of(&current, id);
+        if let Some(from) = from {
+            current.remove(from);
+        }
+        let predecessor = new_index.checked_sub(1).map(|index| &new[index]);
+        let to = predecessor.map_or(0, |pred| position_of(&current, pred).unwrap() + 1);
+        current.insert(to, id);
+        edits.push(match from {
+            Some(from) => Edit::Move { from, to },
+            None => Edit::Insert { index: to, id: id.clone() },
+        });
+    }
+    edits
+}
+
+/// The indices of the elements forming the longest strictly increasing subsequence.
+fn longest_increasing_subsequence(values: &[usize]) -> Vec<usize> {
+    // For every length, the index of the element ending the subsequence of that length with the
+    // smallest last value.
+    let mut tails: Vec<usize> = default();
+    let mut predecessors: Vec<Option<usize>> = Vec::with_capacity(values.len());
+    for (index, value) in values.iter().enumerate() {
+        let length = tails.partition_point(|tail| values[*tail] < *value);
+        predecessors.push(length.checked_sub(1).map(|length| tails[length]));
+        if length == tails.len() {
+            tails.push(index);
+        } else {
+            tails[length] = index;
+        }
+    }
+    let mut subsequence =
+        iter::successors(tails.last().copied(), |index| predecessors[*index]).collect_vec();
+    subsequence.reverse();
+    subsequence
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(old: &[char], new: &[char]) -> Vec<Edit<char>> {
+        let edits = diff(old, new);
+        let mut elements = old.to_vec();
+        apply(&mut elements, &edits, |id| *id);
+        assert_eq!(elements, new);
+        edits
+    }
+
+    #[test]
+    fn minimal_edit_scripts() {
+        assert_eq!(check(&['a', 'b', 'c'], &['a', 'b', 'c']), vec![]);
+        assert_eq!(check(&['a', 'b', 'c'], &['a', 'c']), vec![Edit::Remove { index: 1 }]);
+        assert_eq!(check(&['a', 'c'], &['a', 'b', 'c']), vec![Edit::Insert {
+            index: 1,
+            id:    'b',
+        }]);
+        assert_eq!(check(&['a', 'b', 'c', 'd'], &['b', 'c', 'd', 'a']), vec![Edit::Move {
+            from: 0,
+            to:   3,
+        }]);
+        let edits = check(&['a', 'b', 'c', 'd', 'e'], &['e', 'x', 'b', 'a', 'd']);
+        let moves = edits.iter().filter(|edit| matches!(edit, Edit::Move { .. })).count();
+        assert_eq!(moves, 2);
+        assert_eq!(edits.len(), 4);
+    }
+
+    #[test]
+    fn reversing_and_shuffling() {
+        let old = ('a'..='z').collect_vec();
+        let reversed = old.iter().rev().copied().collect_vec();
+        assert_eq!(check(&old, &reversed).len(), old.len() - 1);
+        let shuffled = old.iter().copied().step_by(3).chain(old.iter().copied().skip(1).step_by(2));
+        let shuffled = shuffled.unique().collect_vec();
+        check(&old, &shuffled);
+        check(&shuffled, &old);
+    }
+}
@@ -0,0 +1,140 @@
+//! An allocator of ids which reuses the indices of freed ids, while telling the old ids apart by
+//! their generations. Useful for indices into external storage, like GPU attribute buffers.
+
+use crate::prelude::*;
+
+use crate::slot_map::Key;
+use crate::slot_map::SlotKey;
+
+
+
+// ===================
+// === IdAllocator ===
+// ===================
+
+/// Allocates ids consisting of an index and a generation, see [`SlotKey`]. Freed indices are
+/// reissued with a bumped generation, so the ids freed before are detected as invalid instead of
+/// aliasing the new ones. Allocation prefers the most recently freed indices, so the issued indices
+/// stay dense.
+///
+/// Unlike [`crate::slot_map::SlotMap`], it stores no values, so it can manage slots of storage
+/// living elsewhere.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+#[derive(Clone, Debug)]
+pub struct IdAllocator<K = Key> {
+    /// The current generation of every index.
+    generations:  Vec<u32>,
+    allocated:    Vec<bool>,
+    free_indices: Vec<usize>,
+    len:          usize,
+    key:          PhantomData<K>,
+}
+
+impl<K: SlotKey> IdAllocator<K> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// The number of allocated ids.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if no id is allocated.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of indices ever issued. All allocated ids have indices below it, so it is the
+    /// required size of the storage indexed by the ids.
+    pub fn index_count(&self) -> usize {
+        self.generations.len()
+    }
+
+    /// Allocate a new id, reusing a freed index if there is any.
+    pub fn allocate(&mut self) -> K {
+        self.len += 1;
+        match self.free_indices.pop() {
+            Some(index) => {
+                self.allocated[index] = true;
+                K::new(index, self.generations[index])
+            }
+            None => {
+                let index = self.generations.len();
+                self.generations.push(0);
+                self.allocated.push(true);
+                K::new(index, 0)
+            }
+        }
+    }
+
+    /// Free the id, making its index available for reuse. Returns `false` if the id was invalid.
+    pub fn free(&mut self, id: K) -> bool {
+        if !self.is_valid(id) {
+            return false;
+        }
+        let index = id.index();
+        self.allocated[index] = false;
+        self.generations[index] = self.generations[index].wrapping_add(1);
+        self.free_indices.push(index);
+        self.len -= 1;
+        true
+    }
+
+    /// Check if the id is allocated and was not freed since.
+    pub fn is_valid(&self, id: K) -> bool {
+        let index = id.index();
+        let allocated = self.allocated.get(index).copied().unwrap_or_default();
+        allocated && self.generations[index] == id.generation()
+    }
+
+    /// Free all ids. The indices are kept for reuse.
+    pub fn clear(&mut self) {
+        for index in 0..self.generations.len() {
+            if mem::take(&mut self.allocated[index]) {
+                self.generations[index] = self.generations[index].wrapping_add(1);
+                self.free_indices.push(index);
+            }
+        }
+        self.len = 0;
+    }
+
+    /// Iterator over all allocated ids, in the order of their indices.
+    pub fn iter(&self) -> impl Iterator<Item = K> + '_ {
+        let indices = self.allocated.iter().enumerate().filter(|(_, allocated)| **allocated);
+        indices.map(|(index, _)| K::new(index, self.generations[index]))
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reissued_ids_have_new_generations() {
+        let mut ids = IdAllocator::<Key>::new();
+        let first = ids.allocate();
+        let second = ids.allocate();
+        assert!(ids.free(first));
+        assert!(!ids.free(first));
+        assert!(!ids.is_valid(first));
+        let third = ids.allocate();
+        assert_eq!(third.index, first.index);
+        assert_eq!(third.generation, first.generation + 1);
+        assert!(ids.is_valid(third));
+        assert_eq!(ids.index_count(), 2);
+        assert_eq!(ids.iter().collect_vec(), vec![third, second]);
+        ids.clear();
+        assert!(ids.is_empty());
+        assert!(!ids.is_valid(second));
+        assert!(!ids.is_valid(Key { index: 10, generation: 0 }));
+    }
+}
@@ -0,0 +1,184 @@
+//! String interning: storing each distinct string once and referring to it by a cheap, copyable
+//! identifier, which can be compared and hashed in O(1).
+
+use crate::prelude::*;
+
+use std::sync::Arc;
+use std::sync::Mutex;
+
+
+
+// ===================
+// === InternedStr ===
+// ===================
+
+/// An identifier of a string stored in an [`Interner`]. Identifiers issued by the same interner are
+/// equal if and only if their strings are equal. Comparing identifiers issued by different
+/// interners is meaningless.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct InternedStr {
+    index: u32,
+}
+
+impl InternedStr {
+    /// The index of the string in the order of interning.
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+}
+
+
+
+// ================
+// === Interner ===
+// ================
+
+/// A set of interned strings. See the module docs to learn more.
+#[derive(Clone, Debug, Default)]
+pub struct Interner {
+    strings: Vec<Arc<str>>,
+    ids:     HashMap<Arc<str>, InternedStr>,
+}
+
+impl Interner {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// The number of interned strings.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Check if no string was interned.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// Intern the string, returning its identifier. Interning an already interned string returns
+    /// the same identifier and does not allocate.
+    pub fn intern(&mut self, string: &str) -> InternedStr {
+        if let Some(id) = self.get(string) {
+            return id;
+        }
+        let index = u32::try_from(self.strings.len()).expect("Too many interned strings.");
+        let id = InternedStr { index };
+        let string: Arc<str> = string.into();
+        self.strings.push(string.clone());
+        self.ids.insert(string, id);
+        id
+    }
+
+    /// The identifier of the string, if it was interned.
+    pub fn get(&self, string: &str) -> Option<InternedStr> {
+        self.ids.get(string).copied()
+    }
+
+    /// The string of the identifier. Returns `None` if the identifier was issued by another
+    /// interner.
+    pub fn resolve(&self, id: InternedStr) -> Option<&str> {
+        self.strings.get(id.index()).map(|string| &**string)
+    }
+
+    /// Iterator over the interned strings and their identifiers, in the order of interning.
+    pub fn iter(&self) -> impl Iterator<Item = (InternedStr, &str)> {
+        self.strings.iter().enumerate().map(|(index, string)| {
+            let id = InternedStr { index: index as u32 };
+            (id, &**string)
+        })
+    }
+}
+
+
+
+// ====================
+// === SyncInterner ===
+// ====================
+
+/// A thread-safe [`Interner`], which can be shared between threads. Clones refer to the same set
+/// of interned strings.
+#[derive(Clone, Debug, Default)]
+pub struct SyncInterner {
+    interner: Arc<Mutex<Interner>>,
+}
+
+impl SyncInterner {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// The number of interned strings.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Check if no string was interned.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Intern the string, returning its identifier. See [`Interner::intern`].
+    pub fn intern(&self, string: &str) -> InternedStr {
+        self.lock().intern(string)
+    }
+
+    /// The identifier of the string, if it was interned.
+    pub fn get(&self, string: &str) -> Option<InternedStr> {
+        self.lock().get(string)
+    }
+
+    /// The string of the identifier. Returns `None` if the identifier was issued by another
+    /// interner.
+    pub fn resolve(&self, id: InternedStr) -> Option<Arc<str>> {
+        self.lock().strings.get(id.index()).cloned()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<Interner> {
+        // The interner is never left in an inconsistent state, so the poisoning can be ignored.
+        self.interner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interning_and_resolving() {
+        let mut interner = Interner::new();
+        assert!(interner.is_empty());
+        let foo = interner.intern("foo");
+        let bar = interner.intern("bar");
+        assert_ne!(foo, bar);
+        assert_eq!(interner.intern("foo"), foo);
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.resolve(foo), Some("foo"));
+        assert_eq!(interner.resolve(bar), Some("bar"));
+        assert_eq!(interner.get("bar"), Some(bar));
+        assert_eq!(interner.get("baz"), None);
+        assert_eq!(interner.iter().collect_vec(), vec![(foo, "foo"), (bar, "bar")]);
+        assert_eq!(Interner::new().resolve(foo), None);
+    }
+
+    #[test]
+    fn interning_from_many_threads() {
+        let interner = SyncInterner::new();
+        let threads = (0..4).map(|_| {
+            let interner = interner.clone();
+            std::thread::spawn(move || ["a", "b", "c"].map(|string| interner.intern(string)))
+        });
+        let ids = threads.collect_vec().into_iter().map(|thread| thread.join().unwrap());
+        let ids = ids.collect_vec();
+        assert!(ids.iter().all(|thread_ids| *thread_ids == ids[0]));
+        assert_eq!(interner.len(), 3);
+        assert_eq!(interner.resolve(ids[0][1]).as_deref(), Some("b"));
+    }
+}
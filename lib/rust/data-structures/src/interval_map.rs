@@ -0,0 +1,432 @@
+//! A map from intervals to values, supporting efficient queries for the intervals containing a
+//! point (stabbing queries) and the intervals overlapping a range.
+
+use crate::prelude::*;
+
+use std::cmp::Ordering;
+use std::ops::Bound;
+use std::ops::Range;
+
+
+
+// ============
+// === Node ===
+// ============
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+/// A node of the AVL tree ordered by the interval start and end. Every node is augmented with the
+/// maximum end of the intervals in its subtree, which allows skipping whole subtrees during
+/// queries.
+#[derive(Clone, Debug)]
+struct Node<K, V> {
+    range:   Range<K>,
+    value:   V,
+    max_end: K,
+    height:  u8,
+    left:    Link<K, V>,
+    right:   Link<K, V>,
+}
+
+impl<K: Copy + Ord, V> Node<K, V> {
+    fn new(range: Range<K>, value: V) -> Self {
+        let max_end = range.end;
+        Self { range, value, max_end, height: 1, left: None, right: None }
+    }
+
+    fn key(&self) -> (K, K) {
+        (self.range.start, self.range.end)
+    }
+
+    /// Recompute the height and the maximum end after the children changed.
+    fn update(&mut self) {
+        self.height = 1 + height(&self.left).max(height(&self.right));
+        let child_ends = [&self.left, &self.right].into_iter().flatten().map(|n| n.max_end);
+        self.max_end = child_ends.fold(self.range.end, K::max);
+    }
+}
+
+fn height<K, V>(link: &Link<K, V>) -> u8 {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+fn rotate_right<K: Copy + Ord, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut left = node.left.take().expect("Rotating right a node without left child.");
+    node.left = left.right.take();
+    node.update();
+    left.right = Some(node);
+    left.update();
+    left
+}
+
+fn rotate_left<K: Copy + Ord, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut right = node.right.take().expect("Rotating left a node without right child.");
+    node.right = right.left.take();
+    node.update();
+    right.left = Some(node);
+    right.update();
+    right
+}
+
+/// Restore the AVL balance of the node whose subtrees' heights differ by at most two.
+fn balance<K: Copy + Ord, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    node.update();
+    let left_height = height(&node.left) as i32;
+    let right_height = height(&node.right) as i32;
+    if left_height > right_height + 1 {
+        let left = node.left.take().expect("Unbalanced node without left child.");
+        let left = if height(&left.left) < height(&left.right) { rotate_left(left) } else { left };
+        node.left = Some(left);
+        rotate_right(node)
+    } else if right_height > left_height + 1 {
+        let right = node.right.take().expect("Unbalanced node without right child.");
+        let right =
+            if height(&right.right) < height(&right.left) { rotate_right(right) } else { right };
+        node.right = Some(right);
+        rotate_left(node)
+    } else {
+        node
+    }
+}
+
+fn insert<K: Copy + Ord, V>(
+    link: Link<K, V>,
+    range: Range<K>,
+    value: V,
+) -> (Box<Node<K, V>>, Option<V>) {
+    match link {
+        None => (Box::new(Node::new(range, value)), None),
+        Some(mut node) => {
+            let replaced = match (range.start, range.end).cmp(&node.key()) {
+                Ordering::Less => {
+                    let (left, replaced) = insert(node.left.take(), range, value);
+                    node.left = Some(left);
+                    replaced
+                }
+                Ordering::Greater => {
+                    let (right, replaced) = insert(node.right.take(), range, value);
+                    node.right = Some(right);
+                    replaced
+                }
+                Ordering::Equal => Some(mem::replace(&mut node.value, value)),
+            };
+            (balance(node), replaced)
+        }
+    }
+}
+
+fn remove<K: Copy + Ord, V>(link: Link<K, V>, key: (K, K)) -> (Link<K, V>, Option<V>) {
+    match link {
+        None => (None, None),
+        Some(mut node) => match key.cmp(&node.key()) {
+            Ordering::Less => {
+                let (left, removed) = remove(node.left.take(), key);
+                node.left = left;
+                (Some(balance(node)), removed)
+            }
+            Ordering::Greater => {
+                let (right, removed) = remove(node.right.take(), key);
+                node.right = right;
+                (Some(balance(node)), removed)
+            }
+            Ordering::Equal => {
+                let Node { value, left, right, .. } = *node;
+                let subtree = match (left, right) {
+                    (None, right) => right,
+                    (left, None) => left,
+                    (left, Some(right)) => {
+                        let (rest, mut min) = remove_min(right);
+                        min.left = left;
+                        min.right = rest;
+                        Some(balance(min))
+                    }
+                };
+                (subtree, Some(value))
+            }
+        },
+    }
+}
+
+/// Detach the leftmost node of the subtree. Returns the rest of the subtree and the detached node.
+fn remove_min<K: Copy + Ord, V>(mut node: Box<Node<K, V>>) -> (Link<K, V>, Box<Node<K, V>>) {
+    match node.left.take() {
+        None => (node.right.take(), node),
+        Some(left) => {
+            let (rest, min) = remove_min(left);
+            node.left = rest;
+            (Some(balance(node)), min)
+        }
+    }
+}
+
+
+
+// ===================
+// === IntervalMap ===
+// ===================
+
+/// A map from half-open intervals to values. The intervals may overlap, but every interval is
+/// present in the map at most once. Insertion and removal take O(log n) time, while querying the
+/// intervals containing a point or overlapping a range takes O(log n + m) time, where m is the
+/// number of reported intervals.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+#[derive(Clone, Debug)]
+pub struct IntervalMap<K, V> {
+    root: Link<K, V>,
+    len:  usize,
+}
+
+impl<K: Copy + Ord, V> IntervalMap<K, V> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// The number of intervals in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the map contains no intervals.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Insert the interval with the value. If the interval is already present, its value is
+    /// replaced and the old one is returned.
+    pub fn insert(&mut self, range: Range<K>, value: V) -> Option<V> {
+        let (root, replaced) = insert(self.root.take(), range, value);
+        self.root = Some(root);
+        if replaced.is_none() {
+            self.len += 1;
+        }
+        replaced
+    }
+
+    /// Remove the interval, returning its value. Returns `None` if the interval is not present.
+    pub fn remove(&mut self, range: &Range<K>) -> Option<V> {
+        let (root, removed) = remove(self.root.take(), (range.start, range.end));
+        self.root = root;
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// The value of the interval, if present.
+    pub fn get(&self, range: &Range<K>) -> Option<&V> {
+        let key = (range.start, range.end);
+        let mut link = &self.root;
+        while let Some(node) = link {
+            link = match key.cmp(&node.key()) {
+                Ordering::Less => &node.left,
+                Ordering::Greater => &node.right,
+                Ordering::Equal => return Some(&node.value),
+            };
+        }
+        None
+    }
+
+    /// Iterator over the intervals containing the point, ordered by their start and end.
+    pub fn stabbing(&self, point: K) -> Iter<K, V> {
+        Iter::new(&self.root, Some(point), Bound::Included(point))
+    }
+
+    /// Iterator over the intervals overlapping the range, ordered by their start and end. Empty
+    /// intervals never overlap anything.
+    pub fn overlapping(&self, range: Range<K>) -> Iter<K, V> {
+        if range.is_empty() {
+            Iter::empty()
+        } else {
+            let mut iter = Iter::new(&self.root, Some(range.start), Bound::Excluded(range.end));
+            iter.skip_empty = true;
+            iter
+        }
+    }
+
+    /// Iterator over all intervals, including the empty ones, ordered by their start and end.
+    pub fn iter(&self) -> Iter<K, V> {
+        Iter::new(&self.root, None, Bound::Unbounded)
+    }
+}
+
+impl<'a, K: Copy + Ord, V> IntoIterator for &'a IntervalMap<K, V> {
+    type Item = (&'a Range<K>, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K: Copy + Ord, V> FromIterator<(Range<K>, V)> for IntervalMap<K, V> {
+    fn from_iter<T: IntoIterator<Item = (Range<K>, V)>>(iter: T) -> Self {
+        let mut map = Self::new();
+        for (range, value) in iter {
+            map.insert(range, value);
+        }
+        map
+    }
+}
+
+
+
+// ============
+// === Iter ===
+// ============
+
+/// In-order iterator over the intervals of [`IntervalMap`] which end after `end_after` and start
+/// before `start_before`, omitting the empty ones if `skip_empty` is set. The subtrees which cannot
+/// contain such intervals are skipped.
+#[derive(Debug)]
+pub struct Iter<'a, K, V> {
+    stack:        Vec<&'a Node<K, V>>,
+    end_after:    Option<K>,
+    start_before: Bound<K>,
+    skip_empty:   bool,
+}
+
+impl<'a, K: Copy + Ord, V> Iter<'a, K, V> {
+    fn new(root: &'a Link<K, V>, end_after: Option<K>, start_before: Bound<K>) -> Self {
+        let skip_empty = false;
+        let mut iter = Self { stack: default(), end_after, start_before, skip_empty };
+        iter.push_left_path(root);
+        iter
+    }
+
+    /// Iterator yielding no intervals.
+    fn empty() -> Self {
+        let stack = default();
+        Self { stack, end_after: None, start_before: Bound::Unbounded, skip_empty: true }
+    }
+
+    fn ends_after(&self, end: K) -> bool {
+        self.end_after.map_or(true, |end_after| end > end_after)
+    }
+
+    fn starts_before(&self, start: K) -> bool {
+        match self.start_before {
+            Bound::Included(bound) => start <= bound,
+            Bound::Excluded(bound) => start < bound,
+            Bound::Unbounded => true,
+        }
+    }
+
+    fn push_left_path(&mut self, mut link: &'a Link<K, V>) {
+        while let Some(node) = link {
+            if !self.ends_after(node.max_end) {
+                break;
+            }
+            self.stack.push(node);
+            link = &node.left;
+        }
+    }
+}
+
+impl<'a, K: Copy + Ord, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a Range<K>, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.stack.pop() {
+            if !self.starts_before(node.range.start) {
+                // All following intervals start no earlier than this one.
+                self.stack.clear();
+                return None;
+            }
+            self.push_left_path(&node.right);
+            let skipped = self.skip_empty && node.range.is_empty();
+            if self.ends_after(node.range.end) && !skipped {
+                return Some((&node.range, &node.value));
+            }
+        }
+        None
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random intervals within `0..100`.
+    fn intervals(count: usize) -> Vec<Range<u32>> {
+        let mut seed = 17_u32;
+        let mut next = move || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 16) % 100
+        };
+        (0..count).map(|_| (next(), next())).map(|(a, b)| a.min(b)..a.max(b)).collect()
+    }
+
+    fn check_balanced<K: Copy + Ord, V>(link: &Link<K, V>) -> u8 {
+        match link {
+            None => 0,
+            Some(node) => {
+                let left = check_balanced(&node.left);
+                let right = check_balanced(&node.right);
+                assert!((left as i32 - right as i32).abs() <= 1);
+                assert_eq!(node.height, 1 + left.max(right));
+                left.max(right) + 1
+            }
+        }
+    }
+
+    #[test]
+    fn insert_get_and_remove() {
+        let mut map = IntervalMap::new();
+        assert_eq!(map.insert(1..5, "a"), None);
+        assert_eq!(map.insert(2..3, "b"), None);
+        assert_eq!(map.insert(1..5, "c"), Some("a"));
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&(1..5)), Some(&"c"));
+        assert_eq!(map.get(&(1..4)), None);
+        assert_eq!(map.remove(&(1..5)), Some("c"));
+        assert_eq!(map.remove(&(1..5)), None);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.iter().collect_vec(), vec![(&(2..3), &"b")]);
+    }
+
+    #[test]
+    fn empty_intervals() {
+        let map: IntervalMap<u32, ()> = [(1..5, ()), (3..3, ())].into_iter().collect();
+        let ranges = |iter: Iter<u32, ()>| iter.map(|(range, _)| range.clone()).collect_vec();
+        assert_eq!(ranges(map.iter()), vec![1..5, 3..3]);
+        assert_eq!(ranges(map.overlapping(2..4)), vec![1..5]);
+        assert!(ranges(map.overlapping(3..3)).is_empty());
+        assert_eq!(ranges(map.stabbing(3)), vec![1..5]);
+    }
+
+    #[test]
+    fn queries_match_linear_scan() {
+        let mut all = intervals(200);
+        all.extend([3..3, 50..50, 99..99]);
+        let mut map: IntervalMap<u32, usize> = all.iter().cloned().zip(0..).collect();
+        let removed = all.iter().step_by(3).cloned().collect_vec();
+        for range in &removed {
+            map.remove(range);
+        }
+        check_balanced(&map.root);
+        let present = all.iter().filter(|range| !removed.contains(range)).cloned();
+        let present = present.sorted_by_key(|range| (range.start, range.end)).dedup().collect_vec();
+        assert_eq!(map.len(), present.len());
+        assert_eq!(map.iter().map(|(range, _)| range.clone()).collect_vec(), present);
+        for point in 0..100 {
+            let expected = present.iter().filter(|range| range.contains(&point)).collect_vec();
+            assert_eq!(map.stabbing(point).map(|(range, _)| range).collect_vec(), expected);
+        }
+        let empty_queries = (0..100).step_by(7).map(|point| point..point);
+        for query in intervals(50).into_iter().chain(empty_queries) {
+            let overlaps = |range: &&Range<u32>| {
+                let non_empty = !range.is_empty() && !query.is_empty();
+                non_empty && range.start < query.end && query.start < range.end
+            };
+            let expected = present.iter().filter(overlaps).collect_vec();
+            let found = map.overlapping(query.clone()).map(|(range, _)| range).collect_vec();
+            assert_eq!(found, expected);
+        }
+    }
+}
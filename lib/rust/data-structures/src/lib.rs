@@ -21,10 +21,28 @@
 // === Export ===
 // ==============
 
+pub mod bimap;
 pub mod dependency_graph;
 pub mod diet;
+pub mod diff;
 pub mod hash_map_tree;
+pub mod id_allocator;
 pub mod index;
+pub mod interner;
+pub mod interval_map;
+pub mod lru_cache;
+pub mod multi_map;
+pub mod observable;
 pub mod opt_vec;
+pub mod ordered_map;
+pub mod persistent_map;
+pub mod priority_queue;
+pub mod ring_buffer;
+pub mod slot_map;
+pub mod sparse_vec;
+pub mod trie;
+pub mod typed_vec;
+pub mod union_find;
+pub mod weak_value_hash_map;
 
 pub use enso_prelude as prelude;
@@ -0,0 +1,293 @@
+//! A cache with limited capacity, evicting the least recently used entries first.
+
+use crate::prelude::*;
+
+use crate::slot_map;
+use crate::slot_map::SlotMap;
+
+use std::borrow::Borrow;
+use std::hash::Hash;
+
+
+
+// =============
+// === Entry ===
+// =============
+
+/// An entry of the [`LruCache`]. The entries form a doubly linked list ordered from the most
+/// recently used to the least recently used one.
+#[derive(Clone, Debug)]
+struct Entry<K, V> {
+    key:    K,
+    value:  V,
+    weight: usize,
+    prev:   Option<slot_map::Key>,
+    next:   Option<slot_map::Key>,
+}
+
+
+
+// ================
+// === LruCache ===
+// ================
+
+/// A map with limited capacity. Every entry has a weight, 1 by default, and the total weight of the
+/// entries never exceeds the capacity: inserting an entry evicts the least recently used entries
+/// until the new one fits. Both insertion and access mark the entry as the most recently used one.
+/// An entry heavier than the whole capacity is evicted right after being inserted.
+///
+/// All operations take O(1) time.
+#[derive(Clone, Debug)]
+pub struct LruCache<K, V> {
+    entries:  SlotMap<Entry<K, V>>,
+    keys:     HashMap<K, slot_map::Key>,
+    /// The most recently used entry.
+    head:     Option<slot_map::Key>,
+    /// The least recently used entry.
+    tail:     Option<slot_map::Key>,
+    capacity: usize,
+    weight:   usize,
+}
+
+
+// === Construction ===
+
+impl<K: Clone + Eq + Hash, V> LruCache<K, V> {
+    /// Constructor. The capacity is the maximum total weight of the entries.
+    pub fn new(capacity: usize) -> Self {
+        let entries = default();
+        let keys = default();
+        Self { entries, keys, head: None, tail: None, capacity, weight: 0 }
+    }
+}
+
+
+// === Status Checks ===
+
+impl<K: Clone + Eq + Hash, V> LruCache<K, V> {
+    /// The number of entries in the cache.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Check if the cache contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The maximum total weight of the entries.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The total weight of the entries.
+    pub fn weight(&self) -> usize {
+        self.weight
+    }
+
+    /// Check if the cache contains the key. Does not mark the entry as used.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        self.keys.contains_key(key)
+    }
+}
+
+
+// === Modifiers ===
+
+impl<K: Clone + Eq + Hash, V> LruCache<K, V> {
+    /// Change the capacity, evicting the least recently used entries if needed.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    /// Insert the entry with weight 1. See [`Self::insert_weighted`].
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.insert_weighted(key, value, 1)
+    }
+
+    /// Insert the entry as the most recently used one, evicting the least recently used entries
+    /// until it fits. If the key was already present, its old value is returned.
+    pub fn insert_weighted(&mut self, key: K, value: V, weight: usize) -> Option<V> {
+        let old_value = self.remove(&key);
+        let entry = Entry { key: key.clone(), value, weight, prev: None, next: None };
+        let slot = self.entries.insert(entry);
+        self.keys.insert(key, slot);
+        self.weight += weight;
+        self.push_front(slot);
+        self.evict();
+        old_value
+    }
+
+    /// Remove the entry, returning its value.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        let slot = self.keys.remove(key)?;
+        Some(self.remove_slot(slot).1)
+    }
+
+    /// Remove the least recently used entry.
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let slot = self.tail?;
+        let (key, value) = self.remove_slot(slot);
+        self.keys.remove(&key);
+        Some((key, value))
+    }
+
+    /// Remove all entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.keys.clear();
+        self.head = None;
+        self.tail = None;
+        self.weight = 0;
+    }
+
+    fn evict(&mut self) {
+        while self.weight > self.capacity {
+            self.pop_lru();
+        }
+    }
+
+    fn remove_slot(&mut self, slot: slot_map::Key) -> (K, V) {
+        self.unlink(slot);
+        let entry = self.entries.remove(slot).expect("Linked entry missing from the cache.");
+        self.weight -= entry.weight;
+        (entry.key, entry.value)
+    }
+
+    fn push_front(&mut self, slot: slot_map::Key) {
+        let old_head = self.head.replace(slot);
+        let entry = &mut self.entries[slot];
+        entry.prev = None;
+        entry.next = old_head;
+        match old_head {
+            Some(old_head) => self.entries[old_head].prev = Some(slot),
+            None => self.tail = Some(slot),
+        }
+    }
+
+    fn unlink(&mut self, slot: slot_map::Key) {
+        let entry = &mut self.entries[slot];
+        let (prev, next) = (entry.prev.take(), entry.next.take());
+        match prev {
+            Some(prev) => self.entries[prev].next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => self.entries[next].prev = prev,
+            None => self.tail = prev,
+        }
+    }
+
+    fn touch(&mut self, slot: slot_map::Key) {
+        if self.head != Some(slot) {
+            self.unlink(slot);
+            self.push_front(slot);
+        }
+    }
+}
+
+
+// === Indexing ===
+
+impl<K: Clone + Eq + Hash, V> LruCache<K, V> {
+    /// The value of the key, marking the entry as the most recently used one.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        self.get_mut(key).map(|value| &*value)
+    }
+
+    /// The mutable value of the key, marking the entry as the most recently used one.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        let slot = *self.keys.get(key)?;
+        self.touch(slot);
+        Some(&mut self.entries[slot].value)
+    }
+
+    /// The value of the key. Unlike [`Self::get`], does not mark the entry as used.
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        let slot = *self.keys.get(key)?;
+        Some(&self.entries[slot].value)
+    }
+}
+
+
+// === Iterators ===
+
+impl<K: Clone + Eq + Hash, V> LruCache<K, V> {
+    /// Iterator over the entries, from the most recently used to the least recently used one. Does
+    /// not mark the entries as used.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        let entries = &self.entries;
+        let mut next = self.head;
+        iter::from_fn(move || {
+            let entry = &entries[next?];
+            next = entry.next;
+            Some((&entry.key, &entry.value))
+        })
+    }
+
+    /// Iterator over the keys, from the most recently used to the least recently used one.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicting_least_recently_used() {
+        let mut cache = LruCache::new(3);
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.insert("c", 3);
+        assert_eq!(cache.get("a"), Some(&1));
+        assert_eq!(cache.peek("b"), Some(&2));
+        cache.insert("d", 4);
+        assert_eq!(cache.len(), 3);
+        assert!(!cache.contains_key("b"));
+        assert_eq!(cache.keys().copied().collect_vec(), vec!["d", "a", "c"]);
+        assert_eq!(cache.insert("c", 30), Some(3));
+        assert_eq!(cache.iter().collect_vec(), vec![(&"c", &30), (&"d", &4), (&"a", &1)]);
+        cache.set_capacity(1);
+        assert_eq!(cache.keys().copied().collect_vec(), vec!["c"]);
+        assert_eq!(cache.pop_lru(), Some(("c", 30)));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn weighted_entries() {
+        let mut cache = LruCache::new(10);
+        cache.insert_weighted(1, "a", 4);
+        cache.insert_weighted(2, "b", 4);
+        assert_eq!(cache.weight(), 8);
+        cache.insert_weighted(3, "c", 5);
+        assert_eq!(cache.keys().copied().collect_vec(), vec![3, 2]);
+        assert_eq!(cache.weight(), 9);
+        cache.insert_weighted(4, "d", 11);
+        assert!(cache.is_empty());
+        assert_eq!(cache.weight(), 0);
+    }
+}
@@ -0,0 +1,184 @@
+//! A map from keys to groups of values, never keeping empty groups around.
+
+use crate::prelude::*;
+
+use std::borrow::Borrow;
+use std::hash::Hash;
+
+
+
+// ================
+// === MultiMap ===
+// ================
+
+/// A map from keys to non-empty groups of values, in the order of insertion within a group. A
+/// replacement for `HashMap<K, Vec<V>>`, which removes the group of a key together with its last
+/// value, so empty vectors do not accumulate.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+#[derive(Clone, Debug)]
+pub struct MultiMap<K, V> {
+    groups: HashMap<K, Vec<V>>,
+    len:    usize,
+}
+
+impl<K: Eq + Hash, V> MultiMap<K, V> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// The number of values in all groups.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the map contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of keys with values.
+    pub fn key_count(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Check if the key has any values.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        self.groups.contains_key(key)
+    }
+
+    /// Append the value to the group of the key.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.groups.entry(key).or_default().push(value);
+        self.len += 1;
+    }
+
+    /// Append the values to the group of the key.
+    pub fn extend(&mut self, key: K, values: impl IntoIterator<Item = V>) {
+        let values = values.into_iter().collect_vec();
+        if !values.is_empty() {
+            self.len += values.len();
+            self.groups.entry(key).or_default().extend(values);
+        }
+    }
+
+    /// The values of the key. Empty if the key has no values.
+    pub fn get<Q>(&self, key: &Q) -> &[V]
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        self.groups.get(key).map_or(&[], |group| group)
+    }
+
+    /// Remove the first value of the key matching the predicate, keeping the order of the rest of
+    /// the group.
+    pub fn remove_first_where<Q>(&mut self, key: &Q, f: impl FnMut(&V) -> bool) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        let group = self.groups.get_mut(key)?;
+        let value = group.remove(group.iter().position(f)?);
+        if group.is_empty() {
+            self.groups.remove(key);
+        }
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Remove the first value of the key equal to the provided one. See
+    /// [`Self::remove_first_where`].
+    pub fn remove_value<Q>(&mut self, key: &Q, value: &V) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        V: PartialEq, {
+        self.remove_first_where(key, |v| v == value)
+    }
+
+    /// Remove all values of the key, returning them.
+    pub fn remove<Q>(&mut self, key: &Q) -> Vec<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        let group = self.groups.remove(key).unwrap_or_default();
+        self.len -= group.len();
+        group
+    }
+
+    /// Keep only the values matching the predicate.
+    pub fn retain(&mut self, mut f: impl FnMut(&K, &V) -> bool) {
+        let mut len = 0;
+        self.groups.retain(|key, group| {
+            group.retain(|value| f(key, value));
+            len += group.len();
+            !group.is_empty()
+        });
+        self.len = len;
+    }
+
+    /// Remove all values.
+    pub fn clear(&mut self) {
+        self.groups.clear();
+        self.len = 0;
+    }
+
+    /// Iterator over the keys and their groups of values, in arbitrary order of keys.
+    pub fn groups(&self) -> impl Iterator<Item = (&K, &[V])> {
+        self.groups.iter().map(|(key, group)| (key, &group[..]))
+    }
+
+    /// Iterator over the keys, in arbitrary order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.groups.keys()
+    }
+
+    /// Iterator over all values and their keys, with the values of a key iterated in a row.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.groups.iter().flat_map(|(key, group)| group.iter().map(move |value| (key, value)))
+    }
+}
+
+impl<K: Eq + Hash, V> FromIterator<(K, V)> for MultiMap<K, V> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut map = Self::new();
+        for (key, value) in iter {
+            map.insert(key, value);
+        }
+        map
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_are_removed_with_last_value() {
+        let mut map: MultiMap<&str, i32> = [("a", 1), ("b", 2), ("a", 3)].into_iter().collect();
+        map.extend("a", [4, 3]);
+        assert_eq!(map.len(), 5);
+        assert_eq!(map.key_count(), 2);
+        assert_eq!(map.get("a"), &[1, 3, 4, 3]);
+        assert_eq!(map.remove_value("a", &3), Some(3));
+        assert_eq!(map.get("a"), &[1, 4, 3]);
+        assert_eq!(map.remove_value("b", &2), Some(2));
+        assert_eq!(map.remove_value("b", &2), None);
+        assert!(!map.contains_key("b"));
+        assert!(map.get("b").is_empty());
+        map.retain(|_, value| *value != 1);
+        assert_eq!(map.iter().collect_vec(), vec![(&"a", &4), (&"a", &3)]);
+        assert_eq!(map.remove("a"), vec![4, 3]);
+        assert!(map.is_empty());
+        assert_eq!(map.key_count(), 0);
+    }
+}
@@ -0,0 +1,341 @@
+//! Collections recording their structural changes, so observers can mirror them incrementally
+//! instead of diffing whole snapshots. The changes are accumulated until [`ObservableVec::flush`]
+//! or [`ObservableHashMap::flush`] is called, and then delivered as a single batch to every
+//! [`Subscription`]. Typically, the collection is flushed once per frame.
+
+use crate::prelude::*;
+
+use std::collections::VecDeque;
+use std::hash::Hash;
+
+
+
+// ====================
+// === Subscription ===
+// ====================
+
+/// A batch of changes, shared between all subscriptions.
+pub type Batch<C> = Rc<Vec<C>>;
+
+/// A subscription to the changes of an observable collection. The batches are queued until taken
+/// with [`Subscription::take`]. Dropping the subscription unsubscribes it.
+#[derive(Debug)]
+pub struct Subscription<C> {
+    batches: Rc<RefCell<VecDeque<Batch<C>>>>,
+}
+
+impl<C> Subscription<C> {
+    /// Take all batches delivered since the last call, oldest first.
+    pub fn take(&self) -> Vec<Batch<C>> {
+        self.batches.borrow_mut().drain(..).collect()
+    }
+
+    /// Check if there are any batches to take.
+    pub fn has_pending(&self) -> bool {
+        !self.batches.borrow().is_empty()
+    }
+}
+
+
+
+// ================
+// === Notifier ===
+// ================
+
+/// Accumulates the changes and delivers them to subscriptions. The changes are not recorded at all
+/// while there are no subscriptions.
+#[derive(Debug)]
+struct Notifier<C> {
+    pending:     Vec<C>,
+    subscribers: Vec<Weak<RefCell<VecDeque<Batch<C>>>>>,
+}
+
+impl<C> Default for Notifier<C> {
+    fn default() -> Self {
+        Self { pending: default(), subscribers: default() }
+    }
+}
+
+impl<C> Notifier<C> {
+    fn subscribe(&mut self) -> Subscription<C> {
+        let batches: Rc<RefCell<VecDeque<Batch<C>>>> = default();
+        self.subscribers.push(Rc::downgrade(&batches));
+        Subscription { batches }
+    }
+
+    fn remove_dropped_subscribers(&mut self) {
+        self.subscribers.retain(|subscriber| subscriber.strong_count() > 0);
+    }
+
+    fn record(&mut self, change: impl FnOnce() -> C) {
+        if !self.subscribers.is_empty() {
+            self.pending.push(change());
+        }
+    }
+
+    fn flush(&mut self) {
+        self.remove_dropped_subscribers();
+        if !self.subscribers.is_empty() && !self.pending.is_empty() {
+            let batch = Rc::new(mem::take(&mut self.pending));
+            for subscriber in self.subscribers.iter().filter_map(Weak::upgrade) {
+                subscriber.borrow_mut().push_back(batch.clone());
+            }
+        } else {
+            self.pending.clear();
+        }
+    }
+}
+
+
+
+// =====================
+// === ObservableVec ===
+// =====================
+
+/// A structural change of the [`ObservableVec`]. The indices refer to the state of the vector
+/// right after the preceding change of the batch was applied.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum VecChange<T> {
+    Insert { index: usize, value: T },
+    Update { index: usize, value: T },
+    Remove { index: usize },
+    Clear,
+}
+
+/// A vector recording its structural changes. See the module docs to learn more.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+#[derive(Debug)]
+pub struct ObservableVec<T> {
+    elements: Vec<T>,
+    notifier: Notifier<VecChange<T>>,
+}
+
+impl<T> Deref for ObservableVec<T> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        &self.elements
+    }
+}
+
+impl<T: Clone> ObservableVec<T> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Subscribe to the changes recorded from now on.
+    pub fn subscribe(&mut self) -> Subscription<VecChange<T>> {
+        self.notifier.subscribe()
+    }
+
+    /// Deliver the changes recorded since the last flush to all subscriptions.
+    pub fn flush(&mut self) {
+        self.notifier.flush()
+    }
+
+    /// Append the element.
+    pub fn push(&mut self, value: T) {
+        self.insert(self.elements.len(), value)
+    }
+
+    /// Insert the element at the index, shifting the following elements.
+    pub fn insert(&mut self, index: usize, value: T) {
+        self.notifier.record(|| VecChange::Insert { index, value: value.clone() });
+        self.elements.insert(index, value);
+    }
+
+    /// Replace the element at the index, returning the old one.
+    pub fn set(&mut self, index: usize, value: T) -> T {
+        self.notifier.record(|| VecChange::Update { index, value: value.clone() });
+        mem::replace(&mut self.elements[index], value)
+    }
+
+    /// Modify the element at the index in place.
+    pub fn update(&mut self, index: usize, f: impl FnOnce(&mut T)) {
+        let element = &mut self.elements[index];
+        f(element);
+        self.notifier.record(|| VecChange::Update { index, value: element.clone() });
+    }
+
+    /// Remove the element at the index, shifting the following elements.
+    pub fn remove(&mut self, index: usize) -> T {
+        let value = self.elements.remove(index);
+        self.notifier.record(|| VecChange::Remove { index });
+        value
+    }
+
+    /// Remove the last element.
+    pub fn pop(&mut self) -> Option<T> {
+        let index = self.elements.len().checked_sub(1)?;
+        Some(self.remove(index))
+    }
+
+    /// Remove all elements.
+    pub fn clear(&mut self) {
+        self.elements.clear();
+        self.notifier.record(|| VecChange::Clear);
+    }
+}
+
+impl<T: Clone> From<Vec<T>> for ObservableVec<T> {
+    fn from(elements: Vec<T>) -> Self {
+        Self { elements, notifier: default() }
+    }
+}
+
+
+
+// =========================
+// === ObservableHashMap ===
+// =========================
+
+/// A structural change of the [`ObservableHashMap`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum MapChange<K, V> {
+    Insert { key: K, value: V },
+    Update { key: K, value: V },
+    Remove { key: K },
+    Clear,
+}
+
+/// A hash map recording its structural changes. See the module docs to learn more.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+#[derive(Debug)]
+pub struct ObservableHashMap<K, V> {
+    entries:  HashMap<K, V>,
+    notifier: Notifier<MapChange<K, V>>,
+}
+
+impl<K, V> Deref for ObservableHashMap<K, V> {
+    type Target = HashMap<K, V>;
+    fn deref(&self) -> &Self::Target {
+        &self.entries
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> ObservableHashMap<K, V> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Subscribe to the changes recorded from now on.
+    pub fn subscribe(&mut self) -> Subscription<MapChange<K, V>> {
+        self.notifier.subscribe()
+    }
+
+    /// Deliver the changes recorded since the last flush to all subscriptions.
+    pub fn flush(&mut self) {
+        self.notifier.flush()
+    }
+
+    /// Insert the entry, returning the previous value of the key. Recorded as an update if the key
+    /// was present.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let old_value = self.entries.insert(key.clone(), value);
+        let entries = &self.entries;
+        let was_present = old_value.is_some();
+        self.notifier.record(|| {
+            let value = entries[&key].clone();
+            match was_present {
+                true => MapChange::Update { key, value },
+                false => MapChange::Insert { key, value },
+            }
+        });
+        old_value
+    }
+
+    /// Modify the value of the key in place. Returns `false` if the key is not present.
+    pub fn update(&mut self, key: &K, f: impl FnOnce(&mut V)) -> bool {
+        match self.entries.get_mut(key) {
+            Some(value) => {
+                f(value);
+                let change = || MapChange::Update { key: key.clone(), value: value.clone() };
+                self.notifier.record(change);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove the entry, returning its value.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let value = self.entries.remove(key)?;
+        self.notifier.record(|| MapChange::Remove { key: key.clone() });
+        Some(value)
+    }
+
+    /// Remove all entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.notifier.record(|| MapChange::Clear);
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> From<HashMap<K, V>> for ObservableHashMap<K, V> {
+    fn from(entries: HashMap<K, V>) -> Self {
+        Self { entries, notifier: default() }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vec_changes() {
+        let mut vec = ObservableVec::from(vec![1, 2]);
+        vec.push(0);
+        let subscription = vec.subscribe();
+        vec.push(3);
+        vec.update(0, |value| *value *= 10);
+        assert_eq!(vec.remove(1), 2);
+        assert!(!subscription.has_pending());
+        vec.flush();
+        vec.flush();
+        vec.clear();
+        vec.flush();
+        let batches = subscription.take();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(*batches[0], vec![
+            VecChange::Insert { index: 3, value: 3 },
+            VecChange::Update { index: 0, value: 10 },
+            VecChange::Remove { index: 1 },
+        ]);
+        assert_eq!(*batches[1], vec![VecChange::Clear]);
+        assert!(subscription.take().is_empty());
+    }
+
+    #[test]
+    fn map_changes_and_unsubscribing() {
+        let mut map = ObservableHashMap::new();
+        let first = map.subscribe();
+        let second = map.subscribe();
+        map.insert("a", 1);
+        map.insert("a", 2);
+        map.update(&"a", |value| *value += 1);
+        map.flush();
+        drop(first);
+        map.remove(&"a");
+        map.flush();
+        assert!(map.is_empty());
+        let batches = second.take();
+        assert_eq!(*batches[0], vec![
+            MapChange::Insert { key: "a", value: 1 },
+            MapChange::Update { key: "a", value: 2 },
+            MapChange::Update { key: "a", value: 3 },
+        ]);
+        assert_eq!(*batches[1], vec![MapChange::Remove { key: "a" }]);
+        assert_eq!(map.notifier.subscribers.len(), 1);
+    }
+}
@@ -0,0 +1,188 @@
+//! A hash map iterated in the order of insertion, for the places where the iteration order must be
+//! deterministic, but not sorted by keys.
+
+use crate::prelude::*;
+
+use std::borrow::Borrow;
+use std::hash::Hash;
+
+
+
+// ==================
+// === OrderedMap ===
+// ==================
+
+/// A hash map preserving the insertion order of its keys. Re-inserting a present key keeps its
+/// position. Lookups, insertions, and removals take amortized O(1) time.
+///
+/// Removed entries leave holes in the entry vector, which are compacted once they outnumber the
+/// present entries.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+#[derive(Clone, Debug)]
+pub struct OrderedMap<K, V> {
+    entries:   Vec<Option<(K, V)>>,
+    positions: HashMap<K, usize>,
+}
+
+impl<K: Clone + Eq + Hash, V> OrderedMap<K, V> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Check if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Check if the map contains the key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        self.positions.contains_key(key)
+    }
+
+    /// Insert the entry, returning the previous value of the key. A new key is placed after all
+    /// present ones, while a present key keeps its position.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.positions.get(&key) {
+            Some(&position) => {
+                let (_, old_value) = self.entries[position].as_mut().expect("Missing entry.");
+                Some(mem::replace(old_value, value))
+            }
+            None => {
+                self.positions.insert(key.clone(), self.entries.len());
+                self.entries.push(Some((key, value)));
+                None
+            }
+        }
+    }
+
+    /// The value of the key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        let position = *self.positions.get(key)?;
+        self.entries[position].as_ref().map(|(_, value)| value)
+    }
+
+    /// The mutable value of the key.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        let position = *self.positions.get(key)?;
+        self.entries[position].as_mut().map(|(_, value)| value)
+    }
+
+    /// Remove the entry, returning its value. The order of other entries is preserved.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        let position = self.positions.remove(key)?;
+        let (_, value) = self.entries[position].take().expect("Missing entry.");
+        if self.entries.len() > 2 * self.positions.len() {
+            self.compact();
+        }
+        Some(value)
+    }
+
+    /// Remove all entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.positions.clear();
+    }
+
+    /// Iterator over the entries, in the order of insertion.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> {
+        self.entries.iter().flatten().map(|(key, value)| (key, value))
+    }
+
+    /// Mutable iterator over the entries, in the order of insertion.
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = (&K, &mut V)> {
+        self.entries.iter_mut().flatten().map(|(key, value)| (&*key, value))
+    }
+
+    /// Iterator over the keys, in the order of insertion.
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Iterator over the values, in the order of insertion of their keys.
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Remove the holes left by the removed entries.
+    fn compact(&mut self) {
+        self.entries.retain(Option::is_some);
+        for (position, entry) in self.entries.iter().enumerate() {
+            if let Some((key, _)) = entry {
+                self.positions.insert(key.clone(), position);
+            }
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash, V> FromIterator<(K, V)> for OrderedMap<K, V> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut map = Self::new();
+        for (key, value) in iter {
+            map.insert(key, value);
+        }
+        map
+    }
+}
+
+impl<K: Clone + Eq + Hash, V> IntoIterator for OrderedMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = std::iter::Flatten<std::vec::IntoIter<Option<(K, V)>>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter().flatten()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insertion_order_is_preserved() {
+        let mut map: OrderedMap<&str, i32> = [("c", 1), ("a", 2), ("b", 3)].into_iter().collect();
+        assert_eq!(map.insert("a", 20), Some(2));
+        assert_eq!(map.keys().copied().collect_vec(), vec!["c", "a", "b"]);
+        assert_eq!(map.remove("c"), Some(1));
+        map.insert("c", 4);
+        *map.get_mut("b").unwrap() = 30;
+        assert_eq!(map.iter().collect_vec(), vec![(&"a", &20), (&"b", &30), (&"c", &4)]);
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get("d"), None);
+    }
+
+    #[test]
+    fn compaction_keeps_lookups_valid() {
+        let mut map: OrderedMap<usize, usize> = (0..100).map(|key| (key, key)).collect();
+        for key in (0..100).filter(|key| key % 10 != 0) {
+            map.remove(&key);
+        }
+        assert!(map.entries.len() < 20);
+        assert!((0..100).step_by(10).all(|key| map.get(&key) == Some(&key)));
+        let expected = (0..100).step_by(10).map(|key| (key, key)).collect_vec();
+        assert_eq!(map.into_iter().collect_vec(), expected);
+    }
+}
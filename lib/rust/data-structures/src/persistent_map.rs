@@ -0,0 +1,338 @@
+//! A persistent hash map: cloning it is O(1), and the clones share all the structure which was not
+//! modified since. Useful for keeping snapshots of a state, like for undo and redo.
+
+use crate::prelude::*;
+
+use std::hash::Hash;
+use std::hash::Hasher;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The number of hash bits consumed by every level of the tree.
+const BITS_PER_LEVEL: u32 = 5;
+/// The mask of hash bits consumed by a single level of the tree.
+const LEVEL_MASK: u64 = (1 << BITS_PER_LEVEL) - 1;
+
+
+
+// ============
+// === Node ===
+// ============
+
+/// A node of the hash array mapped trie. Every branch has up to 32 children, indexed by the
+/// consecutive 5 bits of the key hashes. Only the present children are stored, in the order of
+/// their bits in the bitmap.
+#[derive(Clone, Debug)]
+enum Node<K, V> {
+    Branch {
+        bitmap:   u32,
+        children: Vec<Rc<Node<K, V>>>,
+    },
+    /// The entries with the same hash. Usually, there is a single entry.
+    Leaf {
+        hash:    u64,
+        entries: Vec<(K, V)>,
+    },
+}
+
+impl<K, V> Default for Node<K, V> {
+    fn default() -> Self {
+        Self::Branch { bitmap: 0, children: default() }
+    }
+}
+
+/// The bit of the hash at the level starting at the shift, and the position of the child of that
+/// bit in the branch.
+fn child_bit_and_position(bitmap: u32, hash: u64, shift: u32) -> (u32, usize) {
+    let bit = 1 << ((hash >> shift) & LEVEL_MASK);
+    let position = (bitmap & (bit - 1)).count_ones() as usize;
+    (bit, position)
+}
+
+impl<K: Clone + Eq, V: Clone> Node<K, V> {
+    fn is_empty(&self) -> bool {
+        match self {
+            Self::Branch { children, .. } => children.is_empty(),
+            Self::Leaf { entries, .. } => entries.is_empty(),
+        }
+    }
+
+    /// The only child of the branch, if it is a leaf.
+    fn single_leaf(&self) -> Option<Rc<Self>> {
+        match self {
+            Self::Branch { children, .. } => match &children[..] {
+                [child] if matches!(**child, Self::Leaf { .. }) => Some(child.clone()),
+                _ => None,
+            },
+            Self::Leaf { .. } => None,
+        }
+    }
+
+    fn get(&self, hash: u64, shift: u32, key: &K) -> Option<&V> {
+        match self {
+            Self::Branch { bitmap, children } => {
+                let (bit, position) = child_bit_and_position(*bitmap, hash, shift);
+                let child = (bitmap & bit != 0).as_some_from(|| &children[position])?;
+                child.get(hash, shift + BITS_PER_LEVEL, key)
+            }
+            Self::Leaf { entries, .. } => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+        }
+    }
+
+    fn insert(node: &mut Rc<Self>, hash: u64, shift: u32, key: K, value: V) -> Option<V> {
+        if let Self::Leaf { hash: leaf_hash, .. } = **node {
+            if leaf_hash != hash {
+                // Push the leaf one level down, so the hashes can be told apart.
+                let (bit, _) = child_bit_and_position(0, leaf_hash, shift);
+                let leaf = mem::take(node);
+                *node = Rc::new(Self::Branch { bitmap: bit, children: vec![leaf] });
+            }
+        }
+        match Rc::make_mut(node) {
+            Self::Branch { bitmap, children } => {
+                let (bit, position) = child_bit_and_position(*bitmap, hash, shift);
+                if *bitmap & bit != 0 {
+                    Self::insert(&mut children[position], hash, shift + BITS_PER_LEVEL, key, value)
+                } else {
+                    *bitmap |= bit;
+                    let leaf = Self::Leaf { hash, entries: vec![(key, value)] };
+                    children.insert(position, Rc::new(leaf));
+                    None
+                }
+            }
+            Self::Leaf { entries, .. } => match entries.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, old_value)) => Some(mem::replace(old_value, value)),
+                None => {
+                    entries.push((key, value));
+                    None
+                }
+            },
+        }
+    }
+
+    /// Remove the entry, which must be present in the subtree.
+    fn remove(node: &mut Rc<Self>, hash: u64, shift: u32, key: &K) -> Option<V> {
+        match Rc::make_mut(node) {
+            Self::Branch { bitmap, children } => {
+                let (bit, position) = child_bit_and_position(*bitmap, hash, shift);
+                let child = &mut children[position];
+                let value = Self::remove(child, hash, shift + BITS_PER_LEVEL, key);
+                if child.is_empty() {
+                    *bitmap &= !bit;
+                    children.remove(position);
+                } else if let Some(leaf) = child.single_leaf() {
+                    // The leaf does not need the branch to be told apart from other entries.
+                    *child = leaf;
+                }
+                value
+            }
+            Self::Leaf { entries, .. } => {
+                let position = entries.iter().position(|(k, _)| k == key)?;
+                Some(entries.swap_remove(position).1)
+            }
+        }
+    }
+}
+
+
+
+// =====================
+// === PersistentMap ===
+// =====================
+
+/// A hash map with O(1) cloning. Clones share their structure, and modifying one of them copies
+/// only the O(log n) nodes on the path to the modified entry, which are shared with other clones.
+/// Both lookups and modifications take O(log n) time, where the logarithm base is 32.
+///
+/// The hashes are computed with a fixed hasher, so the structure of the map is deterministic.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Default(bound = ""))]
+#[derive(Debug)]
+pub struct PersistentMap<K, V> {
+    root: Rc<Node<K, V>>,
+    len:  usize,
+}
+
+fn hash_of<K: Hash>(key: &K) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> PersistentMap<K, V> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The value of the key.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.root.get(hash_of(key), 0, key)
+    }
+
+    /// Check if the map contains the key.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Insert the entry, returning the previous value of the key. The clones of the map are not
+    /// affected.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let old_value = Node::insert(&mut self.root, hash_of(&key), 0, key, value);
+        if old_value.is_none() {
+            self.len += 1;
+        }
+        old_value
+    }
+
+    /// Remove the entry, returning its value. The clones of the map are not affected.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        // Checked first, so no nodes are copied if the key is not present.
+        if !self.contains_key(key) {
+            return None;
+        }
+        let value = Node::remove(&mut self.root, hash_of(key), 0, key);
+        self.len -= 1;
+        value
+    }
+
+    /// A copy of the map with the entry inserted. See [`Self::insert`].
+    pub fn inserted(&self, key: K, value: V) -> Self {
+        let mut map = self.clone();
+        map.insert(key, value);
+        map
+    }
+
+    /// A copy of the map with the entry removed. See [`Self::remove`].
+    pub fn removed(&self, key: &K) -> Self {
+        let mut map = self.clone();
+        map.remove(key);
+        map
+    }
+
+    /// Iterator over all entries, in arbitrary order.
+    pub fn iter(&self) -> Iter<K, V> {
+        Iter { stack: vec![&self.root], entries: [].iter() }
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> FromIterator<(K, V)> for PersistentMap<K, V> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut map = Self::new();
+        for (key, value) in iter {
+            map.insert(key, value);
+        }
+        map
+    }
+}
+
+
+
+// ============
+// === Iter ===
+// ============
+
+/// Iterator over the entries of a [`PersistentMap`].
+#[derive(Debug)]
+pub struct Iter<'a, K, V> {
+    stack:   Vec<&'a Node<K, V>>,
+    entries: std::slice::Iter<'a, (K, V)>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((key, value)) = self.entries.next() {
+                return Some((key, value));
+            }
+            match self.stack.pop()? {
+                Node::Branch { children, .. } => self.stack.extend(children.iter().map(|c| &**c)),
+                Node::Leaf { entries, .. } => self.entries = entries.iter(),
+            }
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A key with a hash colliding with the keys of the same parity.
+    #[derive(Clone, Debug, Eq)]
+    struct Colliding(u32);
+
+    impl PartialEq for Colliding {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    impl Hash for Colliding {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            (self.0 % 2).hash(state)
+        }
+    }
+
+    #[test]
+    fn snapshots_are_independent() {
+        let mut map: PersistentMap<u32, u32> = (0..1000).map(|key| (key, key)).collect();
+        let snapshot = map.clone();
+        for key in (0..1000).step_by(2) {
+            assert_eq!(map.remove(&key), Some(key));
+        }
+        assert_eq!(map.insert(1, 10), Some(1));
+        let modified = map.inserted(2000, 2000);
+        assert_eq!(map.len(), 500);
+        assert_eq!(modified.len(), 501);
+        assert_eq!(snapshot.len(), 1000);
+        assert!((0..1000).all(|key| snapshot.get(&key) == Some(&key)));
+        assert_eq!(map.get(&1), Some(&10));
+        assert_eq!(map.get(&2), None);
+        assert_eq!(map.get(&2000), None);
+        assert_eq!(modified.get(&2000), Some(&2000));
+        assert_eq!(map.iter().count(), 500);
+        let mut keys = modified.removed(&2000).iter().map(|(key, _)| *key).collect_vec();
+        keys.sort_unstable();
+        assert_eq!(keys, (1..1000).step_by(2).collect_vec());
+    }
+
+    #[test]
+    fn colliding_hashes() {
+        let mut map = PersistentMap::new();
+        for key in 0..6 {
+            map.insert(Colliding(key), key);
+        }
+        map.insert(Colliding(2), 20);
+        assert_eq!(map.remove(&Colliding(4)), Some(4));
+        assert_eq!(map.remove(&Colliding(4)), None);
+        assert_eq!(map.len(), 5);
+        assert_eq!(map.get(&Colliding(2)), Some(&20));
+        assert_eq!(map.get(&Colliding(3)), Some(&3));
+        for key in [0, 1, 2, 3, 5] {
+            map.remove(&Colliding(key));
+        }
+        assert!(map.is_empty());
+        assert!(map.root.is_empty());
+    }
+}
@@ -0,0 +1,221 @@
+//! A priority queue returning handles to its entries, so arbitrary entries can be reprioritized
+//! or removed, which [`std::collections::BinaryHeap`] does not allow.
+
+use crate::prelude::*;
+
+use crate::slot_map;
+use crate::slot_map::SlotMap;
+
+
+
+// ==============
+// === Handle ===
+// ==============
+
+/// A handle to an entry of the [`PriorityQueue`]. It becomes stale once the entry is popped or
+/// removed, and does not refer to the entries pushed later, see [`SlotMap`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Handle {
+    key: slot_map::Key,
+}
+
+
+
+// =====================
+// === PriorityQueue ===
+// =====================
+
+#[derive(Clone, Debug)]
+struct Entry<P, T> {
+    priority: P,
+    value:    T,
+    /// The position of the entry in the heap.
+    position: usize,
+}
+
+/// A min-heap of values with priorities: the value with the lowest priority is popped first, for
+/// example, the task with the earliest deadline. Entries with equal priorities are popped in an
+/// unspecified order.
+///
+/// Pushing, popping, removing, and changing the priority take O(log n) time.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+#[derive(Clone, Debug)]
+pub struct PriorityQueue<P, T> {
+    entries: SlotMap<Entry<P, T>>,
+    heap:    Vec<slot_map::Key>,
+}
+
+impl<P: Ord, T> PriorityQueue<P, T> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Check if the queue contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Check if the handle refers to an entry which was not popped nor removed yet.
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.entries.contains_key(handle.key)
+    }
+
+    /// Push the value, returning the handle to its entry.
+    pub fn push(&mut self, priority: P, value: T) -> Handle {
+        let position = self.heap.len();
+        let key = self.entries.insert(Entry { priority, value, position });
+        self.heap.push(key);
+        self.sift_up(position);
+        Handle { key }
+    }
+
+    /// The entry with the lowest priority.
+    pub fn peek(&self) -> Option<(Handle, &P, &T)> {
+        let key = *self.heap.first()?;
+        let entry = &self.entries[key];
+        Some((Handle { key }, &entry.priority, &entry.value))
+    }
+
+    /// Remove the entry with the lowest priority.
+    pub fn pop(&mut self) -> Option<(P, T)> {
+        let key = *self.heap.first()?;
+        self.remove(Handle { key })
+    }
+
+    /// Remove the entry. Returns `None` if the handle is stale.
+    pub fn remove(&mut self, handle: Handle) -> Option<(P, T)> {
+        let entry = self.entries.remove(handle.key)?;
+        let last = self.heap.pop().expect("Entry missing from the heap.");
+        if entry.position < self.heap.len() {
+            self.heap[entry.position] = last;
+            self.entries[last].position = entry.position;
+            self.restore(entry.position);
+        }
+        Some((entry.priority, entry.value))
+    }
+
+    /// The value of the entry.
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.entries.get(handle.key).map(|entry| &entry.value)
+    }
+
+    /// The mutable value of the entry.
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        self.entries.get_mut(handle.key).map(|entry| &mut entry.value)
+    }
+
+    /// The priority of the entry.
+    pub fn priority(&self, handle: Handle) -> Option<&P> {
+        self.entries.get(handle.key).map(|entry| &entry.priority)
+    }
+
+    /// Change the priority of the entry, returning the old one. Returns `None` if the handle is
+    /// stale.
+    pub fn set_priority(&mut self, handle: Handle, priority: P) -> Option<P> {
+        let entry = self.entries.get_mut(handle.key)?;
+        let old_priority = mem::replace(&mut entry.priority, priority);
+        let position = entry.position;
+        self.restore(position);
+        Some(old_priority)
+    }
+
+    /// Remove all entries, making all handles stale.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.heap.clear();
+    }
+
+    /// Iterator over all entries, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &P, &T)> {
+        self.entries.iter().map(|(key, entry)| (Handle { key }, &entry.priority, &entry.value))
+    }
+
+    fn priority_at(&self, position: usize) -> &P {
+        &self.entries[self.heap[position]].priority
+    }
+
+    fn swap(&mut self, first: usize, second: usize) {
+        self.heap.swap(first, second);
+        self.entries[self.heap[first]].position = first;
+        self.entries[self.heap[second]].position = second;
+    }
+
+    /// Move the entry at the position up or down, until the heap property holds.
+    fn restore(&mut self, position: usize) {
+        let position = self.sift_up(position);
+        self.sift_down(position);
+    }
+
+    fn sift_up(&mut self, mut position: usize) -> usize {
+        while position > 0 {
+            let parent = (position - 1) / 2;
+            if self.priority_at(position) >= self.priority_at(parent) {
+                break;
+            }
+            self.swap(position, parent);
+            position = parent;
+        }
+        position
+    }
+
+    fn sift_down(&mut self, mut position: usize) {
+        loop {
+            let children = [2 * position + 1, 2 * position + 2];
+            let children = children.into_iter().filter(|child| *child < self.heap.len());
+            let smallest = children.min_by(|a, b| self.priority_at(*a).cmp(self.priority_at(*b)));
+            match smallest {
+                Some(child) if self.priority_at(child) < self.priority_at(position) => {
+                    self.swap(position, child);
+                    position = child;
+                }
+                _ => break,
+            }
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn popping_in_priority_order() {
+        let mut queue = PriorityQueue::new();
+        for (priority, value) in [(5, "e"), (1, "a"), (4, "d"), (2, "b"), (3, "c")] {
+            queue.push(priority, value);
+        }
+        assert_eq!(queue.peek().map(|(_, p, v)| (*p, *v)), Some((1, "a")));
+        let values = iter::from_fn(|| queue.pop()).map(|(_, value)| value).collect_vec();
+        assert_eq!(values, vec!["a", "b", "c", "d", "e"]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn changing_priorities_and_removing() {
+        let mut queue = PriorityQueue::new();
+        let handles = (0..10).map(|value| queue.push(value * 10, value)).collect_vec();
+        assert_eq!(queue.set_priority(handles[7], 5), Some(70));
+        assert_eq!(queue.set_priority(handles[0], 100), Some(0));
+        assert_eq!(queue.remove(handles[3]), Some((30, 3)));
+        assert_eq!(queue.remove(handles[3]), None);
+        assert!(!queue.contains(handles[3]));
+        *queue.get_mut(handles[9]).unwrap() = 99;
+        assert_eq!(queue.priority(handles[9]), Some(&90));
+        let values = iter::from_fn(|| queue.pop()).map(|(_, value)| value).collect_vec();
+        assert_eq!(values, vec![7, 1, 2, 4, 5, 6, 8, 99, 0]);
+        assert_eq!(queue.get(handles[7]), None);
+    }
+}
@@ -0,0 +1,174 @@
+//! Fixed-capacity buffers keeping the most recent elements, like a history of recent logs or a
+//! window of performance samples.
+
+use crate::prelude::*;
+
+use std::collections::VecDeque;
+
+
+
+// ==================
+// === RingBuffer ===
+// ==================
+
+/// A buffer of a fixed capacity. Pushing to a full buffer overwrites its oldest element.
+#[derive(Clone, Debug)]
+pub struct RingBuffer<T> {
+    elements: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T> RingBuffer<T> {
+    /// Constructor. Panics if the capacity is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "Ring buffer capacity must be positive.");
+        Self { elements: VecDeque::with_capacity(capacity), capacity }
+    }
+
+    /// The maximum number of elements.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Check if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Check if the next push will overwrite the oldest element.
+    pub fn is_full(&self) -> bool {
+        self.elements.len() == self.capacity
+    }
+
+    /// Push the element as the newest one, returning the overwritten oldest element, if the buffer
+    /// was full.
+    pub fn push(&mut self, element: T) -> Option<T> {
+        let overwritten = self.is_full().and_option_from(|| self.elements.pop_front());
+        self.elements.push_back(element);
+        overwritten
+    }
+
+    /// Remove the oldest element.
+    pub fn pop_oldest(&mut self) -> Option<T> {
+        self.elements.pop_front()
+    }
+
+    /// The oldest element.
+    pub fn oldest(&self) -> Option<&T> {
+        self.elements.front()
+    }
+
+    /// The newest element.
+    pub fn newest(&self) -> Option<&T> {
+        self.elements.back()
+    }
+
+    /// The element at the index, counting from the oldest one.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.elements.get(index)
+    }
+
+    /// Remove all elements.
+    pub fn clear(&mut self) {
+        self.elements.clear()
+    }
+
+    /// Iterator over the elements, from the oldest to the newest one.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.elements.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RingBuffer<T> {
+    type Item = &'a T;
+    type IntoIter = std::collections::vec_deque::Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.elements.iter()
+    }
+}
+
+
+
+// =============================
+// === TimestampedRingBuffer ===
+// =============================
+
+/// A [`RingBuffer`] of elements with the times they were pushed at. The time type is provided by
+/// the caller, as the clocks differ between platforms, for example, it can be the frame time. The
+/// times are expected to be non-decreasing.
+#[derive(Clone, Debug)]
+pub struct TimestampedRingBuffer<Time, T> {
+    buffer: RingBuffer<(Time, T)>,
+}
+
+impl<Time: Copy + PartialOrd, T> TimestampedRingBuffer<Time, T> {
+    /// Constructor. Panics if the capacity is zero.
+    pub fn new(capacity: usize) -> Self {
+        Self { buffer: RingBuffer::new(capacity) }
+    }
+
+    /// The underlying buffer of the elements and their times.
+    pub fn buffer(&self) -> &RingBuffer<(Time, T)> {
+        &self.buffer
+    }
+
+    /// Push the element with the time. See [`RingBuffer::push`].
+    pub fn push(&mut self, time: Time, element: T) -> Option<(Time, T)> {
+        self.buffer.push((time, element))
+    }
+
+    /// Iterator over the elements pushed at or after the time, from the oldest to the newest one.
+    pub fn since(&self, time: Time) -> impl Iterator<Item = (Time, &T)> {
+        let start = self.buffer.elements.partition_point(|(pushed_at, _)| *pushed_at < time);
+        self.buffer.elements.range(start..).map(|(time, element)| (*time, element))
+    }
+
+    /// Remove the elements pushed before the time.
+    pub fn remove_older_than(&mut self, time: Time) {
+        while self.buffer.oldest().map_or(false, |(pushed_at, _)| *pushed_at < time) {
+            self.buffer.pop_oldest();
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overwriting_oldest() {
+        let mut buffer = RingBuffer::new(3);
+        assert_eq!(buffer.push(1), None);
+        buffer.push(2);
+        buffer.push(3);
+        assert!(buffer.is_full());
+        assert_eq!(buffer.push(4), Some(1));
+        assert_eq!(buffer.iter().copied().collect_vec(), vec![2, 3, 4]);
+        assert_eq!(buffer.oldest(), Some(&2));
+        assert_eq!(buffer.newest(), Some(&4));
+        assert_eq!(buffer.pop_oldest(), Some(2));
+        assert_eq!(buffer.len(), 2);
+    }
+
+    #[test]
+    fn timestamped_elements() {
+        let mut samples = TimestampedRingBuffer::new(4);
+        for (time, sample) in [(1.0, "a"), (2.0, "b"), (2.5, "c"), (4.0, "d"), (5.0, "e")] {
+            samples.push(time, sample);
+        }
+        assert_eq!(samples.since(2.2).collect_vec(), vec![(2.5, &"c"), (4.0, &"d"), (5.0, &"e")]);
+        samples.remove_older_than(4.0);
+        assert_eq!(samples.buffer().len(), 2);
+    }
+}
@@ -0,0 +1,288 @@
+//! A generational slot map: a sparse vector whose keys remember the generation of their slot, so
+//! the keys of removed elements are detected as stale even after their slots are reused.
+
+use crate::prelude::*;
+
+
+
+// ===============
+// === SlotKey ===
+// ===============
+
+/// A key of the [`SlotMap`], consisting of the slot index and the generation of the slot. Custom
+/// key types can be used to prevent mixing keys of different maps, see [`Key`] for the default
+/// one.
+pub trait SlotKey: Copy + Debug {
+    /// Constructor.
+    fn new(index: usize, generation: u32) -> Self;
+    /// The index of the slot.
+    fn index(self) -> usize;
+    /// The generation of the slot at the time the key was issued.
+    fn generation(self) -> u32;
+}
+
+/// The default key of the [`SlotMap`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[allow(missing_docs)]
+pub struct Key {
+    pub index:      usize,
+    pub generation: u32,
+}
+
+impl SlotKey for Key {
+    fn new(index: usize, generation: u32) -> Self {
+        Self { index, generation }
+    }
+
+    fn index(self) -> usize {
+        self.index
+    }
+
+    fn generation(self) -> u32 {
+        self.generation
+    }
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+
+
+// ===============
+// === SlotMap ===
+// ===============
+
+/// A single slot of the [`SlotMap`]. The generation is bumped every time the value is removed.
+#[derive(Clone, Debug)]
+struct Slot<T> {
+    generation: u32,
+    value:      Option<T>,
+}
+
+/// A container with O(1) insertion, removal, and access, addressed by keys issued on insertion.
+/// Similar to [`crate::opt_vec::OptVec`], it reuses the slots of removed elements. Unlike it, every
+/// reuse bumps the generation of the slot, so the keys issued before are stale: accessing the map
+/// with them returns `None` instead of the new element. The generation is a wrapping 32 bit number,
+/// so a key becomes valid again after its slot is reused 2^32 times.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+#[derive(Clone, Debug)]
+pub struct SlotMap<T, K = Key> {
+    slots:      Vec<Slot<T>>,
+    free_slots: Vec<usize>,
+    len:        usize,
+    key:        PhantomData<K>,
+}
+
+
+// === Construction ===
+
+impl<T, K: SlotKey> SlotMap<T, K> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+}
+
+
+// === Status Checks ===
+
+impl<T, K: SlotKey> SlotMap<T, K> {
+    /// The number of elements in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Check if the key is valid, that is, if its element was not removed.
+    pub fn contains_key(&self, key: K) -> bool {
+        self.get(key).is_some()
+    }
+}
+
+
+// === Modifiers ===
+
+impl<T, K: SlotKey> SlotMap<T, K> {
+    /// Insert the element, returning its key. Reuses the slots of removed elements, if any.
+    pub fn insert(&mut self, value: T) -> K {
+        self.insert_with_key(|_| value)
+    }
+
+    /// Insert the element constructed with the key it will be available under.
+    pub fn insert_with_key(&mut self, f: impl FnOnce(K) -> T) -> K {
+        self.len += 1;
+        match self.free_slots.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index];
+                let key = K::new(index, slot.generation);
+                slot.value = Some(f(key));
+                key
+            }
+            None => {
+                let index = self.slots.len();
+                let key = K::new(index, 0);
+                self.slots.push(Slot { generation: 0, value: Some(f(key)) });
+                key
+            }
+        }
+    }
+
+    /// Remove the element, making its key stale. Returns `None` if the key is already stale.
+    pub fn remove(&mut self, key: K) -> Option<T> {
+        let slot = self.slots.get_mut(key.index())?;
+        if slot.generation != key.generation() {
+            return None;
+        }
+        let value = slot.value.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free_slots.push(key.index());
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Remove all elements, making all keys stale. The slots are kept for reuse.
+    pub fn clear(&mut self) {
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if slot.value.take().is_some() {
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free_slots.push(index);
+            }
+        }
+        self.len = 0;
+    }
+}
+
+
+// === Indexing ===
+
+impl<T, K: SlotKey> SlotMap<T, K> {
+    /// The element under the key. Returns `None` if the key is stale.
+    pub fn get(&self, key: K) -> Option<&T> {
+        let slot = self.slots.get(key.index())?;
+        (slot.generation == key.generation()).and_option_from(|| slot.value.as_ref())
+    }
+
+    /// The mutable element under the key. Returns `None` if the key is stale.
+    pub fn get_mut(&mut self, key: K) -> Option<&mut T> {
+        let slot = self.slots.get_mut(key.index())?;
+        (slot.generation == key.generation()).and_option_from(|| slot.value.as_mut())
+    }
+}
+
+impl<T, K: SlotKey> std::ops::Index<K> for SlotMap<T, K> {
+    type Output = T;
+    fn index(&self, key: K) -> &Self::Output {
+        self.get(key).unwrap_or_else(|| panic!("Trying to access stale key `{:?}`.", key))
+    }
+}
+
+impl<T, K: SlotKey> std::ops::IndexMut<K> for SlotMap<T, K> {
+    fn index_mut(&mut self, key: K) -> &mut Self::Output {
+        self.get_mut(key).unwrap_or_else(|| panic!("Trying to access stale key `{:?}`.", key))
+    }
+}
+
+
+// === Iterators ===
+
+impl<T, K: SlotKey> SlotMap<T, K> {
+    /// Iterator over the elements and their keys, in the order of slots.
+    pub fn iter(&self) -> impl Iterator<Item = (K, &T)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.value.as_ref().map(|value| (K::new(index, slot.generation), value))
+        })
+    }
+
+    /// Mutable iterator over the elements and their keys, in the order of slots.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (K, &mut T)> {
+        self.slots.iter_mut().enumerate().filter_map(|(index, slot)| {
+            let generation = slot.generation;
+            slot.value.as_mut().map(|value| (K::new(index, generation), value))
+        })
+    }
+
+    /// Iterator over the keys of all elements.
+    pub fn keys(&self) -> impl Iterator<Item = K> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Iterator over all elements.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.slots.iter().filter_map(|slot| slot.value.as_ref())
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_remove() {
+        let mut map = SlotMap::<&str>::new();
+        assert!(map.is_empty());
+        let a = map.insert("a");
+        let b = map.insert("b");
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[a], "a");
+        assert_eq!(map.get(b), Some(&"b"));
+        assert_eq!(map.remove(a), Some("a"));
+        assert_eq!(map.remove(a), None);
+        assert_eq!(map.len(), 1);
+        assert!(!map.contains_key(a));
+        assert!(map.contains_key(b));
+    }
+
+    #[test]
+    fn stale_keys_after_slot_reuse() {
+        let mut map = SlotMap::<i32>::new();
+        let old = map.insert(1);
+        map.remove(old);
+        let new = map.insert(2);
+        assert_eq!(old.index, new.index);
+        assert_ne!(old.generation, new.generation);
+        assert_eq!(map.get(old), None);
+        assert_eq!(map.get_mut(old), None);
+        assert_eq!(map.remove(old), None);
+        assert_eq!(map[new], 2);
+    }
+
+    #[test]
+    fn clear_makes_keys_stale() {
+        let mut map = SlotMap::<i32>::new();
+        let keys = (0..3).map(|value| map.insert(value)).collect_vec();
+        map.clear();
+        assert!(map.is_empty());
+        assert!(keys.iter().all(|key| !map.contains_key(*key)));
+        let key = map.insert_with_key(|key| key.index as i32);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map[key], key.index as i32);
+    }
+
+    #[test]
+    fn iteration() {
+        let mut map = SlotMap::<i32>::new();
+        let keys = (0..4).map(|value| map.insert(value)).collect_vec();
+        map.remove(keys[1]);
+        for (_, value) in map.iter_mut() {
+            *value *= 10;
+        }
+        let entries = map.iter().map(|(key, value)| (key, *value)).collect_vec();
+        assert_eq!(entries, vec![(keys[0], 0), (keys[2], 20), (keys[3], 30)]);
+        assert_eq!(map.keys().collect_vec(), vec![keys[0], keys[2], keys[3]]);
+        assert_eq!(map.values().copied().collect_vec(), vec![0, 20, 30]);
+    }
+}
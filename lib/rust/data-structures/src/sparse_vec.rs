@@ -0,0 +1,240 @@
+//! A sparse vector, storing optional values addressed by indices or typed ids convertible to
+//! indices. Useful as a faster alternative of `HashMap<Id, T>` when the ids are dense integers.
+
+use crate::prelude::*;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The number of values in a single page.
+const PAGE_SIZE: usize = 64;
+
+
+
+// ============
+// === Page ===
+// ============
+
+#[derive(Clone, Debug)]
+struct Page<T> {
+    values: Vec<Option<T>>,
+    /// The number of present values.
+    len:    usize,
+}
+
+impl<T> Page<T> {
+    fn new() -> Self {
+        Self { values: iter::repeat_with(|| None).take(PAGE_SIZE).collect(), len: 0 }
+    }
+}
+
+
+
+// =================
+// === SparseVec ===
+// =================
+
+/// A sparse vector of optional values addressed by ids convertible to indices, like `usize` or
+/// [`crate::index::Index`]. The values are stored in pages of 64 consecutive indices, allocated on
+/// first insertion and freed when their last value is removed. Thus, accessing values takes O(1)
+/// time, and the memory usage is compact when the present indices are close to each other.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+#[derive(Clone, Debug)]
+pub struct SparseVec<Id, T> {
+    pages: Vec<Option<Box<Page<T>>>>,
+    len:   usize,
+    id:    PhantomData<Id>,
+}
+
+fn page_and_offset(index: usize) -> (usize, usize) {
+    (index / PAGE_SIZE, index % PAGE_SIZE)
+}
+
+
+// === Construction ===
+
+impl<Id: Copy + From<usize> + Into<usize>, T> SparseVec<Id, T> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+}
+
+
+// === Status Checks ===
+
+impl<Id: Copy + From<usize> + Into<usize>, T> SparseVec<Id, T> {
+    /// The number of present values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if no value is present.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Check if the value of the id is present.
+    pub fn contains(&self, id: Id) -> bool {
+        self.get(id).is_some()
+    }
+}
+
+
+// === Modifiers ===
+
+impl<Id: Copy + From<usize> + Into<usize>, T> SparseVec<Id, T> {
+    /// Set the value of the id, returning the previous one.
+    pub fn insert(&mut self, id: Id, value: T) -> Option<T> {
+        let (page, offset) = page_and_offset(id.into());
+        if self.pages.len() <= page {
+            self.pages.resize_with(page + 1, || None);
+        }
+        let page = self.pages[page].get_or_insert_with(|| Box::new(Page::new()));
+        let old_value = page.values[offset].replace(value);
+        if old_value.is_none() {
+            page.len += 1;
+            self.len += 1;
+        }
+        old_value
+    }
+
+    /// Remove the value of the id, returning it.
+    pub fn remove(&mut self, id: Id) -> Option<T> {
+        let (page_index, offset) = page_and_offset(id.into());
+        let page = self.pages.get_mut(page_index)?.as_mut()?;
+        let value = page.values[offset].take()?;
+        page.len -= 1;
+        self.len -= 1;
+        if page.len == 0 {
+            self.pages[page_index] = None;
+        }
+        Some(value)
+    }
+
+    /// Remove all values.
+    pub fn clear(&mut self) {
+        self.pages.clear();
+        self.len = 0;
+    }
+}
+
+
+// === Indexing ===
+
+impl<Id: Copy + From<usize> + Into<usize>, T> SparseVec<Id, T> {
+    /// The value of the id.
+    pub fn get(&self, id: Id) -> Option<&T> {
+        let (page, offset) = page_and_offset(id.into());
+        self.pages.get(page)?.as_ref()?.values[offset].as_ref()
+    }
+
+    /// The mutable value of the id.
+    pub fn get_mut(&mut self, id: Id) -> Option<&mut T> {
+        let (page, offset) = page_and_offset(id.into());
+        self.pages.get_mut(page)?.as_mut()?.values[offset].as_mut()
+    }
+}
+
+impl<Id: Copy + From<usize> + Into<usize>, T> std::ops::Index<Id> for SparseVec<Id, T> {
+    type Output = T;
+    fn index(&self, id: Id) -> &Self::Output {
+        let index = id.into();
+        self.get(id).unwrap_or_else(|| panic!("No value at index {}.", index))
+    }
+}
+
+impl<Id: Copy + From<usize> + Into<usize>, T> std::ops::IndexMut<Id> for SparseVec<Id, T> {
+    fn index_mut(&mut self, id: Id) -> &mut Self::Output {
+        let index = id.into();
+        self.get_mut(id).unwrap_or_else(|| panic!("No value at index {}.", index))
+    }
+}
+
+
+// === Iterators ===
+
+impl<Id: Copy + From<usize> + Into<usize>, T> SparseVec<Id, T> {
+    /// Iterator over the present values and their ids, in the ascending order of ids.
+    pub fn iter(&self) -> impl Iterator<Item = (Id, &T)> {
+        let pages = self.pages.iter().enumerate();
+        let pages = pages.filter_map(|(index, page)| page.as_ref().map(|page| (index, page)));
+        pages.flat_map(|(page_index, page)| {
+            page.values.iter().enumerate().filter_map(move |(offset, value)| {
+                value.as_ref().map(|value| ((page_index * PAGE_SIZE + offset).into(), value))
+            })
+        })
+    }
+
+    /// Mutable iterator over the present values and their ids, in the ascending order of ids.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Id, &mut T)> {
+        let pages = self.pages.iter_mut().enumerate();
+        let pages = pages.filter_map(|(index, page)| page.as_mut().map(|page| (index, page)));
+        pages.flat_map(|(page_index, page)| {
+            page.values.iter_mut().enumerate().filter_map(move |(offset, value)| {
+                value.as_mut().map(|value| ((page_index * PAGE_SIZE + offset).into(), value))
+            })
+        })
+    }
+
+    /// Iterator over the ids of the present values, in the ascending order.
+    pub fn ids(&self) -> impl Iterator<Item = Id> + '_ {
+        self.iter().map(|(id, _)| id)
+    }
+}
+
+impl<Id: Copy + From<usize> + Into<usize>, T> FromIterator<(Id, T)> for SparseVec<Id, T> {
+    fn from_iter<I: IntoIterator<Item = (Id, T)>>(iter: I) -> Self {
+        let mut vec = Self::new();
+        for (id, value) in iter {
+            vec.insert(id, value);
+        }
+        vec
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::Index;
+
+    #[test]
+    fn insert_and_remove() {
+        let mut vec = SparseVec::<usize, &str>::new();
+        assert_eq!(vec.insert(3, "a"), None);
+        assert_eq!(vec.insert(1000, "b"), None);
+        assert_eq!(vec.insert(3, "c"), Some("a"));
+        assert_eq!(vec.len(), 2);
+        assert_eq!(vec[3], "c");
+        assert_eq!(vec.get(4), None);
+        assert_eq!(vec.get(5000), None);
+        assert_eq!(vec.remove(1000), Some("b"));
+        assert_eq!(vec.remove(1000), None);
+        assert!(vec.pages[1000 / PAGE_SIZE].is_none());
+        assert!(!vec.contains(1000));
+        assert_eq!(vec.len(), 1);
+    }
+
+    #[test]
+    fn iteration_with_typed_ids() {
+        struct Node;
+        let ids = [130, 2, 64, 63].map(Index::<Node>::new);
+        let mut vec: SparseVec<Index<Node>, usize> = ids.iter().map(|id| (*id, id.raw)).collect();
+        for (_, value) in vec.iter_mut() {
+            *value *= 2;
+        }
+        let entries = vec.iter().map(|(id, value)| (id.raw, *value)).collect_vec();
+        assert_eq!(entries, vec![(2, 4), (63, 126), (64, 128), (130, 260)]);
+        assert_eq!(vec.ids().map(|id| id.raw).collect_vec(), vec![2, 63, 64, 130]);
+    }
+}
@@ -0,0 +1,256 @@
+//! A prefix tree, mapping sequences of symbols to values. Strings are mapped by their characters,
+//! see [`StringTrie`].
+
+use crate::prelude::*;
+
+use std::collections::BTreeMap;
+
+
+
+// ============
+// === Node ===
+// ============
+
+#[derive(Clone, Debug)]
+struct Node<T, V> {
+    value:    Option<V>,
+    children: BTreeMap<T, Node<T, V>>,
+}
+
+impl<T, V> Default for Node<T, V> {
+    fn default() -> Self {
+        Self { value: None, children: default() }
+    }
+}
+
+impl<T: Ord, V> Node<T, V> {
+    fn is_empty(&self) -> bool {
+        self.value.is_none() && self.children.is_empty()
+    }
+
+    fn remove(&mut self, mut key: impl Iterator<Item = T>) -> Option<V> {
+        match key.next() {
+            None => self.value.take(),
+            Some(symbol) => {
+                let child = self.children.get_mut(&symbol)?;
+                let value = child.remove(key);
+                if child.is_empty() {
+                    self.children.remove(&symbol);
+                }
+                value
+            }
+        }
+    }
+}
+
+
+
+// ============
+// === Trie ===
+// ============
+
+/// A map from sequences of symbols to values, supporting the longest prefix lookup and iteration
+/// over the entries under a prefix. The operations take time proportional to the length of the
+/// key, times the logarithm of the number of distinct symbols following a prefix. The entries are
+/// iterated in the lexicographic order of their keys.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+#[derive(Clone, Debug)]
+pub struct Trie<T, V> {
+    root: Node<T, V>,
+    len:  usize,
+}
+
+/// A [`Trie`] with strings as keys.
+pub type StringTrie<V> = Trie<char, V>;
+
+impl<T: Clone + Ord, V> Trie<T, V> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the trie contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Insert the entry, returning the previous value of the key.
+    pub fn insert(&mut self, key: impl IntoIterator<Item = T>, value: V) -> Option<V> {
+        let node = key
+            .into_iter()
+            .fold(&mut self.root, |node, symbol| node.children.entry(symbol).or_default());
+        let old_value = node.value.replace(value);
+        if old_value.is_none() {
+            self.len += 1;
+        }
+        old_value
+    }
+
+    /// Remove the entry, returning its value.
+    pub fn remove(&mut self, key: impl IntoIterator<Item = T>) -> Option<V> {
+        let value = self.root.remove(key.into_iter());
+        if value.is_some() {
+            self.len -= 1;
+        }
+        value
+    }
+
+    /// The value of the key.
+    pub fn get(&self, key: impl IntoIterator<Item = T>) -> Option<&V> {
+        self.node(key)?.value.as_ref()
+    }
+
+    /// The mutable value of the key.
+    pub fn get_mut(&mut self, key: impl IntoIterator<Item = T>) -> Option<&mut V> {
+        let mut node = &mut self.root;
+        for symbol in key {
+            node = node.children.get_mut(&symbol)?;
+        }
+        node.value.as_mut()
+    }
+
+    /// Check if the key has a value.
+    pub fn contains_key(&self, key: impl IntoIterator<Item = T>) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// The entry with the longest key being a prefix of the provided sequence. Returns the length
+    /// of the matched prefix and the value of the entry.
+    pub fn longest_prefix(&self, sequence: impl IntoIterator<Item = T>) -> Option<(usize, &V)> {
+        let mut node = &self.root;
+        let mut found = node.value.as_ref().map(|value| (0, value));
+        for (index, symbol) in sequence.into_iter().enumerate() {
+            match node.children.get(&symbol) {
+                Some(child) => node = child,
+                None => break,
+            }
+            if let Some(value) = &node.value {
+                found = Some((index + 1, value));
+            }
+        }
+        found
+    }
+
+    /// Iterator over the entries whose keys start with the prefix, including the entry of the
+    /// prefix itself.
+    pub fn iter_prefix(&self, prefix: impl IntoIterator<Item = T>) -> Iter<T, V> {
+        let prefix = prefix.into_iter().collect_vec();
+        let node = self.node(prefix.iter().cloned());
+        let stack = node.map(|node| (prefix, node)).into_iter().collect();
+        Iter { stack }
+    }
+
+    /// Iterator over all entries.
+    pub fn iter(&self) -> Iter<T, V> {
+        self.iter_prefix(None)
+    }
+
+    fn node(&self, key: impl IntoIterator<Item = T>) -> Option<&Node<T, V>> {
+        let mut node = &self.root;
+        for symbol in key {
+            node = node.children.get(&symbol)?;
+        }
+        Some(node)
+    }
+}
+
+impl<V> StringTrie<V> {
+    /// Iterator over the entries whose keys start with the prefix, with the keys collected to
+    /// strings.
+    pub fn iter_str_prefix(&self, prefix: &str) -> impl Iterator<Item = (String, &V)> {
+        self.iter_prefix(prefix.chars()).map(|(key, value)| (key.into_iter().collect(), value))
+    }
+}
+
+impl<T: Clone + Ord, V, K: IntoIterator<Item = T>> FromIterator<(K, V)> for Trie<T, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut trie = Self::new();
+        for (key, value) in iter {
+            trie.insert(key, value);
+        }
+        trie
+    }
+}
+
+
+
+// ============
+// === Iter ===
+// ============
+
+/// Iterator over the entries of a [`Trie`] in the lexicographic order of their keys.
+#[derive(Debug)]
+pub struct Iter<'a, T, V> {
+    stack: Vec<(Vec<T>, &'a Node<T, V>)>,
+}
+
+impl<'a, T: Clone, V> Iterator for Iter<'a, T, V> {
+    type Item = (Vec<T>, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((key, node)) = self.stack.pop() {
+            for (symbol, child) in node.children.iter().rev() {
+                let mut child_key = key.clone();
+                child_key.push(symbol.clone());
+                self.stack.push((child_key, child));
+            }
+            if let Some(value) = &node.value {
+                return Some((key, value));
+            }
+        }
+        None
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_keys() {
+        let mut trie: StringTrie<i32> = [("foo", 1), ("foobar", 2), ("fob", 3), ("bar", 4)]
+            .into_iter()
+            .map(|(key, value)| (key.chars(), value))
+            .collect();
+        assert_eq!(trie.len(), 4);
+        assert_eq!(trie.get("foo".chars()), Some(&1));
+        assert_eq!(trie.get("fo".chars()), None);
+        assert_eq!(trie.longest_prefix("foobaz".chars()), Some((3, &1)));
+        assert_eq!(trie.longest_prefix("foobarbaz".chars()), Some((6, &2)));
+        assert_eq!(trie.longest_prefix("baz".chars()), None);
+        let completions = trie.iter_str_prefix("fo").collect_vec();
+        let expected = vec![("fob".to_owned(), &3), ("foo".to_owned(), &1), ("foobar".into(), &2)];
+        assert_eq!(completions, expected);
+        assert_eq!(trie.remove("foo".chars()), Some(1));
+        assert_eq!(trie.remove("foo".chars()), None);
+        assert_eq!(trie.get("foobar".chars()), Some(&2));
+        assert_eq!(trie.len(), 3);
+    }
+
+    #[test]
+    fn token_sequence_keys() {
+        let mut trie = Trie::new();
+        trie.insert(["if", "then"], "if-then");
+        trie.insert(["if", "then", "else"], "if-then-else");
+        trie.insert([], "empty");
+        assert_eq!(trie.longest_prefix(["if", "then", "foo"]), Some((2, &"if-then")));
+        assert_eq!(trie.longest_prefix(["foo"]), Some((0, &"empty")));
+        *trie.get_mut(["if", "then"]).unwrap() = "if_then";
+        trie.remove(["if", "then", "else"]);
+        assert!(!trie.contains_key(["if", "then", "else"]));
+        let entries = trie.iter().map(|(key, value)| (key.len(), *value)).collect_vec();
+        assert_eq!(entries, vec![(0, "empty"), (2, "if_then")]);
+        assert_eq!(trie.root.children["if"].children["then"].children.len(), 0);
+    }
+}
@@ -0,0 +1,135 @@
+//! A vector indexed by a dedicated id type, so ids of different vectors cannot be mixed up.
+
+use crate::prelude::*;
+
+
+
+// ================
+// === TypedVec ===
+// ================
+
+/// A vector which can be indexed only by its id type, like [`crate::index::Index`] or any other
+/// type convertible to and from `usize`. For example, `TypedVec<Index<nfa::State>, T>` cannot be
+/// accessed with an `Index<dfa::State>` by mistake.
+#[derive(Derivative)]
+#[derivative(Clone(bound = "T: Clone"))]
+#[derivative(Default(bound = ""))]
+#[derivative(Debug(bound = "T: Debug"))]
+#[derivative(PartialEq(bound = "T: PartialEq"))]
+#[derivative(Eq(bound = "T: Eq"))]
+pub struct TypedVec<Id, T> {
+    elements: Vec<T>,
+    #[derivative(Debug = "ignore")]
+    id:       PhantomData<Id>,
+}
+
+impl<Id: From<usize> + Into<usize>, T> TypedVec<Id, T> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Check if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// The id the next pushed element will get.
+    pub fn next_id(&self) -> Id {
+        self.elements.len().into()
+    }
+
+    /// Append the element, returning its id.
+    pub fn push(&mut self, element: T) -> Id {
+        let id = self.next_id();
+        self.elements.push(element);
+        id
+    }
+
+    /// The element of the id.
+    pub fn get(&self, id: Id) -> Option<&T> {
+        self.elements.get(id.into())
+    }
+
+    /// The mutable element of the id.
+    pub fn get_mut(&mut self, id: Id) -> Option<&mut T> {
+        self.elements.get_mut(id.into())
+    }
+
+    /// Iterator over the elements and their ids.
+    pub fn iter(&self) -> impl Iterator<Item = (Id, &T)> {
+        self.elements.iter().enumerate().map(|(index, element)| (index.into(), element))
+    }
+
+    /// Mutable iterator over the elements and their ids.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Id, &mut T)> {
+        self.elements.iter_mut().enumerate().map(|(index, element)| (index.into(), element))
+    }
+
+    /// Iterator over the ids of all elements.
+    pub fn ids(&self) -> impl Iterator<Item = Id> {
+        (0..self.elements.len()).map(Id::from)
+    }
+
+    /// The elements as a slice, indexed by raw indices.
+    pub fn as_slice(&self) -> &[T] {
+        &self.elements
+    }
+}
+
+impl<Id: Into<usize>, T> std::ops::Index<Id> for TypedVec<Id, T> {
+    type Output = T;
+    fn index(&self, id: Id) -> &Self::Output {
+        &self.elements[id.into()]
+    }
+}
+
+impl<Id: Into<usize>, T> std::ops::IndexMut<Id> for TypedVec<Id, T> {
+    fn index_mut(&mut self, id: Id) -> &mut Self::Output {
+        &mut self.elements[id.into()]
+    }
+}
+
+impl<Id, T> From<Vec<T>> for TypedVec<Id, T> {
+    fn from(elements: Vec<T>) -> Self {
+        Self { elements, id: default() }
+    }
+}
+
+impl<Id, T> FromIterator<T> for TypedVec<Id, T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        iter.into_iter().collect_vec().into()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::Index;
+
+    #[test]
+    fn indexing_with_ids() {
+        struct State;
+        let mut states = TypedVec::<Index<State>, &str>::new();
+        let first = states.push("first");
+        assert_eq!(states.next_id(), Index::new(1));
+        let second = states.push("second");
+        states[second] = "last";
+        assert_eq!(states[first], "first");
+        assert_eq!(states.get(Index::new(2)), None);
+        let entries = states.iter().map(|(id, state)| (id.raw, *state)).collect_vec();
+        assert_eq!(entries, vec![(0, "first"), (1, "last")]);
+        assert_eq!(states.ids().collect_vec(), vec![first, second]);
+    }
+}
@@ -0,0 +1,167 @@
+//! A disjoint set forest, tracking a partition of elements into sets which can be merged.
+
+use crate::prelude::*;
+
+
+
+// =================
+// === UnionFind ===
+// =================
+
+/// A partition of the elements `0..len` into disjoint sets, each identified by one of its elements
+/// called the root. Initially, every element is in its own set. The elements can be of any type
+/// convertible to and from `usize`, like `usize` itself or [`crate::index::Index`].
+///
+/// Uses path compression and union by rank, so all operations take amortized nearly constant time.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+#[derive(Clone, Debug)]
+pub struct UnionFind<K = usize> {
+    parents:   Vec<usize>,
+    ranks:     Vec<u8>,
+    set_count: usize,
+    element:   PhantomData<K>,
+}
+
+impl<K: Copy + From<usize> + Into<usize>> UnionFind<K> {
+    /// Constructor of an empty partition.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Constructor of the partition of elements `0..len`, each in its own set.
+    pub fn with_len(len: usize) -> Self {
+        let parents = (0..len).collect();
+        let ranks = vec![0; len];
+        Self { parents, ranks, set_count: len, element: default() }
+    }
+
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Check if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// The number of disjoint sets.
+    pub fn set_count(&self) -> usize {
+        self.set_count
+    }
+
+    /// Add a new element in its own set.
+    pub fn make_set(&mut self) -> K {
+        let element = self.parents.len();
+        self.parents.push(element);
+        self.ranks.push(0);
+        self.set_count += 1;
+        element.into()
+    }
+
+    /// The root of the element's set. Shortens the paths to the root on the way.
+    pub fn find(&mut self, element: K) -> K {
+        let element = element.into();
+        let root = self.root_index(element);
+        let mut current = element;
+        while current != root {
+            current = mem::replace(&mut self.parents[current], root);
+        }
+        root.into()
+    }
+
+    /// The root of the element's set. Unlike [`Self::find`], does not modify the forest, so it may
+    /// be slower when called repeatedly.
+    pub fn root(&self, element: K) -> K {
+        self.root_index(element.into()).into()
+    }
+
+    /// Check if the elements are in the same set.
+    pub fn same_set(&mut self, first: K, second: K) -> bool {
+        self.find(first).into() == self.find(second).into()
+    }
+
+    /// Merge the sets of the elements, returning the root of the merged set.
+    pub fn union(&mut self, first: K, second: K) -> K {
+        let first = self.find(first).into();
+        let second = self.find(second).into();
+        if first == second {
+            return first.into();
+        }
+        self.set_count -= 1;
+        let (child, parent) = match self.ranks[first].cmp(&self.ranks[second]) {
+            std::cmp::Ordering::Less => (first, second),
+            std::cmp::Ordering::Greater => (second, first),
+            std::cmp::Ordering::Equal => {
+                self.ranks[first] += 1;
+                (second, first)
+            }
+        };
+        self.parents[child] = parent;
+        parent.into()
+    }
+
+    /// All sets, each with its elements in ascending order. The sets are ordered by their smallest
+    /// elements.
+    pub fn sets(&mut self) -> Vec<Vec<K>> {
+        let mut set_of_root: HashMap<usize, usize> = default();
+        let mut sets: Vec<Vec<K>> = default();
+        for element in 0..self.len() {
+            let root = self.find(element.into()).into();
+            let next_set = sets.len();
+            let set = *set_of_root.entry(root).or_insert(next_set);
+            if set == next_set {
+                sets.push(default());
+            }
+            sets[set].push(element.into());
+        }
+        sets
+    }
+
+    fn root_index(&self, mut element: usize) -> usize {
+        while self.parents[element] != element {
+            element = self.parents[element];
+        }
+        element
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::Index;
+
+    #[test]
+    fn merging_sets() {
+        let mut sets = UnionFind::<usize>::with_len(6);
+        assert_eq!(sets.set_count(), 6);
+        sets.union(0, 1);
+        sets.union(2, 3);
+        sets.union(1, 3);
+        assert_eq!(sets.union(3, 0), sets.find(2));
+        assert!(sets.same_set(0, 2));
+        assert!(!sets.same_set(0, 4));
+        assert_eq!(sets.root(1), sets.find(3));
+        let extra = sets.make_set();
+        sets.union(extra, 5);
+        assert_eq!(sets.set_count(), 3);
+        assert_eq!(sets.sets(), vec![vec![0, 1, 2, 3], vec![4], vec![5, 6]]);
+    }
+
+    #[test]
+    fn typed_elements() {
+        struct State;
+        let mut sets = UnionFind::<Index<State>>::new();
+        let states = (0..3).map(|_| sets.make_set()).collect_vec();
+        sets.union(states[2], states[0]);
+        assert!(sets.same_set(states[0], states[2]));
+        assert_eq!(sets.set_count(), 2);
+    }
+}
@@ -0,0 +1,148 @@
+//! A hash map holding its values by weak references, which removes the entries of dropped values
+//! on its own, instead of letting them accumulate until their keys are touched.
+
+use crate::prelude::*;
+
+use std::borrow::Borrow;
+use std::hash::Hash;
+
+
+
+// ========================
+// === WeakValueHashMap ===
+// ========================
+
+/// A hash map storing [`Weak`] references to its values. An entry is dead once its value is
+/// dropped. Dead entries are never returned, and are removed:
+/// - when accessed by their key,
+/// - when the number of entries doubles since the last purge, so the map size stays proportional to
+///   the number of live entries,
+/// - when [`WeakValueHashMap::purge`] is called explicitly.
+///
+/// Unlike [`weak_table::WeakValueHashMap`] re-exported by the prelude, the values are always
+/// [`Rc`]s, and the access methods remove dead entries.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+#[derive(Clone, Debug)]
+pub struct WeakValueHashMap<K, V> {
+    entries:         HashMap<K, Weak<V>>,
+    /// The number of entries after the last purge.
+    len_after_purge: usize,
+}
+
+impl<K: Eq + Hash, V> WeakValueHashMap<K, V> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// The number of live entries. Takes O(n) time, as all entries are checked.
+    pub fn live_len(&self) -> usize {
+        self.entries.values().filter(|value| value.strong_count() > 0).count()
+    }
+
+    /// Check if there are no live entries. Takes O(n) time, as all entries are checked.
+    pub fn is_empty(&self) -> bool {
+        self.live_len() == 0
+    }
+
+    /// Insert the weak reference to the value, returning the previous live value of the key.
+    pub fn insert(&mut self, key: K, value: &Rc<V>) -> Option<Rc<V>> {
+        let old_value = self.entries.insert(key, Rc::downgrade(value));
+        if self.entries.len() > 2 * self.len_after_purge.max(8) {
+            self.purge();
+        }
+        old_value.and_then(|old_value| old_value.upgrade())
+    }
+
+    /// The value of the key, if it is still alive. Removes the entry if it is dead.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<Rc<V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        let value = self.entries.get(key)?.upgrade();
+        if value.is_none() {
+            self.entries.remove(key);
+        }
+        value
+    }
+
+    /// Check if the key has a live value. Removes the entry if it is dead.
+    pub fn contains_key<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        self.get(key).is_some()
+    }
+
+    /// Remove the entry, returning its value if it is still alive.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<Rc<V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash, {
+        self.entries.remove(key)?.upgrade()
+    }
+
+    /// Remove all dead entries, returning their number.
+    pub fn purge(&mut self) -> usize {
+        let len_before = self.entries.len();
+        self.entries.retain(|_, value| value.strong_count() > 0);
+        self.len_after_purge = self.entries.len();
+        len_before - self.len_after_purge
+    }
+
+    /// Remove all entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.len_after_purge = 0;
+    }
+
+    /// Iterator over the live entries, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, Rc<V>)> {
+        self.entries.iter().filter_map(|(key, value)| value.upgrade().map(|value| (key, value)))
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    // Shadows the one re-exported by the prelude.
+    use super::WeakValueHashMap;
+
+    #[test]
+    fn dead_entries_are_dropped() {
+        let mut map = WeakValueHashMap::new();
+        let alive = Rc::new(1);
+        let dead = Rc::new(2);
+        map.insert("alive", &alive);
+        map.insert("dead", &dead);
+        map.insert("dropped", &Rc::new(3));
+        drop(dead);
+        assert_eq!(map.live_len(), 1);
+        assert_eq!(map.get("alive"), Some(alive.clone()));
+        assert_eq!(map.get("dead"), None);
+        assert_eq!(map.entries.len(), 2);
+        assert_eq!(map.purge(), 1);
+        assert_eq!(map.iter().collect_vec(), vec![(&"alive", alive.clone())]);
+        assert_eq!(map.remove("alive"), Some(alive));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn purging_on_growth() {
+        let mut map = WeakValueHashMap::new();
+        let kept = (0..100).map(Rc::new).collect_vec();
+        for (index, value) in kept.iter().enumerate() {
+            map.insert(index, value);
+            map.insert(index + 1000, &Rc::new(0));
+        }
+        assert!(map.entries.len() < 2 * kept.len());
+        assert_eq!(map.live_len(), kept.len());
+    }
+}
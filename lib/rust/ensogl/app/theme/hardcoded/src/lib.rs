@@ -151,7 +151,7 @@ macro_rules! define_themes {
 
 /// Enum holding available themes for ease of access.
 #[allow(missing_docs)]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Theme {
     Light,
     Dark,
@@ -179,6 +179,9 @@ define_themes! { [light:0, dark:1]
             hide_delay_duration_ms = 150.0, 150.0;
             show_delay_duration_ms = 150.0, 150.0;
         }
+        modal {
+            backdrop = Rgba(0.0,0.0,0.0,0.3) , Rgba(0.0,0.0,0.0,0.5);
+        }
         searcher {
             action_list_gap = 10.0, 10.0;
             padding         = 5.0, 5.0;
@@ -329,6 +332,47 @@ define_themes! { [light:0, dark:1]
                 }
             }
         }
+        toast {
+            text = text, text;
+            background = graph_editor::node::background , graph_editor::node::background;
+            background {
+                corner_radius = 14.0 , 14.0;
+            }
+            level {
+                info    = Rgba(0.239,0.573,0.808,1.0) , Rgba(0.239,0.573,0.808,1.0);
+                warning = Rgba(0.98,0.584,0.122,1.0)  , Rgba(0.98,0.584,0.122,1.0);
+                error   = Rgba(0.827,0.267,0.255,1.0) , Rgba(0.827,0.267,0.255,1.0);
+            }
+        }
+        project_tabs {
+            text       = text, text;
+            background = graph_editor::node::background , graph_editor::node::background;
+            background {
+                corner_radius = 8.0 , 8.0;
+            }
+            active = Lcha(0.85,0.0,0.0,1.0) , Lcha(0.35,0.0,0.0,1.0);
+        }
+        opening_project {
+            text    = text, text;
+            spinner = Rgba(0.239,0.573,0.808,1.0) , Rgba(0.239,0.573,0.808,1.0);
+        }
+        settings {
+            title = text, text;
+            text  = Lcha(0.7,0.0,0.0,1.0) , Lcha(1.0,0.0,0.0,0.2);
+        }
+        error_screen {
+            title = Rgba(0.827,0.267,0.255,1.0) , Rgba(0.827,0.267,0.255,1.0);
+            text  = text, text;
+            hint  = Lcha(0.7,0.0,0.0,1.0) , Lcha(1.0,0.0,0.0,0.2);
+        }
+        about {
+            title      = text, text;
+            text       = Lcha(0.7,0.0,0.0,1.0) , Lcha(1.0,0.0,0.0,0.2);
+            background = graph_editor::node::background , graph_editor::node::background;
+            background {
+                corner_radius = 14.0 , 14.0;
+            }
+        }
     }
     code {
         syntax {
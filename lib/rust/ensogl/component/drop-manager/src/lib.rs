@@ -73,6 +73,11 @@ impl File {
         Ok(File { name, mime_type, size, reader })
     }
 
+    /// The extension of the file name, if any.
+    pub fn extension(&self) -> Option<&str> {
+        std::path::Path::new(self.name.as_str()).extension().and_then(|ext| ext.to_str())
+    }
+
     #[cfg(target_arch = "wasm32")]
     /// Read the next chunk of file content.
     ///
@@ -35,14 +35,15 @@ pub use view::View;
 #[derive(Debug, Clone, CloneRef)]
 #[allow(missing_docs)]
 pub struct Application {
-    pub logger:           Logger,
-    pub cursor:           Cursor,
-    pub display:          World,
-    pub commands:         command::Registry,
-    pub shortcuts:        shortcut::Registry,
-    pub views:            view::Registry,
-    pub themes:           theme::Manager,
-    update_themes_handle: callback::Handle,
+    pub logger:            Logger,
+    pub cursor:            Cursor,
+    pub display:           World,
+    pub commands:          command::Registry,
+    pub shortcuts:         shortcut::Registry,
+    pub views:             view::Registry,
+    pub themes:            theme::Manager,
+    update_themes_handle:  callback::Handle,
+    tick_shortcuts_handle: callback::Handle,
 }
 
 impl Application {
@@ -61,7 +62,18 @@ impl Application {
         display.add_child(&cursor);
         web::document.body_or_panic().set_style_or_warn("cursor", "none");
         let update_themes_handle = display.on.before_frame.add(f_!(themes.update()));
-        Self { logger, cursor, display, commands, shortcuts, views, themes, update_themes_handle }
+        let tick_shortcuts_handle = display.on.before_frame.add(f_!(shortcuts.on_tick()));
+        Self {
+            logger,
+            cursor,
+            display,
+            commands,
+            shortcuts,
+            views,
+            themes,
+            update_themes_handle,
+            tick_shortcuts_handle,
+        }
     }
 
     /// Create a new instance of a view.
@@ -38,7 +38,7 @@ pub trait View: FrpNetworkProvider + DerefToCommandApi {
     /// Add a new shortcut targeting the self object.
     fn self_shortcut(
         action_type: shortcut::ActionType,
-        pattern: impl Into<String>,
+        pattern: impl Into<shortcut::KeySequence>,
         command: impl Into<shortcut::Command>,
     ) -> Shortcut {
         Shortcut::new(shortcut::Rule::new(action_type, pattern), Self::label(), command)
@@ -47,7 +47,7 @@ pub trait View: FrpNetworkProvider + DerefToCommandApi {
     /// Add a new shortcut targeting the self object.
     fn self_shortcut_when(
         action_type: shortcut::ActionType,
-        pattern: impl Into<String>,
+        pattern: impl Into<shortcut::KeySequence>,
         command: impl Into<shortcut::Command>,
         condition: impl Into<shortcut::Condition>,
     ) -> Shortcut {
@@ -70,6 +70,27 @@ pub trait View: FrpNetworkProvider + DerefToCommandApi {
     where Self: Sized {
         self.app().commands.enable_command(self, name)
     }
+
+    /// FRP input enabling or disabling the shortcut in this component instance, for example while
+    /// an animation is running. Unlike removing and re-adding the shortcut, it keeps the shortcut
+    /// registered. Other shortcuts of the same command are not affected. The shortcut may be one
+    /// of the [`View::default_shortcuts`], and it stays disabled when its command is rebound (see
+    /// [`shortcut::RegistryModel::active_shortcut_key`]). Shortcuts are enabled by default.
+    fn shortcut_enabled(&self, shortcut: Shortcut) -> frp::Any<bool>
+    where Self: Sized {
+        let network = self.network();
+        let commands = self.app().commands.clone_ref();
+        let shortcuts = self.app().shortcuts.clone_ref();
+        let id = network.id();
+        frp::extend! { network
+            enabled <- any_mut::<bool>();
+            eval enabled ([commands, shortcuts] (enabled) {
+                let key = shortcuts.active_shortcut_key(&shortcut);
+                commands.set_shortcut_enabled(id, key, *enabled)
+            });
+        }
+        enabled
+    }
 }
 
 /// FRP Network provider. Used to check whether FRP bindings are still alive.
@@ -84,25 +105,70 @@ pub trait FrpNetworkProvider {
 // === Command ===
 // ===============
 
+/// The FRP endpoint evaluating a command.
+#[derive(Clone, CloneRef, Debug)]
+pub enum Endpoint {
+    /// Endpoint of a command without arguments. The payload of the shortcut is ignored.
+    Unit(frp::Any),
+    /// Endpoint of a command receiving the text payload of the shortcut or, if the shortcut has no
+    /// payload, the name of the key which triggered it, like "a". The latter is used together with
+    /// wildcard rules, like [`shortcut::ANY_CHARACTER`].
+    Key(frp::Any<String>),
+    /// Endpoint of a command receiving the boolean payload of the shortcut.
+    Bool(frp::Any<bool>),
+    /// Endpoint of a command receiving the number payload of the shortcut.
+    Number(frp::Any<f32>),
+}
+
+impl Endpoint {
+    /// Evaluate the command. The `key` is the name of the key which triggered the shortcut, and
+    /// the `payload` is the payload of its rule, see [`shortcut::Payload`]. Returns `false` if the
+    /// command was not evaluated because the payload does not match the endpoint.
+    pub fn emit(&self, key: &str, payload: Option<&shortcut::Payload>) -> bool {
+        use shortcut::Payload;
+        match (self, payload) {
+            (Self::Unit(frp), _) => frp.emit(()),
+            (Self::Key(frp), None) => frp.emit(key.to_string()),
+            (Self::Key(frp), Some(Payload::Text(text))) => frp.emit(text),
+            (Self::Bool(frp), Some(Payload::Bool(value))) => frp.emit(value),
+            (Self::Number(frp), Some(Payload::Number(value))) => frp.emit(value),
+            _ => return false,
+        }
+        true
+    }
+}
+
 /// Abstraction for a command. Includes an frp endpoint which should be called to evaluate the
 /// action and information whether this command was disabled in a particular component view.
 #[derive(Debug)]
 #[allow(missing_docs)]
 pub struct Command {
-    pub frp:     frp::Any,
+    pub frp:     Endpoint,
     pub enabled: bool,
 }
 
-impl Deref for Command {
-    type Target = frp::Any;
-    fn deref(&self) -> &Self::Target {
-        &self.frp
-    }
-}
-
 impl Command {
     /// Constructor.
     pub fn new(frp: frp::Any<()>) -> Self {
+        Self::new_with_endpoint(Endpoint::Unit(frp))
+    }
+
+    /// Constructor of a command receiving the name of the pressed key or a text payload.
+    pub fn new_with_key(frp: frp::Any<String>) -> Self {
+        Self::new_with_endpoint(Endpoint::Key(frp))
+    }
+
+    /// Constructor of a command receiving a boolean payload.
+    pub fn new_with_bool(frp: frp::Any<bool>) -> Self {
+        Self::new_with_endpoint(Endpoint::Bool(frp))
+    }
+
+    /// Constructor of a command receiving a number payload.
+    pub fn new_with_number(frp: frp::Any<f32>) -> Self {
+        Self::new_with_endpoint(Endpoint::Number(frp))
+    }
+
+    fn new_with_endpoint(frp: Endpoint) -> Self {
         let enabled = true;
         Self { frp, enabled }
     }
@@ -137,9 +203,11 @@ pub trait CommandApi: Sized {
 #[derive(Clone, CloneRef, Debug)]
 #[allow(missing_docs)]
 pub struct ProviderInstance {
-    pub network:     frp::WeakNetwork,
-    pub command_map: Rc<RefCell<HashMap<String, Command>>>,
-    pub status_map:  Rc<RefCell<HashMap<String, frp::Sampler<bool>>>>,
+    pub network:            frp::WeakNetwork,
+    pub command_map:        Rc<RefCell<HashMap<String, Command>>>,
+    pub status_map:         Rc<RefCell<HashMap<String, frp::Sampler<bool>>>>,
+    /// Shortcuts disabled in this instance, see [`View::shortcut_enabled`].
+    pub disabled_shortcuts: Rc<RefCell<HashSet<shortcut::ShortcutKey>>>,
 }
 
 impl ProviderInstance {
@@ -196,7 +264,8 @@ impl Registry {
         let network = T::network(target).downgrade();
         let command_map = target.deref().command_api();
         let status_map = target.deref().status_api();
-        let instance = ProviderInstance { network, command_map, status_map };
+        let disabled_shortcuts = default();
+        let instance = ProviderInstance { network, command_map, status_map, disabled_shortcuts };
         let was_registered = self.name_map.borrow().get(label).is_some();
         if !was_registered {
             self.register::<T>();
@@ -240,4 +309,44 @@ impl Registry {
     fn enable_command<T: View>(&self, instance: &T, name: impl AsRef<str>) {
         self.with_command_mut(instance, name, |command| command.enabled = true)
     }
+
+    /// Enables or disables the shortcut for the component instance with the provided network ID.
+    fn set_shortcut_enabled(&self, id: frp::NetworkId, key: shortcut::ShortcutKey, enabled: bool) {
+        match self.id_map.borrow().get(&id) {
+            None => warning!(&self.logger, "The provided component ID is invalid {id}."),
+            Some(instance) => {
+                let mut disabled_shortcuts = instance.disabled_shortcuts.borrow_mut();
+                if enabled {
+                    disabled_shortcuts.remove(&key);
+                } else {
+                    disabled_shortcuts.insert(key);
+                }
+            }
+        }
+    }
+
+    /// Update the disabled shortcuts of the command after its rules were replaced from `previous`
+    /// to `current`. A disabled shortcut stays disabled under the rule at the same position, and is
+    /// forgotten if there is no such rule anymore.
+    pub fn rebind_disabled_shortcuts(
+        &self,
+        key: &shortcut::BindingKey,
+        previous: &[shortcut::Rule],
+        current: &[shortcut::Rule],
+    ) {
+        if previous == current {
+            return;
+        }
+        let name_map = self.name_map.borrow();
+        for instance in name_map.get(&key.0).into_iter().flatten() {
+            let mut disabled_shortcuts = instance.disabled_shortcuts.borrow_mut();
+            let mut rebound = Vec::new();
+            for (index, rule) in previous.iter().enumerate() {
+                if disabled_shortcuts.remove(&(key.clone(), rule.clone())) {
+                    rebound.extend(current.get(index).map(|rule| (key.clone(), rule.clone())));
+                }
+            }
+            disabled_shortcuts.extend(rebound);
+        }
+    }
 }
@@ -34,6 +34,11 @@
 /// and `set_focus(bool)` are always defined and connected. They are mainly used for shortcut
 /// manager to send commands only to focused GUI elements.
 ///
+/// Inputs without arguments are exposed as commands to the shortcut manager. Inputs of the `String`
+/// type are exposed as commands receiving the name of the key which triggered the shortcut (or the
+/// text payload of the shortcut), while inputs of the `bool` and `f32` types are exposed as
+/// commands receiving the payload of the shortcut. See [`crate::application::shortcut::Payload`].
+///
 /// ```compile_fail
 ///     /// Frp network and endpoints.
 ///     #[derive(Debug, Clone, CloneRef)]
@@ -427,6 +432,15 @@ macro_rules! build_command_map {
     ($map:ident $field:ident () $frp:expr) => {
         $map.insert(stringify!($field).into(), Command::new($frp.clone_ref()));
     };
+    ($map:ident $field:ident (String) $frp:expr) => {
+        $map.insert(stringify!($field).into(), Command::new_with_key($frp.clone_ref()));
+    };
+    ($map:ident $field:ident (bool) $frp:expr) => {
+        $map.insert(stringify!($field).into(), Command::new_with_bool($frp.clone_ref()));
+    };
+    ($map:ident $field:ident (f32) $frp:expr) => {
+        $map.insert(stringify!($field).into(), Command::new_with_number($frp.clone_ref()));
+    };
     ($($ts:tt)*) => {};
 }
 
@@ -4,11 +4,18 @@ use crate::prelude::*;
 use enso_shortcuts::traits::*;
 
 use crate::frp;
+use crate::frp::io::js::CurrentJsEvent;
 use crate::frp::io::keyboard;
 use crate::frp::io::mouse::Mouse;
+use crate::system::web;
 
 use super::command;
 use enso_shortcuts as shortcuts;
+use futures::channel::oneshot;
+use futures::Future;
+use futures::FutureExt;
+use serde::Deserialize;
+use serde::Serialize;
 
 
 // ==============
@@ -16,6 +23,76 @@ use enso_shortcuts as shortcuts;
 // ==============
 
 pub use shortcuts::ActionType;
+pub use shortcuts::KeySequence;
+pub use shortcuts::ANY_CHARACTER;
+
+
+
+// ===============
+// === Payload ===
+// ===============
+
+/// A static argument passed by a shortcut to its command, like the zoom step. It allows a single
+/// command to back several parametrized shortcuts. The command has to accept the argument of the
+/// corresponding type, see [`command::Endpoint`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+#[allow(missing_docs)]
+pub enum Payload {
+    Bool(bool),
+    Number(f32),
+    Text(String),
+}
+
+impl Payload {
+    /// A representation of the payload which can be compared and hashed. Numbers are compared by
+    /// their bit patterns.
+    fn key(&self) -> (u8, u32, &str) {
+        match self {
+            Self::Bool(value) => (0, *value as u32, ""),
+            Self::Number(value) => (1, value.to_bits(), ""),
+            Self::Text(value) => (2, 0, value),
+        }
+    }
+}
+
+impl PartialEq for Payload {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Payload {}
+
+impl Hash for Payload {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
+
+impl From<bool> for Payload {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<f32> for Payload {
+    fn from(value: f32) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl From<String> for Payload {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&str> for Payload {
+    fn from(value: &str) -> Self {
+        Self::Text(value.into())
+    }
+}
 
 
 
@@ -24,20 +101,45 @@ pub use shortcuts::ActionType;
 // ============
 
 /// Shortcut action rule, a combination of `ActionType`, like `Press` and a pattern, like
-/// "ctrl shift s".
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+/// "ctrl shift s". The pattern is a [`KeySequence`], so it can also consist of several steps, like
+/// "ctrl k, ctrl s", which is matched when the steps are pressed one after another. See
+/// [`Rule::new_sequence`].
+///
+/// The [`ANY_CHARACTER`] pattern matches a press of any printable character key. The command
+/// triggered by such a rule should accept the key as an argument, see [`command::Endpoint`].
+///
+/// A rule can also carry a [`Payload`], passed as an argument to the command.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Hash, Serialize)]
 #[allow(missing_docs)]
 pub struct Rule {
     pub tp:      ActionType,
-    pub pattern: String,
+    pub pattern: KeySequence,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<Payload>,
 }
 
 impl Rule {
     /// Constructor.
-    pub fn new(tp: impl Into<ActionType>, pattern: impl Into<String>) -> Self {
+    pub fn new(tp: impl Into<ActionType>, pattern: impl Into<KeySequence>) -> Self {
         let tp = tp.into();
         let pattern = pattern.into();
-        Self { tp, pattern }
+        let payload = None;
+        Self { tp, pattern, payload }
+    }
+
+    /// Set the payload passed to the command. See [`Payload`] to learn more.
+    pub fn with_payload(mut self, payload: impl Into<Payload>) -> Self {
+        self.payload = Some(payload.into());
+        self
+    }
+
+    /// Constructor of a key sequence rule, like `["ctrl k", "ctrl s"]`. The action type applies to
+    /// the last step of the sequence.
+    pub fn new_sequence<S: AsRef<str>>(
+        tp: impl Into<ActionType>,
+        steps: impl IntoIterator<Item = S>,
+    ) -> Self {
+        Self::new(tp, KeySequence::new(steps))
     }
 }
 
@@ -72,6 +174,9 @@ pub enum Condition {
     Always,
     Never,
     When(String),
+    /// True if any instance of the view with the given label (see [`command::View::label`]) is
+    /// focused, regardless of the target of the shortcut.
+    Focused(String),
     Not(Box<Condition>),
     Or(Box<Condition>, Box<Condition>),
     And(Box<Condition>, Box<Condition>),
@@ -82,6 +187,10 @@ impl Condition {
         Self::When(t.into())
     }
 
+    fn focused(label: impl Into<String>) -> Self {
+        Self::Focused(label.into())
+    }
+
     fn not(a: Self) -> Self {
         Self::Not(Box::new(a))
     }
@@ -94,36 +203,128 @@ impl Condition {
         Self::Or(Box::new(a), Box::new(b))
     }
 
-    /// Split the input on the provided `separator`, process each chunk with `f`, and fold results
-    /// using the `cons`.
-    fn split_parse(
-        input: &str,
-        separator: char,
-        cons: impl Fn(Self, Self) -> Self,
-        f: impl Fn(&str) -> Self,
-    ) -> Self {
-        input.split(separator).map(|t| t.trim()).map(f).reduce(cons).unwrap_or(Self::Never)
-    }
-
     /// Parses the provided input expression. The currently recognizable symbols are (sorted by
-    /// precedence - high to low): negations(!), conjunctions (&), alternatives (|), and variables.
-    /// For example, it parses the following expression: "a & b | !c". Parentheses are not supported
-    /// yet.
-    #[allow(clippy::redundant_closure)] // Rust TC does not agree.
+    /// precedence - high to low): parentheses, negations(!), conjunctions (&), alternatives (|),
+    /// constants (true and false), focused view checks (like "focused(Searcher)"), and variables.
+    /// For example, it parses the following expression: "a & !(b | c)". An empty expression is
+    /// parsed as `Always`, while a malformed one (like "a & (b") as `Never`, so the shortcut
+    /// guarded by it will never fire.
     fn parse(s: impl AsRef<str>) -> Self {
         let s = s.as_ref().trim();
         if s.is_empty() {
             Self::Always
         } else {
-            Self::split_parse(s, '|', Self::or, |s| {
-                Self::split_parse(s, '&', Self::and, |s| {
-                    if let Some(expr) = s.strip_prefix('!') {
-                        Self::not(Self::when(expr.trim()))
-                    } else {
-                        Self::when(s)
-                    }
-                })
-            })
+            let mut parser = ConditionParser { input: s };
+            let result = parser.alternative();
+            if parser.is_finished() {
+                result.unwrap_or(Self::Never)
+            } else {
+                Self::Never
+            }
+        }
+    }
+}
+
+/// A recursive descent parser of `Condition` expressions. Each method parses a single grammar rule
+/// and consumes the parsed part of the input.
+#[derive(Debug)]
+struct ConditionParser<'a> {
+    input: &'a str,
+}
+
+impl<'a> ConditionParser<'a> {
+    fn is_finished(&self) -> bool {
+        self.input.trim().is_empty()
+    }
+
+    fn consume(&mut self, symbol: char) -> bool {
+        self.input = self.input.trim_start();
+        let rest = self.input.strip_prefix(symbol);
+        rest.for_each(|rest| self.input = rest);
+        rest.is_some()
+    }
+
+    fn alternative(&mut self) -> Option<Condition> {
+        let mut result = self.conjunction()?;
+        while self.consume('|') {
+            result = Condition::or(result, self.conjunction()?);
+        }
+        Some(result)
+    }
+
+    fn conjunction(&mut self) -> Option<Condition> {
+        let mut result = self.negation()?;
+        while self.consume('&') {
+            result = Condition::and(result, self.negation()?);
+        }
+        Some(result)
+    }
+
+    fn negation(&mut self) -> Option<Condition> {
+        if self.consume('!') {
+            self.negation().map(Condition::not)
+        } else if self.consume('(') {
+            let result = self.alternative()?;
+            self.consume(')').then(|| result)
+        } else {
+            self.variable()
+        }
+    }
+
+    fn variable(&mut self) -> Option<Condition> {
+        match self.name()? {
+            "true" => Some(Condition::Always),
+            "false" => Some(Condition::Never),
+            "focused" if self.consume('(') => {
+                let label = self.name()?;
+                self.consume(')').then(|| Condition::focused(label))
+            }
+            name => Some(Condition::when(name)),
+        }
+    }
+
+    fn name(&mut self) -> Option<&'a str> {
+        self.input = self.input.trim_start();
+        let is_name_char = |c: char| c.is_alphanumeric() || c == '_' || c == '-' || c == '.';
+        let end = self.input.find(|c| !is_name_char(c)).unwrap_or(self.input.len());
+        let (name, rest) = self.input.split_at(end);
+        self.input = rest;
+        (!name.is_empty()).then(|| name)
+    }
+}
+
+/// Formats the condition as an expression accepted by [`Condition::parse`].
+impl Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Condition::*;
+        let is_or = |t: &Condition| matches!(t, Or(..));
+        let is_binary = |t: &Condition| matches!(t, Or(..) | And(..));
+        let write_wrapped = |f: &mut fmt::Formatter<'_>, t: &Condition, wrap: bool| {
+            if wrap {
+                write!(f, "({})", t)
+            } else {
+                write!(f, "{}", t)
+            }
+        };
+        match self {
+            Always => write!(f, "true"),
+            Never => write!(f, "false"),
+            When(name) => write!(f, "{}", name),
+            Focused(label) => write!(f, "focused({})", label),
+            Not(a) => {
+                write!(f, "!")?;
+                write_wrapped(f, a, is_binary(a))
+            }
+            And(a, b) => {
+                write_wrapped(f, a, is_or(a))?;
+                write!(f, " & ")?;
+                write_wrapped(f, b, is_binary(b))
+            }
+            Or(a, b) => {
+                write_wrapped(f, a, false)?;
+                write!(f, " | ")?;
+                write_wrapped(f, b, is_or(b))
+            }
         }
     }
 }
@@ -176,11 +377,57 @@ impl Action {
 // ================
 
 /// A keyboard shortcut, an `Rule` associated with a `Action`.
+///
+/// ## Conflict Resolution
+/// When several shortcuts match the same input event, only the most specific ones are executed:
+/// 1. Only the shortcuts with the highest `priority` (0 by default) are considered.
+/// 2. If any of them is executed on a focused component instance, the shortcuts marked as `global`
+///    executed on unfocused instances are dropped, so a shortcut of the focused component
+///    overrides a global one.
+/// 3. Among the remaining shortcuts of the same target, the ones executed on a focused component
+///    instance beat the ones executed on unfocused instances, and the ones having a condition beat
+///    the unconditional ones. Shortcuts of different targets which are not `global` do not
+///    override each other, as components commonly bind the same keys to independent actions, like
+///    the arrows moving the cursor of the focused text area and the selection of a list view.
+///
+/// Shortcuts marked as `parallel` do not take part in the resolution and are always executed.
+///
+/// ## Key Repeat
+/// Holding a key makes the OS repeat its press event. By default, `Press` shortcuts are executed
+/// only once, when the key is pressed for the first time, which is the desired behavior of
+/// destructive commands, like removing nodes. Shortcuts marked as `repeatable` (like navigation
+/// ones) are executed on every repeat as well. Shortcuts with the `PressAndRepeat` rule are always
+/// repeatable.
+///
+/// ## Physical Keys
+/// By default, shortcuts match the characters produced by the keys, which depend on the keyboard
+/// layout. For example, "ctrl z" is matched by pressing the key labeled "Z", which is placed
+/// differently on the QWERTY and AZERTY layouts. Shortcuts marked as `physical` match the physical
+/// keys instead, named after the characters they produce in the US QWERTY layout, so they are
+/// triggered by keys at the same position on every layout. See
+/// [`keyboard::KeyWithCode::physical_name`].
+///
+/// ## Scopes
+/// A shortcut can be assigned to a named scope (like "text-editing" or "graph"). All shortcuts of
+/// a scope can be disabled at once with [`RegistryModel::set_scope_enabled`], for example, when a
+/// modal dialog is open.
+///
+/// ## Documentation
+/// A shortcut can have a human-readable description and a category (like "Navigation"), which are
+/// presented to the user in the shortcut help. See [`RegistryModel::documentation`].
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Shrinkwrap)]
 pub struct Shortcut {
     #[shrinkwrap(main_field)]
-    action: Action,
-    rule:   Rule,
+    action:      Action,
+    rule:        Rule,
+    priority:    i32,
+    parallel:    bool,
+    global:      bool,
+    repeatable:  bool,
+    physical:    bool,
+    description: Option<String>,
+    category:    Option<String>,
+    scope:       Option<String>,
 }
 
 impl Shortcut {
@@ -190,9 +437,7 @@ impl Shortcut {
         target: impl Into<String>,
         command: impl Into<Command>,
     ) -> Self {
-        let action = Action::new(target, command);
-        let rule = rule.into();
-        Self { action, rule }
+        Self::new_when(rule, target, command, Condition::Always)
     }
 
     /// Constructor.
@@ -204,10 +449,430 @@ impl Shortcut {
     ) -> Self {
         let action = Action::new_when(target, command, condition);
         let rule = rule.into();
-        Self { action, rule }
+        let priority = 0;
+        let parallel = false;
+        let global = false;
+        let repeatable = false;
+        let physical = false;
+        let description = None;
+        let category = None;
+        let scope = None;
+        Self {
+            action,
+            rule,
+            priority,
+            parallel,
+            global,
+            repeatable,
+            physical,
+            description,
+            category,
+            scope,
+        }
+    }
+
+    /// Set the priority of the shortcut. See the docs of [`Shortcut`] to learn more.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Exclude the shortcut from the conflict resolution, so it is always executed when matched.
+    /// See the docs of [`Shortcut`] to learn more.
+    pub fn as_parallel(mut self) -> Self {
+        self.parallel = true;
+        self
+    }
+
+    /// Let the shortcuts of focused component instances override this one, regardless of their
+    /// targets. See the docs of [`Shortcut`] to learn more.
+    pub fn as_global(mut self) -> Self {
+        self.global = true;
+        self
+    }
+
+    /// Execute the shortcut on every key repeat event. See the docs of [`Shortcut`] to learn more.
+    pub fn as_repeatable(mut self) -> Self {
+        self.repeatable = true;
+        self
+    }
+
+    /// Match the physical keys instead of the characters they produce. See the docs of
+    /// [`Shortcut`] to learn more.
+    pub fn as_physical(mut self) -> Self {
+        self.physical = true;
+        self
+    }
+
+    /// The identifier of the binding of the shortcut's command. Unlike the shortcut itself, it
+    /// does not change when the command is rebound.
+    pub fn binding_key(&self) -> BindingKey {
+        (self.target.clone(), self.command.clone())
+    }
+
+    /// The identifier of the shortcut among the shortcuts of its command.
+    pub fn shortcut_key(&self) -> ShortcutKey {
+        (self.binding_key(), self.rule.clone())
+    }
+
+    /// Check whether the shortcut is executed on key repeat events.
+    pub fn is_repeatable(&self) -> bool {
+        self.repeatable || self.rule.tp == ActionType::PressAndRepeat
+    }
+
+    /// The action type the shortcut is registered for in the underlying shortcut registry.
+    fn registered_action_type(&self) -> ActionType {
+        match self.rule.tp {
+            ActionType::Press if self.repeatable => ActionType::PressAndRepeat,
+            tp => tp,
+        }
+    }
+
+    /// Set the human-readable description of the shortcut.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the category the shortcut is grouped under in the shortcut help.
+    pub fn with_category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Assign the shortcut to the named scope. See the docs of [`Shortcut`] to learn more.
+    pub fn in_scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = Some(scope.into());
+        self
+    }
+
+    /// Copy the properties not related to the matching (like priority or description) from the
+    /// other shortcut.
+    fn with_properties_of(self, other: &Shortcut) -> Self {
+        let priority = other.priority;
+        let parallel = other.parallel;
+        let global = other.global;
+        let repeatable = other.repeatable;
+        let physical = other.physical;
+        let description = other.description.clone();
+        let category = other.category.clone();
+        let scope = other.scope.clone();
+        Self {
+            priority,
+            parallel,
+            global,
+            repeatable,
+            physical,
+            description,
+            category,
+            scope,
+            ..self
+        }
+    }
+}
+
+
+
+// =============================
+// === ShortcutDocumentation ===
+// =============================
+
+/// Description of an active shortcut, as presented to the user in the shortcut help. See
+/// [`RegistryModel::documentation`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub struct ShortcutDocumentation {
+    pub command:     String,
+    pub rule:        Rule,
+    pub description: Option<String>,
+    pub category:    Option<String>,
+}
+
+impl From<&Shortcut> for ShortcutDocumentation {
+    fn from(shortcut: &Shortcut) -> Self {
+        let command = shortcut.command.name.clone();
+        let rule = shortcut.rule.clone();
+        let description = shortcut.description.clone();
+        let category = shortcut.category.clone();
+        Self { command, rule, description, category }
+    }
+}
+
+
+
+// ==============
+// === Keymap ===
+// ==============
+
+/// Declarative definition of a [`Shortcut`], used by the [`Keymap`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[allow(missing_docs)]
+pub struct ShortcutDefinition {
+    pub target:      String,
+    pub command:     String,
+    pub rule:        Rule,
+    /// The condition expression, see [`Condition`] to learn more. Unconditional shortcuts have no
+    /// condition provided.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition:   Option<String>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub priority:    i32,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub parallel:    bool,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub global:      bool,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub repeatable:  bool,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub physical:    bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category:    Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope:       Option<String>,
+}
+
+fn is_default<T: Default + PartialEq>(t: &T) -> bool {
+    t == &default()
+}
+
+impl From<&Shortcut> for ShortcutDefinition {
+    fn from(shortcut: &Shortcut) -> Self {
+        let target = shortcut.target.clone();
+        let command = shortcut.command.name.clone();
+        let rule = shortcut.rule.clone();
+        let condition = &shortcut.condition;
+        let condition = (condition != &Condition::Always).then(|| condition.to_string());
+        Self {
+            target,
+            command,
+            rule,
+            condition,
+            priority: shortcut.priority,
+            parallel: shortcut.parallel,
+            global: shortcut.global,
+            repeatable: shortcut.repeatable,
+            physical: shortcut.physical,
+            description: shortcut.description.clone(),
+            category: shortcut.category.clone(),
+            scope: shortcut.scope.clone(),
+        }
+    }
+}
+
+impl From<ShortcutDefinition> for Shortcut {
+    fn from(definition: ShortcutDefinition) -> Self {
+        let ShortcutDefinition { target, command, rule, condition, .. } = definition;
+        let condition = condition.map(Condition::parse).unwrap_or(Condition::Always);
+        let action = Action::new_when(target, command, condition);
+        Self {
+            action,
+            rule,
+            priority: definition.priority,
+            parallel: definition.parallel,
+            global: definition.global,
+            repeatable: definition.repeatable,
+            physical: definition.physical,
+            description: definition.description,
+            category: definition.category,
+            scope: definition.scope,
+        }
+    }
+}
+
+/// A declarative description of a set of shortcuts. It is used both to ship keymaps as data files
+/// and to inspect the active shortcuts, for example, when reporting bugs. See
+/// [`RegistryModel::export_keymap`] and [`RegistryModel::import_keymap`].
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[allow(missing_docs)]
+pub struct Keymap {
+    pub shortcuts: Vec<ShortcutDefinition>,
+}
+
+impl Keymap {
+    /// Parse the keymap from a JSON document.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Serialize the keymap to a JSON document.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+
+
+// ====================
+// === UserBindings ===
+// ====================
+
+/// A user-defined binding of a command, overriding all default shortcuts of the command. A binding
+/// without any rules unbinds the command.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[allow(missing_docs)]
+pub struct UserBinding {
+    pub target:    String,
+    pub command:   String,
+    pub rules:     Vec<Rule>,
+    /// The condition expression, see [`Condition`] to learn more. If not provided, the condition
+    /// of the default shortcuts of the command is used.
+    #[serde(default)]
+    pub condition: Option<String>,
+}
+
+impl UserBinding {
+    /// Constructor.
+    pub fn new(target: impl Into<String>, command: impl Into<String>, rules: Vec<Rule>) -> Self {
+        let target = target.into();
+        let command = command.into();
+        Self { target, command, rules, condition: None }
+    }
+
+    fn key(&self) -> BindingKey {
+        (self.target.clone(), self.command.as_str().into())
     }
 }
 
+/// All user-defined bindings. This is the document persisted between application runs by the
+/// [`UserBindingsStorage`].
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[allow(missing_docs)]
+pub struct UserBindings {
+    pub bindings: Vec<UserBinding>,
+}
+
+impl UserBindings {
+    /// Parse the bindings from a JSON document.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Serialize the bindings to a JSON document.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Storage of the user bindings document. The document is loaded once the storage is provided to
+/// the [`Registry`], and saved on every modification of the user bindings.
+pub trait UserBindingsStorage: Debug {
+    /// Load the document. Returns `None` if there is no document saved yet.
+    fn load(&self) -> Option<String>;
+
+    /// Save the document.
+    fn save(&self, document: &str);
+}
+
+/// Identifier of a command binding, consisting of the target name and the command.
+pub type BindingKey = (String, Command);
+
+/// Identifier of a single shortcut of a command binding, consisting of the binding key and the
+/// rule of the shortcut.
+pub type ShortcutKey = (BindingKey, Rule);
+
+
+
+// ===============
+// === Profile ===
+// ===============
+
+/// The name of the profile which is active by default. It contains no bindings, so only the
+/// default shortcuts are active.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// A named set of bindings layered over the default shortcuts, like a vim-style keymap. Each
+/// binding replaces all default shortcuts of its command, just like a [`UserBinding`], while the
+/// user bindings take precedence over the bindings of the active profile. Profiles are meant to be
+/// shipped as data files, see [`Profile::from_json`] and [`RegistryModel::set_profile`].
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[allow(missing_docs)]
+pub struct Profile {
+    pub name:     String,
+    pub bindings: Vec<UserBinding>,
+}
+
+impl Profile {
+    /// Constructor.
+    pub fn new(name: impl Into<String>, bindings: Vec<UserBinding>) -> Self {
+        let name = name.into();
+        Self { name, bindings }
+    }
+
+    /// Parse the profile from a JSON document.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Serialize the profile to a JSON document.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+
+
+// ===============
+// === Capture ===
+// ===============
+
+/// Shortcuts matched by an input event, together with the name of the key which triggered them.
+/// Events consumed by capturing a key combination are marked as `captured`.
+#[derive(Clone, Debug, Default)]
+struct Matched {
+    shortcuts: Vec<Shortcut>,
+    key:       String,
+    captured:  bool,
+}
+
+/// The state of capturing a key combination. See [`RegistryModel::capture_next`].
+#[derive(Debug)]
+struct Capture {
+    /// Keys pressed since the capture started, in the order of pressing.
+    pressed: Vec<String>,
+    sender:  oneshot::Sender<Rule>,
+}
+
+impl Capture {
+    fn new(sender: oneshot::Sender<Rule>) -> Self {
+        Self { pressed: default(), sender }
+    }
+
+    /// The rule matching the pressed keys. Modifiers are placed first, using their side-independent
+    /// names, so the rule matches both the left and the right modifier keys.
+    fn rule(&self) -> Rule {
+        let (modifiers, keys): (Vec<_>, Vec<_>) =
+            self.pressed.iter().partition(|key| shortcuts::modifier_name(key).is_some());
+        let modifiers = modifiers.into_iter().filter_map(|key| shortcuts::modifier_name(key));
+        let pattern = modifiers.unique().chain(keys.into_iter().map(|t| t.as_str())).join(" ");
+        Rule::new(ActionType::Press, pattern)
+    }
+}
+
+
+
+// ===================
+// === TargetStats ===
+// ===================
+
+/// The interval of removing the dead component instances from the command registry.
+pub const DEAD_INSTANCES_SWEEP_INTERVAL_MS: f32 = 5000.0;
+
+/// Statistics of a shortcut target, used to detect components which leak their instances or drop
+/// them prematurely. See [`RegistryModel::stats`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub struct TargetStats {
+    /// The number of active shortcuts targeting the component.
+    pub shortcuts:       usize,
+    pub live_instances:  usize,
+    /// The number of dropped instances which were not removed from the command registry yet.
+    pub dead_instances:  usize,
+    /// The number of dropped instances removed from the command registry so far.
+    pub swept_instances: usize,
+}
+
 
 
 // ================
@@ -220,13 +885,34 @@ impl Shortcut {
 /// dropped, the shortcut will be lazily removed. This is useful when defining shortcuts by GUI
 /// components. When a component is unloaded, all its default shortcuts should be removed as well.
 ///
-/// ## Implementation Notes
-/// There should be a layer for user shortcuts which will remember handles permanently until a
-/// shortcut is unregistered.
+/// ## User Bindings
+/// The default shortcuts (usually provided by `View::default_shortcuts`) can be overridden by the
+/// user. A [`UserBinding`] replaces all default shortcuts of the command. User bindings can be
+/// loaded from and saved to a JSON document using the [`UserBindingsStorage`], and reset to the
+/// defaults with [`RegistryModel::reset_binding`].
+///
+/// ## Profiles
+/// Alternative keymaps are provided as named [`Profile`]s, registered with
+/// [`RegistryModel::add_profile`] and switched at runtime with [`RegistryModel::set_profile`].
+/// The bindings of the active profile override the default shortcuts, and are overridden by the
+/// user bindings in turn.
+///
+/// ## Capturing Shortcuts
+/// In order to let the user assign a shortcut by pressing it, use [`RegistryModel::capture_next`].
+/// No shortcuts are executed while the key combination is being captured, nor on the release of
+/// the keys pressed during the capture.
+///
+/// ## Unhandled Keys
+/// Key presses which did not execute any command are emitted on the `unhandled_keys` output, so
+/// the application can forward them elsewhere, for example, to the native menu. By default, the
+/// browser actions of all key events are prevented. Use [`Registry::pass_unhandled_keys_to_dom`]
+/// to let the browser handle the unhandled keys.
 #[derive(Clone, CloneRef, Debug)]
+#[allow(missing_docs)]
 pub struct Registry {
-    model:   RegistryModel,
-    network: frp::Network,
+    model:              RegistryModel,
+    network:            frp::Network,
+    pub unhandled_keys: frp::Stream<String>,
 }
 
 /// Internal representation of `Registry`.
@@ -237,6 +923,21 @@ pub struct RegistryModel {
     mouse:              Mouse,
     command_registry:   command::Registry,
     shortcuts_registry: shortcuts::HashSetRegistry<Shortcut>,
+    physical_registry:  shortcuts::HashSetRegistry<Shortcut>,
+    /// Physical names of the pressed keys, see [`keyboard::KeyWithCode::physical_name`].
+    physical_keys:      Rc<RefCell<HashMap<String, String>>>,
+    defaults:           Rc<RefCell<HashMap<BindingKey, Vec<Shortcut>>>>,
+    user_bindings:      Rc<RefCell<HashMap<BindingKey, UserBinding>>>,
+    profiles:           Rc<RefCell<HashMap<String, HashMap<BindingKey, UserBinding>>>>,
+    profile:            Rc<RefCell<String>>,
+    storage:            Rc<RefCell<Option<Box<dyn UserBindingsStorage>>>>,
+    disabled_scopes:    Rc<RefCell<HashSet<String>>>,
+    capture:            Rc<RefCell<Option<Capture>>>,
+    /// Keys pressed during the finished capture which are still held. Their release does not
+    /// execute any shortcuts.
+    captured_keys:      Rc<RefCell<HashSet<String>>>,
+    last_sweep_time:    Rc<Cell<f32>>,
+    swept_instances:    Rc<RefCell<HashMap<String, usize>>>,
 }
 
 impl Deref for Registry {
@@ -258,14 +959,26 @@ impl Registry {
         let mouse = &model.mouse;
 
         frp::new_network! { network
-            kb_down    <- keyboard.down.map (f!((t) model.shortcuts_registry.on_press(t.simple_name())));
-            kb_up      <- keyboard.up.map   (f!((t) model.shortcuts_registry.on_release(t.simple_name())));
-            mouse_down <- mouse.down.map    (f!((t) model.shortcuts_registry.on_press(t.simple_name())));
-            mouse_up   <- mouse.up.map      (f!((t) model.shortcuts_registry.on_release(t.simple_name())));
-            event      <- any(kb_down,kb_up,mouse_down,mouse_up);
-            eval event ((m) model.process_rules(m));
+            kb_down    <- keyboard.down_with_code.map (f!((t) model.on_key_press(t)));
+            kb_up      <- keyboard.up.map   (f!((t) model.on_release(t.simple_name())));
+            mouse_down <- mouse.down.map    (f!((t) model.on_press(t.simple_name())));
+            mouse_up   <- mouse.up.map      (f!((t) model.on_release(t.simple_name())));
+            event      <- any(kb_up,mouse_down,mouse_up);
+            eval event ((m) model.process_rules(&m.shortcuts, &m.key));
+            unhandled_keys <- kb_down.filter_map(f!((m) model.process_key_press(m)));
+        }
+        Self { model, network, unhandled_keys }
+    }
+
+    /// Do not prevent the default browser actions of the key presses which did not execute any
+    /// command. Should be called once.
+    pub fn pass_unhandled_keys_to_dom(&self, current_js_event: &CurrentJsEvent) {
+        let network = &self.network;
+        let pass_to_dom = current_js_event.pass_to_dom.clone_ref();
+        let unhandled_keys = self.unhandled_keys.clone_ref();
+        frp::extend! { network
+            eval_ unhandled_keys (pass_to_dom.emit(()));
         }
-        Self { model, network }
     }
 }
 
@@ -282,23 +995,531 @@ impl RegistryModel {
         let mouse = mouse.clone_ref();
         let command_registry = command_registry.clone_ref();
         let shortcuts_registry = default();
-        Self { logger, keyboard, mouse, command_registry, shortcuts_registry }
+        let physical_registry = default();
+        let physical_keys = default();
+        let defaults = default();
+        let user_bindings = default();
+        let profiles = default();
+        let profile = Rc::new(RefCell::new(DEFAULT_PROFILE.to_string()));
+        let storage = default();
+        let disabled_scopes = default();
+        let capture = default();
+        let captured_keys = default();
+        let last_sweep_time = default();
+        let swept_instances = default();
+        Self {
+            logger,
+            keyboard,
+            mouse,
+            command_registry,
+            shortcuts_registry,
+            physical_registry,
+            physical_keys,
+            defaults,
+            user_bindings,
+            profiles,
+            profile,
+            storage,
+            disabled_scopes,
+            capture,
+            captured_keys,
+            last_sweep_time,
+            swept_instances,
+        }
+    }
+
+    /// Capture the next key combination pressed by the user, like "ctrl shift s". The combination
+    /// is finished when a key other than a modifier is pressed. No shortcuts are executed until
+    /// then. To capture a key sequence, capture its steps one after another and join them with
+    /// [`Rule::new_sequence`].
+    ///
+    /// The returned future resolves to `None` if the capture was cancelled, either by
+    /// [`Self::cancel_capture`] or by starting a new capture.
+    pub fn capture_next(&self) -> impl Future<Output = Option<Rule>> {
+        let (sender, receiver) = oneshot::channel();
+        *self.capture.borrow_mut() = Some(Capture::new(sender));
+        receiver.map(|result| result.ok())
+    }
+
+    /// Cancel the key combination capture started with [`Self::capture_next`].
+    pub fn cancel_capture(&self) {
+        if let Some(state) = self.capture.borrow_mut().take() {
+            self.captured_keys.borrow_mut().extend(state.pressed);
+        }
+    }
+
+    /// Check whether a key combination is being captured.
+    pub fn is_capturing(&self) -> bool {
+        self.capture.borrow().is_some()
     }
 
-    fn process_rules(&self, rules: &[Shortcut]) {
-        let mut targets = Vec::new();
+    /// Simulate the user performing the rule, running the same processing as the real input
+    /// events, including the conflict resolution and condition checking. The keys of each step
+    /// are pressed in the order of appearance and released in the reverse order. For `DoublePress`
+    /// and `DoubleClick` rules, each step is performed twice, and for `MultiPress(n)` rules, n
+    /// times. Time-based actions, like `LongPress`, are not simulated. Useful in tests, to check
+    /// that a shortcut reaches the right command.
+    pub fn simulate(&self, rule: &Rule) {
+        let repetitions = match rule.tp {
+            ActionType::DoublePress | ActionType::DoubleClick => 2,
+            ActionType::MultiPress(taps) => taps,
+            _ => 1,
+        };
+        for step in rule.pattern.steps() {
+            let keys = step.split_whitespace().map(shortcuts::resolve_key_alias).collect_vec();
+            for _ in 0..repetitions {
+                for key in &keys {
+                    let matched = self.on_press(key.clone());
+                    self.process_rules(&matched.shortcuts, &matched.key);
+                }
+                for key in keys.iter().rev() {
+                    let matched = self.on_release(key.clone());
+                    self.process_rules(&matched.shortcuts, &matched.key);
+                }
+            }
+        }
+    }
+
+    fn on_key_press(&self, key: &keyboard::KeyWithCode) -> Matched {
+        self.on_press_with_physical_key(key.key.simple_name(), key.physical_name())
+    }
+
+    fn on_press(&self, key: String) -> Matched {
+        self.on_press_with_physical_key(key.clone(), key)
+    }
+
+    fn on_press_with_physical_key(&self, key: String, physical_key: String) -> Matched {
+        let mut shortcuts = self.shortcuts_registry.on_press(&key);
+        shortcuts.extend(self.physical_registry.on_press(&physical_key));
+        self.physical_keys.borrow_mut().insert(key.clone(), physical_key);
+        let mut capture = self.capture.borrow_mut();
+        match capture.as_mut() {
+            None => Matched { shortcuts, key, captured: false },
+            Some(state) => {
+                if !state.pressed.contains(&key) {
+                    state.pressed.push(key.clone());
+                }
+                if shortcuts::modifier_name(&key).is_none() {
+                    if let Some(state) = capture.take() {
+                        let rule = state.rule();
+                        self.captured_keys.borrow_mut().extend(state.pressed);
+                        // The receiver might have been dropped, which means nobody waits for the
+                        // result anymore.
+                        state.sender.send(rule).ok();
+                    }
+                }
+                Matched { captured: true, ..default() }
+            }
+        }
+    }
+
+    fn on_release(&self, key: String) -> Matched {
+        let physical_key = self.physical_keys.borrow_mut().remove(&key);
+        let physical_key = physical_key.unwrap_or_else(|| key.clone());
+        let mut shortcuts = self.shortcuts_registry.on_release(&key);
+        shortcuts.extend(self.physical_registry.on_release(&physical_key));
+        let captured_key = self.captured_keys.borrow_mut().remove(&key);
+        match self.capture.borrow_mut().as_mut() {
+            None if captured_key => Matched { captured: true, ..default() },
+            None => Matched { shortcuts, key, captured: false },
+            Some(state) => {
+                state.pressed.retain(|t| t != &key);
+                Matched { captured: true, ..default() }
+            }
+        }
+    }
+
+    /// Process the actions triggered by the passage of time, like `ActionType::LongPress`. Should
+    /// be called on every frame.
+    pub fn on_tick(&self) {
+        let rules = self.shortcuts_registry.on_tick().extended(self.physical_registry.on_tick());
+        if !rules.is_empty() && !self.is_capturing() {
+            self.process_rules(&rules, "");
+        }
+        let time = web::time_from_start() as f32;
+        if time - self.last_sweep_time.get() >= DEAD_INSTANCES_SWEEP_INTERVAL_MS {
+            self.last_sweep_time.set(time);
+            self.sweep_dead_instances();
+        }
+    }
+
+    /// Remove the instances of dropped components from the command registry. It is done
+    /// periodically by [`Self::on_tick`].
+    pub fn sweep_dead_instances(&self) {
+        let mut name_map = self.command_registry.name_map.borrow_mut();
+        let mut swept_instances = self.swept_instances.borrow_mut();
+        for (target, instances) in name_map.iter_mut() {
+            let count = instances.len();
+            instances.retain(|instance| instance.check_alive());
+            let swept = count - instances.len();
+            if swept > 0 {
+                *swept_instances.entry(target.clone()).or_default() += swept;
+            }
+        }
+        self.command_registry.id_map.borrow_mut().retain(|_, instance| instance.check_alive());
+    }
+
+    /// Statistics of all shortcut targets and registered components. See [`TargetStats`].
+    pub fn stats(&self) -> BTreeMap<String, TargetStats> {
+        let mut stats = BTreeMap::<String, TargetStats>::new();
+        for shortcut in self.all_active_shortcuts() {
+            stats.entry(shortcut.target.clone()).or_default().shortcuts += 1;
+        }
+        for (target, instances) in self.command_registry.name_map.borrow().iter() {
+            let target_stats = stats.entry(target.clone()).or_default();
+            let live_instances = instances.iter().filter(|t| t.check_alive()).count();
+            target_stats.live_instances = live_instances;
+            target_stats.dead_instances = instances.len() - live_instances;
+        }
+        for (target, swept) in self.swept_instances.borrow().iter() {
+            stats.entry(target.clone()).or_default().swept_instances = *swept;
+        }
+        stats
+    }
+
+    /// Set the maximum time between two presses recognized as the `DoublePress` action (and two
+    /// releases recognized as the `DoubleClick` action).
+    pub fn set_double_press_time(&self, time_ms: f32) {
+        self.shortcuts_registry.set_double_event_time(time_ms);
+        self.physical_registry.set_double_event_time(time_ms)
+    }
+
+    /// Set the maximum time between consecutive steps of key sequence shortcuts. See [`Rule`] to
+    /// learn more.
+    pub fn set_sequence_timeout(&self, timeout_ms: f32) {
+        self.shortcuts_registry.set_sequence_timeout(timeout_ms);
+        self.physical_registry.set_sequence_timeout(timeout_ms)
+    }
+
+    /// Enable or disable all shortcuts of the named scope. See the docs of [`Shortcut`] to learn
+    /// more.
+    pub fn set_scope_enabled(&self, scope: impl Into<String>, enabled: bool) {
+        let scope = scope.into();
+        if enabled {
+            self.disabled_scopes.borrow_mut().remove(&scope);
+        } else {
+            self.disabled_scopes.borrow_mut().insert(scope);
+        }
+    }
+
+    /// Check whether the shortcuts of the named scope are enabled.
+    pub fn is_scope_enabled(&self, scope: &str) -> bool {
+        !self.disabled_scopes.borrow().contains(scope)
+    }
+
+    fn is_shortcut_enabled(&self, shortcut: &Shortcut) -> bool {
+        shortcut.scope.as_ref().map_or(true, |scope| self.is_scope_enabled(scope))
+    }
+
+    /// Set the storage of user bindings. The user bindings are loaded from the storage immediately,
+    /// replacing the current ones, and are saved to it on every modification.
+    pub fn set_user_bindings_storage(&self, storage: impl UserBindingsStorage + 'static) {
+        let document = storage.load();
+        *self.storage.borrow_mut() = Some(Box::new(storage));
+        if let Some(document) = document {
+            match UserBindings::from_json(&document) {
+                Ok(bindings) => self.replace_user_bindings(bindings),
+                Err(err) => warning!(&self.logger, "Cannot parse the user shortcuts: {err}."),
+            }
+        }
+    }
+
+    /// Replace all user bindings with the provided ones and save them.
+    pub fn load_user_bindings(&self, bindings: UserBindings) {
+        self.replace_user_bindings(bindings);
+        self.save_user_bindings();
+    }
+
+    /// All user bindings, sorted by the target and command names.
+    pub fn user_bindings(&self) -> UserBindings {
+        let mut bindings = self.user_bindings.borrow().values().cloned().collect_vec();
+        bindings.sort_by(|a, b| (&a.target, &a.command).cmp(&(&b.target, &b.command)));
+        UserBindings { bindings }
+    }
+
+    /// Override the default shortcuts of a command with the user binding and save it.
+    pub fn set_user_binding(&self, binding: UserBinding) {
+        self.set_user_binding_without_saving(binding);
+        self.save_user_bindings();
+    }
+
+    /// Remove the user binding of the command, restoring its default shortcuts, and save the
+    /// change.
+    pub fn reset_binding(&self, target: impl Into<String>, command: impl Into<Command>) {
+        self.reset_binding_without_saving(&(target.into(), command.into()));
+        self.save_user_bindings();
+    }
+
+    /// Rules of all shortcuts of the command which are currently active.
+    pub fn binding(&self, target: impl Into<String>, command: impl Into<Command>) -> Vec<Rule> {
+        let key = (target.into(), command.into());
+        self.active_shortcuts(&key).into_iter().map(|t| t.rule).collect()
+    }
+
+    /// Bind the command to the provided rules, replacing all its current shortcuts. The change is
+    /// saved as a user binding. Returns the rules of the replaced shortcuts.
+    pub fn rebind(
+        &self,
+        target: impl Into<String>,
+        command: impl Into<Command>,
+        rules: Vec<Rule>,
+    ) -> Vec<Rule> {
+        let key = (target.into(), command.into());
+        let previous = self.active_shortcuts(&key).into_iter().map(|t| t.rule).collect();
+        let condition = self.binding_override(&key).and_then(|t| t.condition);
+        let (target, command) = key;
+        let command = command.name;
+        self.set_user_binding(UserBinding { target, command, rules, condition });
+        previous
+    }
+
+    /// Remove all shortcuts of the command. The change is saved as a user binding. Returns the
+    /// rules of the removed shortcuts.
+    pub fn unbind(&self, target: impl Into<String>, command: impl Into<Command>) -> Vec<Rule> {
+        self.rebind(target, command, default())
+    }
+
+    fn replace_user_bindings(&self, bindings: UserBindings) {
+        let keys = self.user_bindings.borrow().keys().cloned().collect_vec();
+        for key in keys {
+            self.reset_binding_without_saving(&key);
+        }
+        for binding in bindings.bindings {
+            self.set_user_binding_without_saving(binding);
+        }
+    }
+
+    fn set_user_binding_without_saving(&self, binding: UserBinding) {
+        let key = binding.key();
+        self.update_bindings(&[key.clone()], || {
+            self.user_bindings.borrow_mut().insert(key.clone(), binding);
+        });
+    }
+
+    fn reset_binding_without_saving(&self, key: &BindingKey) {
+        self.update_bindings(&[key.clone()], || {
+            self.user_bindings.borrow_mut().remove(key);
+        });
+    }
+
+    /// Register the profile, replacing the previously registered one with the same name. If the
+    /// profile is active, its new bindings are applied immediately.
+    pub fn add_profile(&self, profile: Profile) {
+        let bindings: HashMap<_, _> = profile.bindings.into_iter().map(|t| (t.key(), t)).collect();
+        if *self.profile.borrow() == profile.name {
+            let previous = self.profiles.borrow().get(&profile.name).cloned().unwrap_or_default();
+            let keys = previous.keys().chain(bindings.keys()).cloned().unique().collect_vec();
+            self.update_bindings(&keys, || {
+                self.profiles.borrow_mut().insert(profile.name, bindings);
+            });
+        } else {
+            self.profiles.borrow_mut().insert(profile.name, bindings);
+        }
+    }
+
+    /// Activate the profile with the given name, replacing the bindings of the previously active
+    /// one. The [`DEFAULT_PROFILE`] is always available. Returns `false` and keeps the current
+    /// profile if there is no profile with the given name registered.
+    pub fn set_profile(&self, name: impl Into<String>) -> bool {
+        let name = name.into();
+        let profiles = self.profiles.borrow();
+        let is_known = name == DEFAULT_PROFILE || profiles.contains_key(&name);
+        if is_known {
+            let current = profiles.get(&*self.profile.borrow()).into_iter().flat_map(|t| t.keys());
+            let new = profiles.get(&name).into_iter().flat_map(|t| t.keys());
+            let keys = current.chain(new).cloned().unique().collect_vec();
+            drop(profiles);
+            self.update_bindings(&keys, || *self.profile.borrow_mut() = name);
+        } else {
+            warning!(&self.logger, "Cannot activate unknown shortcut profile {name}.");
+        }
+        is_known
+    }
+
+    /// The name of the active profile.
+    pub fn profile(&self) -> String {
+        self.profile.borrow().clone()
+    }
+
+    /// The names of all available profiles, sorted, including the [`DEFAULT_PROFILE`].
+    pub fn profiles(&self) -> Vec<String> {
+        let profiles = self.profiles.borrow();
+        let names = profiles.keys().map(|t| t.as_str()).chain(iter::once(DEFAULT_PROFILE));
+        names.unique().sorted().map(|t| t.into()).collect()
+    }
+
+    /// Run the function modifying the bindings of the given commands, updating their registered
+    /// shortcuts accordingly.
+    fn update_bindings(&self, keys: &[BindingKey], f: impl FnOnce()) {
+        let previous = keys.iter().map(|key| self.active_shortcuts(key)).collect_vec();
+        previous.iter().flatten().for_each(|t| self.remove_shortcut(t));
+        f();
+        for (key, previous) in keys.iter().zip(previous) {
+            let current = self.active_shortcuts(key);
+            let previous_rules = previous.into_iter().map(|t| t.rule).collect_vec();
+            let current_rules = current.iter().map(|t| t.rule.clone()).collect_vec();
+            self.command_registry.rebind_disabled_shortcuts(key, &previous_rules, &current_rules);
+            current.into_iter().for_each(|t| self.add_shortcut(t));
+        }
+    }
+
+    /// The key of the active shortcut corresponding to the provided one. The provided shortcut
+    /// may be one of the default shortcuts of a rebound command, like the ones returned by
+    /// [`command::View::default_shortcuts`], in which case the active shortcut at the same
+    /// position is used.
+    pub fn active_shortcut_key(&self, shortcut: &Shortcut) -> ShortcutKey {
+        let key = shortcut.binding_key();
+        let defaults = self.defaults.borrow().get(&key).cloned().unwrap_or_default();
+        let position = defaults.iter().position(|t| t.rule == shortcut.rule);
+        let active = position.and_then(|index| self.active_shortcuts(&key).into_iter().nth(index));
+        let rule = active.map_or_else(|| shortcut.rule.clone(), |t| t.rule);
+        (key, rule)
+    }
+
+    /// The binding overriding the default shortcuts of the command: the user binding if it
+    /// exists, or the binding of the active profile otherwise.
+    fn binding_override(&self, key: &BindingKey) -> Option<UserBinding> {
+        let user_binding = self.user_bindings.borrow().get(key).cloned();
+        user_binding.or_else(|| {
+            let profiles = self.profiles.borrow();
+            let profile = profiles.get(&*self.profile.borrow())?;
+            profile.get(key).cloned()
+        })
+    }
+
+    fn save_user_bindings(&self) {
+        if let Some(storage) = &*self.storage.borrow() {
+            match self.user_bindings().to_json() {
+                Ok(document) => storage.save(&document),
+                Err(err) => warning!(&self.logger, "Cannot serialize the user shortcuts: {err}."),
+            }
+        }
+    }
+
+    /// All active shortcuts grouped by the target name, to be presented to the user in the shortcut
+    /// help. The shortcuts of each target are sorted by their category and command names. Commands
+    /// unbound by the user are not included.
+    pub fn documentation(&self) -> BTreeMap<String, Vec<ShortcutDocumentation>> {
+        let mut documentation = BTreeMap::<String, Vec<ShortcutDocumentation>>::new();
+        for shortcut in self.all_active_shortcuts() {
+            let entries = documentation.entry(shortcut.target.clone()).or_default();
+            entries.push(ShortcutDocumentation::from(&shortcut));
+        }
+        for entries in documentation.values_mut() {
+            entries.sort_by(|a, b| (&a.category, &a.command).cmp(&(&b.category, &b.command)));
+        }
+        documentation
+    }
+
+    /// Export all active shortcuts (including the ones defined by user bindings) as a keymap. The
+    /// shortcuts are sorted by the target, command, and rule pattern, so keymaps of different
+    /// sessions can be easily compared.
+    pub fn export_keymap(&self) -> Keymap {
+        let shortcuts = self.all_active_shortcuts();
+        let mut shortcuts = shortcuts.iter().map(ShortcutDefinition::from).collect_vec();
+        shortcuts.sort_by(|a, b| {
+            (&a.target, &a.command, &a.rule.pattern).cmp(&(&b.target, &b.command, &b.rule.pattern))
+        });
+        Keymap { shortcuts }
+    }
+
+    /// Add all shortcuts of the keymap as the default shortcuts. They can be overridden by user
+    /// bindings, just like the ones provided by `View::default_shortcuts`.
+    pub fn import_keymap(&self, keymap: Keymap) {
+        for definition in keymap.shortcuts {
+            self.add_default(definition.into())
+        }
+    }
+
+    fn add_default(&self, shortcut: Shortcut) {
+        let key = shortcut.binding_key();
+        self.defaults.borrow_mut().entry(key.clone()).or_default().push(shortcut.clone());
+        if self.binding_override(&key).is_none() {
+            self.add_shortcut(shortcut);
+        }
+    }
+
+    /// Active shortcuts of all commands. See [`Self::active_shortcuts`].
+    fn all_active_shortcuts(&self) -> Vec<Shortcut> {
+        let mut keys = self.defaults.borrow().keys().cloned().collect::<HashSet<_>>();
+        keys.extend(self.user_bindings.borrow().keys().cloned());
+        if let Some(profile) = self.profiles.borrow().get(&*self.profile.borrow()) {
+            keys.extend(profile.keys().cloned());
+        }
+        keys.iter().flat_map(|key| self.active_shortcuts(key)).collect()
+    }
+
+    /// Shortcuts of the command which are currently registered: the ones defined by the binding
+    /// override (see [`Self::binding_override`]) if it exists, or the default ones otherwise.
+    fn active_shortcuts(&self, key: &BindingKey) -> Vec<Shortcut> {
+        let defaults = self.defaults.borrow().get(key).cloned().unwrap_or_default();
+        match self.binding_override(key) {
+            None => defaults,
+            Some(binding) => {
+                let default_condition = defaults.first().map(|t| t.condition.clone());
+                let condition = binding.condition.as_ref().map(Condition::parse);
+                let condition = condition.or(default_condition).unwrap_or(Condition::Always);
+                let rules = binding.rules.iter().cloned();
+                let (target, command) = key;
+                let to_shortcut = |rule| {
+                    let shortcut =
+                        Shortcut::new_when(rule, target, command.clone(), condition.clone());
+                    match defaults.first() {
+                        Some(default) => shortcut.with_properties_of(default),
+                        None => shortcut,
+                    }
+                };
+                rules.map(to_shortcut).collect()
+            }
+        }
+    }
+
+    fn add_shortcut(&self, shortcut: Shortcut) {
+        let action_type = shortcut.registered_action_type();
+        let registry = self.registry_of(&shortcut);
+        registry.add(action_type, &shortcut.rule.pattern, shortcut.clone());
+    }
+
+    fn remove_shortcut(&self, shortcut: &Shortcut) {
+        let action_type = shortcut.registered_action_type();
+        self.registry_of(shortcut).remove(action_type, &shortcut.rule.pattern, shortcut);
+    }
+
+    fn registry_of(&self, shortcut: &Shortcut) -> &shortcuts::HashSetRegistry<Shortcut> {
+        if shortcut.physical {
+            &self.physical_registry
+        } else {
+            &self.shortcuts_registry
+        }
+    }
+
+    /// Execute the commands matched by the key press. Returns the key if no command was executed.
+    fn process_key_press(&self, matched: &Matched) -> Option<String> {
+        let handled = self.process_rules(&matched.shortcuts, &matched.key);
+        (!handled && !matched.captured).then(|| matched.key.clone())
+    }
+
+    /// Execute the commands matched by the rules. Returns `true` if any command was executed.
+    fn process_rules(&self, rules: &[Shortcut], key: &str) -> bool {
+        let mut candidates = Vec::new();
         {
             let borrowed_command_map = self.command_registry.name_map.borrow();
-            for rule in rules {
+            for rule in rules.iter().filter(|t| self.is_shortcut_enabled(t)) {
                 let target = &rule.action.target;
+                let key = rule.shortcut_key();
                 borrowed_command_map.get(target).for_each(|instances| {
-                    for instance in instances {
-                        if Self::condition_checker(&rule.condition, &instance.status_map) {
+                    let is_active = |t: &&command::ProviderInstance| {
+                        t.check_alive() && !t.disabled_shortcuts.borrow().contains(&key)
+                    };
+                    for instance in instances.iter().filter(is_active) {
+                        if self.condition_checker(&rule.condition, &instance.status_map) {
                             let command_name = &rule.command.name;
                             match instance.command_map.borrow().get(command_name) {
                                 Some(cmd) =>
                                     if cmd.enabled {
-                                        targets.push(cmd.frp.clone_ref())
+                                        let focused = Self::is_focused(&instance.status_map);
+                                        let frp = cmd.frp.clone_ref();
+                                        candidates.push(Candidate { shortcut: rule, focused, frp })
                                     },
                                 None => warning!(
                                     &self.logger,
@@ -310,12 +1531,57 @@ impl RegistryModel {
                 })
             }
         }
-        for target in targets {
-            target.emit(())
+        let selected = Self::resolve_conflicts(&candidates);
+        for candidate in &selected {
+            let payload = candidate.shortcut.rule.payload.as_ref();
+            if !candidate.frp.emit(key, payload) {
+                let command = &candidate.shortcut.command.name;
+                let target = &candidate.shortcut.target;
+                warning!(
+                    &self.logger,
+                    "Command {command} on {target} does not accept the payload {payload:?}."
+                );
+            }
+        }
+        !selected.is_empty()
+    }
+
+    /// Choose the commands to be executed from all the matched ones. See the docs of [`Shortcut`]
+    /// to learn more.
+    fn resolve_conflicts<'a>(candidates: &'a [Candidate<'a>]) -> Vec<&'a Candidate<'a>> {
+        let exclusive = candidates.iter().filter(|t| !t.shortcut.parallel);
+        let max_priority = exclusive.clone().map(|t| t.shortcut.priority).max();
+        let has_max_priority = |t: &Candidate| Some(t.shortcut.priority) == max_priority;
+        let any_focused = exclusive.clone().any(|t| has_max_priority(t) && t.focused);
+        let is_overridden_global = |t: &Candidate| any_focused && t.shortcut.global && !t.focused;
+        let is_considered = |t: &Candidate| has_max_priority(t) && !is_overridden_global(t);
+        let specificity = |t: &Candidate| (t.focused, t.shortcut.condition != Condition::Always);
+        let mut max_target_specificity = HashMap::<&str, (bool, bool)>::new();
+        for candidate in exclusive.filter(|t| is_considered(t)) {
+            let max = max_target_specificity.entry(&candidate.shortcut.target).or_default();
+            *max = (*max).max(specificity(candidate));
         }
+        let is_most_specific = |t: &Candidate| {
+            let max = max_target_specificity.get(t.shortcut.target.as_str());
+            is_considered(t) && max == Some(&specificity(t))
+        };
+        let selected = candidates.iter().filter(|t| t.shortcut.parallel || is_most_specific(t));
+        selected.collect()
+    }
+
+    fn is_focused(status: &Rc<RefCell<HashMap<String, frp::Sampler<bool>>>>) -> bool {
+        status.borrow().get("focused").map(|t| t.value()).unwrap_or(false)
+    }
+
+    /// Check whether any live instance of the view with the given label is focused.
+    pub fn is_view_focused(&self, label: &str) -> bool {
+        let name_map = self.command_registry.name_map.borrow();
+        let instances = name_map.get(label).into_iter().flatten();
+        instances.filter(|t| t.check_alive()).any(|t| Self::is_focused(&t.status_map))
     }
 
     fn condition_checker(
+        &self,
         condition: &Condition,
         status: &Rc<RefCell<HashMap<String, frp::Sampler<bool>>>>,
     ) -> bool {
@@ -324,20 +1590,752 @@ impl RegistryModel {
             Always => true,
             Never => false,
             When(name) => status.borrow().get(name).map(|t| t.value()).unwrap_or(false),
-            Not(a) => !Self::condition_checker(a, status),
-            Or(a, b) => Self::condition_checker(a, status) || Self::condition_checker(b, status),
-            And(a, b) => Self::condition_checker(a, status) && Self::condition_checker(b, status),
+            Focused(label) => self.is_view_focused(label),
+            Not(a) => !self.condition_checker(a, status),
+            Or(a, b) => self.condition_checker(a, status) || self.condition_checker(b, status),
+            And(a, b) => self.condition_checker(a, status) && self.condition_checker(b, status),
         }
     }
 }
 
+/// A command matched by a shortcut, taking part in the conflict resolution.
+#[derive(Debug)]
+struct Candidate<'a> {
+    shortcut: &'a Shortcut,
+    focused:  bool,
+    frp:      command::Endpoint,
+}
+
 impl Add<Shortcut> for &Registry {
     type Output = ();
     fn add(self, shortcut: Shortcut) {
-        self.model.shortcuts_registry.add(
-            shortcut.rule.tp,
-            &shortcut.rule.pattern,
-            shortcut.clone(),
-        );
+        self.model.add_default(shortcut)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::application::command::View;
+    use crate::application::Application;
+    use crate::define_endpoints;
+
+    #[test]
+    fn parsing_conditions() {
+        use Condition::*;
+        let when = Condition::when;
+        assert_eq!(Condition::parse(""), Always);
+        assert_eq!(Condition::parse("a"), when("a"));
+        assert_eq!(Condition::parse("!a"), Condition::not(when("a")));
+        let searcher_and_not_read_only =
+            Condition::and(when("searcher_open"), Condition::not(when("read_only")));
+        assert_eq!(Condition::parse("searcher_open & !read_only"), searcher_and_not_read_only);
+        let precedence =
+            Condition::or(Condition::and(when("a"), when("b")), Condition::not(when("c")));
+        assert_eq!(Condition::parse("a & b | !c"), precedence);
+        let parentheses =
+            Condition::and(when("a"), Condition::not(Condition::or(when("b"), when("c"))));
+        assert_eq!(Condition::parse(" a&!( b |c ) "), parentheses);
+        assert_eq!(Condition::parse("a & (b"), Never);
+        assert_eq!(Condition::parse("a b"), Never);
+        assert_eq!(Condition::parse("a &"), Never);
+        let focused_searcher = Condition::and(when("focused"), Condition::focused("Searcher"));
+        assert_eq!(Condition::parse("focused & focused( Searcher )"), focused_searcher);
+        assert_eq!(Condition::parse("focused(Searcher"), Never);
+        assert_eq!(Condition::parse("focused()"), Never);
+    }
+
+    #[test]
+    fn printing_conditions() {
+        let expressions = [
+            "true",
+            "false",
+            "a",
+            "!a",
+            "!!a",
+            "a & !(b | c)",
+            "a & b | !c",
+            "(a | b) & c",
+            "a | (b | c) & d",
+            "a & (b & c)",
+            "!(a & b)",
+            "focused(Searcher) & !focused",
+        ];
+        for expression in expressions {
+            let condition = Condition::parse(expression);
+            assert_eq!(condition.to_string(), expression);
+            assert_eq!(Condition::parse(condition.to_string()), condition);
+        }
+    }
+
+    #[test]
+    fn checking_conditions() {
+        frp::new_network! { network
+            searcher_open <- source::<bool>();
+            read_only <- source::<bool>();
+            searcher_open_sampler <- searcher_open.sampler();
+            read_only_sampler <- read_only.sampler();
+        }
+        let status: Rc<RefCell<HashMap<String, frp::Sampler<bool>>>> = default();
+        status.borrow_mut().insert("searcher_open".into(), searcher_open_sampler);
+        status.borrow_mut().insert("read_only".into(), read_only_sampler);
+        let condition = Condition::parse("searcher_open & !(read_only | unknown)");
+        let registry = registry_with(vec![]);
+        let check = || registry.model.condition_checker(&condition, &status);
+        assert!(!check());
+        searcher_open.emit(true);
+        assert!(check());
+        read_only.emit(true);
+        assert!(!check());
+        drop(network);
+    }
+
+    #[derive(Debug, Default)]
+    struct MemoryStorage {
+        document: Rc<RefCell<Option<String>>>,
+    }
+
+    impl UserBindingsStorage for MemoryStorage {
+        fn load(&self) -> Option<String> {
+            self.document.borrow().clone()
+        }
+
+        fn save(&self, document: &str) {
+            *self.document.borrow_mut() = Some(document.into());
+        }
+    }
+
+    fn commands_on_press(registry: &Registry, keys: &[&str]) -> Vec<String> {
+        let mut commands = vec![];
+        for key in keys {
+            let shortcuts = registry.shortcuts_registry.on_press(key);
+            commands.extend(shortcuts.into_iter().map(|t| t.command.name.clone()));
+        }
+        for key in keys.iter().rev() {
+            registry.shortcuts_registry.on_release(key);
+        }
+        commands
+    }
+
+    fn registry_with(shortcuts: Vec<Shortcut>) -> Registry {
+        let logger = Logger::new("test");
+        let mouse = Mouse::new();
+        let keyboard = keyboard::Keyboard::new();
+        let command_registry = command::Registry::create(&logger);
+        let registry = Registry::new(&logger, &mouse, &keyboard, &command_registry);
+        for shortcut in shortcuts {
+            registry.add(shortcut)
+        }
+        registry
+    }
+
+    #[test]
+    fn user_bindings() {
+        let save_rule = Rule::new(ActionType::Press, "ctrl s");
+        let save_as_rule = Rule::new(ActionType::Press, "ctrl shift s");
+        let save = Shortcut::new_when(save_rule, "Editor", "save", "focused");
+        let save_as = Shortcut::new(save_as_rule, "Editor", "save_as");
+        let registry = registry_with(vec![save, save_as]);
+
+        let rules = vec![Rule::new(ActionType::Press, "ctrl w")];
+        let storage = MemoryStorage::default();
+        let document = UserBindings { bindings: vec![UserBinding::new("Editor", "save", rules)] };
+        *storage.document.borrow_mut() = Some(document.to_json().unwrap());
+        let saved_document = storage.document.clone_ref();
+        registry.set_user_bindings_storage(storage);
+        assert_eq!(registry.user_bindings(), document);
+        assert!(commands_on_press(&registry, &["ctrl-left", "s"]).is_empty());
+        assert_eq!(commands_on_press(&registry, &["ctrl-left", "w"]), vec!["save"]);
+        let shortcuts = registry.shortcuts_registry.on_press("w");
+        registry.shortcuts_registry.on_release("w");
+        assert!(shortcuts.is_empty());
+
+        registry.set_user_binding(UserBinding::new("Editor", "save_as", vec![]));
+        assert!(commands_on_press(&registry, &["ctrl-left", "shift-left", "s"]).is_empty());
+        let saved = UserBindings::from_json(saved_document.borrow().as_ref().unwrap()).unwrap();
+        assert_eq!(saved, registry.user_bindings());
+        assert_eq!(saved.bindings.len(), 2);
+
+        registry.reset_binding("Editor", "save");
+        registry.reset_binding("Editor", "save_as");
+        assert_eq!(commands_on_press(&registry, &["ctrl-left", "s"]), vec!["save"]);
+        assert_eq!(commands_on_press(&registry, &["ctrl-left", "shift-left", "s"]), vec![
+            "save_as"
+        ]);
+        assert!(commands_on_press(&registry, &["ctrl-left", "w"]).is_empty());
+        let saved = UserBindings::from_json(saved_document.borrow().as_ref().unwrap()).unwrap();
+        assert_eq!(saved, default());
+    }
+
+    #[test]
+    fn rebinding() {
+        let undo_rule = Rule::new(ActionType::Press, "ctrl z");
+        let undo = Shortcut::new_when(undo_rule.clone(), "Editor", "undo", "focused");
+        let registry = registry_with(vec![undo]);
+
+        let new_rules = vec![
+            Rule::new(ActionType::Press, "alt z"),
+            Rule::new_sequence(ActionType::Press, ["ctrl k", "ctrl z"]),
+        ];
+        assert_eq!(registry.rebind("Editor", "undo", new_rules.clone()), vec![undo_rule.clone()]);
+        assert_eq!(registry.binding("Editor", "undo"), new_rules);
+        assert!(commands_on_press(&registry, &["ctrl-left", "z"]).is_empty());
+        assert_eq!(commands_on_press(&registry, &["alt-left", "z"]), vec!["undo"]);
+        let shortcuts = registry.shortcuts_registry.on_press("z");
+        assert!(shortcuts.iter().all(|t| t.condition == Condition::when("focused")));
+        registry.shortcuts_registry.on_release("z");
+
+        assert_eq!(registry.unbind("Editor", "undo"), new_rules);
+        assert!(registry.binding("Editor", "undo").is_empty());
+        assert!(commands_on_press(&registry, &["alt-left", "z"]).is_empty());
+
+        registry.reset_binding("Editor", "undo");
+        assert_eq!(registry.binding("Editor", "undo"), vec![undo_rule]);
+        assert_eq!(commands_on_press(&registry, &["ctrl-left", "z"]), vec!["undo"]);
+    }
+
+    #[test]
+    fn profiles() {
+        let rule = |pattern| Rule::new(ActionType::Press, pattern);
+        let undo = Shortcut::new(rule("ctrl z"), "Editor", "undo");
+        let redo = Shortcut::new(rule("ctrl y"), "Editor", "redo");
+        let registry = registry_with(vec![undo, redo]);
+        let vim = Profile::new("vim", vec![
+            UserBinding::new("Editor", "undo", vec![rule("u")]),
+            UserBinding::new("Editor", "redo", vec![rule("ctrl r")]),
+        ]);
+        let vim = Profile::from_json(&vim.to_json().unwrap()).unwrap();
+        registry.add_profile(vim);
+        assert_eq!(registry.profiles(), vec!["default", "vim"]);
+        assert!(!registry.set_profile("emacs"));
+        assert_eq!(registry.profile(), DEFAULT_PROFILE);
+
+        assert!(registry.set_profile("vim"));
+        assert_eq!(registry.binding("Editor", "undo"), vec![rule("u")]);
+        assert!(commands_on_press(&registry, &["ctrl-left", "z"]).is_empty());
+        assert_eq!(commands_on_press(&registry, &["u"]), vec!["undo"]);
+        registry.rebind("Editor", "redo", vec![rule("shift u")]);
+        assert_eq!(registry.binding("Editor", "redo"), vec![rule("shift u")]);
+        registry.add_profile(Profile::new("vim", vec![]));
+        assert_eq!(registry.binding("Editor", "undo"), vec![rule("ctrl z")]);
+        assert_eq!(registry.binding("Editor", "redo"), vec![rule("shift u")]);
+
+        registry.reset_binding("Editor", "redo");
+        assert!(registry.set_profile(DEFAULT_PROFILE));
+        assert_eq!(registry.binding("Editor", "redo"), vec![rule("ctrl y")]);
+        assert_eq!(commands_on_press(&registry, &["ctrl-left", "z"]), vec!["undo"]);
+    }
+
+    /// The names of the commands selected by the conflict resolution from the shortcuts, each
+    /// paired with the focus status of its target instance.
+    fn resolve(candidates: &[(&Shortcut, bool)]) -> Vec<String> {
+        let network = frp::Network::new("test");
+        let candidates = candidates.iter().map(|&(shortcut, focused)| {
+            let frp = command::Endpoint::Unit(network.any_mut::<()>("command"));
+            Candidate { shortcut, focused, frp }
+        });
+        let candidates = candidates.collect_vec();
+        let selected = RegistryModel::resolve_conflicts(&candidates);
+        selected.iter().map(|t| t.shortcut.command.name.clone()).collect()
+    }
+
+    #[test]
+    fn resolving_conflicts() {
+        let rule = Rule::new(ActionType::Press, "enter");
+        let unconditional = Shortcut::new(rule.clone(), "Editor", "unconditional");
+        let conditional = Shortcut::new_when(rule.clone(), "Editor", "conditional", "hovered");
+        let other_target = Shortcut::new(rule.clone(), "Searcher", "other_target");
+        let parallel = Shortcut::new(rule.clone(), "Editor", "parallel").as_parallel();
+        let important = Shortcut::new(rule, "Graph", "important").with_priority(1);
+
+        let candidates = [
+            (&conditional, false),
+            (&unconditional, true),
+            (&other_target, false),
+            (&parallel, false),
+        ];
+        assert_eq!(resolve(&candidates), vec!["unconditional", "other_target", "parallel"]);
+        let candidates = [(&unconditional, false), (&conditional, false), (&other_target, false)];
+        assert_eq!(resolve(&candidates), vec!["conditional", "other_target"]);
+        let candidates = [(&unconditional, true), (&parallel, false), (&important, false)];
+        assert_eq!(resolve(&candidates), vec!["parallel", "important"]);
+    }
+
+    #[test]
+    fn focused_rule_beats_global_rule() {
+        let rule = Rule::new(ActionType::Press, "ctrl z");
+        let global = Shortcut::new(rule.clone(), "Root", "undo").as_global();
+        let global_when =
+            Shortcut::new_when(rule.clone(), "Root", "undo_when", "enabled").as_global();
+        let focused = Shortcut::new(rule.clone(), "TextEditor", "undo");
+        let logger = Shortcut::new(rule, "Logger", "log").as_parallel();
+        let candidates =
+            [(&global, false), (&global_when, false), (&focused, true), (&logger, false)];
+        assert_eq!(resolve(&candidates), vec!["undo", "log"]);
+        let candidates = [(&global, false), (&global_when, false), (&logger, false)];
+        assert_eq!(resolve(&candidates), vec!["undo_when", "log"]);
+    }
+
+    #[test]
+    fn focused_text_area_and_list_view_bound_to_same_key() {
+        let rule = |tp, pattern| Rule::new(tp, pattern);
+        let move_cursor =
+            Shortcut::new_when(rule(ActionType::PressAndRepeat, "up"), "TextArea", "up", "focused");
+        let move_selection =
+            Shortcut::new(rule(ActionType::PressAndRepeat, "up"), "ListView", "up");
+        let candidates = [(&move_cursor, true), (&move_selection, false)];
+        assert_eq!(resolve(&candidates), vec!["up", "up"]);
+        let insert_newline =
+            Shortcut::new_when(rule(ActionType::Press, "enter"), "TextArea", "insert", "focused");
+        let choose_entry = Shortcut::new(rule(ActionType::Press, "enter"), "ListView", "choose");
+        let candidates = [(&insert_newline, true), (&choose_entry, false)];
+        assert_eq!(resolve(&candidates), vec!["insert", "choose"]);
+    }
+
+    #[test]
+    fn escape_with_searcher_opened_and_text_area_focused() {
+        let rule = Rule::new(ActionType::Press, "escape");
+        let keep_cursor =
+            Shortcut::new_when(rule.clone(), "TextArea", "keep_oldest_cursor_only", "focused");
+        let close_searcher =
+            Shortcut::new_when(rule.clone(), "ProjectView", "close_searcher", "is_searcher_opened");
+        let hide_code_editor = Shortcut::new(rule, "CodeEditor", "hide");
+        let candidates =
+            [(&keep_cursor, true), (&close_searcher, false), (&hide_code_editor, false)];
+        let expected = vec!["keep_oldest_cursor_only", "close_searcher", "hide"];
+        assert_eq!(resolve(&candidates), expected);
+    }
+
+    #[test]
+    fn node_press_over_hovered_focused_text_area() {
+        let rule = Rule::new(ActionType::Press, "left-mouse-button");
+        let condition = "focused & hovered";
+        let set_cursor =
+            Shortcut::new_when(rule.clone(), "TextArea", "set_cursor_at_mouse_position", condition);
+        let node_press = Shortcut::new(rule, "GraphEditor", "node_press");
+        let candidates = [(&set_cursor, true), (&node_press, false)];
+        let expected = vec!["set_cursor_at_mouse_position", "node_press"];
+        assert_eq!(resolve(&candidates), expected);
+    }
+
+    #[test]
+    fn documentation() {
+        let rule = |pattern| Rule::new(ActionType::Press, pattern);
+        let save = Shortcut::new(rule("ctrl s"), "Editor", "save")
+            .with_description("Save the file.")
+            .with_category("File");
+        let undo = Shortcut::new(rule("ctrl z"), "Editor", "undo").with_category("Edit");
+        let close = Shortcut::new(rule("escape"), "Searcher", "close");
+        let registry = registry_with(vec![save, undo, close]);
+        registry.rebind("Editor", "save", vec![rule("ctrl w")]);
+        registry.unbind("Searcher", "close");
+
+        let documentation = registry.documentation();
+        assert_eq!(documentation.keys().collect_vec(), vec!["Editor"]);
+        let editor = &documentation["Editor"];
+        assert_eq!(editor.iter().map(|t| t.command.as_str()).collect_vec(), vec!["undo", "save"]);
+        assert_eq!(editor[1].rule, rule("ctrl w"));
+        assert_eq!(editor[1].description.as_deref(), Some("Save the file."));
+        assert_eq!(editor[1].category.as_deref(), Some("File"));
+    }
+
+    #[test]
+    fn scopes() {
+        let rule = Rule::new(ActionType::Press, "delete");
+        let delete_char = Shortcut::new(rule.clone(), "Editor", "delete").in_scope("text-editing");
+        let delete_node = Shortcut::new(rule, "Graph", "remove_nodes").in_scope("graph");
+        let registry = registry_with(vec![delete_char.clone(), delete_node.clone()]);
+        assert!(registry.is_shortcut_enabled(&delete_char));
+        registry.set_scope_enabled("text-editing", false);
+        assert!(!registry.is_scope_enabled("text-editing"));
+        assert!(!registry.is_shortcut_enabled(&delete_char));
+        assert!(registry.is_shortcut_enabled(&delete_node));
+        registry.set_scope_enabled("text-editing", true);
+        assert!(registry.is_shortcut_enabled(&delete_char));
+    }
+
+    #[test]
+    fn key_repeat() {
+        let rule = |pattern| Rule::new(ActionType::Press, pattern);
+        let remove = Shortcut::new(rule("delete"), "Graph", "remove_nodes");
+        let move_up = Shortcut::new(rule("up"), "Graph", "move_up").as_repeatable();
+        let move_down = Shortcut::new(Rule::new(ActionType::PressAndRepeat, "down"), "Graph", "d");
+        assert!(!remove.is_repeatable());
+        assert!(move_up.is_repeatable());
+        assert!(move_down.is_repeatable());
+        let registry = registry_with(vec![remove, move_up]);
+        let on_press = |key| registry.shortcuts_registry.on_press(key).len();
+        assert_eq!(on_press("delete"), 1);
+        assert_eq!(on_press("delete"), 0);
+        registry.shortcuts_registry.on_release("delete");
+        assert_eq!(on_press("arrow-up"), 1);
+        assert_eq!(on_press("arrow-up"), 1);
+        registry.shortcuts_registry.on_release("arrow-up");
+
+        registry.rebind("Graph", "move_up", vec![rule("w")]);
+        assert_eq!(on_press("w"), 1);
+        assert_eq!(on_press("w"), 1);
+        registry.shortcuts_registry.on_release("w");
+        registry.reset_binding("Graph", "move_up");
+        assert_eq!(on_press("w"), 0);
+    }
+
+    #[test]
+    fn capturing() {
+        let save = Shortcut::new(Rule::new(ActionType::Press, "ctrl s"), "Editor", "save");
+        let registry = registry_with(vec![save]);
+        let captured = registry.capture_next();
+        assert!(registry.is_capturing());
+        assert!(registry.on_press("ctrl-right".into()).shortcuts.is_empty());
+        assert!(registry.on_press("shift-left".into()).shortcuts.is_empty());
+        assert!(registry.on_release("shift-left".into()).shortcuts.is_empty());
+        assert!(registry.on_press("s".into()).shortcuts.is_empty());
+        assert!(!registry.is_capturing());
+        let expected = Rule::new(ActionType::Press, "ctrl s");
+        assert_eq!(captured.now_or_never(), Some(Some(expected)));
+        registry.on_release("s".into());
+        assert_eq!(registry.on_press("s".into()).shortcuts.len(), 1);
+        registry.on_release("s".into());
+        registry.on_release("ctrl-right".into());
+
+        let cancelled = registry.capture_next();
+        registry.cancel_capture();
+        assert_eq!(cancelled.now_or_never(), Some(None));
+        let replaced = registry.capture_next();
+        let captured = registry.capture_next();
+        assert_eq!(replaced.now_or_never(), Some(None));
+        assert!(registry.on_press("arrow-up".into()).shortcuts.is_empty());
+        let expected = Rule::new(ActionType::Press, "arrow-up");
+        assert_eq!(captured.now_or_never(), Some(Some(expected)));
+    }
+
+    #[test]
+    fn release_of_captured_keys() {
+        let rule = |tp, pattern| Rule::new(tp, pattern);
+        let hide = Shortcut::new(rule(ActionType::Release, "escape"), "Searcher", "hide");
+        let registry = registry_with(vec![hide]);
+        let captured = registry.capture_next();
+        assert!(registry.on_press("escape".into()).shortcuts.is_empty());
+        assert_eq!(captured.now_or_never(), Some(Some(rule(ActionType::Press, "escape"))));
+        assert!(registry.on_release("escape".into()).shortcuts.is_empty());
+        registry.on_press("escape".into());
+        assert_eq!(registry.on_release("escape".into()).shortcuts.len(), 1);
+    }
+
+    #[test]
+    fn keymap() {
+        let rule = |pattern| Rule::new(ActionType::Press, pattern);
+        let save = Shortcut::new_when(rule("ctrl s"), "Editor", "save", "focused & !read_only")
+            .with_description("Save the file.")
+            .in_scope("text-editing");
+        let close = Shortcut::new(rule("escape"), "Searcher", "close").with_priority(1);
+        let registry = registry_with(vec![save.clone(), close.clone()]);
+        registry.rebind("Editor", "save", vec![rule("ctrl w")]);
+        let keymap = registry.export_keymap();
+        assert_eq!(keymap.shortcuts.len(), 2);
+        assert_eq!(keymap.shortcuts[0].target, "Editor");
+        assert_eq!(keymap.shortcuts[0].rule, rule("ctrl w"));
+        assert_eq!(keymap.shortcuts[0].condition.as_deref(), Some("focused & !read_only"));
+        assert_eq!(keymap.shortcuts[1].condition, None);
+        let json = keymap.to_json().unwrap();
+        assert!(!json.contains("parallel"));
+        assert_eq!(Keymap::from_json(&json).unwrap(), keymap);
+
+        let original = Keymap { shortcuts: vec![(&save).into(), (&close).into()] };
+        let registry = registry_with(vec![]);
+        registry.import_keymap(Keymap::from_json(&original.to_json().unwrap()).unwrap());
+        assert_eq!(registry.export_keymap(), original);
+        assert_eq!(Shortcut::from(original.shortcuts[0].clone()), save);
+        assert_eq!(commands_on_press(&registry, &["escape"]), vec!["close"]);
+    }
+
+    #[test]
+    fn any_character() {
+        let insert =
+            Shortcut::new(Rule::new(ActionType::Press, ANY_CHARACTER), "Searcher", "insert");
+        let registry = registry_with(vec![insert]);
+        let matched = registry.on_press("A".into());
+        assert_eq!(matched.shortcuts.len(), 1);
+        assert_eq!(matched.key, "A");
+        registry.on_release("A".into());
+
+        let network = frp::Network::new("test");
+        let frp = network.any_mut::<String>("insert");
+        let sampler = network.sampler("inserted", &frp);
+        assert!(command::Endpoint::Key(frp).emit(&matched.key, None));
+        assert_eq!(sampler.value(), "A");
+    }
+
+    define_endpoints! {
+        Input {
+            save (),
+            insert (String),
+            zoom (f32),
+        }
+        Output {}
+    }
+
+    #[derive(Clone, CloneRef, Debug)]
+    struct TestView {
+        app: Application,
+        frp: Frp,
+    }
+
+    impl Deref for TestView {
+        type Target = Frp;
+        fn deref(&self) -> &Self::Target {
+            &self.frp
+        }
+    }
+
+    impl command::FrpNetworkProvider for TestView {
+        fn network(&self) -> &frp::Network {
+            &self.frp.network
+        }
+    }
+
+    impl View for TestView {
+        fn label() -> &'static str {
+            "TestView"
+        }
+
+        fn new(app: &Application) -> Self {
+            let app = app.clone_ref();
+            let frp = Frp::new();
+            Self { app, frp }
+        }
+
+        fn app(&self) -> &Application {
+            &self.app
+        }
+
+        fn default_shortcuts() -> Vec<Shortcut> {
+            use ActionType::*;
+            vec![
+                Self::self_shortcut_when(Press, "cmd s", "save", "focused"),
+                Self::self_shortcut(Press, ANY_CHARACTER, "insert"),
+            ]
+        }
+    }
+
+    #[test]
+    fn simulating() {
+        let app = Application::new("root");
+        let view = app.new_view::<TestView>();
+        let saves = Rc::new(Cell::new(0));
+        let network = &view.network;
+        frp::extend! { network
+            eval_ view.save (saves.set(saves.get() + 1));
+            inserted <- view.insert.sampler();
+        }
+
+        app.shortcuts.simulate(&Rule::new(ActionType::Press, "cmd s"));
+        assert_eq!(saves.get(), 0);
+        view.focus();
+        app.shortcuts.simulate(&Rule::new(ActionType::Press, "cmd s"));
+        assert_eq!(saves.get(), 1);
+        app.shortcuts.simulate(&Rule::new(ActionType::DoublePress, "cmd s"));
+        assert_eq!(saves.get(), 3);
+        web::simulate_sleep(1000.0);
+        app.shortcuts.simulate(&Rule::new(ActionType::MultiPress(3), "cmd s"));
+        assert_eq!(saves.get(), 6);
+        app.shortcuts.simulate(&Rule::new(ActionType::Press, "x"));
+        assert_eq!(inserted.value(), "x");
+    }
+
+    #[test]
+    fn payloads() {
+        let app = Application::new("root");
+        let view = app.new_view::<TestView>();
+        let network = &view.network;
+        frp::extend! { network
+            zoom <- view.zoom.sampler();
+        }
+        let zoom_rule =
+            |pattern, step: f32| Rule::new(ActionType::Press, pattern).with_payload(step);
+        let zoom_in = Shortcut::new(zoom_rule("ctrl =", 2.0), "TestView", "zoom");
+        let zoom_out = Shortcut::new(zoom_rule("ctrl -", 0.5), "TestView", "zoom");
+        let invalid = Rule::new(ActionType::Press, "ctrl 0").with_payload("reset");
+        app.shortcuts.model.add_default(zoom_in.clone());
+        app.shortcuts.model.add_default(zoom_out);
+        app.shortcuts.model.add_default(Shortcut::new(invalid.clone(), "TestView", "zoom"));
+
+        app.shortcuts.simulate(&zoom_in.rule);
+        assert_eq!(zoom.value(), 2.0);
+        app.shortcuts.simulate(&Rule::new(ActionType::Press, "ctrl -"));
+        assert_eq!(zoom.value(), 0.5);
+        app.shortcuts.simulate(&invalid);
+        assert_eq!(zoom.value(), 0.5);
+
+        let definition = ShortcutDefinition::from(&zoom_in);
+        let json = serde_json::to_string(&definition).unwrap();
+        let parsed: ShortcutDefinition = serde_json::from_str(&json).unwrap();
+        assert_eq!(Shortcut::from(parsed), zoom_in);
+    }
+
+    #[test]
+    fn focused_view_condition() {
+        let app = Application::new("root");
+        let view = app.new_view::<TestView>();
+        let network = &view.network;
+        frp::extend! { network
+            inserted <- view.insert.sampler();
+        }
+        let insert_when = |condition, text| {
+            let rule = Rule::new(ActionType::Press, "enter").with_payload(text);
+            Shortcut::new_when(rule, "TestView", "insert", condition)
+        };
+        app.shortcuts.model.add_default(insert_when("focused(TestView)", "committed"));
+        app.shortcuts.model.add_default(insert_when("focused(OtherView)", "other"));
+
+        let press_enter = || app.shortcuts.simulate(&Rule::new(ActionType::Press, "enter"));
+        press_enter();
+        assert_eq!(inserted.value(), "");
+        assert!(!app.shortcuts.is_view_focused("TestView"));
+        view.focus();
+        assert!(app.shortcuts.is_view_focused("TestView"));
+        press_enter();
+        assert_eq!(inserted.value(), "committed");
+    }
+
+    #[test]
+    fn disabling_shortcuts() {
+        let app = Application::new("root");
+        let view = app.new_view::<TestView>();
+        let other_view = app.new_view::<TestView>();
+        let saves = Rc::new(Cell::new(0));
+        let network = &view.network;
+        frp::extend! { network
+            eval_ view.save (saves.set(saves.get() + 1));
+        }
+        let save = TestView::self_shortcut_when(ActionType::Press, "cmd s", "save", "focused");
+        let save_enabled = view.shortcut_enabled(save.clone());
+        other_view.shortcut_enabled(save).emit(false);
+        view.focus();
+
+        save_enabled.emit(false);
+        app.shortcuts.simulate(&Rule::new(ActionType::Press, "cmd s"));
+        assert_eq!(saves.get(), 0);
+        save_enabled.emit(true);
+        app.shortcuts.simulate(&Rule::new(ActionType::Press, "cmd s"));
+        assert_eq!(saves.get(), 1);
+
+        app.shortcuts.rebind("TestView", "save", vec![Rule::new(ActionType::Press, "cmd w")]);
+        save_enabled.emit(false);
+        app.shortcuts.simulate(&Rule::new(ActionType::Press, "cmd w"));
+        assert_eq!(saves.get(), 1);
+        save_enabled.emit(true);
+        app.shortcuts.simulate(&Rule::new(ActionType::Press, "cmd w"));
+        assert_eq!(saves.get(), 2);
+    }
+
+    #[test]
+    fn disabling_one_of_command_shortcuts() {
+        let app = Application::new("root");
+        let view = app.new_view::<TestView>();
+        let saves = Rc::new(Cell::new(0));
+        let network = &view.network;
+        frp::extend! { network
+            eval_ view.save (saves.set(saves.get() + 1));
+        }
+        let rule = |pattern| Rule::new(ActionType::Press, pattern);
+        let save = TestView::self_shortcut_when(ActionType::Press, "cmd s", "save", "focused");
+        let other_save =
+            TestView::self_shortcut_when(ActionType::Press, "ctrl s", "save", "focused");
+        app.shortcuts.model.add_default(other_save);
+        view.focus();
+        view.shortcut_enabled(save).emit(false);
+        app.shortcuts.simulate(&rule("cmd s"));
+        assert_eq!(saves.get(), 0);
+        app.shortcuts.simulate(&rule("ctrl s"));
+        assert_eq!(saves.get(), 1);
+
+        app.shortcuts.rebind("TestView", "save", vec![rule("cmd w"), rule("ctrl w")]);
+        app.shortcuts.simulate(&rule("cmd w"));
+        assert_eq!(saves.get(), 1);
+        app.shortcuts.simulate(&rule("ctrl w"));
+        assert_eq!(saves.get(), 2);
+    }
+
+    #[test]
+    fn unhandled_keys() {
+        let app = Application::new("root");
+        let view = app.new_view::<TestView>();
+        let keyboard = &app.display.default_scene.keyboard.frp;
+        let network = &view.network;
+        frp::extend! { network
+            unhandled <- app.shortcuts.unhandled_keys.sampler();
+        }
+        let press = |key: &str, code: &str| {
+            let key = keyboard::KeyWithCode::new(key.into(), code.into());
+            keyboard.source.down.emit(&key);
+            keyboard.source.up.emit(key);
+        };
+        press("Enter", "Enter");
+        assert_eq!(unhandled.value(), "enter");
+        press("x", "KeyX");
+        assert_eq!(unhandled.value(), "enter");
+        let captured = app.shortcuts.capture_next();
+        press("Escape", "Escape");
+        assert!(captured.now_or_never().is_some());
+        assert_eq!(unhandled.value(), "enter");
+    }
+
+    #[test]
+    fn physical_keys() {
+        let rule = |pattern| Rule::new(ActionType::Press, pattern);
+        let undo = Shortcut::new(rule("ctrl z"), "Editor", "undo").as_physical();
+        let redo = Shortcut::new(rule("ctrl y"), "Editor", "redo");
+        let registry = registry_with(vec![undo, redo]);
+        let azerty_key =
+            |key: &str, code: &str| keyboard::KeyWithCode::new(key.into(), code.into());
+        let ctrl = azerty_key("Control", "ControlLeft");
+        assert_eq!(ctrl.physical_name(), "ctrl-left");
+        assert!(registry.on_key_press(&ctrl).shortcuts.is_empty());
+        let z_position = azerty_key("w", "KeyZ");
+        assert_eq!(z_position.physical_name(), "z");
+        let matched = registry.on_key_press(&z_position).shortcuts;
+        assert_eq!(matched.iter().map(|t| t.command.name.as_str()).collect_vec(), vec!["undo"]);
+        registry.on_release("w".into());
+        let y_position = azerty_key("y", "KeyY");
+        let matched = registry.on_key_press(&y_position).shortcuts;
+        assert_eq!(matched.iter().map(|t| t.command.name.as_str()).collect_vec(), vec!["redo"]);
+        registry.on_release("y".into());
+        registry.on_release("ctrl-left".into());
+        assert!(registry.physical_keys.borrow().is_empty());
+        assert!(registry.on_press("z".into()).shortcuts.is_empty());
+    }
+
+    #[test]
+    fn stats() {
+        let app = Application::new("root");
+        let view = app.new_view::<TestView>();
+        let dropped_view = app.new_view::<TestView>();
+        drop(dropped_view);
+        let expected = TargetStats {
+            shortcuts:       2,
+            live_instances:  1,
+            dead_instances:  1,
+            swept_instances: 0,
+        };
+        assert_eq!(app.shortcuts.stats().get("TestView"), Some(&expected));
+        app.shortcuts.sweep_dead_instances();
+        let expected = TargetStats { dead_instances: 0, swept_instances: 1, ..expected };
+        assert_eq!(app.shortcuts.stats().get("TestView"), Some(&expected));
+        assert_eq!(app.commands.id_map.borrow().len(), 1);
+        drop(view);
+    }
+
+    #[test]
+    fn sequence_rule() {
+        let rule = Rule::new_sequence(ActionType::Press, ["ctrl k ", " ctrl s"]);
+        assert_eq!(rule, Rule::new(ActionType::Press, "ctrl k, ctrl s"));
     }
 }
@@ -41,6 +41,8 @@ use web::HtmlElement;
 // === Export ===
 // ==============
 
+#[warn(missing_docs)]
+pub mod accessibility;
 #[warn(missing_docs)]
 pub mod dom;
 #[warn(missing_docs)]
@@ -68,6 +70,7 @@ pub struct ShapeRegistryData {
     scene            : Option<Scene>,
     shape_system_map : HashMap<TypeId,Box<dyn Any>>,
     mouse_target_map : HashMap<PointerTargetId, PointerTarget>,
+    mouse_target_layer_map : HashMap<PointerTargetId, layer::LayerId>,
 }
 
 impl {
@@ -75,7 +78,8 @@ impl {
         let scene = default();
         let shape_system_map = default();
         let mouse_target_map = default();
-        Self {scene, shape_system_map, mouse_target_map} . init(background)
+        let mouse_target_layer_map = default();
+        Self {scene, shape_system_map, mouse_target_map, mouse_target_layer_map} . init(background)
     }
 
     fn get<T:ShapeSystemInstance>(&self) -> Option<T> {
@@ -112,12 +116,25 @@ impl {
 
     pub fn remove_mouse_target
     (&mut self, id:impl Into<PointerTargetId>) {
-        self.mouse_target_map.remove(&id.into());
+        let id = id.into();
+        self.mouse_target_map.remove(&id);
+        self.mouse_target_layer_map.remove(&id);
     }
 
     pub fn get_mouse_target(&self, target:PointerTargetId) -> Option<PointerTarget> {
         self.mouse_target_map.get(&target).cloned()
     }
+
+    /// Remember the layer the mouse target is displayed on. It is used to discover whether the
+    /// target is masked by an event-blocking layer.
+    pub fn set_mouse_target_layer
+    (&mut self, id:impl Into<PointerTargetId>, layer:impl Into<layer::LayerId>) {
+        self.mouse_target_layer_map.insert(id.into(),layer.into());
+    }
+
+    pub fn get_mouse_target_layer(&self, target:PointerTargetId) -> Option<layer::LayerId> {
+        self.mouse_target_layer_map.get(&target).copied()
+    }
 }}
 
 impl ShapeRegistry {
@@ -160,6 +177,9 @@ pub struct Mouse {
     pub position:      Uniform<Vector2<i32>>,
     pub hover_rgba:    Uniform<Vector4<u32>>,
     pub target:        Rc<Cell<PointerTargetId>>,
+    /// Whether the current target is masked by an event-blocking layer. See the docs of [`Layer`]
+    /// to learn more.
+    pub target_masked: Rc<Cell<bool>>,
     pub handles:       Rc<[callback::Handle; 4]>,
     pub frp:           enso_frp::io::Mouse,
     pub scene_frp:     Frp,
@@ -180,6 +200,7 @@ impl Mouse {
         let position = variables.add_or_panic("mouse_position", Vector2(0, 0));
         let hover_rgba = variables.add_or_panic("mouse_hover_ids", Vector4(0, 0, 0, 0));
         let target = Rc::new(Cell::new(target));
+        let target_masked = default();
         let mouse_manager = MouseManager::new_separated(&root.clone_ref().into(), &web::window);
         let frp = frp::io::Mouse::new();
         let on_move = mouse_manager.on_move.add(current_js_event.make_event_handler(
@@ -219,6 +240,7 @@ impl Mouse {
             position,
             hover_rgba,
             target,
+            target_masked,
             handles,
             frp,
             scene_frp,
@@ -559,6 +581,14 @@ pub struct HardcodedLayers {
     pub node_searcher_mask: Layer,
     pub tooltip:            Layer,
     pub tooltip_text:       Layer,
+    /// Layer containing the backdrop displayed below modal widgets, covering all other layers.
+    pub modal_backdrop:     Layer,
+    /// Layer containing modal widgets, like confirmation dialogs.
+    pub modal:              Layer,
+    pub modal_text:         Layer,
+    /// Layer containing transient notifications, displayed above all other views.
+    pub notification:       Layer,
+    pub notification_text:  Layer,
     pub cursor:             Layer,
     pub mask:               Layer,
 }
@@ -594,6 +624,11 @@ impl HardcodedLayers {
         let node_searcher_mask = Layer::new(logger.sub("node_searcher_mask"));
         let tooltip = Layer::new_with_cam(logger.sub("tooltip"), main_cam);
         let tooltip_text = Layer::new_with_cam(logger.sub("tooltip_text"), main_cam);
+        let modal_backdrop = Layer::new(logger.sub("modal_backdrop"));
+        let modal = Layer::new(logger.sub("modal"));
+        let modal_text = Layer::new(logger.sub("modal_text"));
+        let notification = Layer::new(logger.sub("notification"));
+        let notification_text = Layer::new(logger.sub("notification_text"));
         let cursor = Layer::new(logger.sub("cursor"));
 
         let mask = Layer::new_with_cam(logger.sub("mask"), main_cam);
@@ -613,6 +648,11 @@ impl HardcodedLayers {
             &edited_node_text,
             &tooltip,
             &tooltip_text,
+            &modal_backdrop,
+            &modal,
+            &modal_text,
+            &notification,
+            &notification_text,
             &cursor,
         ]);
         Self {
@@ -633,6 +673,11 @@ impl HardcodedLayers {
             node_searcher_mask,
             tooltip,
             tooltip_text,
+            modal_backdrop,
+            modal,
+            modal_text,
+            notification,
+            notification_text,
             cursor,
             mask,
         }
@@ -710,6 +755,7 @@ impl Extensions {
 #[derive(Clone, CloneRef, Debug)]
 pub struct SceneData {
     pub display_object:       display::object::Instance,
+    pub accessibility:        accessibility::Registry,
     pub dom:                  Dom,
     pub context:              Rc<RefCell<Option<Context>>>,
     pub context_lost_handler: Rc<RefCell<Option<ContextLostHandler>>>,
@@ -755,6 +801,7 @@ impl SceneData {
         let stats = stats.clone();
         let background = PointerTarget::new();
         let shapes = ShapeRegistry::new(&background);
+        let accessibility = accessibility::Registry::new();
         let uniforms = Uniforms::new(&variables);
         let renderer = Renderer::new(&logger, &dom, &variables);
         let style_sheet = style::Sheet::new();
@@ -784,6 +831,7 @@ impl SceneData {
         let context_lost_handler = default();
         Self {
             display_object,
+            accessibility,
             dom,
             context,
             context_lost_handler,
@@ -944,30 +992,38 @@ impl SceneData {
 
 impl SceneData {
     /// Init handling of mouse up and down events. It is also responsible for discovering of the
-    /// mouse release events. To learn more see the documentation of [`PointerTarget`].
+    /// mouse release events. To learn more see the documentation of [`PointerTarget`]. Targets
+    /// masked by an event-blocking layer receive neither down nor up events.
     fn init_mouse_down_and_up_events(&self) {
         let network = &self.frp.network;
         let shapes = &self.shapes;
         let target = &self.mouse.target;
+        let target_masked = &self.mouse.target_masked;
         let pressed: Rc<RefCell<HashMap<mouse::Button, PointerTargetId>>> = default();
 
         frp::extend! { network
-            eval self.mouse.frp.down ([shapes,target,pressed](button) {
-                let current_target = target.get();
-                pressed.borrow_mut().insert(*button,current_target);
-                shapes.with_mouse_target(current_target, |t| t.mouse_down.emit(button));
+            eval self.mouse.frp.down ([shapes,target,target_masked,pressed](button) {
+                if !target_masked.get() {
+                    let current_target = target.get();
+                    pressed.borrow_mut().insert(*button,current_target);
+                    shapes.with_mouse_target(current_target, |t| t.mouse_down.emit(button));
+                }
             });
-            eval self.mouse.frp.up ([shapes,target,pressed](button) {
+            eval self.mouse.frp.up ([shapes,target,target_masked,pressed](button) {
                 let current_target = target.get();
                 if let Some(last_target) = pressed.borrow_mut().remove(button) {
                     shapes.with_mouse_target(last_target, |t| t.mouse_release.emit(button));
                 }
-                shapes.with_mouse_target(current_target, |t| t.mouse_up.emit(button));
+                if !target_masked.get() {
+                    shapes.with_mouse_target(current_target, |t| t.mouse_up.emit(button));
+                }
             });
         }
     }
 
-    /// Discover what object the mouse pointer is on.
+    /// Discover what object the mouse pointer is on. If the object is masked by an event-blocking
+    /// layer, it does not receive the `mouse_over` event. In case the object becomes masked while
+    /// being hovered, it receives the `mouse_out` event.
     fn handle_mouse_over_and_out_events(&self) {
         let opt_new_target = PointerTargetId::decode_from_rgba(self.mouse.hover_rgba.get());
         let new_target = opt_new_target.unwrap_or_else(|err| {
@@ -975,13 +1031,30 @@ impl SceneData {
             default()
         });
         let current_target = self.mouse.target.get();
-        if new_target != current_target {
+        let was_masked = self.mouse.target_masked.get();
+        let is_masked = self.is_mouse_target_masked(new_target);
+        if new_target != current_target || is_masked != was_masked {
             self.mouse.target.set(new_target);
-            self.shapes.with_mouse_target(current_target, |t| t.mouse_out.emit(()));
-            self.shapes.with_mouse_target(new_target, |t| t.mouse_over.emit(()));
+            self.mouse.target_masked.set(is_masked);
+            if !was_masked {
+                self.shapes.with_mouse_target(current_target, |t| t.mouse_out.emit(()));
+            }
+            if !is_masked {
+                self.shapes.with_mouse_target(new_target, |t| t.mouse_over.emit(()));
+            }
             self.mouse.re_emit_position_event(); // See docs to learn why.
         }
     }
+
+    /// Check whether the mouse target is placed on a layer masked by an event-blocking layer. See
+    /// the docs of [`Layer`] to learn more. The set of the masked layers is cached, so the check
+    /// is cheap enough to be done on every hover change.
+    pub fn is_mouse_target_masked(&self, target: PointerTargetId) -> bool {
+        self.shapes
+            .get_mouse_target_layer(target)
+            .map(|layer| self.layers.event_masked_layers().contains(&layer))
+            .unwrap_or(false)
+    }
 }
 
 
@@ -1163,6 +1236,7 @@ pub mod test_utils {
     impl MouseExt for Mouse {
         fn click_on_background(&self) {
             self.target.set(PointerTargetId::Background);
+            self.target_masked.set(false);
             let left_mouse_button = frp::io::mouse::Button::Button0;
             self.frp.down.emit(left_mouse_button);
             self.frp.up.emit(left_mouse_button);
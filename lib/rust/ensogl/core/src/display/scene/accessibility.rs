@@ -0,0 +1,317 @@
+//! Accessibility metadata of scene components. Components (like [`crate::gui::widget::Widget`] or
+//! [`crate::gui::component::ShapeView`]) can describe themselves with a role, a label, and a set of
+//! states. The metadata is collected by the [`Registry`] owned by the scene, which can build an
+//! accessibility tree mirroring the display object hierarchy. The tree is used by automated UI
+//! tests to find components semantically, and is meant to be used by screen readers in the future.
+
+use crate::prelude::*;
+
+use crate::display;
+use crate::display::scene::Scene;
+
+use std::collections::BTreeSet;
+
+
+
+// ============
+// === Role ===
+// ============
+
+/// The role of a component, describing what kind of UI element it is.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[allow(missing_docs)]
+pub enum Role {
+    Generic,
+    Button,
+    CheckBox,
+    Dialog,
+    Label,
+    List,
+    ListItem,
+    Panel,
+    Slider,
+    TextInput,
+    /// A role not covered by the other variants.
+    Custom(String),
+}
+
+impl Default for Role {
+    fn default() -> Self {
+        Self::Generic
+    }
+}
+
+
+
+// =============
+// === State ===
+// =============
+
+/// A dynamic state of a component. A component may be in several states at once.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[allow(missing_docs)]
+pub enum State {
+    Checked,
+    Disabled,
+    Expanded,
+    Focused,
+    Selected,
+}
+
+
+
+// ================
+// === Metadata ===
+// ================
+
+/// Accessibility description of a single component.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub struct Metadata {
+    pub role:  Role,
+    pub label: Option<String>,
+    pub state: BTreeSet<State>,
+}
+
+impl Metadata {
+    /// Constructor.
+    pub fn new(role: Role) -> Self {
+        Self { role, ..default() }
+    }
+
+    /// Set the label.
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Add the state.
+    pub fn with_state(mut self, state: State) -> Self {
+        self.state.insert(state);
+        self
+    }
+
+    /// Check whether the component is in the given state.
+    pub fn has_state(&self, state: State) -> bool {
+        self.state.contains(&state)
+    }
+}
+
+
+
+// ============
+// === Node ===
+// ============
+
+/// A node of the accessibility tree. The children of a node are the nearest descendants (in the
+/// display object hierarchy) having the accessibility metadata attached.
+#[derive(Clone, Debug)]
+#[allow(missing_docs)]
+pub struct Node {
+    pub object:   display::object::Instance,
+    pub metadata: Metadata,
+    pub children: Vec<Node>,
+}
+
+impl Node {
+    /// All nodes of this subtree (including this node) fulfilling the predicate, in depth-first
+    /// order.
+    pub fn find_all(&self, f: impl Fn(&Metadata) -> bool) -> Vec<&Node> {
+        let mut result = default();
+        self.find_all_internal(&f, &mut result);
+        result
+    }
+
+    fn find_all_internal<'a>(&'a self, f: &impl Fn(&Metadata) -> bool, out: &mut Vec<&'a Node>) {
+        if f(&self.metadata) {
+            out.push(self)
+        }
+        for child in &self.children {
+            child.find_all_internal(f, out)
+        }
+    }
+}
+
+
+
+// ================
+// === Registry ===
+// ================
+
+#[derive(Debug)]
+struct Entry {
+    object:   display::object::WeakInstance<Scene>,
+    metadata: Metadata,
+}
+
+/// Registry of accessibility metadata of all components in the scene.
+#[derive(Clone, CloneRef, Debug, Default)]
+pub struct Registry {
+    entries: Rc<RefCell<HashMap<display::object::Id, Entry>>>,
+}
+
+impl Registry {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Attach the metadata to the object, replacing the previously attached one. Metadata of
+    /// dropped objects is removed from the registry.
+    pub fn set(&self, object: &impl display::Object, metadata: Metadata) {
+        let object = object.display_object();
+        let entry = Entry { object: object.downgrade(), metadata };
+        let mut entries = self.entries.borrow_mut();
+        entries.retain(|_, entry| entry.object.exists());
+        entries.insert(object.id(), entry);
+    }
+
+    /// Modify the metadata attached to the object. Does nothing if there is no metadata attached.
+    pub fn modify(&self, object: &impl display::Object, f: impl FnOnce(&mut Metadata)) {
+        let id = object.display_object().id();
+        let mut entries = self.entries.borrow_mut();
+        if let Some(entry) = entries.get_mut(&id).filter(|entry| entry.object.exists()) {
+            f(&mut entry.metadata)
+        }
+    }
+
+    /// Remove the metadata attached to the object.
+    pub fn remove(&self, object: &impl display::Object) {
+        self.remove_by_id(object.display_object().id());
+    }
+
+    /// Remove the metadata attached to the object with the given ID. Used by the components which
+    /// are being dropped and cannot access their display object anymore.
+    pub(crate) fn remove_by_id(&self, id: display::object::Id) {
+        self.entries.borrow_mut().remove(&id);
+    }
+
+    /// Get the metadata attached to the object.
+    pub fn get(&self, object: &impl display::Object) -> Option<Metadata> {
+        let id = object.display_object().id();
+        let entries = self.entries.borrow();
+        let entry = entries.get(&id).filter(|entry| entry.object.exists());
+        entry.map(|entry| entry.metadata.clone())
+    }
+
+    /// Build the accessibility tree. Returns the list of root nodes, that is, the nodes without any
+    /// ancestor having the accessibility metadata attached. Metadata of dropped objects is removed
+    /// from the registry.
+    pub fn tree(&self) -> Vec<Node> {
+        let mut entries = self.entries.borrow_mut();
+        entries.retain(|_, entry| entry.object.exists());
+        let mut children: HashMap<Option<display::object::Id>, Vec<display::object::Id>> =
+            default();
+        for (id, entry) in entries.iter() {
+            let mut ancestor = entry.object.upgrade().and_then(|object| object.parent());
+            while let Some(object) = &ancestor {
+                if entries.contains_key(&object.id()) {
+                    break;
+                }
+                ancestor = object.parent();
+            }
+            children.entry(ancestor.map(|object| object.id())).or_default().push(*id);
+        }
+        for ids in children.values_mut() {
+            ids.sort();
+        }
+        Self::build_nodes(&entries, &children, None)
+    }
+
+    fn build_nodes(
+        entries: &HashMap<display::object::Id, Entry>,
+        children: &HashMap<Option<display::object::Id>, Vec<display::object::Id>>,
+        parent: Option<display::object::Id>,
+    ) -> Vec<Node> {
+        let ids = children.get(&parent).into_iter().flatten();
+        let nodes = ids.filter_map(|id| {
+            let entry = entries.get(id)?;
+            let object = entry.object.upgrade()?;
+            let metadata = entry.metadata.clone();
+            let children = Self::build_nodes(entries, children, Some(*id));
+            Some(Node { object, metadata, children })
+        });
+        nodes.collect()
+    }
+
+    /// All nodes of the accessibility tree fulfilling the predicate.
+    pub fn find_all(&self, f: impl Fn(&Metadata) -> bool) -> Vec<Node> {
+        let tree = self.tree();
+        let found = tree.iter().flat_map(|root| root.find_all(&f));
+        found.cloned().collect()
+    }
+
+    /// All nodes of the accessibility tree with the given role and label.
+    pub fn find_by_label(&self, role: &Role, label: &str) -> Vec<Node> {
+        self.find_all(|metadata| &metadata.role == role && metadata.label.as_deref() == Some(label))
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::display::object::ObjectOps;
+
+    #[test]
+    fn building_tree() {
+        let logger = Logger::new("test");
+        let root = display::object::Instance::new(&logger);
+        let dialog = display::object::Instance::new(&logger);
+        let panel = display::object::Instance::new(&logger);
+        let button = display::object::Instance::new(&logger);
+        let dropped = display::object::Instance::new(&logger);
+        root.add_child(&dialog);
+        dialog.add_child(&panel);
+        panel.add_child(&button);
+        panel.add_child(&dropped);
+
+        let registry = Registry::new();
+        registry.set(&dialog, Metadata::new(Role::Dialog).with_label("Settings"));
+        let ok_button = Metadata::new(Role::Button).with_label("Ok").with_state(State::Disabled);
+        registry.set(&button, ok_button.clone());
+        registry.set(&dropped, Metadata::new(Role::Button).with_label("Dropped"));
+        drop(dropped);
+
+        let tree = registry.tree();
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].object, dialog);
+        assert_eq!(tree[0].children.len(), 1);
+        assert_eq!(tree[0].children[0].object, button);
+        assert_eq!(tree[0].children[0].metadata, ok_button);
+
+        registry.modify(&button, |metadata| {
+            metadata.state.remove(&State::Disabled);
+        });
+        let found = registry.find_by_label(&Role::Button, "Ok");
+        assert_eq!(found.len(), 1);
+        assert!(!found[0].metadata.has_state(State::Disabled));
+        assert!(registry.find_by_label(&Role::Button, "Dropped").is_empty());
+
+        registry.remove(&dialog);
+        let tree = registry.tree();
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].object, button);
+    }
+
+    #[test]
+    fn purging_dropped_objects() {
+        let logger = Logger::new("test");
+        let registry = Registry::new();
+        let dropped = display::object::Instance::new(&logger);
+        registry.set(&dropped, Metadata::new(Role::Button));
+        let id = dropped.id();
+        drop(dropped);
+        let kept = display::object::Instance::new(&logger);
+        registry.set(&kept, Metadata::new(Role::Label));
+        assert!(!registry.entries.borrow().contains_key(&id));
+        assert_eq!(registry.entries.borrow().len(), 1);
+        assert_eq!(registry.get(&kept), Some(Metadata::new(Role::Label)));
+    }
+}
@@ -152,6 +152,13 @@ use std::any::TypeId;
 /// Please note that the current implementation does not allow for hierarchical masks (masks applied
 /// to already masked area or masks applied to masks). If you try using masks in hierarchical way,
 /// the nested masks will be skipped and a warning will be emitted to the console.
+///
+/// # Blocking Mouse Events
+/// A layer can be marked as event-blocking by using the [`LayerModel::set_blocks_events`] method.
+/// While such a layer displays any elements, shapes placed on layers drawn below it (visited
+/// earlier by [`Layer::iter_sublayers_nested`]) will not receive hover, press, and release events.
+/// This is useful for modal dialogs – the underlying components do not need to disable themselves
+/// while the dialog is visible. See [`Layer::event_masked_layers`] to learn more.
 #[derive(Clone, CloneRef)]
 pub struct Layer {
     model: Rc<LayerModel>,
@@ -236,6 +243,39 @@ impl Layer {
             layer.iter_sublayers_and_masks_nested_internal(f)
         }
     }
+
+    /// Set of layers (this layer and its nested sublayers) which are drawn below the top-most
+    /// currently blocking layer (see [`LayerModel::is_blocking_events`]). Shapes placed on these
+    /// layers should not receive mouse events. The blocking layer itself and its sublayers are not
+    /// included in the set.
+    ///
+    /// The set is cached, and computed again only after a change of any layer which can affect it:
+    /// of the sublayers, of the event-blocking flag, or of the visibility of an event-blocking
+    /// layer.
+    pub fn event_masked_layers(&self) -> Rc<HashSet<LayerId>> {
+        let version = EVENT_MASK_VERSION.with(|version| version.get());
+        let mut cache = self.event_masked_layers.borrow_mut();
+        match &*cache {
+            Some((cached_version, layers)) if *cached_version == version => layers.clone_ref(),
+            _ => {
+                let layers = Rc::new(self.compute_event_masked_layers());
+                *cache = Some((version, layers.clone_ref()));
+                layers
+            }
+        }
+    }
+
+    fn compute_event_masked_layers(&self) -> HashSet<LayerId> {
+        let visited = RefCell::new(Vec::<LayerId>::new());
+        let masked_count = Cell::new(0);
+        self.iter_sublayers_nested(|layer| {
+            if layer.is_blocking_events() {
+                masked_count.set(visited.borrow().len());
+            }
+            visited.borrow_mut().push(layer.id());
+        });
+        visited.into_inner().into_iter().take(masked_count.get()).collect()
+    }
 }
 
 impl From<&Layer> for LayerId {
@@ -246,6 +286,22 @@ impl From<&Layer> for LayerId {
 
 
 
+// === Event Mask Version ===
+
+thread_local! {
+    /// The version of the state of all layers affecting [`Layer::event_masked_layers`]: the
+    /// layer hierarchy, the event-blocking flags and the visibility of the event-blocking layers.
+    /// It is incremented on every change of this state, invalidating the cached sets.
+    static EVENT_MASK_VERSION: Cell<usize> = Cell::new(0);
+}
+
+/// Invalidate the cached results of [`Layer::event_masked_layers`] of all layers.
+fn invalidate_event_masks() {
+    EVENT_MASK_VERSION.with(|version| version.set(version.get() + 1));
+}
+
+
+
 // =================
 // === WeakLayer ===
 // =================
@@ -302,6 +358,8 @@ pub struct LayerModel {
     sublayers: Sublayers,
     mask: RefCell<Option<WeakLayer>>,
     scissor_box: RefCell<Option<ScissorBox>>,
+    blocks_events: Cell<bool>,
+    event_masked_layers: RefCell<Option<(usize, Rc<HashSet<LayerId>>)>>,
     mem_mark: Rc<()>,
 }
 
@@ -319,6 +377,7 @@ impl Debug for LayerModel {
 impl Drop for LayerModel {
     fn drop(&mut self) {
         let id = self.id();
+        invalidate_event_masks();
         for parent in &mut *self.parents.borrow_mut() {
             let mut model = parent.borrow_mut();
             model.remove(id);
@@ -343,6 +402,8 @@ impl LayerModel {
         let sublayers = Sublayers::new(Logger::new_sub(&logger, "registry"));
         let mask = default();
         let scissor_box = default();
+        let blocks_events = default();
+        let event_masked_layers = default();
         let mem_mark = default();
         Self {
             logger,
@@ -359,6 +420,8 @@ impl LayerModel {
             sublayers,
             mask,
             scissor_box,
+            blocks_events,
+            event_masked_layers,
             mem_mark,
         }
     }
@@ -487,6 +550,7 @@ impl LayerModel {
     /// Internal helper for adding elements to this layer.
     fn add_element(&self, symbol_id: SymbolId, shape_system_info: Option<ShapeSystemInfo>) {
         self.depth_order_dirty.set();
+        let was_blocking = self.is_blocking_events();
         match shape_system_info {
             None => {
                 self.elements.borrow_mut().insert(LayerItem::Symbol(symbol_id));
@@ -498,11 +562,13 @@ impl LayerModel {
                 self.elements.borrow_mut().insert(LayerItem::ShapeSystem(info.id));
             }
         }
+        self.elements_changed(was_blocking);
     }
 
     /// Remove the symbol from the current layer.
     pub fn remove_symbol(&self, symbol_id: impl Into<SymbolId>) {
         self.depth_order_dirty.set();
+        let was_blocking = self.is_blocking_events();
         let symbol_id = symbol_id.into();
 
         self.elements.borrow_mut().remove(&LayerItem::Symbol(symbol_id));
@@ -512,17 +578,20 @@ impl LayerModel {
             self.shape_system_to_symbol_info_map.borrow_mut().remove(&shape_system_id);
             self.elements.borrow_mut().remove(&LayerItem::ShapeSystem(shape_system_id));
         }
+        self.elements_changed(was_blocking);
     }
 
     /// Remove the [`ShapeSystem`] registered in this layer together with all of its [`Symbol`]s.
     pub fn remove_shape_system(&self, shape_system_id: ShapeSystemId) {
         self.depth_order_dirty.set();
+        let was_blocking = self.is_blocking_events();
         self.elements.borrow_mut().remove(&LayerItem::ShapeSystem(shape_system_id));
         if let Some(symbol_id) =
             self.shape_system_to_symbol_info_map.borrow_mut().remove(&shape_system_id)
         {
             self.symbol_to_shape_system_map.borrow_mut().remove(&symbol_id.id);
         }
+        self.elements_changed(was_blocking);
     }
 
     /// Consume all dirty flags and update the ordering of elements if needed.
@@ -625,12 +694,14 @@ impl LayerModel {
     }
 
     fn add_sublayer(&self, layer: &Layer) {
+        invalidate_event_masks();
         let ix = self.sublayers.borrow_mut().layers.insert(layer.downgrade());
         self.sublayers.borrow_mut().layer_placement.insert(layer.id(), ix);
         layer.add_parent(&self.sublayers);
     }
 
     fn remove_all_sublayers(&self) {
+        invalidate_event_masks();
         for layer in self.sublayers.borrow().layers.iter() {
             if let Some(layer) = layer.upgrade() {
                 layer.remove_parent(&self.sublayers)
@@ -687,6 +758,34 @@ impl LayerModel {
         *self.scissor_box.borrow_mut() = scissor_box.cloned();
     }
 
+    /// Check whether this layer was marked as event-blocking. See the "Blocking Mouse Events"
+    /// section in docs of [`Layer`] to learn more.
+    pub fn blocks_events(&self) -> bool {
+        self.blocks_events.get()
+    }
+
+    /// Mark this layer as event-blocking. See the "Blocking Mouse Events" section in docs of
+    /// [`Layer`] to learn more.
+    pub fn set_blocks_events(&self, blocks_events: bool) {
+        if self.blocks_events.replace(blocks_events) != blocks_events {
+            invalidate_event_masks();
+        }
+    }
+
+    /// Check whether this layer currently masks mouse events of layers below it. It happens when
+    /// the layer was marked as event-blocking and it is visible, i.e. it contains any elements.
+    pub fn is_blocking_events(&self) -> bool {
+        self.blocks_events() && !self.elements.borrow().is_empty()
+    }
+
+    /// Invalidate the cached [`Layer::event_masked_layers`] if the change of the elements showed or
+    /// hid this layer as an event-blocking one.
+    fn elements_changed(&self, was_blocking: bool) {
+        if self.is_blocking_events() != was_blocking {
+            invalidate_event_masks();
+        }
+    }
+
     /// Add depth-order dependency between two [`LayerItem`]s in this layer. Returns `true`
     /// if the dependency was inserted successfully (was not already present), and `false`
     /// otherwise. All sublayers will inherit these rules.
@@ -1177,3 +1276,45 @@ impl PartialSemigroup<&ScissorBox> for ScissorBox {
         self.concat_mut(*other)
     }
 }
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_masked_layers() {
+        let root = Layer::new(Logger::new("root"));
+        let bottom = Layer::new(Logger::new("bottom"));
+        let modal = Layer::new(Logger::new("modal"));
+        let modal_text = Layer::new(Logger::new("modal_text"));
+        let top = Layer::new(Logger::new("top"));
+        modal.set_sublayers(&[&modal_text]);
+        root.set_sublayers(&[&bottom, &modal, &top]);
+        assert!(root.event_masked_layers().is_empty());
+
+        modal.set_blocks_events(true);
+        assert!(root.event_masked_layers().is_empty());
+
+        modal.add_symbol(SymbolId::new(0));
+        let expected: HashSet<LayerId> = [root.id(), bottom.id()].into_iter().collect();
+        assert_eq!(*root.event_masked_layers(), expected);
+
+        root.set_sublayers(&[&modal, &bottom, &top]);
+        let expected: HashSet<LayerId> = [root.id()].into_iter().collect();
+        assert_eq!(*root.event_masked_layers(), expected);
+
+        modal.remove_symbol(SymbolId::new(0));
+        assert!(root.event_masked_layers().is_empty());
+
+        modal.add_symbol(SymbolId::new(0));
+        assert!(!root.event_masked_layers().is_empty());
+        modal.set_blocks_events(false);
+        assert!(root.event_masked_layers().is_empty());
+    }
+}
@@ -8,3 +8,4 @@
 pub mod component;
 pub mod cursor;
 pub mod style;
+pub mod widget;
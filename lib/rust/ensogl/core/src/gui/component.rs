@@ -5,6 +5,7 @@ use crate::prelude::*;
 
 use crate::display;
 use crate::display::scene;
+use crate::display::scene::accessibility;
 use crate::display::scene::layer::WeakLayer;
 use crate::display::scene::Scene;
 use crate::display::scene::ShapeRegistry;
@@ -82,6 +83,13 @@ pub struct ShapeViewModel<S> {
     pub events:          PointerTarget,
     pub registry:        RefCell<Option<ShapeRegistry>>,
     pub pointer_targets: RefCell<Vec<symbol::GlobalInstanceId>>,
+    /// Accessibility metadata of the shape. It is registered in the scene once the shape is added
+    /// to a scene layer.
+    accessibility:       RefCell<Option<accessibility::Metadata>>,
+    accessibility_reg:   RefCell<Option<accessibility::Registry>>,
+    /// The ID of the display object the accessibility metadata is registered for, used to remove
+    /// the metadata when the shape is dropped.
+    accessibility_id:    Cell<Option<display::object::Id>>,
 }
 
 impl<S> Deref for ShapeViewModel<S> {
@@ -94,6 +102,7 @@ impl<S> Deref for ShapeViewModel<S> {
 impl<S> Drop for ShapeViewModel<S> {
     fn drop(&mut self) {
         self.unregister_existing_mouse_targets();
+        self.unregister_accessibility();
         self.events.on_drop.emit(());
     }
 }
@@ -135,14 +144,28 @@ impl<S: DynamicShape> ShapeViewModel<S> {
         let events = PointerTarget::new();
         let registry = default();
         let pointer_targets = default();
-        ShapeViewModel { shape, events, registry, pointer_targets }
+        let accessibility = default();
+        let accessibility_reg = default();
+        let accessibility_id = default();
+        ShapeViewModel {
+            shape,
+            events,
+            registry,
+            pointer_targets,
+            accessibility,
+            accessibility_reg,
+            accessibility_id,
+        }
     }
 
     fn add_to_scene_layer(&self, scene: &Scene, layer: &scene::Layer) {
         let instance = layer.instantiate(scene, &self.shape);
         scene.shapes.insert_mouse_target(instance.global_instance_id, self.events.clone_ref());
+        scene.shapes.set_mouse_target_layer(instance.global_instance_id, layer);
         self.pointer_targets.borrow_mut().push(instance.global_instance_id);
         *self.registry.borrow_mut() = Some(scene.shapes.clone_ref());
+        *self.accessibility_reg.borrow_mut() = Some(scene.accessibility.clone_ref());
+        self.register_accessibility();
     }
 }
 
@@ -154,6 +177,45 @@ impl<S> ShapeViewModel<S> {
             }
         }
     }
+
+    fn unregister_accessibility(&self) {
+        if let Some(registry) = &*self.accessibility_reg.borrow() {
+            if let Some(id) = self.accessibility_id.take() {
+                registry.remove_by_id(id);
+            }
+        }
+    }
+}
+
+impl<S: display::Object> ShapeViewModel<S> {
+    /// Attach the accessibility metadata to the shape. See [`accessibility`] to learn more.
+    pub fn set_accessibility(&self, metadata: accessibility::Metadata) {
+        *self.accessibility.borrow_mut() = Some(metadata);
+        self.register_accessibility();
+    }
+
+    /// Modify the accessibility metadata attached to the shape, for example, to update its state.
+    /// Does nothing if there is no metadata attached.
+    pub fn modify_accessibility(&self, f: impl FnOnce(&mut accessibility::Metadata)) {
+        if let Some(metadata) = &mut *self.accessibility.borrow_mut() {
+            f(metadata)
+        }
+        self.register_accessibility();
+    }
+
+    /// The accessibility metadata attached to the shape.
+    pub fn accessibility(&self) -> Option<accessibility::Metadata> {
+        self.accessibility.borrow().clone()
+    }
+
+    fn register_accessibility(&self) {
+        if let Some(registry) = &*self.accessibility_reg.borrow() {
+            if let Some(metadata) = self.accessibility.borrow().clone() {
+                registry.set(self, metadata);
+                self.accessibility_id.set(Some(self.display_object().id()));
+            }
+        }
+    }
 }
 
 impl<T: display::Object> display::Object for ShapeViewModel<T> {
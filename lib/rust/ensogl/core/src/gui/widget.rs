@@ -0,0 +1,270 @@
+//! A generic UI widget consisting of a Model and an FRP, created with a typed [`Builder`].
+//!
+//! The builder enforces the wiring the widgets otherwise need to remember to do by hand: the
+//! model's display object is always attached to the widget's root display object, and the FRP
+//! (together with its network) is always owned by the widget. A builder missing the model or the
+//! FRP does not provide the `build` method, so such mistakes are caught at compile time instead of
+//! manifesting as invisible shapes or dropped networks at runtime.
+
+use crate::display::object::traits::*;
+use crate::prelude::*;
+
+use crate::application::command::FrpNetworkProvider;
+use crate::application::Application;
+use crate::display;
+use crate::display::scene::accessibility;
+use crate::frp;
+
+
+
+// =============
+// === Unset ===
+// =============
+
+/// Type-level marker of a [`Builder`] field which was not provided yet.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Unset;
+
+
+
+// ==============
+// === Widget ===
+// ==============
+
+/// UI widget owning its Model and its FRP. Dereferences to the FRP, so the widget can be used as
+/// its public API. Use [`Widget::builder`] to create it.
+#[derive(CloneRef, Debug, Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct Widget<Model, Frp> {
+    app:            Application,
+    display_object: display::object::Instance,
+    model:          Rc<Model>,
+    frp:            Rc<Frp>,
+}
+
+impl Widget<Unset, Unset> {
+    /// Start building a new widget. The `label` is used to initialize the logger of the widget
+    /// display object.
+    pub fn builder(app: &Application, label: &'static str) -> Builder<Unset, Unset> {
+        Builder::new(app, label)
+    }
+}
+
+impl<Model, Frp> Widget<Model, Frp> {
+    /// The widget's model.
+    pub fn model(&self) -> &Model {
+        &self.model
+    }
+
+    /// The widget's FRP.
+    pub fn frp(&self) -> &Frp {
+        &self.frp
+    }
+
+    /// Reference to the application the widget belongs to.
+    pub fn app(&self) -> &Application {
+        &self.app
+    }
+
+    /// Attach the accessibility metadata to the widget. See [`accessibility`] to learn more.
+    pub fn set_accessibility(&self, metadata: accessibility::Metadata) {
+        self.app.display.default_scene.accessibility.set(self, metadata);
+    }
+
+    /// Modify the accessibility metadata attached to the widget, for example, to update its state.
+    pub fn modify_accessibility(&self, f: impl FnOnce(&mut accessibility::Metadata)) {
+        self.app.display.default_scene.accessibility.modify(self, f);
+    }
+
+    /// The accessibility metadata attached to the widget.
+    pub fn accessibility(&self) -> Option<accessibility::Metadata> {
+        self.app.display.default_scene.accessibility.get(self)
+    }
+}
+
+impl<Model, Frp> Drop for Widget<Model, Frp> {
+    fn drop(&mut self) {
+        let is_last_clone = Rc::strong_count(&self.model) == 1;
+        if is_last_clone {
+            self.app.display.default_scene.accessibility.remove(&self.display_object);
+        }
+    }
+}
+
+impl<Model, Frp> display::Object for Widget<Model, Frp> {
+    fn display_object(&self) -> &display::object::Instance {
+        &self.display_object
+    }
+}
+
+impl<Model, Frp> Deref for Widget<Model, Frp> {
+    type Target = Frp;
+    fn deref(&self) -> &Self::Target {
+        &self.frp
+    }
+}
+
+impl<Model, Frp: FrpNetworkProvider> FrpNetworkProvider for Widget<Model, Frp> {
+    fn network(&self) -> &frp::Network {
+        self.frp.network()
+    }
+}
+
+
+
+// ===============
+// === Builder ===
+// ===============
+
+/// Builder of [`Widget`]. The widget is constructed in the following steps:
+/// 1. [`Builder::model`] creates the model. Its display object is attached to the widget's one.
+/// 2. [`Builder::frp`] creates the FRP, having access to the already created model.
+/// 3. [`Builder::wiring`] (optional, may be called many times) extends the FRP network, having
+///    access to the model and the FRP.
+/// 4. [`Builder::build`] returns the widget owning all the above.
+///
+/// ```text
+/// let widget = Widget::builder(app, "MyWidget")
+///     .model(|app, _display_object| Model::new(app))
+///     .frp(|_app, _model| Frp::new())
+///     .wiring(|network, _app, model, frp| {
+///         frp::extend! { network
+///             eval frp.set_size ((size) model.set_size(*size));
+///         }
+///     })
+///     .build();
+/// ```
+#[derive(Debug)]
+pub struct Builder<Model, Frp> {
+    app:            Application,
+    display_object: display::object::Instance,
+    model:          Model,
+    frp:            Frp,
+}
+
+impl Builder<Unset, Unset> {
+    /// Constructor.
+    pub fn new(app: &Application, label: &'static str) -> Self {
+        let app = app.clone_ref();
+        let logger = Logger::new(label);
+        let display_object = display::object::Instance::new(&logger);
+        Self { app, display_object, model: Unset, frp: Unset }
+    }
+
+    /// Create the model. The closure is given the widget's root display object, to which the
+    /// model's display object is attached automatically.
+    pub fn model<Model: display::Object>(
+        self,
+        f: impl FnOnce(&Application, &display::object::Instance) -> Model,
+    ) -> Builder<Model, Unset> {
+        let Self { app, display_object, .. } = self;
+        let model = f(&app, &display_object);
+        display_object.add_child(&model);
+        Builder { app, display_object, model, frp: Unset }
+    }
+}
+
+impl<Model> Builder<Model, Unset> {
+    /// Create the FRP. The closure is given the already created model.
+    pub fn frp<Frp: FrpNetworkProvider>(
+        self,
+        f: impl FnOnce(&Application, &Model) -> Frp,
+    ) -> Builder<Model, Frp> {
+        let Self { app, display_object, model, .. } = self;
+        let frp = f(&app, &model);
+        Builder { app, display_object, model, frp }
+    }
+}
+
+impl<Model: display::Object, Frp: FrpNetworkProvider> Builder<Model, Frp> {
+    /// Extend the FRP network with the logic connecting the FRP with the model.
+    pub fn wiring(self, f: impl FnOnce(&frp::Network, &Application, &Model, &Frp)) -> Self {
+        f(self.frp.network(), &self.app, &self.model, &self.frp);
+        self
+    }
+
+    /// Finish building the widget.
+    pub fn build(self) -> Widget<Model, Frp> {
+        let Self { app, display_object, model, frp } = self;
+        let model = Rc::new(model);
+        let frp = Rc::new(frp);
+        Widget { app, display_object, model, frp }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::define_endpoints;
+
+    define_endpoints! {
+        Input {
+            set_value (f32),
+        }
+        Output {
+            value (f32),
+        }
+    }
+
+    #[derive(Clone, CloneRef, Debug)]
+    struct Model {
+        display_object: display::object::Instance,
+        value:          Rc<Cell<f32>>,
+    }
+
+    impl display::Object for Model {
+        fn display_object(&self) -> &display::object::Instance {
+            &self.display_object
+        }
+    }
+
+    #[test]
+    fn building_widget() {
+        let app = Application::new("root");
+        let widget = Widget::builder(&app, "TestWidget")
+            .model(|_, _| {
+                let display_object = display::object::Instance::new(Logger::new("TestModel"));
+                let value = default();
+                Model { display_object, value }
+            })
+            .frp(|_, _| Frp::new())
+            .wiring(|network, _, model, frp| {
+                let model = model.clone_ref();
+                frp::extend! { network
+                    eval frp.set_value ((value) model.value.set(*value));
+                    frp.source.value <+ frp.set_value;
+                }
+            })
+            .build();
+        assert!(widget.model().has_parent());
+        widget.set_value(2.0);
+        assert_eq!(widget.model().value.get(), 2.0);
+        assert_eq!(widget.value.value(), 2.0);
+
+        let metadata =
+            accessibility::Metadata::new(accessibility::Role::Slider).with_label("Value");
+        widget.set_accessibility(metadata.clone());
+        widget.modify_accessibility(|m| {
+            m.state.insert(accessibility::State::Focused);
+        });
+        let found = app.display.default_scene.accessibility.find_by_label(&metadata.role, "Value");
+        assert_eq!(found.len(), 1);
+        assert_eq!(&found[0].object, widget.display_object());
+        assert!(found[0].metadata.has_state(accessibility::State::Focused));
+
+        let accessibility = &app.display.default_scene.accessibility;
+        let display_object = widget.display_object().clone_ref();
+        let widget_clone = widget.clone_ref();
+        drop(widget);
+        assert!(accessibility.get(&display_object).is_some());
+        drop(widget_clone);
+        assert!(accessibility.get(&display_object).is_none());
+    }
+}
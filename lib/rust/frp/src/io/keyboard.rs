@@ -199,6 +199,33 @@ impl KeyWithCode {
         let key = Key::new(key, code.as_str());
         KeyWithCode { key, code }
     }
+
+    /// The name of the physical key, independent of the keyboard layout. For character keys, it is
+    /// the character the key produces in the US QWERTY layout, so, for example, the key producing
+    /// "z" on the QWERTY layout is named "z" on the AZERTY layout as well (while it produces "w").
+    /// For other keys, it is the same as [`Key::simple_name`].
+    pub fn physical_name(&self) -> String {
+        let code = self.code.as_str();
+        let letter = code.strip_prefix("Key").filter(|t| t.len() == 1);
+        let digit = code.strip_prefix("Digit").filter(|t| t.len() == 1);
+        let symbol = match code {
+            "Minus" => Some("-"),
+            "Equal" => Some("="),
+            "BracketLeft" => Some("["),
+            "BracketRight" => Some("]"),
+            "Backslash" => Some("\\"),
+            "Semicolon" => Some(";"),
+            "Quote" => Some("'"),
+            "Backquote" => Some("`"),
+            "Period" => Some("."),
+            "Slash" => Some("/"),
+            _ => None,
+        };
+        match letter.or(digit).or(symbol) {
+            Some(name) => name.to_lowercase(),
+            None => self.key.simple_name(),
+        }
+    }
 }
 
 impl From<&KeyboardEvent> for KeyWithCode {
@@ -273,6 +300,13 @@ impl KeyboardModel {
         key
     }
 
+    /// Simulate press of the provided key. Returns the pressed key together with its code.
+    pub fn press_with_code(&self, key_with_code: &KeyWithCode) -> KeyWithCode {
+        let key = self.press(key_with_code);
+        let code = key_with_code.code.clone();
+        KeyWithCode { key, code }
+    }
+
     /// Simulate release of the provided key.
     pub fn release(&self, KeyWithCode { key, code }: &KeyWithCode) -> Key {
         let key = self.pressed_code_to_key.borrow_mut().remove(code).unwrap_or_else(|| key.clone());
@@ -353,6 +387,7 @@ pub struct Keyboard {
     pub network:          frp::Network,
     pub source:           KeyboardSource,
     pub down:             frp::Stream<Key>,
+    pub down_with_code:   frp::Stream<KeyWithCode>,
     pub up:               frp::Stream<Key>,
     pub is_meta_down:     frp::Stream<bool>,
     pub is_control_down:  frp::Stream<bool>,
@@ -367,10 +402,11 @@ impl Keyboard {
         let model = KeyboardModel::default();
         let source = KeyboardSource::new(&network);
         frp::extend! { network
-            down         <- source.down.map(f!((kc) model.press(kc)));
-            up           <- source.up.map(f!((kc) model.release(kc)));
-            is_meta_down <- any(&down,&up).map(f_!(model.is_meta_down()));
-            meta_release <= source.down.gate(&is_meta_down).map(
+            down_with_code   <- source.down.map(f!((kc) model.press_with_code(kc)));
+            down             <- down_with_code.map(|kc: &KeyWithCode| kc.key.clone());
+            up               <- source.up.map(f!((kc) model.release(kc)));
+            is_meta_down     <- any(&down,&up).map(f_!(model.is_meta_down()));
+            meta_release     <= source.down.gate(&is_meta_down).map(
                 f_!(model.release_meta_dependent())
             );
             defocus_release  <= source.window_defocused.map(f_!(model.release_all()));
@@ -387,6 +423,7 @@ impl Keyboard {
             network,
             source,
             down,
+            down_with_code,
             up,
             is_meta_down,
             is_control_down,
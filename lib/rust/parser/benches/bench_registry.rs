@@ -0,0 +1,96 @@
+//! This file contains benchmarks of the lookup of macro definitions in the registry, compared with
+//! the scan of a flat list of definitions, on growing sets of definitions.
+
+use criterion::black_box;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use parser_new::macros::definition::Definition;
+use parser_new::macros::definition::Section;
+use parser_new::macros::literal::Literal;
+use parser_new::macros::registry::Registry;
+use std::time::Duration;
+
+
+
+// =================
+// === Utilities ===
+// =================
+
+/// The base configuration for the benchmarks.
+fn bench_config() -> Criterion {
+    Criterion::default()
+        .measurement_time(Duration::from_secs(10))
+        .warm_up_time(Duration::from_secs(3))
+        .sample_size(25)
+}
+
+/// Create `count` definitions of `sections` sections each. Every group of ten definitions shares
+/// the first section head, like the variants of a keyword.
+fn gen_definitions(count: usize, sections: usize) -> Vec<Definition> {
+    let definition = |index: usize| {
+        let head = Literal::variable(format!("head_{}", index / 10));
+        let rest = (1..sections).map(|section| format!("section_{}_{}", index, section));
+        let heads = std::iter::once(head).chain(rest.map(Literal::variable));
+        Definition::new(format!("macro_{}", index), heads.map(Section::new).collect())
+    };
+    (0..count).map(definition).collect()
+}
+
+/// The sizes of the definition sets the lookups are measured on.
+const SIZES: [usize; 3] = [10, 100, 1000];
+
+
+
+// ==================
+// === Benchmarks ===
+// ==================
+
+/// A benchmark of the lookup of the last definition by its path, in the registry and in a flat
+/// list of definitions.
+fn lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Definition Lookup");
+    for size in SIZES {
+        let definitions = gen_definitions(size, 4);
+        let path = definitions.last().unwrap().path();
+        let registry = Registry::try_from(definitions.clone()).unwrap();
+        group.bench_with_input(BenchmarkId::new("Registry", size), &path, |b, path| {
+            b.iter(|| registry.definition(black_box(path)))
+        });
+        group.bench_with_input(BenchmarkId::new("Flat List", size), &path, |b, path| {
+            b.iter(|| definitions.iter().find(|definition| &definition.path() == black_box(path)))
+        });
+    }
+    group.finish();
+}
+
+/// A benchmark of the lookup of the definitions continuing a head, done by the resolver for every
+/// token of the resolved stream.
+fn head_lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Head Lookup");
+    for size in SIZES {
+        let definitions = gen_definitions(size, 4);
+        let head = Literal::variable(format!("head_{}", (size - 1) / 10));
+        let registry = Registry::try_from(definitions.clone()).unwrap();
+        group.bench_with_input(BenchmarkId::new("Registry", size), &head, |b, head| {
+            b.iter(|| registry.root().branches.get(black_box(head)))
+        });
+        group.bench_with_input(BenchmarkId::new("Flat List", size), &head, |b, head| {
+            b.iter(|| {
+                let heads =
+                    definitions.iter().map(|definition| definition.sections[0].start_symbol());
+                heads.filter(|first| first == black_box(&head)).count()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name    = registry_benches;
+    config  = bench_config();
+    targets = lookup, head_lookup
+}
+
+criterion_main!(registry_benches);
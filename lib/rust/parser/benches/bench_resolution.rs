@@ -0,0 +1,73 @@
+//! This file contains benchmarks of the macro resolution of representative Enso files from the
+//! standard library, with the builtin macros.
+
+use criterion::black_box;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use criterion::Throughput;
+use parser_new::macros::builtin;
+use parser_new::macros::Resolver;
+use std::time::Duration;
+
+
+
+// =================
+// === Utilities ===
+// =================
+
+/// The base configuration for the benchmarks.
+fn bench_config() -> Criterion {
+    Criterion::default()
+        .measurement_time(Duration::from_secs(10))
+        .warm_up_time(Duration::from_secs(3))
+        .sample_size(25)
+}
+
+/// The resolved files, by their names.
+const FILES: [(&str, &str); 3] = [
+    (
+        "Vector",
+        include_str!("../../../../distribution/lib/Standard/Base/0.0.0-dev/src/Data/Vector.enso"),
+    ),
+    (
+        "Text Extensions",
+        include_str!(
+            "../../../../distribution/lib/Standard/Base/0.0.0-dev/src/Data/Text/Extensions.enso"
+        ),
+    ),
+    (
+        "Table",
+        include_str!("../../../../distribution/lib/Standard/Table/0.0.0-dev/src/Data/Table.enso"),
+    ),
+];
+
+
+
+// ==================
+// === Benchmarks ===
+// ==================
+
+/// A benchmark of the resolution of whole files, the work done by the IDE on opening a module.
+fn resolve_file(c: &mut Criterion) {
+    let mut group = c.benchmark_group("File Resolution");
+    let resolver = Resolver::new(builtin()).unwrap();
+    for (name, source) in FILES {
+        resolver.resolve_source(source);
+        println!("{}: {}", name, resolver.last_counters());
+        group.throughput(Throughput::Bytes(source.len() as u64));
+        group.bench_with_input(BenchmarkId::new("Builtin Macros", name), source, |b, source| {
+            b.iter(|| resolver.resolve_source(black_box(source)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name    = resolution_benches;
+    config  = bench_config();
+    targets = resolve_file
+}
+
+criterion_main!(resolution_benches);
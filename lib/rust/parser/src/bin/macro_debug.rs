@@ -0,0 +1,127 @@
+//! Tool that shows how the macro resolver splits an Enso snippet into macro invocations, for
+//! debugging new macro definitions without writing tests.
+//!
+//! # Usage
+//!
+//! The tool reads the snippet from stdin, resolves it with the builtin macros and the macros
+//! defined in the files given as the arguments, see [`parser_new::macros::source`], and writes the
+//! resolved segment tree, the trace of the resolution and the diagnostics to stdout. The trace
+//! covers the top-level tokens of the snippet, as the lines of blocks are not traced.
+//!
+//! For example:
+//!
+//! ```console
+//! ~/git/enso/lib/rust/parser $ echo "unless a then b" | cargo run --bin macro_debug unless.enso
+//! ```
+
+// === Standard Linter Configuration ===
+#![deny(non_ascii_idents)]
+#![warn(unsafe_code)]
+// === Non-Standard Linter Configuration ===
+#![warn(missing_docs)]
+
+use parser_new::prelude::*;
+
+use parser_new::macros;
+use parser_new::macros::literal::Literal;
+use parser_new::macros::resolution::Block;
+use parser_new::macros::resolution::Match;
+use parser_new::macros::resolution::Segment;
+use parser_new::macros::visit;
+use parser_new::macros::visit::Visitor;
+use parser_new::macros::Resolver;
+use parser_new::prelude::lexer::token::Token;
+
+
+
+// ===============
+// === Printer ===
+// ===============
+
+/// Prints the resolved items as an indented tree, with the source text of the invocations and
+/// segments.
+#[derive(Debug)]
+struct Printer<'a> {
+    source: &'a str,
+    depth:  usize,
+}
+
+impl<'a> Printer<'a> {
+    /// Print the `line` at the current depth.
+    fn print(&self, line: impl Display) {
+        println!("{:indent$}{}", "", line, indent = 2 * self.depth);
+    }
+
+    /// Visit the children of a node with `walk`, one level deeper.
+    fn nested(&mut self, walk: impl FnOnce(&mut Self)) {
+        self.depth += 1;
+        walk(self);
+        self.depth -= 1;
+    }
+}
+
+impl<'a> Visitor for Printer<'a> {
+    fn visit_token(&mut self, token: &Token) {
+        match Literal::try_from(&token.shape) {
+            Ok(literal) => self.print(format!("Token `{}`", literal)),
+            Err(_) => self.print(format!("Token {:?}", token.shape)),
+        }
+    }
+
+    fn visit_match(&mut self, matched: &Match) {
+        let name = &matched.definition.name;
+        let incomplete = if matched.complete { "" } else { " (incomplete)" };
+        let text = &self.source[matched.span.range()];
+        self.print(format!("Macro `{}`{}: {:?}", name, incomplete, text));
+        self.nested(|printer| visit::walk_match(printer, matched));
+    }
+
+    fn visit_segment(&mut self, segment: &Segment) {
+        self.print(format!("Segment {:?}", &self.source[segment.span.range()]));
+        self.nested(|printer| visit::walk_segment(printer, segment));
+    }
+
+    fn visit_block(&mut self, block: &Block) {
+        self.print("Block");
+        self.nested(|printer| {
+            for (index, line) in block.lines.iter().enumerate() {
+                printer.print(format!("Line {}", index));
+                printer.nested(|printer| visit::walk_items(printer, line));
+            }
+        });
+    }
+}
+
+
+
+// ============
+// === main ===
+// ============
+
+fn main() {
+    use std::io::Read;
+
+    let mut resolver = Resolver::new(macros::builtin()).unwrap();
+    for path in std::env::args().skip(1) {
+        let definitions = std::fs::read_to_string(&path).unwrap();
+        if let Err(error) = resolver.define_macros_from_source(&definitions) {
+            eprintln!("{}: {}", path, error);
+            std::process::exit(1);
+        }
+    }
+    let mut source = String::new();
+    std::io::stdin().read_to_string(&mut source).unwrap();
+    let source = source.trim_end();
+    resolver.set_tracing(true);
+    let resolution = resolver.resolve_source(source);
+    println!("Resolution:");
+    Printer { source, depth: 1 }.visit_resolution(&resolution);
+    println!("Trace:");
+    for line in resolver.last_trace().unwrap_or_default().to_string().lines() {
+        println!("  {}", line);
+    }
+    println!("Diagnostics:");
+    for diagnostic in &resolution.diagnostics {
+        println!("  {}", diagnostic);
+    }
+}
@@ -12,6 +12,7 @@
 pub mod macros;
 pub mod operator;
 pub mod parser;
+pub mod span;
 
 pub use crate::parser::*;
 
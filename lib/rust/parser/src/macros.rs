@@ -1,27 +1,68 @@
 //! The macro system for the Enso parser.
 
-use crate::prelude::logger::*;
 use crate::prelude::*;
 
+use crate::macros::context::Context;
+use crate::macros::counters::Counters;
 use crate::macros::definition::Definition;
+use crate::macros::definition::Form;
+use crate::macros::diagnostic::Diagnostic;
+use crate::macros::hook::Hook;
+use crate::macros::hook::Notification;
+use crate::macros::incremental::Cache;
+use crate::macros::literal::Literal;
+use crate::macros::module::Exports;
+use crate::macros::module::Filter;
+use crate::macros::registry::Conflict;
 use crate::macros::registry::Registry;
+use crate::macros::registry::Tree;
+use crate::macros::resolution::Block;
+use crate::macros::resolution::Item;
+use crate::macros::resolution::Match;
+use crate::macros::resolution::Resolution;
+use crate::macros::resolution::Segment;
+use crate::macros::trace::EndReason;
+use crate::macros::trace::Event;
+use crate::macros::trace::Record;
+use crate::macros::trace::Trace;
+use crate::prelude::lexer::token;
+use crate::prelude::lexer::token::Token;
+use crate::span;
+use crate::span::Span;
 
 
 // ==============
 // === Export ===
 // ==============
 
+pub mod builder;
+pub mod builtin;
+pub mod context;
+pub mod counters;
 pub mod definition;
+pub mod diagnostic;
+pub mod documentation;
+pub mod hook;
+pub mod incremental;
 pub mod literal;
+pub mod module;
 pub mod registry;
+pub mod resolution;
+pub mod source;
+pub mod trace;
+pub mod visit;
 
+pub use builtin::builtin;
 
 
-// ====================
-// === Type Aliases ===
-// ====================
 
-type DebugLevel = crate::prelude::logger::entry::level::Debug;
+// =================
+// === Constants ===
+// =================
+
+/// The default limit of the nesting depth of the macro invocations and blocks, see
+/// [`Resolver::set_depth_limit`].
+pub const DEFAULT_DEPTH_LIMIT: usize = 256;
 
 
 
@@ -30,26 +71,1192 @@ type DebugLevel = crate::prelude::logger::entry::level::Debug;
 // ================
 
 /// The Enso macro resolver.
-#[derive(Clone, Debug, PartialEq)]
+///
+/// The work of the resolver can be observed by a [`Hook`], for example the [`hook::LoggerHook`]
+/// writing it to a logger.
+///
+/// The macros belonging to an experimental feature, see [`Definition::with_feature`], are defined
+/// only when the feature is enabled, see [`Self::enable_feature`]. Until then they are kept aside.
+#[derive(Clone, Derivative)]
+#[derivative(Debug, PartialEq)]
 #[allow(missing_docs)]
-pub struct Resolver<Logger> {
-    registry: Registry,
-    logger:   Logger,
+pub struct Resolver {
+    registry:      Registry,
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    hook:          Option<Rc<dyn Hook>>,
+    tracing:       bool,
+    last_trace:    RefCell<Option<Trace>>,
+    /// The counters of the work done by the last resolution.
+    last_counters: Cell<Counters>,
+    depth_limit:   usize,
+    features:      HashSet<String>,
+    /// The definitions of the features which are not enabled.
+    gated:         Vec<Definition>,
+}
+
+impl Resolver {
+    /// Constructor. Fails if the `macros` conflict with each other, see [`Conflict`].
+    /// The macros of the experimental features are kept aside, as no feature is enabled yet.
+    pub fn new(macros: Vec<Definition>) -> Result<Self, Conflict> {
+        let (macros, gated) = macros.into_iter().partition(|macro_| macro_.feature.is_none());
+        let registry = Registry::try_from(macros)?;
+        let hook = default();
+        let tracing = default();
+        let last_trace = default();
+        let last_counters = default();
+        let depth_limit = DEFAULT_DEPTH_LIMIT;
+        let features = default();
+        Ok(Self {
+            registry,
+            hook,
+            tracing,
+            last_trace,
+            last_counters,
+            depth_limit,
+            features,
+            gated,
+        })
+    }
+
+    /// Set the hook notified about the work of the resolver, replacing the previous one.
+    pub fn set_hook(&mut self, hook: impl Hook + 'static) {
+        self.hook = Some(Rc::new(hook));
+    }
+
+    /// Remove the hook notified about the work of the resolver.
+    pub fn remove_hook(&mut self) {
+        self.hook = None;
+    }
+
+    /// Notify the hook, if there is any.
+    fn notify(&self, notification: Notification) {
+        if let Some(hook) = &self.hook {
+            hook.notify(notification);
+        }
+    }
+
+    /// Define the macro described by `definition` in the macro resolver `self`. The macro is not
+    /// defined if it conflicts with the defined ones, see [`Conflict`]. The macro of a feature
+    /// which is not enabled is kept aside until the feature is enabled.
+    pub fn define_macro(&mut self, definition: Definition) -> Result<(), Conflict> {
+        self.define_macros(vec![definition])
+    }
+
+    /// Define all macros described by the `definitions` of the enabled features, and keep aside
+    /// the other ones. No macro is defined if any of them conflicts with the defined ones.
+    fn define_macros(&mut self, definitions: Vec<Definition>) -> Result<(), Conflict> {
+        let (definitions, gated): (Vec<_>, Vec<_>) = definitions
+            .into_iter()
+            .partition(|definition| definition.is_enabled_by(&self.features));
+        for definition in &definitions {
+            self.notify(Notification::DefineMacro(definition));
+        }
+        self.registry.merge(definitions)?;
+        self.gated.extend(gated);
+        Ok(())
+    }
+
+    /// Enable the experimental `feature`, defining its macros. The feature is not enabled if any
+    /// of its macros conflicts with the defined ones.
+    pub fn enable_feature(&mut self, feature: impl Str) -> Result<(), Conflict> {
+        let feature = feature.into();
+        let is_of_feature = |definition: &Definition| definition.feature.as_ref() == Some(&feature);
+        let definitions = self.gated.iter().filter(|d| is_of_feature(d)).cloned().collect_vec();
+        for definition in &definitions {
+            self.notify(Notification::DefineMacro(definition));
+        }
+        self.registry.merge(definitions)?;
+        self.gated.retain(|definition| !is_of_feature(definition));
+        self.features.insert(feature);
+        Ok(())
+    }
+
+    /// Check if the experimental `feature` is enabled.
+    pub fn is_feature_enabled(&self, feature: &str) -> bool {
+        self.features.contains(feature)
+    }
+
+    /// Define the macros described in the Enso `source` in the macro resolver `self`. See
+    /// [`source`] for the syntax of the definitions. No macro is defined if the source is invalid
+    /// or any of the macros conflicts with the defined ones.
+    pub fn define_macros_from_source(&mut self, source: &str) -> Result<(), source::Error> {
+        let definitions = source::definitions(source)?;
+        self.define_macros(definitions).map_err(source::Error::Conflict)
+    }
+
+    /// Define the macros of the `exports` of another module selected by the `filter`, under their
+    /// qualified names, see [`module`]. The macros imported before are kept, so the same macro can
+    /// be imported through many modules. No macro is defined if any of them conflicts with the
+    /// defined ones.
+    pub fn import_macros(&mut self, exports: &Exports, filter: &Filter) -> Result<(), Conflict> {
+        let definitions = exports.select(filter).cloned().collect_vec();
+        self.define_macros(definitions)
+    }
+
+    /// Remove the macro named `name` from the macro resolver `self`, returning its definition. The
+    /// macros of the features which are not enabled are removed as well.
+    pub fn remove_macro(&mut self, name: &str) -> Option<Definition> {
+        self.notify(Notification::RemoveMacro(name));
+        let gated = self.gated.iter().position(|definition| definition.name == name);
+        let gated = gated.map(|index| self.gated.remove(index));
+        self.registry.remove(name).or(gated)
+    }
+
+    /// Open a new scope of macro definitions. See [`Registry::push_scope`].
+    pub fn push_scope(&mut self) {
+        self.registry.push_scope()
+    }
+
+    /// Close the most recently opened scope of macro definitions. See [`Registry::pop_scope`].
+    pub fn pop_scope(&mut self) {
+        self.registry.pop_scope()
+    }
+
+    /// Run `f` with the macro described by `definition` defined in a new scope, shadowing the
+    /// macro with the same sections. Fails without running `f` if the macro conflicts with the
+    /// defined ones.
+    pub fn with_macro<T>(
+        &mut self,
+        definition: Definition,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> Result<T, Conflict> {
+        self.push_scope();
+        let result = self.define_macro(definition).map(|()| f(self));
+        self.pop_scope();
+        result
+    }
+
+    /// Resolve the macro invocations in the `tokens`.
+    ///
+    /// A token matching a literal which heads a section of a macro starts a new invocation, unless
+    /// it continues one of the invocations already started. The invocations are matched greedily,
+    /// and an invocation nested in a segment of another one ends when the outer one continues, so
+    /// the following tokens belong to the next segment of the outer invocation. An invocation can
+    /// be ended this way only if it already matches a full definition. It can also be ended by an
+    /// operator binding looser than the matched macro, see [`Definition`], or by a closing section,
+    /// see [`definition::Section`].
+    ///
+    /// The invocations not matching a full definition by the end of the stream are recovered from,
+    /// see [`Match::complete`], and reported in the diagnostics of the resolution.
+    ///
+    /// The lines of the blocks in the stream are resolved independently, with the macros defined in
+    /// a block visible only in it, see [`Block`]. The invocations and blocks can be nested up to a
+    /// limited depth, see [`Self::set_depth_limit`].
+    ///
+    /// The spans of the results assume that every character of the source takes a single byte. Use
+    /// [`Self::resolve_source`] to get the exact spans of any source.
+    ///
+    /// The tokens are resolved as an [expression](Context::Expression), see [`Self::resolve_in`].
+    pub fn resolve(&self, tokens: &[Token]) -> Resolution {
+        self.resolve_in(tokens, default())
+    }
+
+    /// Resolve the macro invocations in the `tokens` of the `context`, like [`Self::resolve`]. Only
+    /// the macros active in the context of a body are matched in it, see [`Context`].
+    pub fn resolve_in(&self, tokens: &[Token], context: Context) -> Resolution {
+        self.resolve_at(tokens, default(), context)
+    }
+
+    /// Lex the `source` and resolve the macro invocations in its top-level tokens. See
+    /// [`Self::resolve`]. The spans of the results are the byte offsets in the `source`. The
+    /// source is resolved as a module, in the [top-level context](Context::TopLevel).
+    pub fn resolve_source(&self, source: &str) -> Resolution {
+        let tokens: Vec<Token> = ::lexer::run(source).tokens.into();
+        let byte_offsets = span::byte_offsets(source);
+        let byte_offsets = Some(&byte_offsets[..]);
+        self.resolve_at(&tokens, Positions { byte_offsets, ..default() }, Context::TopLevel)
+    }
+
+    /// Resolve the macro invocations in each of the `lines` independently, like [`Self::resolve`].
+    /// The resolutions of the lines found in the `cache` are reused, so after an edit only the
+    /// changed lines are resolved again. The spans of the results are relative to the starts of
+    /// the lines, so the resolution of a line does not depend on its position in the source.
+    ///
+    /// The reused resolutions are not traced, see [`Self::set_tracing`].
+    pub fn resolve_lines(&self, lines: &[&[Token]], cache: &mut Cache) -> Vec<Resolution> {
+        let resolutions = cache.resolve_all(&self.registry, lines, |line| self.resolve(line));
+        let (resolved, count) = (cache.last_misses(), lines.len());
+        self.notify(Notification::ResolvedLines { resolved, count });
+        resolutions
+    }
+
+    fn resolve_at(&self, tokens: &[Token], positions: Positions, context: Context) -> Resolution {
+        let nesting = Nesting { depth: 0, limit: self.depth_limit };
+        let matcher =
+            Matcher::new(&self.registry, tokens, positions, context, nesting, self.tracing);
+        let (resolution, trace, counters) = matcher.run();
+        self.notify(Notification::Resolved { resolution: &resolution, trace: trace.as_ref() });
+        *self.last_trace.borrow_mut() = trace;
+        self.last_counters.set(counters);
+        resolution
+    }
+
+    /// Enable or disable the tracing of the resolution. When enabled, every matching decision of
+    /// [`Self::resolve`] is recorded in a [`Trace`].
+    pub fn set_tracing(&mut self, enabled: bool) {
+        self.tracing = enabled;
+    }
+
+    /// The trace of the last resolution, if the tracing was enabled for it.
+    pub fn last_trace(&self) -> Option<Trace> {
+        self.last_trace.borrow().clone()
+    }
+
+    /// The counters of the work done by the last resolution, including the lines of its blocks.
+    /// Unlike the timings, they do not depend on the machine, so they are suitable for catching the
+    /// performance regressions of the resolver in tests.
+    pub fn last_counters(&self) -> Counters {
+        self.last_counters.get()
+    }
+
+    /// Set the limit of the nesting depth of the macro invocations and blocks, counting both. The
+    /// invocations and blocks nested deeper are left unresolved and reported in the diagnostics,
+    /// so the pathological inputs cannot exhaust the stack. See [`DEFAULT_DEPTH_LIMIT`].
+    pub fn set_depth_limit(&mut self, limit: usize) {
+        self.depth_limit = limit;
+    }
+}
+
+
+
+// ===============
+// === Matcher ===
+// ===============
+
+/// The state of a single resolution of a token stream.
+#[derive(Debug)]
+struct Matcher<'a> {
+    registry:    &'a Registry,
+    tokens:      &'a [Token],
+    positions:   Positions<'a>,
+    /// The spans of the tokens, in characters.
+    char_spans:  Vec<Span>,
+    /// The spans of the tokens.
+    spans:       Vec<Span>,
+    /// The invocations in the process of resolution, each nested in the last segment of the
+    /// previous one.
+    stack:       Vec<Frame<'a>>,
+    /// The resolved items of the top level.
+    items:       Vec<Item>,
+    /// The context of the top-level body.
+    context:     Context,
+    nesting:     Nesting,
+    diagnostics: Vec<Diagnostic>,
+    trace:       Option<Trace>,
+    counters:    Counters,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(
+        registry: &'a Registry,
+        tokens: &'a [Token],
+        positions: Positions<'a>,
+        context: Context,
+        nesting: Nesting,
+        tracing: bool,
+    ) -> Self {
+        let mut char_spans = span::of_tokens_at(positions.start, tokens);
+        let is_block = |token: &Token| matches!(token.shape, token::Shape::Block { .. });
+        let block_spans = char_spans.iter_mut().zip(tokens).filter(|(_, token)| is_block(token));
+        for (span, _) in block_spans {
+            let line_ending = positions.line_ending;
+            *span = Span::new(span.start + line_ending, span.end + line_ending);
+        }
+        let spans = char_spans.iter().map(|span| positions.to_bytes(*span)).collect();
+        let stack = default();
+        let items = default();
+        let diagnostics = default();
+        let trace = tracing.as_some_from(default);
+        let counters = default();
+        Self {
+            registry,
+            tokens,
+            positions,
+            char_spans,
+            spans,
+            stack,
+            items,
+            context,
+            nesting,
+            diagnostics,
+            trace,
+            counters,
+        }
+    }
+
+    fn run(mut self) -> (Resolution, Option<Trace>, Counters) {
+        for (index, token) in self.tokens.iter().enumerate() {
+            self.step(index, token);
+        }
+        while !self.stack.is_empty() {
+            self.end_frame(self.tokens.len(), EndReason::EndOfStream);
+        }
+        let items = self.items;
+        let diagnostics = self.diagnostics;
+        (Resolution { items, diagnostics }, self.trace, self.counters)
+    }
+
+    /// Resolve the `token` at the `index`.
+    fn step(&mut self, index: usize, token: &Token) {
+        self.counters.tokens_consumed += 1;
+        if let token::Shape::Block { indent, lines, .. } = &token.shape {
+            if self.depth() >= self.nesting.limit {
+                self.report_nesting_limit(index);
+                self.current_body().0.push(Item::Token(token.clone()));
+                return;
+            }
+            let block = self.resolve_block(index, *indent, lines);
+            self.current_body().0.push(Item::Block(block));
+            return;
+        }
+        if let Ok(literal) = Literal::try_from(&token.shape) {
+            if let Some(depth) = self.continued_frame(&literal) {
+                while self.stack.len() > depth + 1 {
+                    self.end_frame(index, EndReason::Continuation);
+                }
+                self.record(depth + 1, Event::Continue { head: literal.clone(), index });
+                let frame = self.stack.last_mut().expect("Missing continued frame.");
+                if let Some((head, node)) = frame.continuation(&literal) {
+                    frame.node = node;
+                    frame.head = head;
+                }
+                frame.body_context = frame.context;
+                frame.segments.push(Segment::new(token.clone(), self.spans[index]));
+                frame.head_indices.push(index);
+                frame.body_start = index + 1;
+                self.end_closed_frame(index);
+                return;
+            }
+            let context = *self.current_context();
+            self.counters.definitions_probed += 1;
+            let started = self.registry.root().branches.get_key_value(&literal);
+            let started = started.filter(|(_, node)| has_definitions_in(node, context));
+            if started.is_some() && self.depth() >= self.nesting.limit {
+                self.report_nesting_limit(index);
+            } else if let Some((head, node)) = started {
+                let (body, body_start) = self.current_body();
+                let (start, prefix) = match Self::infix_definition(node, context) {
+                    Some(definition) => {
+                        let split = Self::prefix_start(body, definition);
+                        let skipped = body[..split].iter().map(Item::token_count).sum::<usize>();
+                        (body_start + skipped, body.split_off(split))
+                    }
+                    None => (index, default()),
+                };
+                self.record(self.stack.len(), Event::Start { head: literal, index, start });
+                let segments = vec![Segment::new(token.clone(), self.spans[index])];
+                let head_indices = vec![index];
+                let body_start = index + 1;
+                let body_context = context;
+                let frame = Frame {
+                    node,
+                    head,
+                    context,
+                    body_context,
+                    start,
+                    prefix,
+                    segments,
+                    head_indices,
+                    body_start,
+                };
+                self.stack.push(frame);
+                self.end_closed_frame(index);
+                return;
+            }
+        }
+        if let token::Shape::Operator(operator) = &token.shape {
+            while self.is_top_frame_ended_by(operator) {
+                self.end_frame(index, EndReason::Operator);
+            }
+            let context = self.current_context();
+            *context = context.following(operator);
+        }
+        self.current_body().0.push(Item::Token(token.clone()));
+    }
+
+    /// Resolve the `lines` of the block token at the `index`, each in its own matcher. The macros
+    /// defined in the block are inserted into a new scope of the registry, so they are not visible
+    /// outside of the block. The documentation comments are attached to the definitions and the
+    /// invocations starting the lines they precede. The lines start in the context of the body
+    /// containing the block. The lines of the block are not traced.
+    fn resolve_block(&mut self, index: usize, indent: usize, lines: &[Token]) -> Block {
+        let context = *self.current_context();
+        let mut scope: Option<Registry> = None;
+        let mut position = self.char_spans[index].start;
+        let mut resolved = Vec::new();
+        let mut documentation = None;
+        for (line_index, line) in lines.iter().enumerate() {
+            let (tokens, line_ending) = match &line.shape {
+                token::Shape::Line { tokens, trailing_line_ending } =>
+                    (tokens, trailing_line_ending),
+                _ => {
+                    documentation = None;
+                    position += line.source_length();
+                    continue;
+                }
+            };
+            let start = position + indent + line.offset;
+            position += indent + line.source_length();
+            let line_documentation =
+                mem::replace(&mut documentation, documentation::of_line(tokens));
+            let definition = source::definition(line_index, tokens).and_then(|definition| {
+                let insert = |mut definition: Definition| {
+                    definition.documentation = line_documentation.clone();
+                    let registry = scope.get_or_insert_with(|| {
+                        let mut registry = self.registry.clone();
+                        registry.push_scope();
+                        registry
+                    });
+                    registry.insert(definition).map_err(source::Error::Conflict)
+                };
+                definition.map(insert).transpose()
+            });
+            let is_definition = !matches!(definition, Ok(None));
+            if let Err(error) = definition {
+                let kind = diagnostic::Kind::InvalidDefinition { error };
+                let tokens = 0..tokens.len();
+                let span = self.positions.to_bytes(Span::new(start, position - line_ending.size()));
+                self.diagnostics.push(Diagnostic { kind, tokens, span });
+            }
+            if is_definition {
+                resolved.push(tokens.iter().cloned().map(Item::Token).collect());
+                continue;
+            }
+            let registry = scope.as_ref().unwrap_or(self.registry);
+            let line_ending = line_ending.size();
+            let positions = Positions { start, line_ending, ..self.positions };
+            let nesting = Nesting { depth: self.depth() + 1, ..self.nesting };
+            let matcher = Matcher::new(registry, tokens, positions, context, nesting, false);
+            let (mut resolution, _, counters) = matcher.run();
+            self.counters += counters;
+            if let Some(Item::Macro(matched)) = resolution.items.first_mut() {
+                matched.documentation = line_documentation;
+            }
+            self.diagnostics.extend(resolution.diagnostics);
+            resolved.push(resolution.items);
+        }
+        let token = self.tokens[index].clone();
+        Block { token, lines: resolved, span: self.spans[index] }
+    }
+
+    /// The depth of the frame on the stack continued by the `literal`, either with the next section
+    /// or with a repetition of the last one. Only the frames matching a full definition can be
+    /// ended by a continuation of a frame below.
+    fn continued_frame(&mut self, literal: &Literal) -> Option<usize> {
+        for (depth, frame) in self.stack.iter().enumerate().rev() {
+            self.counters.definitions_probed += 1;
+            if frame.continuation(literal).is_some() || frame.repeats(literal) {
+                return Some(depth);
+            }
+            frame.definition()?;
+        }
+        None
+    }
+
+    /// The definition deciding the items consumed by an invocation starting at the `node` in the
+    /// `context`. As the matched definition is not known until the invocation ends, it is the first
+    /// definition with this head of the [infix form](Form::Infix) active in the context, if there
+    /// is any.
+    fn infix_definition(node: &Tree, context: Context) -> Option<&Definition> {
+        let mut definitions = node.iter().flat_map(|(_, definitions)| definitions);
+        definitions
+            .find(|definition| definition.form == Form::Infix && definition.is_active_in(context))
+    }
+
+    /// The index of the first item of the `body` consumed by an infix invocation of the
+    /// `definition`. The items up to the last operator binding looser than the macro are left out.
+    fn prefix_start(body: &[Item], definition: &Definition) -> usize {
+        let is_bound = |item: &Item| match item {
+            Item::Token(Token { shape: token::Shape::Operator(operator), .. }) =>
+                definition.is_bounded_by(operator),
+            _ => false,
+        };
+        body.iter().rposition(is_bound).map_or(0, |index| index + 1)
+    }
+
+    /// Check if the `operator` ends the last section of the top frame.
+    fn is_top_frame_ended_by(&self, operator: &str) -> bool {
+        let definition = self.stack.last().and_then(|frame| frame.definition());
+        definition.map_or(false, |definition| definition.is_ended_by(operator))
+    }
+
+    /// The body that the next item belongs to, together with the index of its first token.
+    fn current_body(&mut self) -> (&mut Vec<Item>, usize) {
+        match self.stack.last_mut() {
+            Some(frame) => {
+                let segment = frame.segments.last_mut().expect("Frame without segments.");
+                (&mut segment.body, frame.body_start)
+            }
+            None => (&mut self.items, 0),
+        }
+    }
+
+    /// The context of the body that the next item belongs to.
+    fn current_context(&mut self) -> &mut Context {
+        match self.stack.last_mut() {
+            Some(frame) => &mut frame.body_context,
+            None => &mut self.context,
+        }
+    }
+
+    /// End the top frame if its last section, headed by the token at the `index`, is closing and
+    /// the frame cannot be continued.
+    fn end_closed_frame(&mut self, index: usize) {
+        let top = self.stack.last();
+        let is_leaf = |frame: &&Frame| frame.continuations().next().is_none();
+        let closed = top.filter(is_leaf).and_then(|frame| frame.definition());
+        let closed = closed.and_then(|definition| definition.sections.last());
+        if closed.map_or(false, |section| section.is_closing()) {
+            self.end_frame(index + 1, EndReason::ClosingSection);
+        }
+    }
+
+    /// Pop the top frame and append the resolved invocation to the body it is nested in. The
+    /// invocation ends before the token at the `end` index. An incomplete invocation is recovered
+    /// from and reported.
+    fn end_frame(&mut self, end: usize, reason: EndReason) {
+        let frame = self.stack.pop().expect("Missing frame.");
+        let tokens = frame.start..end;
+        let span = self.spans[tokens.start].join(self.spans[end - 1]);
+        let depth = self.stack.len();
+        let body_missing = frame.is_body_missing();
+        let complete = frame.definition().is_some() && !body_missing;
+        let definition = frame.definition().unwrap_or_else(|| frame.recovery_definition()).clone();
+        if !complete {
+            let name = definition.name.clone();
+            let continuations = frame.continuations().map(|(head, _)| head.clone());
+            let mut expected = if body_missing { default() } else { continuations.collect_vec() };
+            expected.sort();
+            let kind = diagnostic::Kind::IncompleteMacro { name, expected };
+            self.diagnostics.push(Diagnostic { kind, tokens: tokens.clone(), span });
+            self.record(depth, Event::Incomplete { tokens: tokens.clone() });
+        }
+        self.counters.invocations += 1;
+        if !complete || reason == EndReason::Continuation {
+            self.counters.backtracks += 1;
+        }
+        let name = definition.name.clone();
+        self.record(depth, Event::End { definition: name, tokens, reason });
+        let Frame { prefix, mut segments, head_indices, .. } = frame;
+        let segment_ends = head_indices.iter().skip(1).copied().chain(iter::once(end));
+        for (segment, segment_end) in segments.iter_mut().zip(segment_ends) {
+            segment.span = segment.span.join(self.spans[segment_end - 1]);
+        }
+        let documentation = default();
+        let item = Match { definition, prefix, segments, complete, span, documentation };
+        let item = Item::Macro(item);
+        self.current_body().0.push(item);
+    }
+
+    /// The nesting depth of the next item.
+    fn depth(&self) -> usize {
+        self.nesting.depth + self.stack.len()
+    }
+
+    /// Report the invocation or the block started by the token at the `index`, which is left
+    /// unresolved as nested too deep.
+    fn report_nesting_limit(&mut self, index: usize) {
+        let kind = diagnostic::Kind::NestingLimit { limit: self.nesting.limit };
+        let span = self.spans[index];
+        self.diagnostics.push(Diagnostic { kind, tokens: index..index + 1, span });
+    }
+
+    /// Record the `event` in the trace, if the tracing is enabled.
+    fn record(&mut self, depth: usize, event: Event) {
+        if let Some(trace) = &mut self.trace {
+            trace.records.push(Record { depth, event });
+        }
+    }
+}
+
+
+
+// ===============
+// === Nesting ===
+// ===============
+
+/// The nesting depth of a stream, counting the macro invocations and blocks enclosing it, together
+/// with its limit, see [`Resolver::set_depth_limit`].
+#[derive(Clone, Copy, Debug)]
+struct Nesting {
+    depth: usize,
+    limit: usize,
 }
 
-impl<Logger> Resolver<Logger>
-where Logger: AnyLogger<Owned = Logger> + LoggerOps<DebugLevel>
-{
-    /// Constructor.
-    pub fn new(macros: Vec<Definition>, parent_logger: &Logger) -> Self {
-        let logger = <Logger>::sub(parent_logger, "Resolver");
-        let registry = Registry::from(macros);
-        Self { registry, logger }
+
+
+// =================
+// === Positions ===
+// =================
+
+/// The positions of the tokens of a stream in the source.
+#[derive(Clone, Copy, Debug, Default)]
+struct Positions<'a> {
+    /// The character of the source the stream starts at.
+    start:        usize,
+    /// The length of the line ending preceding the blocks of the stream, in characters. It is not
+    /// included in the lengths of the tokens, so the blocks start after it.
+    line_ending:  usize,
+    /// The byte offsets of the characters of the source, if the spans are to be in bytes. See
+    /// [`span::byte_offsets`].
+    byte_offsets: Option<&'a [usize]>,
+}
+
+impl<'a> Positions<'a> {
+    /// Convert the `span` in characters to the span of the results.
+    fn to_bytes(self, span: Span) -> Span {
+        match self.byte_offsets {
+            Some(byte_offsets) => span::to_bytes(span, byte_offsets),
+            None => span,
+        }
+    }
+}
+
+
+
+// =============
+// === Frame ===
+// =============
+
+/// A macro invocation in the process of resolution.
+#[derive(Debug)]
+struct Frame<'a> {
+    /// The registry node of the path of the matched sections.
+    node:         &'a Tree,
+    /// The literal heading the last matched section.
+    head:         &'a Literal,
+    /// The context the invocation is in. Only the definitions active in it are matched.
+    context:      Context,
+    /// The context of the body of the last segment.
+    body_context: Context,
+    /// The index of the first token of the invocation.
+    start:        usize,
+    prefix:       Vec<Item>,
+    segments:     Vec<Segment>,
+    /// The indices of the tokens heading the segments.
+    head_indices: Vec<usize>,
+    /// The index of the first token of the body of the last segment.
+    body_start:   usize,
+}
+
+impl<'a> Frame<'a> {
+    /// The definition matched by the sections so far.
+    fn definition(&self) -> Option<&'a Definition> {
+        self.node.value.iter().find(|definition| definition.is_active_in(self.context))
+    }
+
+    /// Check if the last segment has an empty body while it requires one, like the `then` of
+    /// `if a then`. Only the closing sections, like the `]` of a list, have no bodies.
+    fn is_body_missing(&self) -> bool {
+        let body_empty = self.segments.last().map_or(false, |segment| segment.body.is_empty());
+        let sections = self.definitions().flat_map(|definition| &definition.sections);
+        let mut sections = sections.filter(|section| section.start_symbol() == self.head);
+        body_empty && !sections.any(|section| section.is_closing())
+    }
+
+    /// The definitions the invocation could be continued to.
+    fn definitions(&self) -> impl Iterator<Item = &'a Definition> {
+        let context = self.context;
+        let definitions = self.node.iter().flat_map(|(_, definitions)| definitions);
+        definitions.filter(move |definition| definition.is_active_in(context))
+    }
+
+    /// The registry node of the section headed by the `literal` following the matched ones,
+    /// together with the literal, if there is such a section.
+    fn continuation(&self, literal: &Literal) -> Option<(&'a Literal, &'a Tree)> {
+        let continuation = self.node.branches.get_key_value(literal);
+        continuation.filter(|(_, node)| has_definitions_in(node, self.context))
+    }
+
+    /// The registry nodes of the sections following the matched ones, together with their heads.
+    fn continuations(&self) -> impl Iterator<Item = (&'a Literal, &'a Tree)> {
+        let context = self.context;
+        let continuations = self.node.branches.iter();
+        continuations.filter(move |(_, node)| has_definitions_in(node, context))
+    }
+
+    /// Check if the `literal` repeats the last matched section of any definition the invocation
+    /// could be continued to.
+    fn repeats(&self, literal: &Literal) -> bool {
+        self.head == literal && self.definitions().any(|definition| definition.repeats(literal))
+    }
+
+    /// The definition to recover an incomplete invocation as, the one with the fewest sections
+    /// among the definitions the invocation could be continued to.
+    fn recovery_definition(&self) -> &'a Definition {
+        let definitions = self.definitions();
+        let definition = definitions.min_by_key(|def| (def.sections.len(), def.name.clone()));
+        definition.expect("Registry node without definitions.")
+    }
+}
+
+
+
+/// Check if the `node` or any node below it has a definition active in the `context`.
+fn has_definitions_in(node: &Tree, context: Context) -> bool {
+    let mut definitions = node.iter().flat_map(|(_, definitions)| definitions);
+    definitions.any(|definition| definition.is_active_in(context))
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::macro_definition;
+    use crate::operator::associativity::Associativity;
+    use crate::operator::precedence;
+    use crate::prelude::logger::WarningLogger;
+
+    fn resolver(definitions: Vec<Definition>) -> Resolver {
+        Resolver::new(definitions).unwrap()
+    }
+
+    fn var(name: &str) -> Token {
+        Token::variable(name, 1)
+    }
+
+    fn tokens(names: &str) -> Vec<Token> {
+        names.split(' ').map(var).collect()
+    }
+
+    fn token_items(names: &str) -> Vec<Item> {
+        tokens(names).into_iter().map(Item::Token).collect()
+    }
+
+    /// The invocation the `items` consist of. Used by the tests of the submodules as well.
+    pub(crate) fn single_macro(items: &[Item]) -> &Match {
+        match items {
+            [Item::Macro(matched)] => matched,
+            items => panic!("Expected a single macro, got {:?}.", items),
+        }
+    }
+
+    /// The block the `items` consist of.
+    pub(crate) fn single_block(items: &[Item]) -> &Block {
+        match items {
+            [Item::Block(block)] => block,
+            items => panic!("Expected a single block, got {:?}.", items),
+        }
+    }
+
+    fn if_then_else() -> Vec<Definition> {
+        vec![macro_definition!(if_then: if then), macro_definition!(if_then_else: if then else)]
+    }
+
+    #[test]
+    fn resolve_segments() {
+        let resolver = resolver(if_then_else());
+        let resolution = resolver.resolve(&tokens("a if b c then d else e"));
+        assert!(resolution.is_complete());
+        assert_eq!(resolution.items.len(), 2);
+        assert_eq!(resolution.items[0], Item::Token(var("a")));
+        let matched = match &resolution.items[1] {
+            Item::Macro(matched) => matched,
+            item => panic!("Expected a macro, got {:?}.", item),
+        };
+        assert_eq!(matched.definition.name, "if_then_else");
+        assert!(matched.prefix.is_empty());
+        assert_eq!(matched.heads().cloned().collect_vec(), tokens("if then else"));
+        let bodies = matched.segments.iter().map(|segment| segment.body.clone()).collect_vec();
+        assert_eq!(bodies, vec![token_items("b c"), token_items("d"), token_items("e")]);
+    }
+
+    #[test]
+    fn resolve_nested() {
+        let resolver = resolver(if_then_else());
+        let resolution = resolver.resolve(&tokens("if if a then b then c else d"));
+        let outer = single_macro(&resolution.items);
+        assert_eq!(outer.definition.name, "if_then_else");
+        let inner = single_macro(&outer.segments[0].body);
+        assert_eq!(inner.definition.name, "if_then");
+        assert_eq!(inner.segments[1].body, token_items("b"));
+        assert_eq!(outer.segments[1].body, token_items("c"));
+        assert_eq!(outer.segments[2].body, token_items("d"));
+    }
+
+    #[test]
+    fn innermost_invocation_is_continued() {
+        let resolver = resolver(if_then_else());
+        let resolution = resolver.resolve(&tokens("if a then if b then c else d"));
+        let outer = single_macro(&resolution.items);
+        assert_eq!(outer.definition.name, "if_then");
+        let inner = single_macro(&outer.segments[1].body);
+        assert_eq!(inner.definition.name, "if_then_else");
+        assert_eq!(inner.segments[2].body, token_items("d"));
+    }
+
+    #[test]
+    fn optional_and_repeated_sections() {
+        let if_then_else = macro_definition!(if_then_else: if then else?);
+        let list = macro_definition!(list: "[" ","* "]"!);
+        let resolver = resolver(vec![if_then_else, list]);
+        for (source, segment_count) in [("if a then b", 2), ("if a then b else c", 3)] {
+            let resolution = resolver.resolve(&tokens(source));
+            assert!(resolution.is_complete());
+            let matched = single_macro(&resolution.items);
+            assert_eq!(matched.definition.name, "if_then_else");
+            assert_eq!(matched.segments.len(), segment_count);
+        }
+        let operator = |name: &str| Token::operator(name, 1);
+        let stream = vec![operator("["), var("a"), operator(","), var("b"), operator(",")];
+        let stream = [&stream[..], &[var("c"), operator("]"), var("d")]].concat();
+        let resolution = resolver.resolve(&stream);
+        assert!(resolution.is_complete());
+        let matched = match &resolution.items[..] {
+            [Item::Macro(matched), Item::Token(_)] => matched,
+            items => panic!("Expected a macro and a token, got {:?}.", items),
+        };
+        let bodies = matched.segments.iter().map(|segment| segment.body.clone()).collect_vec();
+        let expected = vec![token_items("a"), token_items("b"), token_items("c"), vec![]];
+        assert_eq!(bodies, expected);
+        let resolution = resolver.resolve(&[operator("["), operator("]")]);
+        assert!(resolution.is_complete());
+    }
+
+    #[test]
+    fn resolve_infix() {
+        let resolver = resolver(vec![macro_definition!(lambda: _ "->")]);
+        let stream = vec![var("a"), Token::operator("->", 1), var("b"), Token::operator("->", 1)];
+        let resolution = resolver.resolve(&[&stream[..], &tokens("c")].concat());
+        let outer = single_macro(&resolution.items);
+        assert_eq!(outer.prefix, token_items("a"));
+        let inner = single_macro(&outer.segments[0].body);
+        assert_eq!(inner.prefix, token_items("b"));
+        assert_eq!(inner.segments[0].body, token_items("c"));
+    }
+
+    #[test]
+    fn operators_end_macros_binding_tighter() {
+        let mut resolver = resolver(if_then_else());
+        let stream = [&tokens("if a then b else c")[..], &[Token::operator("+", 1), var("d")]];
+        let stream = stream.concat();
+        let items = resolver.resolve(&stream).items;
+        let matched = single_macro(&items);
+        assert_eq!(matched.segments[2].body.len(), 3);
+        let definition = resolver.remove_macro("if_then_else").unwrap();
+        let associativity = Associativity::Left;
+        let definition = definition.with_precedence(precedence::of("+"), associativity);
+        resolver.define_macro(definition).unwrap();
+        let items = resolver.resolve(&stream).items;
+        let matched = match &items[..] {
+            [Item::Macro(matched), Item::Token(plus), Item::Token(d)] => {
+                assert_eq!(plus, &Token::operator("+", 1));
+                assert_eq!(d, &var("d"));
+                matched
+            }
+            items => panic!("Expected a macro followed by tokens, got {:?}.", items),
+        };
+        assert_eq!(matched.segments[2].body, token_items("c"));
+    }
+
+    #[test]
+    fn operators_bound_infix_macros() {
+        let arrow = macro_definition!(lambda: _ "->")
+            .with_precedence(precedence::of("->"), Associativity::of("->"));
+        let resolver = resolver(vec![arrow]);
+        let operator = |name: &str| Token::operator(name, 1);
+        let stream =
+            vec![var("x"), operator("="), var("a"), operator("->"), var("b"), operator("+")];
+        let stream = [&stream[..], &[var("c"), operator("="), var("d")]].concat();
+        let resolution = resolver.resolve(&stream);
+        let matched = match &resolution.items[..] {
+            [Item::Token(_), Item::Token(_), Item::Macro(matched), Item::Token(_), Item::Token(_)] =>
+                matched,
+            items => panic!("Expected a macro between tokens, got {:?}.", items),
+        };
+        assert_eq!(matched.prefix, token_items("a"));
+        assert_eq!(matched.segments[0].body.len(), 3);
+    }
+
+    #[test]
+    fn recover_incomplete() {
+        let mut definitions = if_then_else();
+        definitions.retain(|definition| definition.name == "if_then_else");
+        let resolver = resolver(definitions);
+        let resolution = resolver.resolve(&tokens("a if b then c"));
+        let matched = match &resolution.items[..] {
+            [Item::Token(_), Item::Macro(matched)] => matched,
+            items => panic!("Expected a token and a macro, got {:?}.", items),
+        };
+        assert!(!matched.complete);
+        assert_eq!(matched.definition.name, "if_then_else");
+        assert_eq!(matched.segments.len(), 2);
+        assert_eq!(matched.segments[1].body, token_items("c"));
+        let expected = vec![Literal::variable("else")];
+        let kind = diagnostic::Kind::IncompleteMacro { name: "if_then_else".into(), expected };
+        let span = Span::new(3, 14);
+        assert_eq!(resolution.diagnostics, vec![Diagnostic { kind, tokens: 1..5, span }]);
+        let resolution = resolver.resolve(&tokens("a then b"));
+        assert!(resolution.is_complete());
+        assert_eq!(resolution.items, token_items("a then b"));
+    }
+
+    #[test]
+    fn resolve_macros_from_source() {
+        let mut resolver = resolver(default());
+        resolver.define_macros_from_source("macro lambda _ ->").unwrap();
+        let stream = vec![var("a"), Token::operator("->", 1), var("b")];
+        let items = resolver.resolve(&stream).items;
+        assert!(
+            matches!(&items[..], [Item::Macro(matched)] if matched.definition.name == "lambda")
+        );
+        assert!(resolver.define_macros_from_source("macro").is_err());
+        let conflicting = resolver.define_macros_from_source("macro arrow _ ->\nmacro if_then if");
+        assert!(matches!(conflicting, Err(source::Error::Conflict(_))));
+        assert_eq!(resolver.registry.iter().count(), 1);
+    }
+
+    #[test]
+    fn source_spans() {
+        let resolver = resolver(if_then_else());
+        let source = "if ą then (b)  else c";
+        let resolution = resolver.resolve_source(source);
+        let matched = single_macro(&resolution.items);
+        assert_eq!(&source[matched.span.range()], source);
+        let segments = matched.segments.iter().map(|segment| &source[segment.span.range()]);
+        assert_eq!(segments.collect_vec(), vec!["if ą", "then (b)", "else c"]);
+    }
+
+    #[test]
+    fn resolve_lines_incrementally() {
+        let mut resolver = resolver(if_then_else());
+        let mut cache = Cache::new();
+        let lines = [tokens("if a then b"), tokens("c d"), tokens("if e then f else g")];
+        let lines = lines.iter().map(|line| &line[..]).collect_vec();
+        let resolutions = resolver.resolve_lines(&lines, &mut cache);
+        assert_eq!(cache.last_misses(), 3);
+        assert_eq!(resolutions[2], resolver.resolve(lines[2]));
+        let edited = tokens("c e");
+        let edited_lines = [lines[2], &edited, lines[0]];
+        let edited_resolutions = resolver.resolve_lines(&edited_lines, &mut cache);
+        assert_eq!(cache.last_misses(), 1);
+        assert_eq!(cache.len(), 3);
+        assert_eq!(edited_resolutions[0], resolutions[2]);
+        assert_eq!(edited_resolutions[1].items, token_items("c e"));
+        resolver.remove_macro("if_then_else");
+        let resolutions = resolver.resolve_lines(&edited_lines, &mut cache);
+        assert_eq!(cache.last_misses(), 3);
+        assert_eq!(resolutions[0], resolver.resolve(edited_lines[0]));
+    }
+
+    #[test]
+    fn block_scoped_definitions() {
+        let resolver = resolver(if_then_else());
+        let source = "a =\n    macro unless unless then\n\n    unless b then c\nunless d then e\n";
+        let resolution = resolver.resolve_source(source);
+        assert!(resolution.is_complete());
+        let lines = &single_block(&resolution.items).lines;
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].iter().all(|item| matches!(item, Item::Token(_))));
+        let nested = match &lines[0][..] {
+            [Item::Token(_), Item::Token(_), Item::Block(nested)] => nested,
+            items => panic!("Expected a block after tokens, got {:?}.", items),
+        };
+        let nested_source = "    macro unless unless then\n\n    unless b then c\n";
+        assert_eq!(&source[nested.span.range()], nested_source);
+        let nested = &nested.lines;
+        assert_eq!(nested.len(), 2);
+        let matched = single_macro(&nested[1]);
+        assert_eq!(matched.definition.name, "unless");
+        assert_eq!(&source[matched.span.range()], "unless b then c");
+        let resolution = resolver.resolve_source("a\n    macro\n    if b then c");
+        let kind =
+            diagnostic::Kind::InvalidDefinition { error: source::Error::MissingName { line: 0 } };
+        assert_eq!(resolution.diagnostics, vec![Diagnostic {
+            kind,
+            tokens: 0..1,
+            span: Span::new(6, 11)
+        }]);
+    }
+
+    #[test]
+    fn attach_documentation() {
+        let resolver = resolver(if_then_else());
+        let source =
+            "## Unless.\nmacro unless unless then\n## Doc.\nunless a then b\n\nif c then d";
+        let resolution = resolver.resolve_source(source);
+        let lines = &single_block(&resolution.items).lines;
+        let documentation = lines.iter().filter_map(|line| match &line[..] {
+            [Item::Macro(matched), ..] => Some(matched.documentation.as_deref()),
+            _ => None,
+        });
+        assert_eq!(documentation.collect_vec(), vec![Some("Doc."), None]);
+        let unless = &single_macro(&lines[3]).definition;
+        assert_eq!(unless.documentation.as_deref(), Some("Unless."));
+    }
+
+    #[test]
+    fn resolve_in_contexts() {
+        let foreign = macro_definition!(foreign: foreign).with_contexts(vec![Context::TopLevel]);
+        let arrow = macro_definition!(arrow: _ "->").with_contexts(vec![Context::TypeSignature]);
+        let resolver = resolver(vec![foreign, arrow]);
+        let stream = vec![var("a"), Token::operator("->", 1), var("b")];
+        let items = resolver.resolve(&stream).items;
+        assert!(items.iter().all(|item| matches!(item, Item::Token(_))));
+        let items = resolver.resolve_in(&stream, Context::TypeSignature).items;
+        assert!(matches!(&items[..], [Item::Macro(matched)] if matched.definition.name == "arrow"));
+        let resolution = resolver.resolve_source("foreign a\nb =\n    foreign c");
+        let lines = &single_block(&resolution.items).lines;
+        assert!(matches!(&lines[0][..], [Item::Macro(_)]));
+        let nested = match &lines[1][..] {
+            [Item::Token(_), Item::Token(_), Item::Block(nested)] => &nested.lines,
+            items => panic!("Expected a block after tokens, got {:?}.", items),
+        };
+        assert!(nested[0].iter().all(|item| matches!(item, Item::Token(_))));
+    }
+
+    #[test]
+    fn interleaved_segments() {
+        let resolver = resolver(builtin());
+        let names = |items: &[Item]| {
+            let name = |item: &Item| match item {
+                Item::Macro(matched) => matched.definition.name.clone(),
+                _ => "token".into(),
+            };
+            items.iter().map(name).collect_vec()
+        };
+        let items = resolver.resolve_source("if (case x of y) then z else w").items;
+        let outer = single_macro(&items);
+        assert_eq!(outer.segments.len(), 3);
+        let group = single_macro(&outer.segments[0].body);
+        assert_eq!(names(&group.segments[0].body), vec!["case_of"]);
+        let items = resolver.resolve_source("if a then (b else c) else d").items;
+        let outer = single_macro(&items);
+        assert_eq!(names(&outer.segments[1].body), vec!["group"]);
+        assert_eq!(outer.segments[2].body, token_items("d"));
+        let group = single_macro(&outer.segments[1].body);
+        assert_eq!(names(&group.segments[0].body), vec!["token", "token", "token"]);
+    }
+
+    #[test]
+    fn limit_nesting() {
+        let mut resolver = resolver(builtin());
+        resolver.set_depth_limit(2);
+        let resolution = resolver.resolve_source("((a)) (((b)))");
+        assert!(matches!(&resolution.items[..], [Item::Macro(_), Item::Macro(_), Item::Token(_)]));
+        let kind = diagnostic::Kind::NestingLimit { limit: 2 };
+        let span = Span::new(8, 9);
+        assert_eq!(resolution.diagnostics, vec![Diagnostic { kind, tokens: 7..8, span }]);
+        let resolution = resolver.resolve_source("if a then\n    if b then\n        c");
+        let lines = &single_block(&resolution.items).lines;
+        let matched = single_macro(&lines[0]);
+        assert!(matches!(&matched.segments[1].body[..], [Item::Token(_)]));
+        assert!(
+            matches!(&resolution.diagnostics[..], [Diagnostic { tokens, .. }] if *tokens == (3..4))
+        );
+    }
+
+    #[test]
+    fn gate_features() {
+        let unless = macro_definition!(unless: unless then).with_feature("unless");
+        let mut resolver = resolver(vec![unless.clone()]);
+        let stream = tokens("unless a then b");
+        assert_eq!(resolver.resolve(&stream).items, token_items("unless a then b"));
+        let when = macro_definition!(when: when then).with_feature("when");
+        resolver.define_macro(when).unwrap();
+        resolver.enable_feature("unless").unwrap();
+        assert!(resolver.is_feature_enabled("unless"));
+        let items = resolver.resolve(&stream).items;
+        assert!(matches!(&items[..], [Item::Macro(matched)] if matched.definition == unless));
+        assert_eq!(resolver.resolve(&tokens("when a then b")).items.len(), 4);
+        let conflicting = macro_definition!(when_2: when then);
+        resolver.define_macro(conflicting).unwrap();
+        assert!(resolver.enable_feature("when").is_err());
+        assert!(!resolver.is_feature_enabled("when"));
+        assert_eq!(resolver.remove_macro("when").map(|d| d.feature), Some(Some("when".into())));
+    }
+
+    #[test]
+    fn count_work() {
+        let resolver = resolver(if_then_else());
+        assert_eq!(resolver.last_counters(), Counters::default());
+        resolver.resolve(&tokens("if if a then b then c"));
+        let counters = resolver.last_counters();
+        let expected = Counters {
+            tokens_consumed:    7,
+            definitions_probed: 13,
+            invocations:        2,
+            backtracks:         1,
+        };
+        assert_eq!(counters, expected);
+        resolver.resolve_source("if a then\n    if b then c\n    if d");
+        let counters = resolver.last_counters();
+        assert_eq!(counters.invocations, 3);
+        assert_eq!(counters.backtracks, 1);
+    }
+
+    #[test]
+    fn trace_resolution() {
+        let mut resolver = resolver(if_then_else());
+        resolver.resolve(&tokens("a b"));
+        assert_eq!(resolver.last_trace(), None);
+        resolver.set_tracing(true);
+        resolver.resolve(&tokens("if if a then b then c"));
+        let trace = resolver.last_trace().unwrap();
+        let expected = "\
+Start `if` at 0, consuming tokens from 0.
+  Start `if` at 1, consuming tokens from 1.
+    Continue `then` at 3.
+  End `if_then` over tokens 1..5, as an enclosing invocation continued.
+  Continue `then` at 5.
+End `if_then` over tokens 0..7, as the stream ended.
+";
+        assert_eq!(trace.to_string(), expected);
+        resolver.resolve(&tokens("if a"));
+        let trace = resolver.last_trace().unwrap();
+        assert_eq!(trace.events().nth(1), Some(&Event::Incomplete { tokens: 0..2 }));
+    }
+
+    #[test]
+    fn notify_hook() {
+        let mut resolver = resolver(if_then_else());
+        let notifications = Rc::new(RefCell::new(Vec::new()));
+        let sink = notifications.clone();
+        resolver.set_hook(move |notification: Notification| {
+            let notification = match notification {
+                Notification::DefineMacro(definition) => format!("define {}", definition.name),
+                Notification::RemoveMacro(name) => format!("remove {}", name),
+                Notification::Resolved { resolution, trace } =>
+                    format!("resolve {} traced {}", resolution.items.len(), trace.is_some()),
+                Notification::ResolvedLines { resolved, count } =>
+                    format!("resolve {} of {} lines", resolved, count),
+            };
+            sink.borrow_mut().push(notification);
+        });
+        resolver.remove_macro("if_then");
+        resolver.resolve(&tokens("a if b then c"));
+        resolver.resolve_lines(&[&tokens("a b")], &mut Cache::new());
+        let logger: WarningLogger = WarningLogger::new("Test");
+        resolver.set_hook(hook::LoggerHook::new(&logger));
+        resolver.define_macro(if_then_else().remove(0)).unwrap();
+        let expected = ["remove if_then", "resolve 2 traced false", "resolve 2 traced false"];
+        let expected = [&expected[..], &["resolve 1 of 1 lines"]].concat();
+        assert_eq!(*notifications.borrow(), expected);
     }
 
-    /// Define the macro described by `definition` in the macro resolver `self`.
-    pub fn define_macro(&mut self, definition: Definition) {
-        debug!(self.logger, "Define Macro: {&definition:?}.");
-        self.registry.insert(definition)
+    #[test]
+    fn redefine_in_scope() {
+        let mut resolver = resolver(if_then_else());
+        let lambda = macro_definition!(lambda: _ then);
+        let items = resolver
+            .with_macro(lambda, |resolver| {
+                resolver.remove_macro("if_then");
+                resolver.remove_macro("if_then_else");
+                resolver.resolve(&tokens("if a then b")).items
+            })
+            .unwrap();
+        let matched = single_macro(&items);
+        assert_eq!(matched.definition.name, "lambda");
+        assert_eq!(matched.prefix, token_items("if a"));
+        let items = resolver.resolve(&tokens("if a then b")).items;
+        assert!(
+            matches!(&items[..], [Item::Macro(matched)] if matched.definition.name == "if_then")
+        );
     }
 }
@@ -0,0 +1,215 @@
+//! The builders of the results of macro invocations, like the AST nodes, defined together with the
+//! syntax of the macros.
+//!
+//! A [`Syntax`] pairs a macro [`Definition`] with the [`Builder`] of its invocations. The builders
+//! are collected in [`Builders`], which build the result of every resolved invocation with the
+//! builder of its macro. A builder receives the invocation as a [`MacroMatch`], which exposes the
+//! segments without the details of the resolution.
+
+use crate::prelude::*;
+
+use crate::macros::definition::Definition;
+use crate::macros::literal::Literal;
+use crate::macros::resolution::Item;
+use crate::macros::resolution::Match;
+use crate::prelude::lexer::token::Token;
+use crate::span::Span;
+
+
+
+// ==================
+// === MacroMatch ===
+// ==================
+
+/// A resolved macro invocation, as seen by the [`Builder`] of its macro.
+#[derive(Clone, Debug)]
+#[allow(missing_docs)]
+pub struct MacroMatch<'a> {
+    pub definition: &'a Definition,
+    /// The items preceding the first segment, consumed by the macros of the
+    /// [infix form](crate::macros::definition::Form::Infix).
+    pub prefix:     &'a [Item],
+    pub segments:   Vec<MatchSegment<'a>>,
+    /// Whether all sections of the definition were matched, see [`Match::complete`].
+    pub complete:   bool,
+    pub span:       Span,
+}
+
+impl<'a> MacroMatch<'a> {
+    /// The first segment headed by the `literal`, if any.
+    pub fn segment(&self, literal: &Literal) -> Option<&MatchSegment<'a>> {
+        self.segments.iter().find(|segment| &segment.literal == literal)
+    }
+
+    /// Iterator over the segments headed by the `literal`, in order. There are many of them for
+    /// the [repeated](crate::macros::definition::Section::repeated) sections.
+    pub fn segments_headed_by<'b>(
+        &'b self,
+        literal: &'b Literal,
+    ) -> impl Iterator<Item = &'b MatchSegment<'a>> {
+        self.segments.iter().filter(move |segment| &segment.literal == literal)
+    }
+}
+
+impl<'a> From<&'a Match> for MacroMatch<'a> {
+    fn from(matched: &'a Match) -> Self {
+        let definition = &matched.definition;
+        let prefix = &matched.prefix[..];
+        let segments = matched.segments.iter().map(|segment| {
+            let literal = Literal::try_from(&segment.head.shape).expect("Segment without a head.");
+            MatchSegment { literal, head: &segment.head, body: &segment.body, span: segment.span }
+        });
+        let segments = segments.collect();
+        let complete = matched.complete;
+        let span = matched.span;
+        Self { definition, prefix, segments, complete, span }
+    }
+}
+
+
+// === MatchSegment ===
+
+/// A matched segment of a [`MacroMatch`].
+#[derive(Clone, Debug)]
+#[allow(missing_docs)]
+pub struct MatchSegment<'a> {
+    /// The literal heading the segment.
+    pub literal: Literal,
+    /// The token heading the segment.
+    pub head:    &'a Token,
+    /// The items following the head, including the nested invocations.
+    pub body:    &'a [Item],
+    /// The span of the head and the body.
+    pub span:    Span,
+}
+
+
+
+// ===============
+// === Builder ===
+// ===============
+
+/// The function building the result of an invocation of a macro. It can build the results of the
+/// invocations nested in the segments with the [`Builders`] it receives.
+pub type Builder<T> = Rc<dyn Fn(&MacroMatch, &Builders<T>) -> T>;
+
+
+// === Syntax ===
+
+/// A macro definition, together with the builder of the results of its invocations.
+#[derive(Clone, Derivative)]
+#[derivative(Debug)]
+#[allow(missing_docs)]
+pub struct Syntax<T> {
+    pub definition: Definition,
+    #[derivative(Debug = "ignore")]
+    pub builder:    Builder<T>,
+}
+
+impl<T> Syntax<T> {
+    /// Constructor.
+    pub fn new(
+        definition: Definition,
+        builder: impl Fn(&MacroMatch, &Builders<T>) -> T + 'static,
+    ) -> Self {
+        let builder = Rc::new(builder);
+        Self { definition, builder }
+    }
+}
+
+
+// === Builders ===
+
+/// The builders of the results of macro invocations, keyed by the names of the macros.
+#[derive(Clone, Derivative)]
+#[derivative(Debug, Default(bound = ""))]
+pub struct Builders<T> {
+    #[derivative(Debug = "ignore")]
+    builders: HashMap<String, Builder<T>>,
+}
+
+impl<T> Builders<T> {
+    /// Constructor of empty builders.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Split the `syntax` into the definitions to resolve the macros with, see
+    /// [`crate::macros::Resolver::new`], and the builders of their invocations.
+    pub fn split(syntax: Vec<Syntax<T>>) -> (Vec<Definition>, Self) {
+        let mut builders = Self::new();
+        let definitions = syntax.into_iter().map(|Syntax { definition, builder }| {
+            builders.builders.insert(definition.name.clone(), builder);
+            definition
+        });
+        (definitions.collect(), builders)
+    }
+
+    /// Set the `builder` of the invocations of the macro named `name`, replacing the previous one.
+    pub fn insert(
+        &mut self,
+        name: impl Str,
+        builder: impl Fn(&MacroMatch, &Builders<T>) -> T + 'static,
+    ) {
+        self.builders.insert(name.into(), Rc::new(builder));
+    }
+
+    /// Build the result of the `matched` invocation, if there is a builder of its macro.
+    pub fn build(&self, matched: &Match) -> Option<T> {
+        let builder = self.builders.get(&matched.definition.name)?;
+        Some(builder(&MacroMatch::from(matched), self))
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::macro_definition;
+    use crate::macros::tests::single_macro;
+    use crate::macros::Resolver;
+
+    /// The text of the `items`, with the invocations built with the `builders`.
+    fn text(items: &[Item], builders: &Builders<String>) -> String {
+        let text = |item: &Item| match item {
+            Item::Token(token) => match Literal::try_from(&token.shape) {
+                Ok(literal) => literal.to_string(),
+                Err(_) => "?".into(),
+            },
+            Item::Macro(matched) => builders.build(matched).unwrap_or_else(|| "?".into()),
+            Item::Block(_) => "?".into(),
+        };
+        items.iter().map(text).join(" ")
+    }
+
+    fn syntax() -> Vec<Syntax<String>> {
+        let if_then_else = Syntax::new(macro_definition!(if_then_else: if then else?), |m, b| {
+            let segment = |head: &str| m.segment(&Literal::variable(head)).map(|s| text(s.body, b));
+            let condition = segment("if").unwrap_or_default();
+            let branches = [segment("then"), segment("else")].into_iter().flatten().join(" ");
+            format!("(if {} {})", condition, branches)
+        });
+        let list = Syntax::new(macro_definition!(list: "[" ","* "]"!), |m, b| {
+            let elements = m.segments.iter().filter(|s| !s.body.is_empty());
+            format!("(list {})", elements.map(|s| text(s.body, b)).join(" "))
+        });
+        vec![if_then_else, list]
+    }
+
+    #[test]
+    fn build_invocations() {
+        let (definitions, builders) = Builders::split(syntax());
+        let resolver = Resolver::new(definitions).unwrap();
+        let resolution = resolver.resolve_source("if [a, b] then if c then d else e");
+        assert_eq!(text(&resolution.items, &builders), "(if (list a b) (if c d e))");
+        let matched = single_macro(&resolution.items);
+        let list = MacroMatch::from(single_macro(&matched.segments[0].body));
+        assert_eq!(list.segments_headed_by(&Literal::operator(",")).count(), 1);
+    }
+}
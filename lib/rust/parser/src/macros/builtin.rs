@@ -0,0 +1,207 @@
+//! The macros defining the core syntax of Enso.
+
+use crate::macro_definition;
+use crate::macros::context::Context;
+use crate::macros::definition::Definition;
+use crate::operator::associativity::Associativity;
+use crate::operator::precedence;
+
+
+
+// ===============
+// === Builtin ===
+// ===============
+
+/// The definitions of all macros of the core syntax of Enso.
+pub fn builtin() -> Vec<Definition> {
+    vec![
+        if_then_else(),
+        lambda(),
+        function_type(),
+        case_of(),
+        import(),
+        from_import(),
+        export(),
+        from_export(),
+        group(),
+    ]
+}
+
+/// The `if a then b` expression, with an optional `else c`.
+pub fn if_then_else() -> Definition {
+    macro_definition!(if_then_else: if then else?)
+}
+
+/// The `a -> b` lambda expression, in all contexts but the type signatures.
+pub fn lambda() -> Definition {
+    let arrow = "->";
+    let definition = macro_definition!(lambda: _ "->");
+    let definition = definition.with_precedence(precedence::of(arrow), Associativity::of(arrow));
+    definition.with_contexts(vec![Context::TopLevel, Context::Expression, Context::Pattern])
+}
+
+/// The `a -> b` function type, in the type signatures.
+pub fn function_type() -> Definition {
+    let arrow = "->";
+    let definition = macro_definition!(function_type: _ "->");
+    let definition = definition.with_precedence(precedence::of(arrow), Associativity::of(arrow));
+    definition.with_contexts(vec![Context::TypeSignature])
+}
+
+/// The `case a of` expression, followed by a block of the branches.
+pub fn case_of() -> Definition {
+    macro_definition!(case_of: case of)
+}
+
+/// The `import A` statement, with an optional `as B`.
+pub fn import() -> Definition {
+    macro_definition!(import: import as?)
+}
+
+/// The `from A import b` statement.
+pub fn from_import() -> Definition {
+    macro_definition!(from_import: from import)
+}
+
+/// The `export A` statement.
+pub fn export() -> Definition {
+    macro_definition!(export: export)
+}
+
+/// The `from A export b` statement.
+pub fn from_export() -> Definition {
+    macro_definition!(from_export: from export)
+}
+
+/// The `(a)` group.
+pub fn group() -> Definition {
+    macro_definition!(group: "(" ")"!)
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    use crate::macros::diagnostic;
+    use crate::macros::resolution::Item;
+    use crate::macros::tests::single_block;
+    use crate::macros::tests::single_macro;
+    use crate::macros::Resolver;
+    use crate::prelude::lexer::token::Token;
+    fn resolver(definitions: Vec<Definition>) -> Resolver {
+        Resolver::new(definitions).unwrap()
+    }
+
+    fn resolve(source: &str) -> Vec<Item> {
+        let resolver = resolver(builtin());
+        let tokens: Vec<Token> = ::lexer::run(source).tokens.into();
+        let resolution = resolver.resolve(&tokens);
+        assert!(resolution.is_complete());
+        resolution.items
+    }
+
+    fn name(item: &Item) -> &str {
+        match item {
+            Item::Macro(matched) => &matched.definition.name,
+            Item::Token(_) => "token",
+            Item::Block(_) => "block",
+        }
+    }
+
+    fn names(items: &[Item]) -> Vec<&str> {
+        items.iter().map(name).collect()
+    }
+
+    #[test]
+    fn all_definitions_are_registered() {
+        let definitions = builtin();
+        let unique_names = definitions.iter().map(|definition| &definition.name).unique().count();
+        assert_eq!(unique_names, definitions.len());
+        let resolver = resolver(definitions.clone());
+        assert!(definitions.iter().all(|def| def
+            .paths()
+            .into_iter()
+            .all(|path| resolver.registry.definitions(path).contains(def))));
+    }
+
+    #[test]
+    fn conditionals() {
+        let items = resolve("if a then b else c");
+        let matched = single_macro(&items);
+        assert_eq!(matched.definition, if_then_else());
+        let items = resolve("if a then if b then c");
+        let matched = single_macro(&items);
+        assert_eq!(matched.segments.len(), 2);
+        assert_eq!(single_macro(&matched.segments[1].body).segments.len(), 2);
+    }
+
+    #[test]
+    fn conditionals_missing_bodies() {
+        let resolver = resolver(builtin());
+        for source in ["if a then", "if a then b else"] {
+            let tokens: Vec<Token> = ::lexer::run(source).tokens.into();
+            let resolution = resolver.resolve(&tokens);
+            let matched = single_macro(&resolution.items);
+            assert_eq!(matched.definition, if_then_else());
+            assert!(!matched.complete);
+            let name = "if_then_else".into();
+            let kind = diagnostic::Kind::IncompleteMacro { name, expected: vec![] };
+            let diagnostics = resolution.diagnostics.iter().map(|diagnostic| &diagnostic.kind);
+            assert_eq!(diagnostics.collect_vec(), vec![&kind]);
+        }
+    }
+
+    #[test]
+    fn lambdas() {
+        let items = resolve("f = x -> y -> x + y");
+        assert_eq!(names(&items), vec!["token", "token", "lambda"]);
+        let outer = single_macro(&items[2..]);
+        assert_eq!(outer.prefix.len(), 1);
+        let inner = single_macro(&outer.segments[0].body);
+        assert_eq!(inner.segments[0].body.len(), 3);
+    }
+
+    #[test]
+    fn function_types() {
+        let items = resolve("f : A -> B -> C");
+        assert_eq!(names(&items), vec!["token", "token", "function_type"]);
+        let outer = single_macro(&items[2..]);
+        assert_eq!(names(&outer.segments[0].body), vec!["function_type"]);
+        let items = resolve("f : (A -> B) = x -> y");
+        assert_eq!(names(&items), vec!["token", "token", "group", "token", "lambda"]);
+        let group = single_macro(&items[2..3]);
+        assert_eq!(names(&group.segments[0].body), vec!["function_type"]);
+    }
+
+    #[test]
+    fn groups() {
+        let items = resolve("(a (b c)) d");
+        assert_eq!(names(&items), vec!["group", "token"]);
+        let outer = single_macro(&items[..1]);
+        assert_eq!(names(&outer.segments[0].body), vec!["token", "group"]);
+        assert!(outer.segments[1].body.is_empty());
+    }
+
+    #[test]
+    fn imports_and_case() {
+        assert_eq!(single_macro(&resolve("import Standard.Base")).definition, import());
+        assert_eq!(single_macro(&resolve("import Standard.Base as B")).segments.len(), 2);
+        assert_eq!(
+            single_macro(&resolve("from Standard.Base import all")).definition,
+            from_import()
+        );
+        assert_eq!(single_macro(&resolve("export Foo")).definition, export());
+        assert_eq!(single_macro(&resolve("from Foo export Bar")).definition, from_export());
+        let items = resolve("case x of\n    y -> z");
+        let case = single_macro(&single_block(&items).lines[0]);
+        assert_eq!(case.definition, case_of());
+        assert!(matches!(&case.segments[1].body[..], [Item::Block(_)]));
+    }
+}
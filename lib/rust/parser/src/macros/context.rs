@@ -0,0 +1,50 @@
+//! The syntactic contexts the macros can be restricted to, so the same section heads can mean
+//! different macros in different contexts, like the `->` of a lambda and of a function type.
+
+use crate::prelude::*;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+
+
+// ===============
+// === Context ===
+// ===============
+
+/// The syntactic context of a token stream.
+///
+/// The resolver starts a stream in the context it is given, see
+/// [`crate::macros::Resolver::resolve_in`], and tracks the context of every body it resolves. A
+/// body starts in the context of the enclosing one, and the lines of a block start in the context
+/// of the body containing the block. The context of a body changes after some of its operators,
+/// see [`Context::following`].
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum Context {
+    /// The lines of a module, like the imports and the definitions.
+    TopLevel,
+    /// An expression, like the body of a definition.
+    Expression,
+    /// A pattern, like the arguments of a definition or a branch of a `case`.
+    Pattern,
+    /// A type signature, like the one following the `:` of `foo : Integer -> Integer`.
+    TypeSignature,
+}
+
+impl Context {
+    /// The context of the tokens following the `operator` in a body of this context. A type
+    /// signature follows the `:` operator, and an expression follows the `=` operator.
+    pub fn following(self, operator: &str) -> Self {
+        match operator {
+            ":" => Self::TypeSignature,
+            "=" => Self::Expression,
+            _ => self,
+        }
+    }
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self::Expression
+    }
+}
@@ -0,0 +1,43 @@
+//! The counters of the work done by the macro resolution, so the performance regressions of the
+//! resolver can be caught by comparing them, independently of the timing noise.
+
+use crate::prelude::*;
+
+use std::ops::AddAssign;
+
+
+
+// ================
+// === Counters ===
+// ================
+
+/// The work done by a resolution, including the resolution of the lines of the blocks.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Counters {
+    /// The resolved tokens.
+    pub tokens_consumed:    usize,
+    /// The registry nodes probed for the sections started or continued by the tokens.
+    pub definitions_probed: usize,
+    /// The resolved invocations.
+    pub invocations:        usize,
+    /// The invocations the resolver stopped matching before they could be continued: the ones
+    /// ended by a continuation of an enclosing invocation, and the incomplete ones.
+    pub backtracks:         usize,
+}
+
+impl AddAssign for Counters {
+    fn add_assign(&mut self, other: Self) {
+        self.tokens_consumed += other.tokens_consumed;
+        self.definitions_probed += other.definitions_probed;
+        self.invocations += other.invocations;
+        self.backtracks += other.backtracks;
+    }
+}
+
+impl Display for Counters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} tokens consumed, ", self.tokens_consumed)?;
+        write!(f, "{} definitions probed, ", self.definitions_probed)?;
+        write!(f, "{} invocations, {} backtracks", self.invocations, self.backtracks)
+    }
+}
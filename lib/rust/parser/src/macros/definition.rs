@@ -2,9 +2,14 @@
 
 use crate::prelude::*;
 
+use crate::macros::context::Context;
 use crate::macros::literal::Literal;
+use crate::operator::associativity::Associativity;
+use crate::operator::precedence;
 
 use itertools::Itertools;
+use serde::Deserialize;
+use serde::Serialize;
 
 
 
@@ -16,19 +21,106 @@ use itertools::Itertools;
 ///
 /// A macro definition consists of a name, which identifies the macro to users, and a list of
 /// [sections](`Section`). The sections are the most important portion of the macro definition, as
-/// they define the literal portions of the token stream on which the macro will match.
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+/// they define the literal portions of the token stream on which the macro will match. The sections
+/// can be optional or repeated, see [`Section`].
+///
+/// A macro can have a precedence and an associativity, like an operator. The operators binding
+/// looser than the macro end its last section, and the ones binding looser than an infix macro
+/// bound the tokens it consumes before its first section. Without the precedence, the last section
+/// extends up to the end of the enclosing section or line.
+///
+/// A macro can be restricted to some [contexts](Context), so the definitions active in disjoint
+/// contexts can share their sections, like the `->` of a lambda and of a function type.
+///
+/// A macro of an experimental syntax can belong to a feature, so it is defined only in the projects
+/// enabling the feature, see [`crate::macros::Resolver::enable_feature`].
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 #[allow(missing_docs)]
 pub struct Definition {
-    pub name:     String,
-    pub sections: Vec<Section>,
+    pub name:          String,
+    pub form:          Form,
+    pub sections:      Vec<Section>,
+    /// The precedence level, as defined by [`crate::operator::precedence`].
+    pub precedence:    Option<usize>,
+    pub associativity: Associativity,
+    /// The documentation comment preceding the definition in the source, see
+    /// [`crate::macros::documentation`].
+    pub documentation: Option<String>,
+    /// The contexts the macro is active in. Active in all contexts if empty.
+    pub contexts:      Vec<Context>,
+    /// The feature the macro belongs to, if it is experimental.
+    pub feature:       Option<String>,
 }
 
 impl Definition {
-    /// Constructor.
+    /// Constructor of a macro of the [prefix form](Form::Prefix).
     pub fn new(name: impl Str, sections: Vec<Section>) -> Self {
         let name = name.into();
-        Self { name, sections }
+        let form = Form::Prefix;
+        let precedence = default();
+        let associativity = default();
+        let documentation = default();
+        let contexts = default();
+        let feature = default();
+        Self { name, form, sections, precedence, associativity, documentation, contexts, feature }
+    }
+
+    /// Constructor of a macro of the [infix form](Form::Infix).
+    pub fn infix(name: impl Str, sections: Vec<Section>) -> Self {
+        let form = Form::Infix;
+        Self { form, ..Self::new(name, sections) }
+    }
+
+    /// Set the precedence level and associativity of the macro.
+    pub fn with_precedence(self, precedence: usize, associativity: Associativity) -> Self {
+        let precedence = Some(precedence);
+        Self { precedence, associativity, ..self }
+    }
+
+    /// Restrict the macro to the `contexts`.
+    pub fn with_contexts(self, contexts: Vec<Context>) -> Self {
+        Self { contexts, ..self }
+    }
+
+    /// Make the macro belong to the experimental `feature`.
+    pub fn with_feature(self, feature: impl Str) -> Self {
+        let feature = Some(feature.into());
+        Self { feature, ..self }
+    }
+
+    /// Check if the macro can be defined with the `features` enabled.
+    pub fn is_enabled_by(&self, features: &HashSet<String>) -> bool {
+        self.feature.as_ref().map_or(true, |feature| features.contains(feature))
+    }
+
+    /// Check if the macro is active in the `context`.
+    pub fn is_active_in(&self, context: Context) -> bool {
+        self.contexts.is_empty() || self.contexts.contains(&context)
+    }
+
+    /// Check if there is a context both the macro and the `other` one are active in.
+    pub fn shares_context_with(&self, other: &Definition) -> bool {
+        self.contexts.is_empty() || self.contexts.iter().any(|&context| other.is_active_in(context))
+    }
+
+    /// Check if the `operator` following the last section ends it, as binding looser than the
+    /// macro.
+    pub fn is_ended_by(&self, operator: &str) -> bool {
+        self.precedence.map_or(false, |precedence| {
+            let operator_precedence = precedence::of(operator);
+            operator_precedence < precedence
+                || operator_precedence == precedence && self.associativity == Associativity::Left
+        })
+    }
+
+    /// Check if the `operator` preceding the first section bounds the tokens consumed by the
+    /// [infix form](Form::Infix) of the macro, as binding looser than the macro.
+    pub fn is_bounded_by(&self, operator: &str) -> bool {
+        self.precedence.map_or(false, |precedence| {
+            let operator_precedence = precedence::of(operator);
+            operator_precedence < precedence
+                || operator_precedence == precedence && self.associativity == Associativity::Right
+        })
     }
 
     /// Get the path for the definition.
@@ -38,6 +130,53 @@ impl Definition {
     pub fn path(&self) -> Vec<Literal> {
         self.sections.iter().map(|s| s.start_symbol.clone()).collect_vec()
     }
+
+    /// Get all paths matching the definition, one for every choice of the optional sections to
+    /// leave out. The repeated sections occur once in the paths. The empty path is skipped.
+    pub fn paths(&self) -> Vec<Vec<Literal>> {
+        let mut paths = vec![vec![]];
+        for section in &self.sections {
+            let extended = paths.iter().map(|path| {
+                let mut path = path.clone();
+                path.push(section.start_symbol.clone());
+                path
+            });
+            let extended = extended.collect_vec();
+            if !section.optional {
+                paths.clear();
+            }
+            paths.extend(extended);
+        }
+        paths.retain(|path| !path.is_empty());
+        paths
+    }
+
+    /// Check if the invocation of the macro can repeat the section headed by the `literal`.
+    pub fn repeats(&self, literal: &Literal) -> bool {
+        self.sections.iter().any(|section| section.repeated && &section.start_symbol == literal)
+    }
+}
+
+
+
+// ============
+// === Form ===
+// ============
+
+/// The form of a macro, describing whether it consumes the tokens preceding its first section.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Form {
+    /// The macro starts at its first section head, like `if a then b`.
+    Prefix,
+    /// The macro consumes the tokens preceding its first section head, up to the start of the
+    /// enclosing section or line, like `a -> b`.
+    Infix,
+}
+
+impl Default for Form {
+    fn default() -> Self {
+        Self::Prefix
+    }
 }
 
 
@@ -51,16 +190,56 @@ impl Definition {
 ///
 /// The literal is the _most_ important portion of a section, as they are constants that allow the
 /// macro resolver to divide up the input token stream based on these constants.
-#[derive(Clone, Debug, Eq, PartialEq)]
+///
+/// A closing section contains no tokens, so the macro ends right after its literal, like the `)` of
+/// a group. Only the last section of a macro can be closing.
+///
+/// An optional section can be left out of an invocation, like the `else` of a conditional. A
+/// repeated section can be matched many times in a row, like the `,` of a list. A section that is
+/// both can be matched any number of times.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[allow(missing_docs)]
 pub struct Section {
     start_symbol: Literal, // TODO Pattern
+    closing:      bool,
+    optional:     bool,
+    repeated:     bool,
 }
 
 impl Section {
     /// Constructor.
     pub fn new(symbol: Literal) -> Self {
-        Self { start_symbol: symbol }
+        Self { start_symbol: symbol, closing: false, optional: false, repeated: false }
+    }
+
+    /// Constructor of a closing section.
+    pub fn closing(symbol: Literal) -> Self {
+        Self { closing: true, ..Self::new(symbol) }
+    }
+
+    /// Make the section optional.
+    pub fn optional(self) -> Self {
+        Self { optional: true, ..self }
+    }
+
+    /// Make the section repeated.
+    pub fn repeated(self) -> Self {
+        Self { repeated: true, ..self }
+    }
+
+    /// Check if the section is closing.
+    pub fn is_closing(&self) -> bool {
+        self.closing
+    }
+
+    /// Check if the section is optional.
+    pub fn is_optional(&self) -> bool {
+        self.optional
+    }
+
+    /// Check if the section is repeated.
+    pub fn is_repeated(&self) -> bool {
+        self.repeated
     }
 
     /// Get a reference to the literal that heads the section.
@@ -68,3 +247,107 @@ impl Section {
         &self.start_symbol
     }
 }
+
+
+
+// ==============
+// === Macros ===
+// ==============
+
+/// Construct a macro [`Definition`] from its name and the heads of its sections.
+///
+/// An identifier heads a section with the [identifier literal](Literal::identifier) of its name,
+/// an `@` followed by an identifier with the annotation literal, and a string with the operator
+/// literal. The operators are checked at compile time. A blank before the first head defines the
+/// macro of the [infix form](Form::Infix). A head can be followed by a modifier of its section:
+/// `?` makes it optional, `+` repeated, `*` optional and repeated, and `!` closing.
+///
+/// ```
+/// use parser_new::macro_definition;
+/// use parser_new::macros::definition::Form;
+///
+/// let if_then_else = macro_definition!(if_then_else: if then else?);
+/// assert_eq!(if_then_else.paths().len(), 2);
+/// let list = macro_definition!(list: "[" ","* "]"!);
+/// assert!(list.sections[1].is_repeated());
+/// let lambda = macro_definition!(lambda: _ "->");
+/// assert_eq!(lambda.form, Form::Infix);
+/// ```
+///
+/// A head which is not an operator fails to compile:
+///
+/// ```compile_fail
+/// use parser_new::macro_definition;
+///
+/// let invalid = macro_definition!(invalid: "[" "a");
+/// ```
+#[macro_export]
+macro_rules! macro_definition {
+    ($name:ident : _ $($heads:tt)+) => {
+        $crate::macros::definition::Definition::infix(
+            stringify!($name),
+            $crate::macro_definition!(@sections [] $($heads)+),
+        )
+    };
+    ($name:ident : $($heads:tt)+) => {
+        $crate::macros::definition::Definition::new(
+            stringify!($name),
+            $crate::macro_definition!(@sections [] $($heads)+),
+        )
+    };
+
+
+    // === Sections ===
+
+    (@sections [$($sections:tt)*]) => {
+        vec![$($sections)*]
+    };
+    (@sections [$($sections:tt)*] @ $head:ident $($rest:tt)*) => {
+        $crate::macro_definition!(@modifier [$($sections)*] (
+            $crate::macros::literal::Literal::annotation(stringify!($head))
+        ) $($rest)*)
+    };
+    (@sections [$($sections:tt)*] $head:ident $($rest:tt)*) => {
+        $crate::macro_definition!(@modifier [$($sections)*] (
+            $crate::macros::literal::Literal::identifier(stringify!($head))
+        ) $($rest)*)
+    };
+    (@sections [$($sections:tt)*] $head:literal $($rest:tt)*) => {
+        $crate::macro_definition!(@modifier [$($sections)*] ({
+            const _: () = assert!(
+                $crate::macros::literal::is_operator($head),
+                "A macro section can be headed only by an Enso operator string."
+            );
+            $crate::macros::literal::Literal::operator($head)
+        }) $($rest)*)
+    };
+
+
+    // === Modifiers ===
+
+    (@modifier [$($sections:tt)*] ($literal:expr) ? $($rest:tt)*) => {
+        $crate::macro_definition!(@sections [$($sections)*
+            $crate::macros::definition::Section::new($literal).optional(),
+        ] $($rest)*)
+    };
+    (@modifier [$($sections:tt)*] ($literal:expr) + $($rest:tt)*) => {
+        $crate::macro_definition!(@sections [$($sections)*
+            $crate::macros::definition::Section::new($literal).repeated(),
+        ] $($rest)*)
+    };
+    (@modifier [$($sections:tt)*] ($literal:expr) * $($rest:tt)*) => {
+        $crate::macro_definition!(@sections [$($sections)*
+            $crate::macros::definition::Section::new($literal).optional().repeated(),
+        ] $($rest)*)
+    };
+    (@modifier [$($sections:tt)*] ($literal:expr) ! $($rest:tt)*) => {
+        $crate::macro_definition!(@sections [$($sections)*
+            $crate::macros::definition::Section::closing($literal),
+        ] $($rest)*)
+    };
+    (@modifier [$($sections:tt)*] ($literal:expr) $($rest:tt)*) => {
+        $crate::macro_definition!(@sections [$($sections)*
+            $crate::macros::definition::Section::new($literal),
+        ] $($rest)*)
+    };
+}
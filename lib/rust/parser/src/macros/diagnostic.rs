@@ -0,0 +1,72 @@
+//! The diagnostics reported by the macro resolution, describing the invalid input the resolver
+//! recovered from.
+
+use crate::prelude::*;
+
+use crate::macros::literal::Literal;
+use crate::macros::source;
+use crate::span::Span;
+
+
+
+// ==================
+// === Diagnostic ===
+// ==================
+
+/// A problem found in the resolved stream. The tokens are identified by their indices in the
+/// stream, or in the line of a [block](crate::macros::resolution::Block) the problem is found in.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    /// The kind of the problem.
+    pub kind:   Kind,
+    /// The tokens the problem concerns.
+    pub tokens: Range<usize>,
+    /// The span of the tokens.
+    pub span:   Span,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Bytes {:?}: {}", self.span.range(), self.kind)
+    }
+}
+
+
+
+// ============
+// === Kind ===
+// ============
+
+/// The kinds of [`Diagnostic`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum Kind {
+    /// The invocation of the macro `name` did not match all its sections by the end of the stream.
+    /// The `expected` literals could head the next section. No literals are expected if the last
+    /// matched section is missing its body, like the `then` of `if a then`.
+    IncompleteMacro { name: String, expected: Vec<Literal> },
+    /// The macro definition in a block is invalid or conflicts with the ones in scope.
+    InvalidDefinition { error: source::Error },
+    /// The invocation or the block would be nested deeper than the `limit`, so it was left
+    /// unresolved. See [`crate::macros::Resolver::set_depth_limit`].
+    NestingLimit { limit: usize },
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Kind::IncompleteMacro { name, expected } if expected.is_empty() => write!(
+                f,
+                "the macro `{}` is incomplete, expected the body of its last section.",
+                name
+            ),
+            Kind::IncompleteMacro { name, expected } => {
+                let expected = expected.iter().map(|literal| format!("`{}`", literal)).join(", ");
+                write!(f, "the macro `{}` is incomplete, expected one of: {}.", name, expected)
+            }
+            Kind::InvalidDefinition { error } => write!(f, "invalid macro definition. {}", error),
+            Kind::NestingLimit { limit } =>
+                write!(f, "the nesting exceeds the limit of {} invocations and blocks.", limit),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! The documentation comments attached to the macro definitions and invocations. A line consisting
+//! of a documentation comment documents the line following it.
+
+use crate::prelude::*;
+
+use crate::prelude::lexer::token;
+use crate::prelude::lexer::token::Token;
+
+
+
+// =====================
+// === Documentation ===
+// =====================
+
+/// The text of the documentation comment making up the `line`, if it is such a line. The lines of
+/// the comment are joined with line feeds.
+pub fn of_line(line: &[Token]) -> Option<String> {
+    match line {
+        [Token { shape: token::Shape::DocComment { lines, .. }, .. }] => {
+            let lines = lines.iter().map(|line| match &line.shape {
+                token::Shape::Line { tokens, .. } => tokens.iter().map(text).collect::<String>(),
+                _ => default(),
+            });
+            Some(lines.collect_vec().join("\n"))
+        }
+        _ => None,
+    }
+}
+
+/// The text of a segment of a documentation comment line.
+fn text(token: &Token) -> String {
+    let text = match &token.shape {
+        token::Shape::TextSegmentRaw(text) => text.as_str(),
+        _ => "",
+    };
+    format!("{:offset$}{}", "", text, offset = token.offset)
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn documentation_lines() {
+        let tokens: Vec<Token> = ::lexer::run("## Doc text\n   more\nfoo").tokens.into();
+        let lines = match &tokens[..] {
+            [Token { shape: token::Shape::Block { lines, .. }, .. }] => lines,
+            tokens => panic!("Expected a block, got {:?}.", tokens),
+        };
+        let lines = lines.iter().map(|line| match &line.shape {
+            token::Shape::Line { tokens, .. } => of_line(tokens),
+            _ => None,
+        });
+        assert_eq!(lines.collect_vec(), vec![Some("Doc text\nmore".into()), None]);
+    }
+}
@@ -0,0 +1,95 @@
+//! The hooks notified about the work of the macro resolver, for integrating it with logging or
+//! telemetry.
+
+use crate::prelude::logger::*;
+use crate::prelude::*;
+
+use crate::macros::definition::Definition;
+use crate::macros::resolution::Resolution;
+use crate::macros::trace::Trace;
+
+
+
+// ====================
+// === Type Aliases ===
+// ====================
+
+type DebugLevel = crate::prelude::logger::entry::level::Debug;
+
+
+
+// ====================
+// === Notification ===
+// ====================
+
+/// A notification about the work of the [resolver](crate::macros::Resolver).
+#[derive(Clone, Copy, Debug)]
+#[allow(missing_docs)]
+pub enum Notification<'a> {
+    /// The macro was defined.
+    DefineMacro(&'a Definition),
+    /// The macro with the name was removed.
+    RemoveMacro(&'a str),
+    /// The stream was resolved. The trace is recorded only if the tracing is enabled.
+    Resolved { resolution: &'a Resolution, trace: Option<&'a Trace> },
+    /// The lines of a source were resolved, of which only `resolved` were not found in the cache.
+    ResolvedLines { resolved: usize, count: usize },
+}
+
+
+
+// ============
+// === Hook ===
+// ============
+
+/// A receiver of the [notifications](Notification) of the resolver. Implemented for the closures
+/// taking the notifications.
+pub trait Hook {
+    /// Handle the `notification`.
+    fn notify(&self, notification: Notification);
+}
+
+impl<F: Fn(Notification)> Hook for F {
+    fn notify(&self, notification: Notification) {
+        self(notification)
+    }
+}
+
+
+
+// ==================
+// === LoggerHook ===
+// ==================
+
+/// The hook writing the notifications to the logger at the debug level.
+#[derive(Clone, Debug)]
+pub struct LoggerHook<Logger> {
+    logger: Logger,
+}
+
+impl<Logger> LoggerHook<Logger>
+where Logger: AnyLogger<Owned = Logger>
+{
+    /// Constructor.
+    pub fn new(parent_logger: &Logger) -> Self {
+        let logger = <Logger>::sub(parent_logger, "Resolver");
+        Self { logger }
+    }
+}
+
+impl<Logger> Hook for LoggerHook<Logger>
+where Logger: LoggerOps<DebugLevel>
+{
+    fn notify(&self, notification: Notification) {
+        match notification {
+            Notification::DefineMacro(definition) =>
+                debug!(self.logger, "Define Macro: {definition:?}."),
+            Notification::RemoveMacro(name) => debug!(self.logger, "Remove Macro: {name}."),
+            Notification::Resolved { trace: Some(trace), .. } =>
+                debug!(self.logger, "Resolution Trace:\n{trace}"),
+            Notification::Resolved { trace: None, .. } => {}
+            Notification::ResolvedLines { resolved, count } =>
+                debug!(self.logger, "Resolved {resolved} of {count} lines anew."),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! The cache of macro resolutions, so an edited source can be re-resolved incrementally. The lines
+//! of a source are resolved independently, so only the lines changed by an edit need to be
+//! resolved again.
+
+use crate::prelude::*;
+
+use crate::macros::registry::Registry;
+use crate::macros::resolution::Resolution;
+use crate::prelude::lexer::token::Token;
+
+use std::collections::hash_map::DefaultHasher;
+
+
+
+// =============
+// === Cache ===
+// =============
+
+/// The resolutions of token streams, keyed by the hashes of the streams. The resolutions are valid
+/// only for the macro registry they were made with, so the cache is cleared when it changes.
+///
+/// The cache keeps only the resolutions used by the last resolution of a source, so it does not
+/// grow as the source is edited.
+#[derive(Clone, Debug, Default)]
+pub struct Cache {
+    registry: Option<Registry>,
+    entries:  HashMap<u64, Vec<Entry>>,
+    misses:   usize,
+}
+
+/// A cached resolution of the `tokens`.
+#[derive(Clone, Debug)]
+struct Entry {
+    tokens:     Vec<Token>,
+    resolution: Resolution,
+}
+
+impl Cache {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// The number of cached resolutions.
+    pub fn len(&self) -> usize {
+        self.entries.values().map(Vec::len).sum()
+    }
+
+    /// Check if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The number of token streams resolved anew by the last use of the cache.
+    pub fn last_misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Get the resolution of each of the `streams`, resolving only the ones not found in the cache
+    /// with `resolve`. The resolutions made with a registry other than `registry` are discarded.
+    pub(crate) fn resolve_all(
+        &mut self,
+        registry: &Registry,
+        streams: &[&[Token]],
+        mut resolve: impl FnMut(&[Token]) -> Resolution,
+    ) -> Vec<Resolution> {
+        if self.registry.as_ref() != Some(registry) {
+            self.registry = Some(registry.clone());
+            self.entries.clear();
+        }
+        let mut previous = mem::take(&mut self.entries);
+        self.misses = 0;
+        let resolutions = streams.iter().map(|tokens| {
+            let hash = hash(tokens);
+            let is_entry_of = |entry: &Entry| entry.tokens[..] == **tokens;
+            let resolved =
+                self.entries.get(&hash).and_then(|entries| entries.iter().find(|e| is_entry_of(e)));
+            if let Some(entry) = resolved {
+                return entry.resolution.clone();
+            }
+            let cached = previous.get_mut(&hash).and_then(|entries| {
+                let index = entries.iter().position(is_entry_of)?;
+                Some(entries.swap_remove(index))
+            });
+            let entry = cached.unwrap_or_else(|| {
+                self.misses += 1;
+                Entry { tokens: tokens.to_vec(), resolution: resolve(tokens) }
+            });
+            let resolution = entry.resolution.clone();
+            self.entries.entry(hash).or_default().push(entry);
+            resolution
+        });
+        resolutions.collect()
+    }
+}
+
+/// The hash of the `tokens`.
+fn hash(tokens: &[Token]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    tokens.hash(&mut hasher);
+    hasher.finish()
+}
@@ -2,8 +2,12 @@
 
 use crate::prelude::*;
 
+use crate::prelude::lexer::library::lexeme;
 use crate::prelude::lexer::token;
 
+use serde::Deserialize;
+use serde::Serialize;
+
 
 
 // ===============
@@ -14,7 +18,7 @@ use crate::prelude::lexer::token;
 ///
 /// For more detailed descriptions of the various literal types, please see the documentation of the
 /// tokens in the Lexer.
-#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
 pub enum Literal {
     Referent(String),
     Variable(String),
@@ -54,6 +58,63 @@ impl Literal {
     pub fn annotation(lit: impl Str) -> Literal {
         Literal::Annotation(lit.into())
     }
+
+    /// Construct the literal of the identifier `name`, of the kind the lexer would give it: a
+    /// variable for the lowercase names, a referent for the capitalized ones, and an external
+    /// identifier otherwise.
+    pub fn identifier(name: &str) -> Literal {
+        let segments = name.split('_').collect_vec();
+        let is_segment_of = |is_case: fn(&char) -> bool, segment: &str| {
+            let mut chars = segment.chars();
+            let first = chars.next();
+            let is_body = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit();
+            first.map_or(false, |c| is_case(&c) || c.is_ascii_digit()) && chars.all(is_body)
+        };
+        let is_first_of =
+            |is_case: fn(&char) -> bool| name.chars().next().filter(is_case).is_some();
+        let is_of = |is_case: fn(&char) -> bool| {
+            is_first_of(is_case) && segments.iter().all(|segment| is_segment_of(is_case, segment))
+        };
+        if is_of(char::is_ascii_lowercase) {
+            Literal::variable(name)
+        } else if is_of(char::is_ascii_uppercase) {
+            Literal::referent(name)
+        } else {
+            Literal::external(name)
+        }
+    }
+}
+
+/// Check if `name` consists of the characters of Enso operators. Usable in constant expressions,
+/// so the operators heading the sections of [`macro_definition`](crate::macro_definition) are
+/// checked at compile time.
+pub const fn is_operator(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        let is_operator_char = contains(lexeme::literal::OPERATOR_CHARS, byte)
+            || contains(lexeme::literal::GROUP_CHARS, byte)
+            || contains("=,.", byte);
+        if !is_operator_char {
+            return false;
+        }
+        index += 1;
+    }
+    !bytes.is_empty()
+}
+
+/// Check if the `chars` contain the `byte`.
+const fn contains(chars: &str, byte: u8) -> bool {
+    let chars = chars.as_bytes();
+    let mut index = 0;
+    while index < chars.len() {
+        if chars[index] == byte {
+            return true;
+        }
+        index += 1;
+    }
+    false
 }
 
 
@@ -65,6 +126,19 @@ impl From<&Literal> for Literal {
     }
 }
 
+impl Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Literal::Referent(name)
+            | Literal::Variable(name)
+            | Literal::External(name)
+            | Literal::Operator(name) => write!(f, "{}", name),
+            Literal::Blank => write!(f, "_"),
+            Literal::Annotation(name) => write!(f, "@{}", name),
+        }
+    }
+}
+
 impl From<Literal> for token::Shape {
     fn from(lit: Literal) -> Self {
         match lit {
@@ -78,6 +152,22 @@ impl From<Literal> for token::Shape {
     }
 }
 
+impl TryFrom<&token::Shape> for Literal {
+    type Error = ();
+
+    fn try_from(shape: &token::Shape) -> Result<Self, Self::Error> {
+        match shape {
+            token::Shape::Referent(name) => Ok(Literal::Referent(name.clone())),
+            token::Shape::Variable(name) => Ok(Literal::Variable(name.clone())),
+            token::Shape::External(name) => Ok(Literal::External(name.clone())),
+            token::Shape::Blank => Ok(Literal::Blank),
+            token::Shape::Operator(name) => Ok(Literal::Operator(name.clone())),
+            token::Shape::Annotation(name) => Ok(Literal::Annotation(name.clone())),
+            _ => Err(()),
+        }
+    }
+}
+
 impl TryFrom<token::Shape> for Literal {
     type Error = token::Shape;
 
@@ -93,3 +183,23 @@ impl TryFrom<token::Shape> for Literal {
         }
     }
 }
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_identifiers() {
+        assert_eq!(Literal::identifier("if_then"), Literal::variable("if_then"));
+        assert_eq!(Literal::identifier("Standard_2"), Literal::referent("Standard_2"));
+        assert_eq!(Literal::identifier("fooBar"), Literal::external("fooBar"));
+        assert!(is_operator("->") && is_operator("(") && is_operator(","));
+        assert!(!is_operator("") && !is_operator("a+"));
+    }
+}
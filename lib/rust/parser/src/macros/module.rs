@@ -0,0 +1,153 @@
+//! Sharing macro definitions between Enso modules, so the syntax defined in a library can be used
+//! in the modules importing it.
+//!
+//! The macros exported by a module are collected in its [`Exports`], under the names qualified by
+//! the module, like `Standard.Base.unless`. A module importing them selects the ones it uses with a
+//! [`Filter`], see [`crate::macros::Resolver::import_macros`], and can re-export them to its own
+//! importers. A re-exported macro keeps the name qualified by the module defining it, so importing
+//! it through many modules defines it once.
+
+use crate::prelude::*;
+
+use crate::macros::definition::Definition;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+
+
+// ===============
+// === Exports ===
+// ===============
+
+/// The macros exported by a module, under their qualified names.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[allow(missing_docs)]
+pub struct Exports {
+    /// The qualified name of the module, like `Standard.Base`.
+    pub module:      String,
+    pub definitions: Vec<Definition>,
+}
+
+impl Exports {
+    /// Constructor of the exports of the `module`, initially empty.
+    pub fn new(module: impl Str) -> Self {
+        let module = module.into();
+        let definitions = default();
+        Self { module, definitions }
+    }
+
+    /// Export the `definition` of a macro defined in the module, qualifying its name by the module.
+    pub fn export(&mut self, definition: Definition) {
+        let name = format!("{}.{}", self.module, definition.name);
+        self.insert(Definition { name, ..definition });
+    }
+
+    /// Re-export the macros of the `exports` of another module selected by the `filter`. The
+    /// macros keep the names qualified by the modules defining them.
+    pub fn reexport(&mut self, exports: &Exports, filter: &Filter) {
+        for definition in exports.select(filter) {
+            self.insert(definition.clone());
+        }
+    }
+
+    /// The exported macros selected by the `filter`.
+    pub fn select<'a>(&'a self, filter: &'a Filter) -> impl Iterator<Item = &'a Definition> {
+        self.definitions.iter().filter(|definition| filter.accepts(local_name(&definition.name)))
+    }
+
+    /// Insert the `definition`, replacing the one with the same qualified name.
+    fn insert(&mut self, definition: Definition) {
+        self.definitions.retain(|exported| exported.name != definition.name);
+        self.definitions.push(definition);
+    }
+}
+
+/// The name of a macro without the qualification by its module.
+pub fn local_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+
+
+// ==============
+// === Filter ===
+// ==============
+
+/// The selection of the macros imported from a module, by their unqualified names. The variants
+/// correspond to the forms of the Enso imports.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Filter {
+    /// All macros, like `from A import all`.
+    All,
+    /// Only the named macros, like `from A import x, y`.
+    Only(Vec<String>),
+    /// All macros but the named ones, like `from A import all hiding x, y`.
+    Hiding(Vec<String>),
+}
+
+impl Filter {
+    /// Check if the macro with the unqualified `name` is selected.
+    pub fn accepts(&self, name: &str) -> bool {
+        match self {
+            Filter::All => true,
+            Filter::Only(names) => names.iter().any(|selected| selected == name),
+            Filter::Hiding(names) => names.iter().all(|hidden| hidden != name),
+        }
+    }
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self::All
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::macro_definition;
+    use crate::macros::registry::ConflictKind;
+    use crate::macros::resolution::Item;
+    use crate::macros::tests::single_macro;
+    use crate::macros::Resolver;
+
+    fn library() -> Exports {
+        let mut exports = Exports::new("Standard.Base");
+        exports.export(macro_definition!(unless: unless then));
+        exports.export(macro_definition!(when: when then));
+        exports
+    }
+
+    #[test]
+    fn import_and_reexport() {
+        let library = library();
+        let mut reexports = Exports::new("Standard.Prelude");
+        reexports.reexport(&library, &Filter::Hiding(vec!["when".into()]));
+        let names = reexports.definitions.iter().map(|d| d.name.as_str()).collect_vec();
+        assert_eq!(names, vec!["Standard.Base.unless"]);
+        let mut resolver = Resolver::new(default()).unwrap();
+        resolver.import_macros(&library, &Filter::Only(vec!["unless".into()])).unwrap();
+        resolver.import_macros(&reexports, &Filter::All).unwrap();
+        let items = resolver.resolve_source("unless a then b").items;
+        let name = &single_macro(&items).definition.name;
+        assert_eq!(name, "Standard.Base.unless");
+        let items = resolver.resolve_source("when a then b").items;
+        assert!(items.iter().all(|item| matches!(item, Item::Token(_))));
+        let mut other = Exports::new("Other");
+        other.export(macro_definition!(when: when then));
+        other.export(macro_definition!(unless: unless then));
+        let conflict = resolver.import_macros(&other, &Filter::All).unwrap_err();
+        assert_eq!(conflict.kind, ConflictKind::SamePath);
+        assert_eq!(conflict.inserted, "Other.unless");
+        let items = resolver.resolve_source("when a then b").items;
+        assert!(items.iter().all(|item| matches!(item, Item::Token(_))));
+    }
+}
@@ -3,8 +3,13 @@
 use crate::prelude::*;
 use enso_data_structures::hash_map_tree::*;
 
+use crate::macros::context::Context;
 use crate::macros::definition::Definition;
 use crate::macros::literal::Literal;
+use crate::prelude::lexer::token::Token;
+
+use serde::Deserialize;
+use serde::Serialize;
 
 
 
@@ -12,21 +17,235 @@ use crate::macros::literal::Literal;
 // === Registry ===
 // ================
 
-/// The type of the tree that underlies the registry.
-pub type Tree = HashMapTree<Literal, Option<Definition>>;
+/// The type of the tree that underlies the registry. The definitions at a path are active in
+/// disjoint [contexts](Context), and are kept in the order of their names.
+pub type Tree = HashMapTree<Literal, Vec<Definition>>;
 
 /// The registry is responsible for the registration of macro definitions, and the querying of said
 /// definitions.
-#[derive(Clone, Debug, Default, PartialEq)]
+///
+/// The registry supports nested scopes. The definitions inserted or removed in a scope are restored
+/// to their previous state when the scope is popped, so macros can be shadowed temporarily.
+///
+/// The definitions active in disjoint [contexts](Context) can share their paths, and the resolver
+/// matches the ones active in the context of the resolved tokens.
+///
+/// The registry is serialized as the list of its visible definitions, so a macro table can be
+/// prepared ahead of time, like at the build time of the standard library. The scopes are not
+/// preserved.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(into = "Vec<Definition>", try_from = "Vec<Definition>")]
 #[allow(missing_docs)]
 pub struct Registry {
-    tree: Tree,
+    tree:   Tree,
+    /// For every open scope, the definitions replaced in it, along with their paths.
+    scopes: Vec<Vec<(Vec<Literal>, Vec<Definition>)>>,
 }
 
 impl Registry {
-    /// Insert `definition` into the macro registry.
-    pub fn insert(&mut self, definition: Definition) {
-        self.tree.set(definition.path(), Some(definition));
+    /// Insert `definition` into the macro registry at all its [paths](Definition::paths).
+    ///
+    /// A definition with the same name is replaced, and so are the definitions of the enclosing
+    /// scopes sharing a context with it, which are restored when the current scope is popped. The
+    /// definition is not inserted if it makes the resolution ambiguous, see [`Conflict`].
+    pub fn insert(&mut self, definition: Definition) -> Result<(), Conflict> {
+        if let Some(conflict) = self.conflict(&definition) {
+            return Err(conflict);
+        }
+        for path in definition.paths() {
+            let mut definitions = self.definitions(&path).to_vec();
+            definitions.retain(|existing| {
+                existing.name != definition.name && !existing.shares_context_with(&definition)
+            });
+            definitions.push(definition.clone());
+            definitions.sort_by(|a, b| a.name.cmp(&b.name));
+            self.set(path, definitions);
+        }
+        Ok(())
+    }
+
+    /// Insert all `definitions` into the macro registry, like [`Self::insert`]. Either all of them
+    /// are inserted, or none if any of them conflicts with the registry or with the others.
+    pub fn merge(
+        &mut self,
+        definitions: impl IntoIterator<Item = Definition>,
+    ) -> Result<(), Conflict> {
+        let mut merged = self.clone();
+        definitions.into_iter().try_for_each(|definition| merged.insert(definition))?;
+        *self = merged;
+        Ok(())
+    }
+
+    /// The first conflict of `definition` with the definitions in the registry. Only the
+    /// definitions sharing a context with it can conflict.
+    fn conflict(&self, definition: &Definition) -> Option<Conflict> {
+        let paths = definition.paths();
+        let is_replaced = |path: &[Literal]| paths.iter().any(|p| p == path);
+        let conflicts = |existing: &&Definition| {
+            existing.name != definition.name && existing.shares_context_with(definition)
+        };
+        let conflict = |kind, path: &[Literal], existing: &Definition| {
+            let path = path.to_vec();
+            let inserted = definition.name.clone();
+            let existing = existing.name.clone();
+            Some(Conflict { kind, path, inserted, existing })
+        };
+        for path in &paths {
+            let existing = self.definitions(path).iter().find(&conflicts);
+            if let Some(existing) = existing.filter(|_| !self.is_shadowed_in_scope(path)) {
+                return conflict(ConflictKind::SamePath, path, existing);
+            }
+            for prefix in (1..path.len()).map(|len| &path[..len]) {
+                let mut existing = self.definitions(prefix).iter().filter(&conflicts);
+                let closed = existing.find(|d| is_closed_at(d, prefix) && !is_replaced(prefix));
+                if let Some(existing) = closed {
+                    return conflict(ConflictKind::Closing, prefix, existing);
+                }
+            }
+            if is_closed_at(definition, path) {
+                let node = self.subtree(path);
+                let continuations = node.into_iter().flat_map(|node| node.branches.values());
+                let mut definitions = continuations.flat_map(|node| node.iter());
+                let existing =
+                    definitions.find_map(|(_, existing)| existing.iter().find(&conflicts));
+                if let Some(existing) = existing {
+                    return conflict(ConflictKind::Closing, path, existing);
+                }
+            }
+        }
+        let head = definition.sections.first()?.start_symbol().clone();
+        let node = self.subtree(iter::once(&head))?;
+        let existing = node.iter().find_map(|(path, existing)| {
+            let path = iter::once(&head).chain(path).cloned().collect_vec();
+            let mut existing = existing.iter().filter(&conflicts);
+            existing.find(|existing| existing.form != definition.form && !is_replaced(&path))
+        })?;
+        conflict(ConflictKind::Form, &[head], existing)
+    }
+
+    /// Check if the definition at `path` comes from an enclosing scope, so it can be shadowed in
+    /// the current one.
+    fn is_shadowed_in_scope(&self, path: &[Literal]) -> bool {
+        let scope = self.scopes.last();
+        scope.map_or(false, |scope| scope.iter().all(|(replaced, _)| replaced != path))
+    }
+
+    /// Remove the definition named `name` from the macro registry, returning it.
+    pub fn remove(&mut self, name: &str) -> Option<Definition> {
+        let paths = self.tree.iter().filter_map(|(path, definitions)| {
+            let matches = definitions.iter().any(|definition| definition.name == name);
+            matches.as_some_from(|| path.into_iter().cloned().collect_vec())
+        });
+        let paths = paths.collect_vec();
+        let mut removed = None;
+        for path in paths {
+            let definitions = self.definitions(&path).iter().cloned();
+            let (matching, rest): (Vec<_>, _) = definitions.partition(|d| d.name == name);
+            removed = matching.into_iter().next().or(removed);
+            self.set(path, rest);
+        }
+        removed
+    }
+
+    /// Open a new scope. The changes made in the scope are reverted by [`Self::pop_scope`].
+    pub fn push_scope(&mut self) {
+        self.scopes.push(default());
+    }
+
+    /// Close the most recently opened scope, restoring the definitions inserted or removed in it.
+    /// Does nothing if there is no open scope.
+    pub fn pop_scope(&mut self) {
+        if let Some(shadowed) = self.scopes.pop() {
+            for (path, definitions) in shadowed.into_iter().rev() {
+                self.set(path, definitions);
+            }
+        }
+    }
+
+    /// Run `f` with `definition` inserted into the macro registry in a new scope.
+    pub fn with_definition<T>(
+        &mut self,
+        definition: Definition,
+        f: impl FnOnce(&Self) -> T,
+    ) -> Result<T, Conflict> {
+        self.push_scope();
+        let result = self.insert(definition).map(|()| f(self));
+        self.pop_scope();
+        result
+    }
+
+    /// Iterator over all definitions in the registry.
+    pub fn iter(&self) -> impl Iterator<Item = &Definition> {
+        let definitions = self.tree.iter().flat_map(|(_, definitions)| definitions);
+        definitions.unique_by(|definition| &definition.name)
+    }
+
+    /// All definitions whose opening sections are matched by the partial invocation in the
+    /// `tokens`, like `if x`, in the order of their names. The tokens are matched like by the
+    /// resolver, so the definitions of the innermost invocation which is not yet complete are
+    /// returned. Empty if the tokens do not start any invocation. The definitions of all contexts
+    /// are matched.
+    pub fn lookup_prefix(&self, tokens: &[Token]) -> Vec<&Definition> {
+        let mut definitions = match self.prefix_match(tokens) {
+            Some((node, _)) => node.iter().flat_map(|(_, definitions)| definitions).collect(),
+            None => vec![],
+        };
+        definitions.sort_by(|a, b| a.name.cmp(&b.name));
+        definitions.dedup_by(|a, b| a.name == b.name);
+        definitions
+    }
+
+    /// The literals which can head the next section of the partial invocation in the `tokens`, like
+    /// `then` after `if x`, in order. See [`Self::lookup_prefix`].
+    pub fn expected_heads(&self, tokens: &[Token]) -> Vec<Literal> {
+        let mut heads = match self.prefix_match(tokens) {
+            Some((node, last_head)) => {
+                let mut definitions = node.iter().flat_map(|(_, definitions)| definitions);
+                let repeats = definitions.any(|definition| definition.repeats(last_head));
+                let repeated = repeats.as_some_from(|| last_head.clone());
+                node.branches.keys().cloned().chain(repeated).collect_vec()
+            }
+            None => vec![],
+        };
+        heads.sort();
+        heads.dedup();
+        heads
+    }
+
+    /// The node of the innermost invocation started in the `tokens`, together with its last matched
+    /// section head. The invocations are continued by the heads of their next or repeated
+    /// sections, and the invocations nested in them end when they are continued. The invocations
+    /// end by their closing sections as well.
+    fn prefix_match(&self, tokens: &[Token]) -> Option<(&Tree, &Literal)> {
+        let mut stack: Vec<(&Tree, &Literal)> = default();
+        let literals = tokens.iter().filter_map(|token| Literal::try_from(&token.shape).ok());
+        for literal in literals {
+            let continued = stack.iter().rposition(|(node, last_head)| {
+                let repeats = || {
+                    let mut definitions = node.iter().flat_map(|(_, definitions)| definitions);
+                    *last_head == &literal && definitions.any(|d| d.repeats(&literal))
+                };
+                node.branches.contains_key(&literal) || repeats()
+            });
+            if let Some(depth) = continued {
+                stack.truncate(depth + 1);
+                let (node, last_head) = stack.last_mut().expect("Missing continued invocation.");
+                if let Some((head, next)) = node.branches.get_key_value(&literal) {
+                    *node = next;
+                    *last_head = head;
+                }
+            } else if let Some((head, node)) = self.tree.branches.get_key_value(&literal) {
+                stack.push((node, head));
+            }
+            let is_closed = |node: &Tree| {
+                let mut last_sections = node.value.iter().filter_map(|d| d.sections.last());
+                node.is_leaf() && last_sections.any(|section| section.is_closing())
+            };
+            if stack.last().map_or(false, |(node, _)| is_closed(node)) {
+                stack.pop();
+            }
+        }
+        stack.pop()
     }
 
     /// Get a reference to the root of the registry.
@@ -53,15 +272,29 @@ impl Registry {
         self.subtree(path).expect("A tree exists at the input path.")
     }
 
-    /// Query the registry for a definition.
+    /// Query the registry for the definitions at a path, active in disjoint contexts.
+    pub fn definitions<P>(&self, path: P) -> &[Definition]
+    where
+        P: IntoIterator,
+        P::Item: Into<Literal>, {
+        self.tree.get(path).map_or(&[], |definitions| &definitions[..])
+    }
+
+    /// Query the registry for a definition. The first one by name is returned if there are
+    /// definitions at the path active in different contexts, see [`Self::definition_in`].
     pub fn definition<P>(&self, path: P) -> Option<&Definition>
     where
         P: IntoIterator,
         P::Item: Into<Literal>, {
-        match self.tree.get(path) {
-            Some(Some(def)) => Some(def),
-            _ => None,
-        }
+        self.definitions(path).first()
+    }
+
+    /// Query the registry for the definition active in the `context`.
+    pub fn definition_in<P>(&self, path: P, context: Context) -> Option<&Definition>
+    where
+        P: IntoIterator,
+        P::Item: Into<Literal>, {
+        self.definitions(path).iter().find(|definition| definition.is_active_in(context))
     }
 
     /// Query the registry for a definition, assuming such a definition is present.
@@ -74,21 +307,115 @@ impl Registry {
         P::Item: Into<Literal>, {
         self.definition(path).expect("A definition exists at the input path.")
     }
+
+    /// Set the definitions at `path`, returning the replaced ones. The nodes left without
+    /// definitions and branches are removed, so they are not matched by the resolver.
+    fn set(&mut self, path: Vec<Literal>, definitions: Vec<Definition>) -> Vec<Definition> {
+        let replaced = if definitions.is_empty() {
+            let replaced = self.tree.get_mut(&path).map(mem::take).unwrap_or_default();
+            prune(&mut self.tree, &path);
+            replaced
+        } else {
+            mem::replace(&mut self.tree.get_or_create_node(&path).value, definitions)
+        };
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push((path, replaced.clone()));
+        }
+        replaced
+    }
+}
+
+/// Check if the `path` ends with the closing section of the `definition`.
+fn is_closed_at(definition: &Definition, path: &[Literal]) -> bool {
+    let closing = definition.sections.last().filter(|section| section.is_closing());
+    closing.map_or(false, |section| path.last() == Some(section.start_symbol()))
+}
+
+/// Remove the nodes along `path` which have no definition and no branches.
+fn prune(node: &mut Tree, path: &[Literal]) {
+    if let Some((head, rest)) = path.split_first() {
+        if let Some(child) = node.branches.get_mut(head) {
+            prune(child, rest);
+            if child.value.is_empty() && child.is_leaf() {
+                node.branches.remove(head);
+            }
+        }
+    }
 }
 
 
 // === Trait Impls ===
 
-impl From<Vec<Definition>> for Registry {
-    fn from(defs: Vec<Definition>) -> Self {
+impl From<Registry> for Vec<Definition> {
+    fn from(registry: Registry) -> Self {
+        let mut definitions = registry.iter().cloned().collect_vec();
+        definitions.sort_by(|a, b| a.name.cmp(&b.name));
+        definitions
+    }
+}
+
+impl TryFrom<Vec<Definition>> for Registry {
+    type Error = Conflict;
+
+    fn try_from(defs: Vec<Definition>) -> Result<Self, Self::Error> {
         let mut registry: Registry = default();
-        defs.into_iter().for_each(|def| registry.insert(def));
-        registry
+        defs.into_iter().try_for_each(|def| registry.insert(def))?;
+        Ok(registry)
     }
 }
 
 
 
+// ================
+// === Conflict ===
+// ================
+
+/// A definition which cannot be inserted into the registry, as the resolution of its invocations
+/// would be ambiguous.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub struct Conflict {
+    pub kind:     ConflictKind,
+    /// The section heads the definitions collide at.
+    pub path:     Vec<Literal>,
+    /// The name of the definition being inserted.
+    pub inserted: String,
+    /// The name of the definition in the registry.
+    pub existing: String,
+}
+
+/// The kinds of [`Conflict`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConflictKind {
+    /// Both definitions match the same sections in the same scope.
+    SamePath,
+    /// One definition ends with a closing section, and the other continues it.
+    Closing,
+    /// The definitions start with the same section, but are of different forms, so the items
+    /// consumed by their invocations differ.
+    Form,
+}
+
+
+// === Trait Impls ===
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.path.iter().join(" ");
+        let reason = match self.kind {
+            ConflictKind::SamePath => "both match the same sections",
+            ConflictKind::Closing => "one continues the closing section of the other",
+            ConflictKind::Form => "they are of different forms",
+        };
+        let (inserted, existing) = (&self.inserted, &self.existing);
+        write!(f, "The macro `{inserted}` conflicts with `{existing}` at `{path}`: {reason}.")
+    }
+}
+
+impl std::error::Error for Conflict {}
+
+
+
 // =============
 // === Tests ===
 // =============
@@ -96,20 +423,16 @@ impl From<Vec<Definition>> for Registry {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::macros::definition::Section;
+    use crate::macro_definition;
 
     #[test]
     fn insert_query() {
         let mut registry = Registry::default();
-        let definition = Definition::new("Test", vec![
-            Section::new(Literal::variable("if")),
-            Section::new(Literal::variable("then")),
-            Section::new(Literal::variable("else")),
-        ]);
+        let definition = macro_definition!(Test: if then else);
         let path_1 =
             &[Literal::variable("if"), Literal::variable("then"), Literal::variable("else")];
         let path_2 = &[Literal::variable("if"), Literal::variable("then")];
-        registry.insert(definition.clone());
+        registry.insert(definition.clone()).unwrap();
         let result_1 = registry.definition(path_1);
         let result_2 = registry.definition(path_2);
         assert!(result_1.is_some());
@@ -120,21 +443,11 @@ mod tests {
     #[test]
     fn from_defs() {
         let definitions = vec![
-            Definition::new("if_then_else", vec![
-                Section::new(Literal::variable("if")),
-                Section::new(Literal::variable("then")),
-                Section::new(Literal::variable("else")),
-            ]),
-            Definition::new("if_then", vec![
-                Section::new(Literal::variable("if")),
-                Section::new(Literal::variable("then")),
-            ]),
-            Definition::new("if_let", vec![
-                Section::new(Literal::variable("if")),
-                Section::new(Literal::variable("let")),
-            ]),
+            macro_definition!(if_then_else: if then else),
+            macro_definition!(if_then: if then),
+            macro_definition!(if_let: if let),
         ];
-        let registry = Registry::from(definitions.clone());
+        let registry = Registry::try_from(definitions.clone()).unwrap();
         let path_1 =
             &[Literal::variable("if"), Literal::variable("then"), Literal::variable("else")];
         let path_2 = &[Literal::variable("if"), Literal::variable("then")];
@@ -152,4 +465,118 @@ mod tests {
         assert_eq!(result_2, definitions.get(1));
         assert_eq!(result_3, definitions.get(2));
     }
+
+    #[test]
+    fn remove_and_shadow() {
+        let if_then = macro_definition!(if_then: if then);
+        let if_then_else = macro_definition!(if_then_else: if then else);
+        let mut registry = Registry::try_from(vec![if_then.clone(), if_then_else.clone()]).unwrap();
+        let path = if_then.path();
+        let shadowing = Definition::new("shadowing", if_then.sections.clone());
+        let result = registry.with_definition(shadowing.clone(), |registry| {
+            assert_eq!(registry.definition(&path), Some(&shadowing));
+        });
+        assert!(result.is_ok());
+        assert_eq!(registry.definition(&path), Some(&if_then));
+        registry.push_scope();
+        assert_eq!(registry.remove("if_then_else"), Some(if_then_else.clone()));
+        assert_eq!(registry.remove("if_then"), Some(if_then.clone()));
+        assert!(registry.root().is_leaf());
+        registry.pop_scope();
+        assert_eq!(registry.definition(&path), Some(&if_then));
+        assert_eq!(registry.definition(&if_then_else.path()), Some(&if_then_else));
+        assert_eq!(registry.remove("if_then_else"), Some(if_then_else));
+        assert!(registry.unsafe_subtree(&path).is_leaf());
+        assert_eq!(registry.remove("missing"), None);
+    }
+
+    #[test]
+    fn optional_sections() {
+        let definition = macro_definition!(if_then_else: if then else?);
+        let mut registry = Registry::try_from(vec![definition.clone()]).unwrap();
+        let if_then = [Literal::variable("if"), Literal::variable("then")];
+        assert_eq!(registry.definition(&if_then), Some(&definition));
+        assert_eq!(registry.definition(&definition.path()), Some(&definition));
+        assert_eq!(registry.iter().count(), 1);
+        assert_eq!(registry.remove("if_then_else"), Some(definition));
+        assert!(registry.root().is_leaf());
+    }
+
+    #[test]
+    fn detect_conflicts() {
+        let if_then = macro_definition!(if_then: if then);
+        let mut registry = Registry::try_from(vec![if_then.clone()]).unwrap();
+        let if_then_2 = macro_definition!(if_then_2: if then);
+        let conflict = registry.insert(if_then_2.clone()).unwrap_err();
+        assert_eq!(conflict.kind, ConflictKind::SamePath);
+        assert_eq!(conflict.path, if_then.path());
+        assert_eq!(conflict.existing, "if_then");
+        let result = registry.with_definition(if_then_2, |registry| registry.iter().count());
+        assert_eq!(result, Ok(1));
+        let lambda = macro_definition!(lambda: _ if);
+        assert_eq!(registry.insert(lambda).unwrap_err().kind, ConflictKind::Form);
+        registry.insert(macro_definition!(group: "(" ")"!)).unwrap();
+        let conflict = registry.insert(macro_definition!(call: "(" ")" then)).unwrap_err();
+        assert_eq!(conflict.kind, ConflictKind::Closing);
+        assert_eq!(
+            conflict.to_string(),
+            "The macro `call` conflicts with `group` at `( )`: one \
+            continues the closing section of the other."
+        );
+        assert_eq!(registry.definition(&if_then.path()), Some(&if_then));
+    }
+
+    #[test]
+    fn contextual_definitions() {
+        let lambda = macro_definition!(lambda: _ "->").with_contexts(vec![Context::Expression]);
+        let arrow = macro_definition!(arrow: _ "->").with_contexts(vec![Context::TypeSignature]);
+        let mut registry = Registry::try_from(vec![lambda.clone(), arrow.clone()]).unwrap();
+        let path = lambda.path();
+        assert_eq!(registry.definitions(&path), &[arrow.clone(), lambda.clone()]);
+        assert_eq!(registry.definition_in(&path, Context::Expression), Some(&lambda));
+        assert_eq!(registry.definition_in(&path, Context::Pattern), None);
+        let anywhere = macro_definition!(anywhere: _ "->");
+        let conflict = registry.insert(anywhere).unwrap_err();
+        assert_eq!((conflict.kind, conflict.existing.as_str()), (ConflictKind::SamePath, "arrow"));
+        let pattern = arrow.with_contexts(vec![Context::Pattern, Context::TypeSignature]);
+        registry.insert(pattern.clone()).unwrap();
+        assert_eq!(registry.definitions(&path), &[pattern, lambda.clone()]);
+        registry.remove("arrow");
+        assert_eq!(registry.definitions(&path), &[lambda]);
+    }
+
+    #[test]
+    fn lookup_by_prefix() {
+        let definitions = vec![
+            macro_definition!(if_then_else: if then else?),
+            macro_definition!(if_let: if let),
+            macro_definition!(list: "[" ","* "]"!),
+        ];
+        let registry = Registry::try_from(definitions).unwrap();
+        let tokens = |source: &str| -> Vec<Token> { ::lexer::run(source).tokens.into() };
+        let names = |tokens: &[Token]| {
+            registry.lookup_prefix(tokens).into_iter().map(|d| d.name.as_str()).collect_vec()
+        };
+        assert_eq!(names(&tokens("if x")), vec!["if_let", "if_then_else"]);
+        assert_eq!(names(&tokens("if x then y")), vec!["if_then_else"]);
+        assert!(names(&tokens("x y")).is_empty());
+        let heads = registry.expected_heads(&tokens("if x"));
+        assert_eq!(heads, vec![Literal::variable("let"), Literal::variable("then")]);
+        let heads = registry.expected_heads(&tokens("if a then [b , c"));
+        assert_eq!(heads, vec![Literal::operator(","), Literal::operator("]")]);
+        let heads = registry.expected_heads(&tokens("if a then [b]"));
+        assert_eq!(heads, vec![Literal::variable("else")]);
+    }
+
+    #[test]
+    fn serialization() {
+        let registry = Registry::try_from(crate::macros::builtin()).unwrap();
+        let serialized = serde_json::to_string(&registry).unwrap();
+        let deserialized: Registry = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, registry);
+        let duplicated =
+            format!("[{0},{0}]", serde_json::to_string(&registry.iter().next()).unwrap());
+        let renamed = duplicated.replacen("\"name\":\"", "\"name\":\"renamed_", 1);
+        assert!(serde_json::from_str::<Registry>(&renamed).is_err());
+    }
 }
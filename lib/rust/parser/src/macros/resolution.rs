@@ -0,0 +1,156 @@
+//! The structures produced by the macro resolution. See [`crate::macros::visit`] for their
+//! traversals.
+
+use crate::prelude::*;
+
+use crate::macros::definition::Definition;
+use crate::macros::diagnostic::Diagnostic;
+use crate::prelude::lexer::token::Token;
+use crate::span::Span;
+
+
+
+// ============
+// === Item ===
+// ============
+
+/// An element of a resolved token stream.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Item {
+    /// A token which is not a section head of any macro.
+    Token(Token),
+    /// A resolved macro invocation.
+    Macro(Match),
+    /// A block of lines, resolved in its own scope.
+    Block(Block),
+}
+
+impl Item {
+    /// The number of tokens making up the item. A block is a single token.
+    pub fn token_count(&self) -> usize {
+        match self {
+            Item::Token(_) | Item::Block(_) => 1,
+            Item::Macro(matched) => matched.token_count(),
+        }
+    }
+}
+
+
+
+// =============
+// === Match ===
+// =============
+
+/// A resolved macro invocation.
+///
+/// The body of every segment extends up to the head of the next segment. The body of the last one
+/// extends up to the end of the enclosing segment or stream, or up to an operator binding looser
+/// than the macro, see [`Definition`]. The invocations of macros nested in the bodies are
+/// resolved as well.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Match {
+    /// The definition of the matched macro.
+    pub definition:    Definition,
+    /// The items preceding the first segment head, consumed by the macros of the
+    /// [infix form](crate::macros::definition::Form::Infix). Empty for the prefix form.
+    pub prefix:        Vec<Item>,
+    /// The matched segments, one for every matched section of the definition, and one for every
+    /// repetition of a [repeated](crate::macros::definition::Section::repeated) section.
+    pub segments:      Vec<Segment>,
+    /// Whether all sections of the definition were matched. An invocation matching only the first
+    /// sections by the end of the stream is recovered from as an incomplete invocation of the
+    /// shortest definition it could be continued to, and reported in a [`Diagnostic`].
+    pub complete:      bool,
+    /// The span of the tokens of the invocation, including the prefix.
+    pub span:          Span,
+    /// The documentation comment preceding the line of a block starting with the invocation, see
+    /// [`crate::macros::documentation`].
+    pub documentation: Option<String>,
+}
+
+impl Match {
+    /// Iterator over the tokens heading the segments.
+    pub fn heads(&self) -> impl Iterator<Item = &Token> {
+        self.segments.iter().map(|segment| &segment.head)
+    }
+
+    /// The number of tokens making up the invocation.
+    pub fn token_count(&self) -> usize {
+        let segments = self.segments.iter().map(|segment| 1 + items_token_count(&segment.body));
+        items_token_count(&self.prefix) + segments.sum::<usize>()
+    }
+}
+
+
+/// The number of tokens making up the `items`.
+fn items_token_count(items: &[Item]) -> usize {
+    items.iter().map(Item::token_count).sum()
+}
+
+
+
+// ===============
+// === Segment ===
+// ===============
+
+/// A matched section of a macro invocation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Segment {
+    /// The token matching the literal heading the section.
+    pub head: Token,
+    /// The items following the head.
+    pub body: Vec<Item>,
+    /// The span of the head and the body.
+    pub span: Span,
+}
+
+impl Segment {
+    /// Constructor of a segment with an empty body, spanning the head.
+    pub fn new(head: Token, span: Span) -> Self {
+        let body = default();
+        Self { head, body, span }
+    }
+}
+
+
+
+// =============
+// === Block ===
+// =============
+
+/// A resolved block of lines.
+///
+/// The macros defined in a block, see [`crate::macros::source`], apply to the following lines of
+/// the block and to the blocks nested in them, but not outside of the block. The lines defining the
+/// macros are left unresolved.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Block {
+    /// The token of the block.
+    pub token: Token,
+    /// The resolved items of the lines of the block, without the blank lines.
+    pub lines: Vec<Vec<Item>>,
+    /// The span of the block.
+    pub span:  Span,
+}
+
+
+
+// ==================
+// === Resolution ===
+// ==================
+
+/// The result of resolving a token stream.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Resolution {
+    /// The resolved items.
+    pub items:       Vec<Item>,
+    /// The problems found in the stream.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Resolution {
+    /// Check if the stream was resolved without problems.
+    pub fn is_complete(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+}
@@ -0,0 +1,179 @@
+//! Loading macro definitions from Enso source code, so syntax extensions can be shipped in
+//! libraries.
+//!
+//! A macro is defined by a top-level line of the form `macro <name> <section heads>`, for example
+//! `macro if_then_else if then else`. A blank preceding the section heads defines a macro of the
+//! [infix form](crate::macros::definition::Form::Infix), for example `macro lambda _ ->`. All other
+//! lines are ignored.
+
+use crate::prelude::*;
+
+use crate::macros::definition::Definition;
+use crate::macros::definition::Section;
+use crate::macros::documentation;
+use crate::macros::literal::Literal;
+use crate::macros::registry::Conflict;
+use crate::prelude::lexer::token;
+use crate::prelude::lexer::token::Token;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The keyword starting a macro definition.
+pub const KEYWORD: &str = "macro";
+
+
+
+// =============
+// === Error ===
+// =============
+
+/// Errors that arise when loading macro definitions. The line numbers count the top-level lines of
+/// the source from zero.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum Error {
+    /// The lexer did not succeed on the source.
+    Lexing,
+    /// The definition has no name.
+    MissingName { line: usize },
+    /// The definition has no sections.
+    MissingSections { line: usize, name: String },
+    /// The token cannot head a section.
+    InvalidHead { line: usize, token: Token },
+    /// The definition conflicts with a defined macro.
+    Conflict(Conflict),
+}
+
+
+// === Trait Impls ===
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Lexing => write!(f, "The macro definitions could not be lexed."),
+            Error::MissingName { line } => write!(f, "Line {}: the macro has no name.", line),
+            Error::MissingSections { line, name } =>
+                write!(f, "Line {}: the macro `{}` has no sections.", line, name),
+            Error::InvalidHead { line, token } =>
+                write!(f, "Line {}: `{:?}` cannot head a macro section.", line, token.shape),
+            Error::Conflict(conflict) => write!(f, "{}", conflict),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+
+
+// ===============
+// === Loading ===
+// ===============
+
+/// Load the macro definitions from the Enso `source`.
+pub fn definitions(source: &str) -> Result<Vec<Definition>, Error> {
+    let result = ::lexer::run(source);
+    match result.kind {
+        enso_flexer::ResultKind::Success => definitions_in(&result.tokens),
+        _ => Err(Error::Lexing),
+    }
+}
+
+/// Load the macro definitions from the lexed Enso source. The source consisting of a single line is
+/// lexed into the tokens of the line, while the longer one into a block of lines. The definitions
+/// are documented by the documentation comments preceding them.
+pub fn definitions_in(tokens: &[Token]) -> Result<Vec<Definition>, Error> {
+    let lines = match tokens {
+        [Token { shape: token::Shape::Block { lines, .. }, .. }] => lines
+            .iter()
+            .map(|line| match &line.shape {
+                token::Shape::Line { tokens, .. } => &tokens[..],
+                _ => &[],
+            })
+            .collect_vec(),
+        line => vec![line],
+    };
+    let mut definitions = Vec::new();
+    let mut documentation = None;
+    for (index, line) in lines.into_iter().enumerate() {
+        let line_documentation = mem::replace(&mut documentation, documentation::of_line(line));
+        if let Some(mut definition) = definition(index, line)? {
+            definition.documentation = line_documentation;
+            definitions.push(definition);
+        }
+    }
+    Ok(definitions)
+}
+
+/// Load the macro definition from the `line` with the `index`, if it defines one.
+pub(crate) fn definition(index: usize, line: &[Token]) -> Result<Option<Definition>, Error> {
+    match line {
+        [keyword, rest @ ..] if keyword.shape == token::Shape::variable(KEYWORD) => {
+            let (name, heads) = match rest {
+                [Token { shape: token::Shape::Variable(name), .. }, heads @ ..] => (name, heads),
+                _ => return Err(Error::MissingName { line: index }),
+            };
+            let (infix, heads) = match heads {
+                [Token { shape: token::Shape::Blank, .. }, heads @ ..] => (true, heads),
+                _ => (false, heads),
+            };
+            if heads.is_empty() {
+                return Err(Error::MissingSections { line: index, name: name.clone() });
+            }
+            let section = |token: &Token| match Literal::try_from(&token.shape) {
+                Ok(literal) => Ok(Section::new(literal)),
+                Err(()) => Err(Error::InvalidHead { line: index, token: token.clone() }),
+            };
+            let sections = heads.iter().map(section).collect::<Result<_, _>>()?;
+            let definition = if infix {
+                Definition::infix(name, sections)
+            } else {
+                Definition::new(name, sections)
+            };
+            Ok(Some(definition))
+        }
+        _ => Ok(None),
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::macros::definition::Form;
+
+    #[test]
+    fn load_definitions() {
+        let source = "macro if_then_else if then else\nfoo = bar\n## Lambda.\nmacro lambda _ ->\n";
+        let definitions = definitions(source).unwrap();
+        assert_eq!(definitions.len(), 2);
+        let if_then_else = &definitions[0];
+        assert_eq!(if_then_else.name, "if_then_else");
+        assert_eq!(if_then_else.form, Form::Prefix);
+        let heads = ["if", "then", "else"].into_iter().map(Literal::variable).collect_vec();
+        assert_eq!(if_then_else.path(), heads);
+        let lambda = &definitions[1];
+        assert_eq!(lambda.form, Form::Infix);
+        assert_eq!(lambda.documentation.as_deref(), Some("Lambda."));
+        assert_eq!(if_then_else.documentation, None);
+        assert_eq!(lambda.path(), vec![Literal::operator("->")]);
+    }
+
+    #[test]
+    fn invalid_definitions() {
+        assert_eq!(definitions("macro"), Err(Error::MissingName { line: 0 }));
+        let missing_sections = Error::MissingSections { line: 1, name: "foo".into() };
+        assert_eq!(definitions("a\nmacro foo _"), Err(missing_sections));
+        let invalid_head = definitions("macro foo if 12");
+        assert!(matches!(invalid_head, Err(Error::InvalidHead { line: 0, .. })));
+    }
+}
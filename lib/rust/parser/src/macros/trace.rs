@@ -0,0 +1,120 @@
+//! The trace of the macro resolution, recording every matching decision of the resolver, for
+//! debugging the resolution of new macro definitions.
+
+use crate::prelude::*;
+
+use crate::macros::literal::Literal;
+
+
+
+// =============
+// === Trace ===
+// =============
+
+/// The matching decisions made during a resolution, in order. It is displayed as a tree of the
+/// invocations, one decision per line.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Trace {
+    /// The recorded decisions.
+    pub records: Vec<Record>,
+}
+
+impl Trace {
+    /// Iterator over the recorded events.
+    pub fn events(&self) -> impl Iterator<Item = &Event> {
+        self.records.iter().map(|record| &record.event)
+    }
+}
+
+impl Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for record in &self.records {
+            writeln!(f, "{:indent$}{}", "", record.event, indent = 2 * record.depth)?;
+        }
+        Ok(())
+    }
+}
+
+
+
+// ==============
+// === Record ===
+// ==============
+
+/// A single matching decision.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Record {
+    /// The nesting depth of the invocation the decision is about. The continuations are recorded
+    /// one level deeper than the invocations they continue.
+    pub depth: usize,
+    /// The decision.
+    pub event: Event,
+}
+
+
+
+// =============
+// === Event ===
+// =============
+
+/// A matching decision of the resolver. The tokens are identified by their indices in the
+/// resolved stream.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum Event {
+    /// An invocation was started by the token at the `index`, heading the first section. The
+    /// invocation starts with the token at the `start` index, which precedes the head for the
+    /// macros of the infix form.
+    Start { head: Literal, index: usize, start: usize },
+    /// An invocation was continued by the token at the `index`, heading its next section.
+    Continue { head: Literal, index: usize },
+    /// An invocation of the `definition` was resolved.
+    End { definition: String, tokens: Range<usize>, reason: EndReason },
+    /// An invocation did not match a full definition by the end of the stream, so it was recovered
+    /// from as an incomplete one.
+    Incomplete { tokens: Range<usize> },
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Start { head, index, start } =>
+                write!(f, "Start `{}` at {}, consuming tokens from {}.", head, index, start),
+            Event::Continue { head, index } => write!(f, "Continue `{}` at {}.", head, index),
+            Event::End { definition, tokens, reason } =>
+                write!(f, "End `{}` over tokens {:?}, {}.", definition, tokens, reason),
+            Event::Incomplete { tokens } => write!(f, "Incomplete over tokens {:?}.", tokens),
+        }
+    }
+}
+
+
+
+// =================
+// === EndReason ===
+// =================
+
+/// The reason for ending an invocation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EndReason {
+    /// An enclosing invocation was continued.
+    Continuation,
+    /// An operator binding looser than the macro followed.
+    Operator,
+    /// The last section of the macro is closing.
+    ClosingSection,
+    /// The stream ended.
+    EndOfStream,
+}
+
+impl Display for EndReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            EndReason::Continuation => "as an enclosing invocation continued",
+            EndReason::Operator => "as an operator binding looser followed",
+            EndReason::ClosingSection => "as the section is closing",
+            EndReason::EndOfStream => "as the stream ended",
+        };
+        write!(f, "{}", description)
+    }
+}
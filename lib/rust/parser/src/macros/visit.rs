@@ -0,0 +1,215 @@
+//! The traversals of the structures produced by the macro resolution, so the later passes can
+//! process them without matching on all of their variants.
+//!
+//! A [`Visitor`] traverses the structures by reference, and a [`Fold`] rebuilds them. Every method
+//! of the traits handles a single kind of node, and by default descends into its children with the
+//! corresponding `walk` or `fold` function of this module. An implementation overrides only the
+//! methods of the nodes it is interested in, and calls the default function to continue the
+//! traversal below them.
+
+use crate::macros::resolution::Block;
+use crate::macros::resolution::Item;
+use crate::macros::resolution::Match;
+use crate::macros::resolution::Resolution;
+use crate::macros::resolution::Segment;
+use crate::prelude::lexer::token::Token;
+
+
+
+// ===============
+// === Visitor ===
+// ===============
+
+/// A traversal of the resolved items by reference.
+#[allow(missing_docs)]
+pub trait Visitor {
+    fn visit_resolution(&mut self, resolution: &Resolution) {
+        walk_items(self, &resolution.items)
+    }
+
+    fn visit_item(&mut self, item: &Item) {
+        walk_item(self, item)
+    }
+
+    fn visit_token(&mut self, _token: &Token) {}
+
+    fn visit_match(&mut self, matched: &Match) {
+        walk_match(self, matched)
+    }
+
+    fn visit_segment(&mut self, segment: &Segment) {
+        walk_segment(self, segment)
+    }
+
+    fn visit_block(&mut self, block: &Block) {
+        walk_block(self, block)
+    }
+}
+
+/// Visit each of the `items`.
+pub fn walk_items<V: Visitor + ?Sized>(visitor: &mut V, items: &[Item]) {
+    for item in items {
+        visitor.visit_item(item);
+    }
+}
+
+/// Visit the node making up the `item`.
+pub fn walk_item<V: Visitor + ?Sized>(visitor: &mut V, item: &Item) {
+    match item {
+        Item::Token(token) => visitor.visit_token(token),
+        Item::Macro(matched) => visitor.visit_match(matched),
+        Item::Block(block) => visitor.visit_block(block),
+    }
+}
+
+/// Visit the prefix and the segments of the `matched` invocation.
+pub fn walk_match<V: Visitor + ?Sized>(visitor: &mut V, matched: &Match) {
+    walk_items(visitor, &matched.prefix);
+    for segment in &matched.segments {
+        visitor.visit_segment(segment);
+    }
+}
+
+/// Visit the head and the body of the `segment`.
+pub fn walk_segment<V: Visitor + ?Sized>(visitor: &mut V, segment: &Segment) {
+    visitor.visit_token(&segment.head);
+    walk_items(visitor, &segment.body);
+}
+
+/// Visit the items of the lines of the `block`, line by line.
+pub fn walk_block<V: Visitor + ?Sized>(visitor: &mut V, block: &Block) {
+    for line in &block.lines {
+        walk_items(visitor, line);
+    }
+}
+
+
+
+// ============
+// === Fold ===
+// ============
+
+/// A traversal of the resolved items rebuilding them.
+#[allow(missing_docs)]
+pub trait Fold {
+    fn fold_resolution(&mut self, resolution: Resolution) -> Resolution {
+        fold_resolution(self, resolution)
+    }
+
+    fn fold_item(&mut self, item: Item) -> Item {
+        fold_item(self, item)
+    }
+
+    fn fold_token(&mut self, token: Token) -> Token {
+        token
+    }
+
+    fn fold_match(&mut self, matched: Match) -> Match {
+        fold_match(self, matched)
+    }
+
+    fn fold_segment(&mut self, segment: Segment) -> Segment {
+        fold_segment(self, segment)
+    }
+
+    fn fold_block(&mut self, block: Block) -> Block {
+        fold_block(self, block)
+    }
+}
+
+/// Fold the items of the `resolution`.
+pub fn fold_resolution<F: Fold + ?Sized>(folder: &mut F, resolution: Resolution) -> Resolution {
+    let items = fold_items(folder, resolution.items);
+    Resolution { items, ..resolution }
+}
+
+/// Fold each of the `items`.
+pub fn fold_items<F: Fold + ?Sized>(folder: &mut F, items: Vec<Item>) -> Vec<Item> {
+    items.into_iter().map(|item| folder.fold_item(item)).collect()
+}
+
+/// Fold the node making up the `item`.
+pub fn fold_item<F: Fold + ?Sized>(folder: &mut F, item: Item) -> Item {
+    match item {
+        Item::Token(token) => Item::Token(folder.fold_token(token)),
+        Item::Macro(matched) => Item::Macro(folder.fold_match(matched)),
+        Item::Block(block) => Item::Block(folder.fold_block(block)),
+    }
+}
+
+/// Fold the prefix and the segments of the `matched` invocation.
+pub fn fold_match<F: Fold + ?Sized>(folder: &mut F, matched: Match) -> Match {
+    let prefix = fold_items(folder, matched.prefix);
+    let segments = matched.segments.into_iter().map(|segment| folder.fold_segment(segment));
+    let segments = segments.collect();
+    Match { prefix, segments, ..matched }
+}
+
+/// Fold the head and the body of the `segment`.
+pub fn fold_segment<F: Fold + ?Sized>(folder: &mut F, segment: Segment) -> Segment {
+    let head = folder.fold_token(segment.head);
+    let body = fold_items(folder, segment.body);
+    Segment { head, body, ..segment }
+}
+
+/// Fold the items of the lines of the `block`, line by line.
+pub fn fold_block<F: Fold + ?Sized>(folder: &mut F, block: Block) -> Block {
+    let lines = block.lines.into_iter().map(|line| fold_items(folder, line)).collect();
+    Block { lines, ..block }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::macros::builtin;
+    use crate::macros::Resolver;
+
+    /// Collects the names of the matched macros, in the order of their invocations.
+    #[derive(Debug, Default)]
+    struct Names {
+        names: Vec<String>,
+    }
+
+    impl Visitor for Names {
+        fn visit_match(&mut self, matched: &Match) {
+            self.names.push(matched.definition.name.clone());
+            walk_match(self, matched)
+        }
+    }
+
+    /// Unwraps the groups, replacing them with their bodies.
+    struct Ungroup;
+
+    impl Fold for Ungroup {
+        fn fold_item(&mut self, item: Item) -> Item {
+            match fold_item(self, item) {
+                Item::Macro(matched) if matched.definition.name == "group" => {
+                    let mut body = matched.segments.into_iter().next().unwrap().body;
+                    assert_eq!(body.len(), 1);
+                    body.remove(0)
+                }
+                item => item,
+            }
+        }
+    }
+
+    #[test]
+    fn visit_and_fold() {
+        let resolver = Resolver::new(builtin()).unwrap();
+        let resolution = resolver.resolve_source("f = if (a) then\n    x -> (import b)");
+        let mut names = Names::default();
+        names.visit_resolution(&resolution);
+        assert_eq!(names.names, vec!["if_then_else", "group", "lambda", "group", "import"]);
+        let resolution = Ungroup.fold_resolution(resolution);
+        let mut names = Names::default();
+        names.visit_resolution(&resolution);
+        assert_eq!(names.names, vec!["if_then_else", "lambda", "import"]);
+    }
+}
@@ -1 +1,79 @@
 //! Associativity inference for Enso.
+
+use serde::Deserialize;
+use serde::Serialize;
+
+
+
+// =====================
+// === Associativity ===
+// =====================
+
+/// The associativity of an operator, deciding the grouping of a chain of operators of the same
+/// precedence.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum Associativity {
+    /// The chain groups from the left, like `(a - b) - c`.
+    Left,
+    /// The chain groups from the right, like `a , (b , c)`.
+    Right,
+}
+
+impl Associativity {
+    /// Infer the associativity of the operator. Applicative operators, like `<*>`, are left
+    /// associative. Otherwise, the characters of the operator weigh towards either associativity,
+    /// with the left one winning ties.
+    pub fn of(operator: &str) -> Self {
+        let weight = operator.chars().map(char_weight).sum::<i32>();
+        if is_applicative(operator) || weight >= 0 {
+            Self::Left
+        } else {
+            Self::Right
+        }
+    }
+}
+
+impl Default for Associativity {
+    fn default() -> Self {
+        Self::Left
+    }
+}
+
+/// Check if the operator is applicative, like `<*>`, `<*`, or `$>`.
+pub fn is_applicative(operator: &str) -> bool {
+    let operator = operator.strip_prefix('<').unwrap_or(operator);
+    let operator = operator.strip_suffix('>').unwrap_or(operator);
+    matches!(operator, "+" | "*" | "$")
+}
+
+/// The weight of a character of an operator. Negative values weigh towards the right
+/// associativity, positive ones towards the left one.
+fn char_weight(char: char) -> i32 {
+    match char {
+        '=' | ',' | '>' => -1,
+        '<' => 1,
+        _ => 0,
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infer_associativity() {
+        assert_eq!(Associativity::of("+"), Associativity::Left);
+        assert_eq!(Associativity::of("<*>"), Associativity::Left);
+        assert_eq!(Associativity::of("*>"), Associativity::Left);
+        assert_eq!(Associativity::of("<<"), Associativity::Left);
+        assert_eq!(Associativity::of(","), Associativity::Right);
+        assert_eq!(Associativity::of("->"), Associativity::Right);
+        assert_eq!(Associativity::of("="), Associativity::Right);
+    }
+}
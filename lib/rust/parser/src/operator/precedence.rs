@@ -1 +1,62 @@
 //! Operator precedence levels.
+
+
+
+// ==================
+// === Precedence ===
+// ==================
+
+/// The operators of every precedence level, from the loosest to the tightest binding one.
+pub const HIERARCHY: &[&[&str]] = &[
+    &["=", "#="],
+    &[";"],
+    &[":="],
+    &[":"],
+    &["->", "<-"],
+    &["~>", "<~"],
+    &["!"],
+    &["in"],
+    &["<:", "~"],
+    &["|"],
+    &["&"],
+    &["\\"],
+    &["?"],
+    &["|>", "<|", ">>", "<<"],
+    &["<*", "<*>", "*>", "<$", "<$>", "$>", "<+", "<+>", "+>"],
+    &[","],
+    &["==", ">", "<", ">=", "<="],
+    &["+", "-"],
+    &["*", "/", "%"],
+    &["^"],
+    &["."],
+    &[" "],
+];
+
+/// The precedence of the operators missing from the [`HIERARCHY`], the one of `^`.
+pub const DEFAULT: usize = 19;
+
+/// The precedence level of the operator. The higher levels bind tighter.
+pub fn of(operator: &str) -> usize {
+    let level = HIERARCHY.iter().position(|operators| operators.contains(&operator));
+    level.unwrap_or(DEFAULT)
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precedence_levels() {
+        assert_eq!(of("^"), DEFAULT);
+        assert_eq!(of("<&>"), DEFAULT);
+        assert!(of("=") < of("->"));
+        assert!(of("+") < of("*"));
+        assert_eq!(of("+"), of("-"));
+    }
+}
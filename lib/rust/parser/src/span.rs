@@ -0,0 +1,120 @@
+//! Spans of the source code, mapping the parsing results back to the source text.
+
+use crate::prelude::*;
+
+use crate::prelude::lexer::token::Token;
+
+
+
+// ============
+// === Span ===
+// ============
+
+/// A range of the source code, in bytes.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[allow(missing_docs)]
+pub struct Span {
+    pub start: usize,
+    pub end:   usize,
+}
+
+impl Span {
+    /// Constructor.
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// The length of the span in bytes.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Check if the span is empty.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// The smallest span containing both spans.
+    pub fn join(self, other: Span) -> Span {
+        Span::new(self.start.min(other.start), self.end.max(other.end))
+    }
+
+    /// The span as a range, for indexing the source.
+    pub fn range(self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+impl From<Range<usize>> for Span {
+    fn from(range: Range<usize>) -> Self {
+        Span::new(range.start, range.end)
+    }
+}
+
+
+
+// =================
+// === Functions ===
+// =================
+
+/// The spans of the `tokens` of a stream, assuming every character of the source takes a single
+/// byte, which is the case for the ASCII sources. The spans do not include the offsets, which are
+/// the spaces preceding the tokens.
+pub fn of_tokens(tokens: &[Token]) -> Vec<Span> {
+    of_tokens_at(0, tokens)
+}
+
+/// The spans of the `tokens` of a stream starting at the `start` character of the source, in
+/// characters. See [`of_tokens`].
+pub fn of_tokens_at(start: usize, tokens: &[Token]) -> Vec<Span> {
+    let mut position = start;
+    let spans = tokens.iter().map(|token| {
+        let start = position + token.offset;
+        position += token.source_length();
+        Span::new(start, position)
+    });
+    spans.collect()
+}
+
+/// The spans of the `tokens` of a stream lexed from the `source`.
+pub fn of_tokens_in(source: &str, tokens: &[Token]) -> Vec<Span> {
+    let byte_offsets = byte_offsets(source);
+    of_tokens(tokens).into_iter().map(|span| to_bytes(span, &byte_offsets)).collect()
+}
+
+/// The byte offsets of the characters of the `source`, followed by the length of the source.
+pub fn byte_offsets(source: &str) -> Vec<usize> {
+    let byte_offsets = source.char_indices().map(|(offset, _)| offset);
+    byte_offsets.chain(iter::once(source.len())).collect()
+}
+
+/// Convert the `span` in characters to the one in bytes, given the [`byte_offsets`] of the
+/// source.
+pub fn to_bytes(span: Span, byte_offsets: &[usize]) -> Span {
+    let byte_offset = |char_offset: usize| byte_offsets[char_offset.min(byte_offsets.len() - 1)];
+    Span::new(byte_offset(span.start), byte_offset(span.end))
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_spans() {
+        let tokens =
+            vec![Token::variable("ab", 0), Token::operator("+", 1), Token::variable("c", 2)];
+        let expected = vec![Span::new(0, 2), Span::new(3, 4), Span::new(6, 7)];
+        assert_eq!(of_tokens(&tokens), expected);
+        let source = "ąb +  c";
+        let spans = of_tokens_in(source, &tokens);
+        assert_eq!(spans, vec![Span::new(0, 3), Span::new(4, 5), Span::new(7, 8)]);
+        assert_eq!(&source[spans[0].range()], "ąb");
+        assert_eq!(spans[0].join(spans[2]), Span::new(0, 8));
+    }
+}
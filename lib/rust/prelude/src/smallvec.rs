@@ -7,10 +7,29 @@ use std::cmp::Ordering;
 // === Export ===
 // ==============
 
+pub use smallvec::smallvec;
 pub use smallvec::SmallVec;
 
 
 
+// ===============
+// === Aliases ===
+// ===============
+
+// Most per-item collections, like links of a node or targets of a pointer, have up to a few
+// elements. The following aliases keep them inline, so they do not allocate until they outgrow
+// their inline capacity. Note that the inline capacity increases the size of the container itself,
+// so it should be chosen according to the expected number of elements.
+
+/// A vector storing up to 1 element inline.
+pub type SmallVec1<T> = SmallVec<[T; 1]>;
+/// A vector storing up to 2 elements inline.
+pub type SmallVec2<T> = SmallVec<[T; 2]>;
+/// A vector storing up to 4 elements inline.
+pub type SmallVec4<T> = SmallVec<[T; 4]>;
+
+
+
 /// Adds methods to the `SmallVec` type.
 pub trait SmallVecOps {
     type Item;
@@ -108,6 +127,16 @@ mod tests {
         assert_eq!(v.binary_search_by(|probe| probe.cmp(&11)), Err(2));
     }
 
+    #[test]
+    fn test_inline_capacity() {
+        let mut v: SmallVec4<usize> = smallvec![1, 2, 3];
+        v.push(4);
+        assert!(!v.spilled());
+        v.push(5);
+        assert!(v.spilled());
+        assert_eq!(v.as_slice(), &[1, 2, 3, 4, 5]);
+    }
+
     #[test]
     fn test_binary_search() {
         let v = SmallVec::<[usize; 4]>::from_iter([5, 10, 20, 40].iter().copied());
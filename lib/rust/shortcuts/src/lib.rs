@@ -22,6 +22,9 @@ extern crate test;
 use enso_automata::*;
 use enso_prelude::*;
 use enso_web as web;
+use serde::Deserialize;
+use serde::Serialize;
+use std::time::Duration;
 
 pub use enso_logger as logger;
 pub use enso_logger::AnyLogger;
@@ -76,7 +79,27 @@ lazy_static! {
     static ref SIDE_KEYS_SET: HashSet<&'static str> = SIDE_KEYS.iter().copied().collect();
 }
 
-const DOUBLE_EVENT_TIME_MS: f32 = 300.0;
+/// If the key is a modifier, like "ctrl" or "shift-left", return its side-independent name, like
+/// "ctrl" or "shift".
+pub fn modifier_name(key: &str) -> Option<&str> {
+    let base = key.strip_suffix("-left").or_else(|| key.strip_suffix("-right")).unwrap_or(key);
+    SIDE_KEYS_SET.contains(base).then(|| base)
+}
+
+/// The default maximum time between two presses (or releases) to be recognized as `DoublePress`
+/// (or `DoubleClick`).
+pub const DOUBLE_EVENT_TIME_MS: f32 = 300.0;
+
+/// The default maximum time between consecutive steps of a key sequence, like `ctrl k, ctrl s`.
+pub const SEQUENCE_TIMEOUT_MS: f32 = 1000.0;
+
+/// Separator of steps in key sequence expressions, like `ctrl k, ctrl s`. See [`KeySequence`].
+const SEQUENCE_SEPARATOR: char = ',';
+
+/// Wildcard expression matching a press of any printable character key, like "a" or "?", while no
+/// modifiers other than shift (which is needed to type some characters) are pressed. Supported by
+/// `HashSetRegistry` for the `Press` and `PressAndRepeat` actions only.
+pub const ANY_CHARACTER: &str = "any-character";
 
 
 
@@ -88,7 +111,18 @@ const DOUBLE_EVENT_TIME_MS: f32 = 300.0;
 /// As a clarification, the event `DoublePress` is emitted on second press of a button/key happening
 /// in short time interval from the first one. `DoubleClick`, on the other hand, happens on release,
 /// not on press.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+///
+/// The `MultiPress(n)` event is emitted on the n-th press of a button/key, if each press happened
+/// in short time interval from the previous one, like a triple press of the escape key. It shares
+/// the time interval with `DoublePress`, which is equivalent to `MultiPress(2)`. It is supported by
+/// `HashSetRegistry` only.
+///
+/// The `LongPress` event is emitted when the keys are held longer than the provided duration. As
+/// it does not correspond to any input event, it is reported by the `Registry::on_tick` method,
+/// which should be called periodically, for example, on every frame. The complementary
+/// `LongPressRelease` event is emitted when the keys are released after being held longer than the
+/// provided duration, which allows implementing press-and-hold interactions.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 #[allow(missing_docs)]
 pub enum ActionType {
     Press,
@@ -96,11 +130,152 @@ pub enum ActionType {
     Release,
     DoublePress,
     DoubleClick,
+    MultiPress(usize),
+    LongPress(Duration),
+    LongPressRelease(Duration),
 }
 pub use ActionType::*;
 
 
 
+// ================
+// === Modifier ===
+// ================
+
+/// A modifier key. The `Primary` modifier is the one used by most shortcuts on the current
+/// platform, that is, `meta` (Cmd) on macOS and `ctrl` elsewhere. It can also be used in shortcut
+/// expressions as `primary` (or its aliases `cmd` and `command`), so a single shortcut definition
+/// works on all platforms.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[allow(missing_docs)]
+pub enum Modifier {
+    Primary,
+    Ctrl,
+    Alt,
+    Shift,
+    Meta,
+}
+
+impl Modifier {
+    /// The name of the key used in shortcut expressions. The `Primary` modifier is resolved to the
+    /// key of the current platform.
+    pub fn key_name(self) -> &'static str {
+        match self {
+            Self::Primary => match web::platform::current() {
+                Some(web::platform::MacOS) => "meta",
+                _ => "ctrl",
+            },
+            Self::Ctrl => "ctrl",
+            Self::Alt => "alt",
+            Self::Shift => "shift",
+            Self::Meta => "meta",
+        }
+    }
+
+    /// Build a shortcut expression from the modifiers and the rest of the expression, like
+    /// `Modifier::expr(&[Modifier::Primary, Modifier::Shift], "s")`, which results in
+    /// "ctrl shift s" (or "meta shift s" on macOS).
+    pub fn expr(modifiers: &[Modifier], expr: impl AsRef<str>) -> String {
+        let modifiers = modifiers.iter().map(|t| t.key_name());
+        let expr = Some(expr.as_ref()).filter(|t| !t.trim().is_empty());
+        modifiers.chain(expr).join(" ")
+    }
+}
+
+
+
+// ===================
+// === KeySequence ===
+// ===================
+
+/// An ordered list of key combinations (steps) pressed one after another, like
+/// `["ctrl k", "ctrl s"]`. Most shortcuts consist of a single step, like `["ctrl s"]`.
+///
+/// In the textual form, used in shortcut definitions and configuration files, the steps are
+/// separated by a comma directly following the last key of a step, like "ctrl k, ctrl s". A comma
+/// separated from the preceding key by a space is the comma key itself, like in "cmd ,".
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(from = "String", into = "String")]
+pub struct KeySequence {
+    steps: Vec<String>,
+}
+
+impl KeySequence {
+    /// Constructor. Each step is a list of keys separated by space, like "ctrl k".
+    pub fn new<S: AsRef<str>>(steps: impl IntoIterator<Item = S>) -> Self {
+        let steps = steps.into_iter().map(|step| step.as_ref().split_whitespace().join(" "));
+        Self { steps: steps.collect() }
+    }
+
+    /// Parse the textual form of the sequence, like "ctrl k, ctrl s".
+    pub fn parse(expr: &str) -> Self {
+        let mut steps = vec![];
+        let mut step = Vec::<&str>::new();
+        for chunk in expr.split_whitespace() {
+            match chunk.strip_suffix(SEQUENCE_SEPARATOR).filter(|key| !key.is_empty()) {
+                Some(key) => {
+                    step.push(key);
+                    steps.push(mem::take(&mut step).join(" "));
+                }
+                None => step.push(chunk),
+            }
+        }
+        if !step.is_empty() || steps.is_empty() {
+            steps.push(step.join(" "));
+        }
+        Self { steps }
+    }
+
+    /// The key combinations to be pressed one after another.
+    pub fn steps(&self) -> &[String] {
+        &self.steps
+    }
+
+    /// Check whether the sequence consists of more than one step, like "ctrl k, ctrl s".
+    pub fn is_multi_step(&self) -> bool {
+        self.steps.len() > 1
+    }
+}
+
+impl Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let separator = format!("{} ", SEQUENCE_SEPARATOR);
+        write!(f, "{}", self.steps.join(&separator))
+    }
+}
+
+impl From<&str> for KeySequence {
+    fn from(expr: &str) -> Self {
+        Self::parse(expr)
+    }
+}
+
+impl From<String> for KeySequence {
+    fn from(expr: String) -> Self {
+        Self::parse(&expr)
+    }
+}
+
+impl From<&String> for KeySequence {
+    fn from(expr: &String) -> Self {
+        Self::parse(expr)
+    }
+}
+
+impl From<&KeySequence> for KeySequence {
+    fn from(sequence: &KeySequence) -> Self {
+        sequence.clone()
+    }
+}
+
+impl From<KeySequence> for String {
+    fn from(sequence: KeySequence) -> Self {
+        sequence.to_string()
+    }
+}
+
+
+
 // ================
 // === Registry ===
 // ================
@@ -108,9 +283,12 @@ pub use ActionType::*;
 /// Abstraction for shortcut registry implementation.
 #[allow(missing_docs)]
 pub trait Registry<T>: Default {
-    /// Add a new action mapping. `The expr` needs to be a list of keys separated by space, like
-    /// "ctrl shift a".
-    fn add(&self, action_type: ActionType, expr: impl AsRef<str>, action: impl Into<T>);
+    /// Add a new action mapping. Each step of the `sequence` needs to be a list of keys separated
+    /// by space, like "ctrl shift a". Mouse buttons can be used as keys as well, like
+    /// "ctrl left-mouse-button" (or its shorter form "ctrl click"). Sequences of more than one
+    /// step, like "ctrl k, ctrl s", are supported by `HashSetRegistry` only. See
+    /// [`KeySequence`].
+    fn add(&self, action_type: ActionType, sequence: impl Into<KeySequence>, action: impl Into<T>);
 
     /// Get a list of items registered for the action that just happened. It might include items
     /// registered for `DoublePress` or `DoubleClick` if the actions were performed fast enough.
@@ -120,6 +298,17 @@ pub trait Registry<T>: Default {
     /// registered for `DoublePress` or `DoubleClick` if the actions were performed fast enough.
     fn on_release(&self, input: impl AsRef<str>) -> Vec<T>;
 
+    /// Set the maximum time between two presses (or releases) to be recognized as `DoublePress` (or
+    /// `DoubleClick`). Defaults to `DOUBLE_EVENT_TIME_MS`.
+    fn set_double_event_time(&self, time_ms: f32);
+
+    /// Get a list of items registered for the actions triggered by the passage of time, like
+    /// `LongPress`. Should be called periodically. Implementations not supporting such actions
+    /// always return an empty list.
+    fn on_tick(&self) -> Vec<T> {
+        default()
+    }
+
     /// Some engines might implement a separate optimization stage. This is intended to force the
     /// optimization at a given point in time. Used mainly in benchmarks.
     fn optimize(&self) {}
@@ -136,21 +325,23 @@ type FullExprString = String;
 /// Internal model of `AutomataRegistry`.
 #[derive(Debug)]
 pub struct AutomataRegistryModel<T> {
-    dirty:         bool,
-    nfa:           Nfa,
-    dfa:           Dfa,
-    states:        HashMap<FullExprString, nfa::State>,
+    logger:               Logger,
+    dirty:                bool,
+    nfa:                  Nfa,
+    dfa:                  Dfa,
+    states:               HashMap<FullExprString, nfa::State>,
     /// This field is used to allow efficient check if a connection already exists.
-    connections:   HashSet<(nfa::State, nfa::State)>,
+    connections:          HashSet<(nfa::State, nfa::State)>,
     /// The always state is a special state which always triggers. No matter what event happens,
     /// this state will always be re-entered. It is useful for implementing such rules as `-a`,
     /// which fire always when key `a` is released, no matter if other keys are pressed or not.
-    always_state:  nfa::State,
-    current:       dfa::State,
-    pressed:       HashSet<FullExprString>,
-    action_map:    HashMap<ActionType, HashMap<nfa::State, T>>,
-    press_times:   HashMap<dfa::State, f32>,
-    release_times: HashMap<dfa::State, f32>,
+    always_state:         nfa::State,
+    current:              dfa::State,
+    pressed:              HashSet<FullExprString>,
+    action_map:           HashMap<ActionType, HashMap<nfa::State, T>>,
+    press_times:          HashMap<dfa::State, f32>,
+    release_times:        HashMap<dfa::State, f32>,
+    double_event_time_ms: f32,
 }
 
 
@@ -172,6 +363,7 @@ impl<T> AutomataRegistryModel<T> {
 impl<T> AutomataRegistryModel<T> {
     /// Constructor.
     pub fn new() -> Self {
+        let logger = Logger::new("AutomataRegistry");
         let mut nfa = Nfa::default();
         let dfa = Dfa::from(&nfa);
         let states = default();
@@ -183,7 +375,9 @@ impl<T> AutomataRegistryModel<T> {
         let action_map = default();
         let press_times = default();
         let release_times = default();
+        let double_event_time_ms = DOUBLE_EVENT_TIME_MS;
         Self {
+            logger,
             dirty,
             nfa,
             dfa,
@@ -195,14 +389,29 @@ impl<T> AutomataRegistryModel<T> {
             action_map,
             press_times,
             release_times,
+            double_event_time_ms,
         }
     }
 }
 
 impl<T: Clone> AutomataRegistryModel<T> {
-    fn add(&mut self, action_type: ActionType, expr: impl AsRef<str>, action: impl Into<T>) {
+    /// Add a new action mapping. Key sequences of more than one step are not supported. They are
+    /// reported as a warning and ignored.
+    fn add(
+        &mut self,
+        action_type: ActionType,
+        sequence: impl Into<KeySequence>,
+        action: impl Into<T>,
+    ) {
+        let sequence = sequence.into();
+        let expr = match sequence.steps() {
+            [expr] => expr,
+            _ => {
+                warning!(self.logger, "Key sequences are not supported, ignoring \"{sequence}\".");
+                return;
+            }
+        };
         self.dirty = true;
-        let expr = expr.as_ref();
         let end_state = if let Some(key) = expr.strip_prefix('-') {
             let key = format!("-{}", key.trim().to_lowercase());
             let sym = Symbol::new_named(hash(&key), key);
@@ -349,7 +558,7 @@ impl<T: Clone> AutomataRegistryModel<T> {
         let time_map = if press { &self.press_times } else { &self.release_times };
         let last_time = time_map.get(&focus_state);
         let time_diff = last_time.map(|t| time - t);
-        let is_double = time_diff.map(|t| t < DOUBLE_EVENT_TIME_MS) == Some(true);
+        let is_double = time_diff.map(|t| t < self.double_event_time_ms) == Some(true);
         let new_time = if is_double { 0.0 } else { time };
         self.current = next_state;
         let mut actions =
@@ -422,7 +631,8 @@ impl<T> Default for AutomataRegistryModel<T> {
 /// Please note, that this registry type allows for broader expression input syntax than what is
 /// described in the `Registry` documentation. In particular, it allows for expressions like `-a`,
 /// which means, that they will be triggered every time the key `a` is released, no matter if other
-/// keys were pressed or not.
+/// keys were pressed or not. Key sequences of more than one step, like "ctrl k, ctrl s", are not
+/// supported.
 #[derive(CloneRef, Debug, Derivative)]
 #[derivative(Clone(bound = ""))]
 #[derivative(Default(bound = ""))]
@@ -455,8 +665,8 @@ impl<T: Clone> AutomataRegistry<T> {
 }
 
 impl<T: Clone> Registry<T> for AutomataRegistry<T> {
-    fn add(&self, action_type: ActionType, expr: impl AsRef<str>, action: impl Into<T>) {
-        self.rc.borrow_mut().add(action_type, expr, action)
+    fn add(&self, action_type: ActionType, sequence: impl Into<KeySequence>, action: impl Into<T>) {
+        self.rc.borrow_mut().add(action_type, sequence, action)
     }
 
     /// Process the press input event. See `on_event` docs to learn more.
@@ -469,6 +679,10 @@ impl<T: Clone> Registry<T> for AutomataRegistry<T> {
         self.rc.borrow_mut().on_release(input)
     }
 
+    fn set_double_event_time(&self, time_ms: f32) {
+        self.rc.borrow_mut().double_event_time_ms = time_ms;
+    }
+
     fn optimize(&self) {
         self.rc.borrow_mut().optimize();
     }
@@ -482,16 +696,43 @@ impl<T: Clone> Registry<T> for AutomataRegistry<T> {
 
 pub trait HashSetRegistryItem = Clone + Debug + Eq + Hash;
 
+/// A key sequence registered for an action. See `HashSetRegistryModel` to learn more.
+#[derive(Debug)]
+struct SequenceAction<T> {
+    /// Expressions of all steps of the sequence but the last one.
+    prefix: Vec<String>,
+    action: T,
+}
+
 /// Internal model for `HashSetRegistry`.
+///
+/// ## Key Sequences
+/// Besides single key combinations, the registry supports key sequences, like "ctrl k, ctrl s".
+/// Every press of a non-modifier key is recorded as a sequence step in the `sequence_history`,
+/// which keeps only as many recent steps as the longest registered sequence has. A
+/// sequence is matched if the most recent steps are equal to the sequence steps and the time
+/// between consecutive steps does not exceed the `sequence_timeout_ms`. When a sequence is
+/// matched, actions registered for the last step alone (like "ctrl s") are not triggered, and the
+/// history is cleared.
 #[derive(Debug)]
 pub struct HashSetRegistryModel<T> {
-    current_expr:  String,
-    actions:       HashMap<ActionType, HashMap<String, Vec<T>>>,
-    pressed:       HashSet<String>,
-    press_times:   HashMap<String, f32>,
-    release_times: HashMap<String, f32>,
-    side_keys:     HashMap<String, Vec<String>>,
-    key_aliases:   HashMap<String, String>,
+    current_expr:         String,
+    actions:              HashMap<ActionType, HashMap<String, Vec<T>>>,
+    sequences:            HashMap<ActionType, HashMap<String, Vec<SequenceAction<T>>>>,
+    sequence_history:     Vec<(String, f32)>,
+    sequence_timeout_ms:  f32,
+    double_event_time_ms: f32,
+    /// The time since the current expression is being held, and the `LongPress` actions already
+    /// triggered for it.
+    hold_start_time:      f32,
+    long_presses_fired:   HashSet<Duration>,
+    pressed:              HashSet<String>,
+    /// The time of the last press (or release) of each expression, and the number of its presses
+    /// (or releases) which happened in short time intervals one after another.
+    press_taps:           HashMap<String, (f32, usize)>,
+    release_taps:         HashMap<String, (f32, usize)>,
+    side_keys:            HashMap<String, Vec<String>>,
+    key_aliases:          HashMap<String, String>,
 }
 
 impl<T> HashSetRegistryModel<T> {
@@ -499,13 +740,33 @@ impl<T> HashSetRegistryModel<T> {
     pub fn new() -> Self {
         let current_expr = default();
         let actions = default();
+        let sequences = default();
+        let sequence_history = default();
+        let sequence_timeout_ms = SEQUENCE_TIMEOUT_MS;
+        let double_event_time_ms = DOUBLE_EVENT_TIME_MS;
+        let hold_start_time = default();
+        let long_presses_fired = default();
         let pressed = default();
-        let press_times = default();
-        let release_times = default();
+        let press_taps = default();
+        let release_taps = default();
         let side_keys = default();
         let key_aliases = key_aliases();
-        Self { current_expr, actions, pressed, press_times, release_times, side_keys, key_aliases }
-            .init()
+        Self {
+            current_expr,
+            actions,
+            sequences,
+            sequence_history,
+            sequence_timeout_ms,
+            double_event_time_ms,
+            hold_start_time,
+            long_presses_fired,
+            pressed,
+            press_taps,
+            release_taps,
+            side_keys,
+            key_aliases,
+        }
+        .init()
     }
 
     fn init(mut self) -> Self {
@@ -519,20 +780,149 @@ impl<T> HashSetRegistryModel<T> {
     fn current_expr(&self) -> String {
         self.pressed.iter().sorted().join(" ")
     }
+
+    /// Set the maximum time between consecutive steps of a key sequence.
+    pub fn set_sequence_timeout(&mut self, timeout_ms: f32) {
+        self.sequence_timeout_ms = timeout_ms;
+    }
+
+    /// Check whether the key is a modifier, like "ctrl" or "shift-left". Pressing a modifier does
+    /// not start a new key sequence step.
+    fn is_modifier(&self, key: &str) -> bool {
+        let key = self.key_aliases.get(key).map(|t| t.as_str()).unwrap_or(key);
+        modifier_name(key).is_some()
+    }
+
+    /// The number of steps of the longest registered key sequence, or 0 if there are none.
+    fn max_sequence_len(&self) -> usize {
+        let sequences = self.sequences.values().flat_map(|t| t.values()).flatten();
+        sequences.map(|sequence| sequence.prefix.len() + 1).max().unwrap_or_default()
+    }
+
+    /// Record a new key sequence step, forgetting the steps which are too old to be continued or
+    /// which cannot be a part of any registered sequence.
+    fn push_sequence_step(&mut self, expr: String) {
+        let max_len = self.max_sequence_len();
+        if max_len == 0 {
+            self.sequence_history.clear();
+            return;
+        }
+        let time = web::time_from_start() as f32;
+        let last_time = self.sequence_history.last().map(|(_, t)| *t);
+        if last_time.map(|t| time - t > self.sequence_timeout_ms) == Some(true) {
+            self.sequence_history.clear();
+        }
+        self.sequence_history.push((expr, time));
+        let excess = self.sequence_history.len().saturating_sub(max_len);
+        self.sequence_history.drain(..excess);
+    }
 }
 
 impl<T: HashSetRegistryItem> HashSetRegistryModel<T> {
     /// Add a new shortcut definition.
-    pub fn add(&mut self, action_type: ActionType, input: impl AsRef<str>, action: impl Into<T>) {
-        let input = input.as_ref();
+    pub fn add(
+        &mut self,
+        action_type: ActionType,
+        sequence: impl Into<KeySequence>,
+        action: impl Into<T>,
+    ) {
+        let sequence = sequence.into();
         let action = action.into();
-        let exprs = self.possible_exprs(input);
-        let map = self.actions.entry(action_type).or_default();
-        for expr in exprs {
-            map.entry(expr).or_default().push(action.clone());
+        match sequence.steps() {
+            [step] => {
+                let exprs = self.possible_exprs(step);
+                let map = self.actions.entry(action_type).or_default();
+                for expr in exprs {
+                    map.entry(expr).or_default().push(action.clone());
+                }
+            }
+            _ => self.add_sequence(action_type, &sequence, action),
+        }
+    }
+
+    /// Add a new key sequence definition, like "ctrl k, ctrl s".
+    fn add_sequence(&mut self, action_type: ActionType, sequence: &KeySequence, action: T) {
+        let (prefixes, last_step) = self.possible_sequence_exprs(sequence);
+        let map = self.sequences.entry(action_type).or_default();
+        for expr in last_step {
+            let sequences = map.entry(expr).or_default();
+            for prefix in &prefixes {
+                let prefix = prefix.clone();
+                sequences.push(SequenceAction { prefix, action: action.clone() });
+            }
+        }
+    }
+
+    /// Remove the shortcut definition. The `sequence` and `action` have to be the same as the ones
+    /// provided to `add`.
+    pub fn remove(
+        &mut self,
+        action_type: ActionType,
+        sequence: impl Into<KeySequence>,
+        action: &T,
+    ) {
+        let sequence = sequence.into();
+        match sequence.steps() {
+            [step] => {
+                let exprs = self.possible_exprs(step);
+                if let Some(map) = self.actions.get_mut(&action_type) {
+                    for expr in exprs {
+                        if let Some(actions) = map.get_mut(&expr) {
+                            actions.retain(|t| t != action);
+                        }
+                    }
+                }
+            }
+            _ => {
+                let (prefixes, last_step) = self.possible_sequence_exprs(&sequence);
+                if let Some(map) = self.sequences.get_mut(&action_type) {
+                    for expr in last_step {
+                        if let Some(sequences) = map.get_mut(&expr) {
+                            sequences
+                                .retain(|t| &t.action != action || !prefixes.contains(&t.prefix));
+                        }
+                    }
+                }
+            }
         }
     }
 
+    /// Return all possible expressions of a key sequence. The first element of the result contains
+    /// all possible combinations of the steps but the last one, while the second element contains
+    /// all possible expressions of the last step.
+    fn possible_sequence_exprs(&self, sequence: &KeySequence) -> (Vec<Vec<String>>, Vec<String>) {
+        let mut steps = sequence.steps().iter().map(|t| self.possible_exprs(t)).collect_vec();
+        let last_step = steps.pop().unwrap_or_default();
+        let mut prefixes = vec![Vec::<String>::new()];
+        for step in steps {
+            let local_prefixes = mem::take(&mut prefixes);
+            for prefix in local_prefixes {
+                prefixes.extend(step.iter().map(|expr| prefix.clone().pushed(expr.clone())));
+            }
+        }
+        (prefixes, last_step)
+    }
+
+    /// Actions of all sequences ending with the current expression and matching the recent steps.
+    fn matching_sequences(&self, action: ActionType, expr: &str) -> Vec<T> {
+        let history = &self.sequence_history;
+        let last_step = history.last().map(|(last_expr, _)| last_expr.as_str());
+        if last_step != Some(expr) {
+            return default();
+        }
+        let sequences = self.sequences.get(&action).and_then(|t| t.get(expr)).into_iter().flatten();
+        let matching = sequences.filter(|sequence| {
+            let len = sequence.prefix.len() + 1;
+            len <= history.len() && {
+                let steps = &history[history.len() - len..];
+                let timely = steps.windows(2).all(|t| t[1].1 - t[0].1 <= self.sequence_timeout_ms);
+                let prefix_matches = steps.iter().zip(&sequence.prefix).all(|(t, s)| &t.0 == s);
+                timely && prefix_matches
+            }
+        });
+        matching.map(|sequence| sequence.action.clone()).collect()
+    }
+
     #[allow(clippy::collapsible_else_if)]
     fn on_event(&mut self, input: impl AsRef<str>, press: bool) -> Vec<T> {
         let input = input.as_ref().to_lowercase();
@@ -540,12 +930,19 @@ impl<T: HashSetRegistryItem> HashSetRegistryModel<T> {
         let repeat = if press { exists } else { !exists };
         if !repeat {
             let out = self.process_event(Release);
+            let out = out.extended(self.process_long_press_release());
+            let is_sequence_step = press && !self.is_modifier(&input);
             if press {
                 self.pressed.insert(input);
             } else {
                 self.pressed.remove(&input);
             }
             self.current_expr = self.current_expr();
+            self.hold_start_time = web::time_from_start() as f32;
+            self.long_presses_fired.clear();
+            if is_sequence_step {
+                self.push_sequence_step(self.current_expr.clone());
+            }
             out.extended(self.process_event(Press)).extended(self.process_event(PressAndRepeat))
         } else {
             if press {
@@ -558,6 +955,11 @@ impl<T: HashSetRegistryItem> HashSetRegistryModel<T> {
 
     fn process_event(&mut self, action: ActionType) -> Vec<T> {
         let expr = &self.current_expr;
+        let sequence_out = self.matching_sequences(action, expr);
+        if !sequence_out.is_empty() {
+            self.sequence_history.clear();
+            return sequence_out;
+        }
         let mut out = self
             .actions
             .get(&action)
@@ -566,26 +968,87 @@ impl<T: HashSetRegistryItem> HashSetRegistryModel<T> {
             .flatten()
             .cloned()
             .collect_vec();
+        if (action == Press || action == PressAndRepeat) && self.is_character_input() {
+            let actions = self.actions.get(&action).and_then(|t| t.get(ANY_CHARACTER));
+            out.extend(actions.into_iter().flatten().cloned());
+        }
         if action != PressAndRepeat {
             let is_press = action == Press;
-            let action2 = if is_press { DoublePress } else { DoubleClick };
-            let time_map = if is_press { &mut self.press_times } else { &mut self.release_times };
+            let max_taps = if is_press { self.max_multi_press(expr) } else { 2 };
+            let taps_map = if is_press { &mut self.press_taps } else { &mut self.release_taps };
             let time = web::time_from_start() as f32;
-            let last_time = time_map.get(expr);
-            let time_diff = last_time.map(|t| time - t);
-            let is_double = time_diff.map(|t| t < DOUBLE_EVENT_TIME_MS) == Some(true);
-            if is_double {
-                out.extend(
-                    self.actions
-                        .get(&action2)
-                        .and_then(|t| t.get(expr))
-                        .into_iter()
-                        .flatten()
-                        .cloned(),
-                );
-                time_map.remove(expr);
-            } else {
-                *time_map.entry(expr.clone()).or_default() = time;
+            let (last_time, taps_entry) = taps_map.entry(expr.clone()).or_default();
+            let in_time = *taps_entry > 0 && time - *last_time < self.double_event_time_ms;
+            let taps = if in_time { *taps_entry + 1 } else { 1 };
+            // Once the highest registered multi-press is reached, the next press starts counting
+            // anew, so four fast presses are two double presses if no triple press is registered.
+            *taps_entry = if taps >= max_taps { 0 } else { taps };
+            *last_time = time;
+            let mut multi_actions = vec![];
+            if taps == 2 {
+                multi_actions.push(if is_press { DoublePress } else { DoubleClick });
+            }
+            if is_press {
+                multi_actions.push(MultiPress(taps));
+            }
+            for multi_action in multi_actions {
+                let actions = self.actions.get(&multi_action).and_then(|t| t.get(expr));
+                out.extend(actions.into_iter().flatten().cloned());
+            }
+        }
+        out
+    }
+
+    /// The highest number of presses of the expression with registered actions, at least 2.
+    fn max_multi_press(&self, expr: &str) -> usize {
+        let multi_presses =
+            self.actions.iter().filter_map(|(action_type, actions)| match action_type {
+                MultiPress(n) if actions.get(expr).map_or(false, |t| !t.is_empty()) => Some(*n),
+                _ => None,
+            });
+        multi_presses.fold(2, usize::max)
+    }
+
+    /// Check whether the pressed keys are matched by the `ANY_CHARACTER` wildcard.
+    fn is_character_input(&self) -> bool {
+        let is_shift = |key: &&String| modifier_name(key) == Some("shift");
+        let mut keys = self.pressed.iter().filter(|key| !is_shift(key));
+        let key = keys.next();
+        let is_character = key.map(|key| key.chars().count() == 1) == Some(true);
+        is_character && keys.next().is_none()
+    }
+
+    /// Actions registered for `LongPressRelease` of the current expression, if it was held long
+    /// enough.
+    fn process_long_press_release(&self) -> Vec<T> {
+        let hold_time = web::time_from_start() as f32 - self.hold_start_time;
+        let mut out = vec![];
+        for (action_type, actions) in &self.actions {
+            if let LongPressRelease(duration) = action_type {
+                if hold_time >= duration.as_secs_f32() * 1000.0 {
+                    out.extend(actions.get(&self.current_expr).into_iter().flatten().cloned());
+                }
+            }
+        }
+        out
+    }
+
+    /// Handle the passage of time. Returns actions registered for `LongPress` of the current
+    /// expression which was just held long enough.
+    pub fn on_tick(&mut self) -> Vec<T> {
+        let hold_time = web::time_from_start() as f32 - self.hold_start_time;
+        let mut out = vec![];
+        for (action_type, actions) in &self.actions {
+            if let LongPress(duration) = action_type {
+                let held_long_enough = hold_time >= duration.as_secs_f32() * 1000.0;
+                if held_long_enough && !self.long_presses_fired.contains(duration) {
+                    let actions = actions.get(&self.current_expr).into_iter().flatten().cloned();
+                    let actions = actions.collect_vec();
+                    if !actions.is_empty() {
+                        self.long_presses_fired.insert(*duration);
+                        out.extend(actions);
+                    }
+                }
             }
         }
         out
@@ -641,12 +1104,15 @@ impl<T> Default for HashSetRegistryModel<T> {
     }
 }
 
+/// Resolve the key alias, like "cmd" or "click", to the key name recognized by the registry, like
+/// "ctrl" or "mouse-button-0". Other key names are returned unchanged.
+pub fn resolve_key_alias(key: &str) -> String {
+    key_aliases().remove(key).unwrap_or_else(|| key.into())
+}
+
 fn key_aliases() -> HashMap<String, String> {
     let mut map = HashMap::<String, String>::new();
-    let cmd_target = match web::platform::current() {
-        Some(web::platform::MacOS) => "meta",
-        _ => "ctrl",
-    };
+    let cmd_target = Modifier::Primary.key_name();
     #[allow(clippy::useless_format)]
     let insert_side_key = |map: &mut HashMap<String, String>, k: &str, v: &str| {
         map.insert(format!("{}", k), format!("{}", v));
@@ -660,6 +1126,7 @@ fn key_aliases() -> HashMap<String, String> {
     insert_side_key(&mut map, "option", "alt");
     insert_side_key(&mut map, "cmd", cmd_target);
     insert_side_key(&mut map, "command", cmd_target);
+    insert_side_key(&mut map, "primary", cmd_target);
     insert(&mut map, "left", "arrow-left");
     insert(&mut map, "right", "arrow-right");
     insert(&mut map, "up", "arrow-up");
@@ -667,6 +1134,12 @@ fn key_aliases() -> HashMap<String, String> {
     insert(&mut map, "left-mouse-button", "mouse-button-0");
     insert(&mut map, "middle-mouse-button", "mouse-button-1");
     insert(&mut map, "right-mouse-button", "mouse-button-2");
+    insert(&mut map, "back-mouse-button", "mouse-button-3");
+    insert(&mut map, "forward-mouse-button", "mouse-button-4");
+    insert(&mut map, "click", "mouse-button-0");
+    insert(&mut map, "left-click", "mouse-button-0");
+    insert(&mut map, "middle-click", "mouse-button-1");
+    insert(&mut map, "right-click", "mouse-button-2");
     map
 }
 
@@ -691,11 +1164,25 @@ impl<T> HashSetRegistry<T> {
     pub fn new() -> Self {
         default()
     }
+
+    /// Set the maximum time between consecutive steps of a key sequence. Defaults to
+    /// `SEQUENCE_TIMEOUT_MS`.
+    pub fn set_sequence_timeout(&self, timeout_ms: f32) {
+        self.rc.borrow_mut().set_sequence_timeout(timeout_ms)
+    }
+}
+
+impl<T: HashSetRegistryItem> HashSetRegistry<T> {
+    /// Remove the shortcut definition. The `sequence` and `action` have to be the same as the ones
+    /// provided to `add`.
+    pub fn remove(&self, action_type: ActionType, sequence: impl Into<KeySequence>, action: &T) {
+        self.rc.borrow_mut().remove(action_type, sequence, action)
+    }
 }
 
 impl<T: HashSetRegistryItem> Registry<T> for HashSetRegistry<T> {
-    fn add(&self, action_type: ActionType, expr: impl AsRef<str>, action: impl Into<T>) {
-        self.rc.borrow_mut().add(action_type, expr, action)
+    fn add(&self, action_type: ActionType, sequence: impl Into<KeySequence>, action: impl Into<T>) {
+        self.rc.borrow_mut().add(action_type, sequence, action)
     }
 
     fn on_press(&self, input: impl AsRef<str>) -> Vec<T> {
@@ -705,6 +1192,14 @@ impl<T: HashSetRegistryItem> Registry<T> for HashSetRegistry<T> {
     fn on_release(&self, input: impl AsRef<str>) -> Vec<T> {
         self.rc.borrow_mut().on_release(input)
     }
+
+    fn set_double_event_time(&self, time_ms: f32) {
+        self.rc.borrow_mut().double_event_time_ms = time_ms;
+    }
+
+    fn on_tick(&self) -> Vec<T> {
+        self.rc.borrow_mut().on_tick()
+    }
 }
 
 
@@ -790,6 +1285,215 @@ mod tests {
     }
 
 
+    #[test]
+    fn hash_set_registry_double_press_time() {
+        double_press_time::<HashSetRegistry<i32>>();
+    }
+    fn double_press_time<T: Registry<i32>>() -> T {
+        let nothing = Vec::<i32>::new();
+        let registry = <T>::default();
+        registry.add(DoublePress, "shift", 0);
+        registry.set_double_event_time(500.0);
+        for _ in 0..10 {
+            assert_eq!(registry.on_press("shift-left"), nothing);
+            assert_eq!(registry.on_release("shift-left"), nothing);
+            web::simulate_sleep(400.0);
+            assert_eq!(registry.on_press("shift-right"), nothing);
+            assert_eq!(registry.on_release("shift-right"), nothing);
+            web::simulate_sleep(400.0);
+            assert_eq!(registry.on_press("shift-right"), vec![0]);
+            assert_eq!(registry.on_release("shift-right"), nothing);
+            web::simulate_sleep(1000.0);
+        }
+        registry
+    }
+
+
+    // === MultiPress ===
+
+    #[test]
+    fn hash_set_registry_multi_press() {
+        multi_press::<HashSetRegistry<&'static str>>();
+    }
+    fn multi_press<T: Registry<&'static str>>() -> T {
+        let nothing = Vec::<&'static str>::new();
+        let registry = <T>::default();
+        registry.add(DoublePress, "escape", "double");
+        registry.add(MultiPress(3), "escape", "triple");
+        for _ in 0..10 {
+            assert_eq!(registry.on_press("escape"), nothing);
+            assert_eq!(registry.on_release("escape"), nothing);
+            web::simulate_sleep(100.0);
+            assert_eq!(registry.on_press("escape"), vec!["double"]);
+            assert_eq!(registry.on_release("escape"), nothing);
+            web::simulate_sleep(100.0);
+            assert_eq!(registry.on_press("escape"), vec!["triple"]);
+            assert_eq!(registry.on_release("escape"), nothing);
+            web::simulate_sleep(100.0);
+            assert_eq!(registry.on_press("escape"), nothing);
+            assert_eq!(registry.on_release("escape"), nothing);
+            web::simulate_sleep(1000.0);
+            assert_eq!(registry.on_press("escape"), nothing);
+            assert_eq!(registry.on_release("escape"), nothing);
+            web::simulate_sleep(500.0);
+        }
+        registry
+    }
+
+    #[test]
+    fn hash_set_registry_repeated_double_press() {
+        let nothing = Vec::<&'static str>::new();
+        let registry = HashSetRegistry::<&'static str>::default();
+        registry.add(DoublePress, "left-mouse-button", "double");
+        for expected in [&nothing, &vec!["double"], &nothing, &vec!["double"]] {
+            assert_eq!(&registry.on_press("mouse-button-0"), expected);
+            assert_eq!(registry.on_release("mouse-button-0"), nothing);
+            web::simulate_sleep(100.0);
+        }
+    }
+
+
+    // === LongPress ===
+
+    #[test]
+    fn hash_set_registry_long_press() {
+        long_press::<HashSetRegistry<&'static str>>();
+    }
+    fn long_press<T: Registry<&'static str>>() -> T {
+        let nothing = Vec::<&'static str>::new();
+        let registry = <T>::default();
+        let threshold = Duration::from_millis(500);
+        registry.add(LongPress(threshold), "space", "show preview");
+        registry.add(LongPressRelease(threshold), "space", "hide preview");
+        for _ in 0..10 {
+            // Short press.
+            assert_eq!(registry.on_press("space"), nothing);
+            web::simulate_sleep(100.0);
+            assert_eq!(registry.on_tick(), nothing);
+            assert_eq!(registry.on_release("space"), nothing);
+            assert_eq!(registry.on_tick(), nothing);
+            // Long press.
+            assert_eq!(registry.on_press("space"), nothing);
+            web::simulate_sleep(300.0);
+            assert_eq!(registry.on_tick(), nothing);
+            web::simulate_sleep(300.0);
+            assert_eq!(registry.on_tick(), vec!["show preview"]);
+            web::simulate_sleep(300.0);
+            assert_eq!(registry.on_tick(), nothing);
+            assert_eq!(registry.on_release("space"), vec!["hide preview"]);
+            web::simulate_sleep(1000.0);
+            assert_eq!(registry.on_tick(), nothing);
+        }
+        registry
+    }
+
+
+    // === Mouse Buttons ===
+
+    #[test]
+    fn hash_set_registry_mouse_buttons() {
+        mouse_buttons::<HashSetRegistry<&'static str>>();
+    }
+    fn mouse_buttons<T: Registry<&'static str>>() -> T {
+        let nothing = Vec::<&'static str>::new();
+        let registry = <T>::default();
+        registry.add(Press, "ctrl click", "add cursor");
+        registry.add(Release, "ctrl left-mouse-button", "stop selection");
+        registry.add(Press, "shift right-click", "context menu");
+        registry.add(DoublePress, "left-mouse-button", "select word");
+        assert_eq!(registry.on_press("ctrl-left"), nothing);
+        assert_eq!(registry.on_press("mouse-button-0"), vec!["add cursor"]);
+        assert_eq!(registry.on_release("mouse-button-0"), vec!["stop selection"]);
+        assert_eq!(registry.on_press("mouse-button-2"), nothing);
+        assert_eq!(registry.on_release("mouse-button-2"), nothing);
+        assert_eq!(registry.on_release("ctrl-left"), nothing);
+        assert_eq!(registry.on_press("shift-right"), nothing);
+        assert_eq!(registry.on_press("mouse-button-2"), vec!["context menu"]);
+        assert_eq!(registry.on_release("mouse-button-2"), nothing);
+        assert_eq!(registry.on_release("shift-right"), nothing);
+        assert_eq!(registry.on_press("mouse-button-0"), nothing);
+        assert_eq!(registry.on_release("mouse-button-0"), nothing);
+        assert_eq!(registry.on_press("mouse-button-0"), vec!["select word"]);
+        assert_eq!(registry.on_release("mouse-button-0"), nothing);
+        registry
+    }
+
+
+    // === Removing Shortcuts ===
+
+    #[test]
+    fn hash_set_registry_remove() {
+        let nothing = Vec::<i32>::new();
+        let registry = HashSetRegistry::<i32>::new();
+        registry.add(Press, "ctrl a", 0);
+        registry.add(Press, "ctrl a", 1);
+        registry.add(Press, "ctrl k, ctrl a", 2);
+        registry.remove(Press, "ctrl a", &0);
+        assert_eq!(registry.on_press("ctrl-left"), nothing);
+        assert_eq!(registry.on_press("a"), vec![1]);
+        assert_eq!(registry.on_release("a"), nothing);
+        registry.remove(Press, "ctrl k, ctrl a", &2);
+        assert_eq!(registry.on_press("k"), nothing);
+        assert_eq!(registry.on_release("k"), nothing);
+        assert_eq!(registry.on_press("a"), vec![1]);
+        assert_eq!(registry.on_release("a"), nothing);
+    }
+
+
+    // === Primary Modifier ===
+
+    #[test]
+    fn hash_set_registry_primary_modifier() {
+        primary_modifier::<HashSetRegistry<i32>>();
+    }
+    fn primary_modifier<T: Registry<i32>>() -> T {
+        let nothing = Vec::<i32>::new();
+        let registry = <T>::default();
+        let primary = Modifier::Primary.key_name();
+        registry.add(Press, "primary a", 0);
+        registry.add(Press, Modifier::expr(&[Modifier::Primary, Modifier::Shift], "a"), 1);
+        registry.add(Press, Modifier::expr(&[Modifier::Alt], ""), 2);
+        assert_eq!(registry.on_press(format!("{}-left", primary)), nothing);
+        assert_eq!(registry.on_press("a"), vec![0]);
+        assert_eq!(registry.on_release("a"), nothing);
+        assert_eq!(registry.on_press("shift-right"), nothing);
+        assert_eq!(registry.on_press("a"), vec![1]);
+        assert_eq!(registry.on_release("a"), nothing);
+        assert_eq!(registry.on_release("shift-right"), nothing);
+        assert_eq!(registry.on_release(format!("{}-left", primary)), nothing);
+        assert_eq!(registry.on_press("alt-left"), vec![2]);
+        registry
+    }
+
+
+    // === Any Character ===
+
+    #[test]
+    fn hash_set_registry_any_character() {
+        any_character::<HashSetRegistry<i32>>();
+    }
+    fn any_character<T: Registry<i32>>() -> T {
+        let nothing = Vec::<i32>::new();
+        let registry = <T>::default();
+        registry.add(Press, ANY_CHARACTER, 0);
+        registry.add(Press, "ctrl a", 1);
+        assert_eq!(registry.on_press("a"), vec![0]);
+        assert_eq!(registry.on_press("a"), nothing);
+        assert_eq!(registry.on_release("a"), nothing);
+        assert_eq!(registry.on_press("shift-left"), nothing);
+        assert_eq!(registry.on_press("?"), vec![0]);
+        assert_eq!(registry.on_release("?"), nothing);
+        assert_eq!(registry.on_release("shift-left"), nothing);
+        assert_eq!(registry.on_press("ctrl-left"), nothing);
+        assert_eq!(registry.on_press("a"), vec![1]);
+        assert_eq!(registry.on_release("a"), nothing);
+        assert_eq!(registry.on_release("ctrl-left"), nothing);
+        assert_eq!(registry.on_press("enter"), nothing);
+        assert_eq!(registry.on_release("enter"), nothing);
+        registry
+    }
+
+
     // === Overlapping Shortcuts ===
 
     // #[test] fn automata_registry_overlapping() { overlapping::<AutomataRegistry<i32>>(); }
@@ -870,6 +1574,126 @@ mod tests {
     }
 
 
+    // === Key Sequences ===
+
+    #[test]
+    fn hash_set_registry_key_sequence() {
+        key_sequence::<HashSetRegistry<&'static str>>();
+    }
+    fn key_sequence<T: Registry<&'static str>>() -> T {
+        let nothing = Vec::<&'static str>::new();
+        let registry: T = default();
+        registry.add(Press, "ctrl k, ctrl s", "save all");
+        registry.add(Press, "ctrl s", "save");
+        // Sequence with the modifier held.
+        assert_eq!(registry.on_press("ctrl-left"), nothing);
+        assert_eq!(registry.on_press("k"), nothing);
+        assert_eq!(registry.on_release("k"), nothing);
+        assert_eq!(registry.on_press("s"), vec!["save all"]);
+        assert_eq!(registry.on_release("s"), nothing);
+        // The sequence was consumed, so the single step works again.
+        assert_eq!(registry.on_press("s"), vec!["save"]);
+        assert_eq!(registry.on_release("s"), nothing);
+        assert_eq!(registry.on_release("ctrl-left"), nothing);
+        // Sequence with the modifier released between steps.
+        assert_eq!(registry.on_press("ctrl-right"), nothing);
+        assert_eq!(registry.on_press("k"), nothing);
+        assert_eq!(registry.on_release("k"), nothing);
+        assert_eq!(registry.on_release("ctrl-right"), nothing);
+        assert_eq!(registry.on_press("ctrl-left"), nothing);
+        assert_eq!(registry.on_press("s"), vec!["save all"]);
+        assert_eq!(registry.on_release("s"), nothing);
+        // Interrupted sequence.
+        assert_eq!(registry.on_press("k"), nothing);
+        assert_eq!(registry.on_release("k"), nothing);
+        assert_eq!(registry.on_press("a"), nothing);
+        assert_eq!(registry.on_release("a"), nothing);
+        assert_eq!(registry.on_press("s"), vec!["save"]);
+        assert_eq!(registry.on_release("s"), nothing);
+        // Timed out sequence.
+        assert_eq!(registry.on_press("k"), nothing);
+        assert_eq!(registry.on_release("k"), nothing);
+        web::simulate_sleep((SEQUENCE_TIMEOUT_MS + 100.0) as f64);
+        assert_eq!(registry.on_press("s"), vec!["save"]);
+        assert_eq!(registry.on_release("s"), nothing);
+        registry
+    }
+
+    #[test]
+    fn hash_set_registry_key_sequence_timeout() {
+        let nothing = Vec::<&'static str>::new();
+        let registry = HashSetRegistry::<&'static str>::new();
+        registry.set_sequence_timeout(5000.0);
+        registry.add(Press, "g, g", "go to top");
+        assert_eq!(registry.on_press("g"), nothing);
+        assert_eq!(registry.on_release("g"), nothing);
+        web::simulate_sleep(3000.0);
+        assert_eq!(registry.on_press("g"), vec!["go to top"]);
+        assert_eq!(registry.on_release("g"), nothing);
+        assert_eq!(registry.on_press("g"), nothing);
+        assert_eq!(registry.on_release("g"), nothing);
+    }
+
+    #[test]
+    fn hash_set_registry_key_sequence_history_is_bounded() {
+        let registry = HashSetRegistry::<&'static str>::new();
+        let type_text = |text: &str| {
+            for key in text.chars().map(|t| t.to_string()) {
+                registry.on_press(&key);
+                registry.on_release(&key);
+            }
+        };
+        registry.add(Press, "ctrl s", "save");
+        type_text("some text");
+        assert!(registry.rc.borrow().sequence_history.is_empty());
+        registry.add(Press, "ctrl k, ctrl s", "save all");
+        registry.add(Press, "g, g, g", "go to top");
+        type_text("some longer text");
+        assert_eq!(registry.rc.borrow().sequence_history.len(), 3);
+        assert_eq!(registry.on_press("g"), Vec::<&'static str>::new());
+        registry.on_release("g");
+        registry.on_press("g");
+        registry.on_release("g");
+        assert_eq!(registry.on_press("g"), vec!["go to top"]);
+    }
+
+    #[test]
+    fn automata_registry_key_sequence_unsupported() {
+        let nothing = Vec::<&'static str>::new();
+        let registry = AutomataRegistry::<&'static str>::new();
+        registry.add(Press, "ctrl k, ctrl s", "save all");
+        assert_eq!(registry.on_press("ctrl-left"), nothing);
+        assert_eq!(registry.on_press("k"), nothing);
+        assert_eq!(registry.on_release("k"), nothing);
+        assert_eq!(registry.on_press("s"), nothing);
+        assert_eq!(registry.on_release("s"), nothing);
+        assert_eq!(registry.on_press("k,"), nothing);
+    }
+
+    #[test]
+    fn key_sequence_parsing() {
+        let steps = |expr: &str| KeySequence::parse(expr).steps().to_vec();
+        assert_eq!(steps("ctrl  s "), vec!["ctrl s"]);
+        assert_eq!(steps("ctrl k, ctrl s"), vec!["ctrl k", "ctrl s"]);
+        assert_eq!(steps("g, g"), vec!["g", "g"]);
+        assert_eq!(steps("cmd ,"), vec!["cmd ,"]);
+        assert_eq!(steps("cmd ,, x"), vec!["cmd ,", "x"]);
+        let sequence = KeySequence::new(["cmd ,", " x"]);
+        assert_eq!(sequence.to_string(), "cmd ,, x");
+        assert_eq!(KeySequence::parse(&sequence.to_string()), sequence);
+        assert!(!KeySequence::parse("cmd ,").is_multi_step());
+    }
+
+    #[test]
+    fn hash_set_registry_comma_key() {
+        let nothing = Vec::<&'static str>::new();
+        let registry = HashSetRegistry::<&'static str>::new();
+        registry.add(Press, "ctrl ,", "settings");
+        assert_eq!(registry.on_press("ctrl-left"), nothing);
+        assert_eq!(registry.on_press(","), vec!["settings"]);
+    }
+
+
     // === Disabled Key Repeat ===
 
     // #[test] fn automata_registry_repeat() { repeat::<AutomataRegistry<&'static str>>(); }
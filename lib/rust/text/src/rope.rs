@@ -23,6 +23,7 @@ pub use spans::Spans;
 
 pub use xi_rope::interval::Interval;
 pub use xi_rope::interval::IntervalBounds;
+pub use xi_rope::rope::ChunkIter;
 pub use xi_rope::rope::Lines;
 pub use xi_rope::Cursor;
 pub use xi_rope::DeltaBuilder;
@@ -143,6 +143,14 @@ impl Text {
         self.rope.lines(range)
     }
 
+    /// An iterator over the chunks of the text in the provided range. The chunks are the string
+    /// slices stored in the leaves of the rope, so the text can be processed without copying it to
+    /// a `String`.
+    pub fn chunks(&self, range: impl RangeBounds) -> rope::ChunkIter {
+        let range = self.crop_byte_range(range);
+        self.rope.iter_chunks(range.into_rope_interval())
+    }
+
     /// Replaces the provided range with the provided text.
     pub fn replace(&mut self, range: impl RangeBounds, text: impl Into<Text>) {
         let text = text.into();
@@ -996,4 +1004,16 @@ mod test {
             case.run()
         }
     }
+
+    #[test]
+    fn chunks_of_edited_text() {
+        let mut text: Text = "line\n".repeat(1000).into();
+        let middle = text.byte_offset_of_line_index(500.into()).unwrap();
+        text.replace(middle..middle, "inserted\n");
+        let start = text.byte_offset_of_line_index(499.into()).unwrap();
+        let end = text.byte_offset_of_line_index(502.into()).unwrap();
+        let chunks: String = text.chunks(start..end).collect();
+        assert_eq!(chunks, "line\ninserted\nline\n");
+        assert_eq!(text.chunks(..).map(str::len).sum::<usize>(), text.byte_size().as_usize());
+    }
 }
@@ -558,6 +558,7 @@ mock_data! { HtmlElement => Element
     fn set_class_name(&self, n: &str);
     fn set_inner_text(&self, value: &str);
     fn inner_text(&self) -> String;
+    fn focus(&self) -> Result<(), JsValue>;
     fn get_elements_by_class_name(&self, class_names: &str) -> HtmlCollection;
     fn style(&self) -> CssStyleDeclaration;
 }